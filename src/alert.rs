@@ -0,0 +1,109 @@
+// Group alerts for Backrooms Doom
+// An enemy that spots the player raises an alert zone around itself. Every
+// enemy in the zone converges on where the player was last seen, then keeps
+// searching the area around it until SEARCH_TIME has passed without another
+// sighting; after that the alert fades out and they go back to what they were
+// doing (patrols pick up again on their own). The strongest alert drives the
+// intensity of the ambient music.
+
+use crate::maze::Maze;
+use rand::Rng;
+
+const ZONE_RADIUS: f32 = 10.0;   // Tiles around the spotting enemy that get alerted
+const SEARCH_TIME: f32 = 20.0;   // Seconds of searching after the last sighting
+const DECAY_TIME: f32 = 8.0;     // Seconds the alert then takes to fade out
+const SEARCH_RADIUS: f32 = 4.0;  // How far from the last known position searchers look
+const RECONVERGE_DISTANCE: f32 = 1.0; // The player moved this far since the last call for help
+
+pub struct AlertZone {
+    pub x: f32, // Center: where it was raised
+    pub y: f32,
+    pub last_known: (f32, f32), // Where the player was last seen
+    called: Option<(f32, f32)>, // Last known position enemies were last sent to
+    since_seen: f32,
+}
+
+impl AlertZone {
+    /// 1.0 while searching, then fading to 0.0
+    pub fn level(&self) -> f32 {
+        if self.searching() {
+            1.0
+        } else {
+            (1.0 - (self.since_seen - SEARCH_TIME) / DECAY_TIME).max(0.0)
+        }
+    }
+
+    pub fn searching(&self) -> bool {
+        self.since_seen < SEARCH_TIME
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        ((x - self.x).powi(2) + (y - self.y).powi(2)).sqrt() <= ZONE_RADIUS
+    }
+}
+
+pub struct Alerts {
+    pub zones: Vec<AlertZone>,
+}
+
+impl Alerts {
+    pub fn new() -> Self {
+        Self { zones: Vec::new() }
+    }
+
+    /// An enemy at `spotter` sees the player at `player`. Raises a new zone or refreshes the
+    /// one the enemy is in. Returns the position to send the zone's enemies to when they
+    /// should (re)converge: a new alert, or the player got well away from the last call
+    pub fn raise(&mut self, spotter: (f32, f32), player: (f32, f32)) -> Option<&AlertZone> {
+        let index = match self.zones.iter().position(|zone| zone.contains(spotter.0, spotter.1)) {
+            Some(index) => index,
+            None => {
+                self.zones.push(AlertZone { x: spotter.0, y: spotter.1, last_known: player, called: None, since_seen: 0.0 });
+                self.zones.len() - 1
+            }
+        };
+
+        let zone = &mut self.zones[index];
+        zone.last_known = player;
+        zone.since_seen = 0.0;
+        let moved = zone
+            .called
+            .is_none_or(|(x, y)| ((player.0 - x).powi(2) + (player.1 - y).powi(2)).sqrt() > RECONVERGE_DISTANCE);
+        if moved {
+            zone.called = Some(player);
+            return Some(zone);
+        }
+        None
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for zone in &mut self.zones {
+            zone.since_seen += delta_time;
+        }
+        self.zones.retain(|zone| zone.level() > 0.0);
+    }
+
+    /// Strongest alert level, 0.0 - 1.0
+    pub fn intensity(&self) -> f32 {
+        self.zones.iter().map(|zone| zone.level()).fold(0.0, f32::max)
+    }
+
+    /// Zone still being searched that covers (x, y)
+    pub fn searching_at(&self, x: f32, y: f32) -> Option<&AlertZone> {
+        self.zones.iter().find(|zone| zone.searching() && zone.contains(x, y))
+    }
+}
+
+/// Random walkable spot within SEARCH_RADIUS of the zone's last known position
+pub fn search_point(maze: &Maze, zone: &AlertZone, rng: &mut impl Rng) -> (f32, f32) {
+    let (x, y) = zone.last_known;
+    for _ in 0..8 {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let distance = rng.gen_range(1.0..SEARCH_RADIUS);
+        let point = (x + angle.cos() * distance, y + angle.sin() * distance);
+        if maze.is_walkable(point.0, point.1) {
+            return point;
+        }
+    }
+    (x, y)
+}
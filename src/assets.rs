@@ -0,0 +1,266 @@
+// Asset preloading for Backrooms Doom
+// Reads the asset manifest and loads everything it lists with progress reporting.
+// Textures are decoded on a worker thread; audio is loaded on the main thread
+// because raylib sounds are bound to the audio device.
+
+use crate::audio::AudioManager;
+use crate::textures::Texture;
+use log::{debug, info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+pub const MANIFEST_PATH: &str = "assets/manifest.txt";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AssetKind {
+    Texture,
+    Sound,
+    Music,
+}
+
+#[derive(Clone)]
+pub struct AssetEntry {
+    pub kind: AssetKind,
+    pub name: String,
+    pub path: String,
+}
+
+pub struct AssetManifest {
+    pub entries: Vec<AssetEntry>,
+}
+
+impl AssetManifest {
+    /// Load the entries of one section ("[default]", "[level0]", ...) from a manifest file
+    pub fn load(path: &str, section: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read asset manifest {}: {}", path, e))?;
+
+        let mut entries = Vec::new();
+        let mut current_section = String::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                current_section = line[1..line.len() - 1].trim().to_string();
+                continue;
+            }
+
+            if current_section != section {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 3 {
+                return Err(format!(
+                    "{}:{}: expected '<kind> <name> <path>', got '{}'",
+                    path,
+                    line_number + 1,
+                    line
+                ));
+            }
+
+            let kind = match parts[0] {
+                "texture" => AssetKind::Texture,
+                "sound" => AssetKind::Sound,
+                "music" => AssetKind::Music,
+                other => {
+                    return Err(format!("{}:{}: unknown asset kind '{}'", path, line_number + 1, other));
+                }
+            };
+
+            entries.push(AssetEntry {
+                kind,
+                name: parts[1].to_string(),
+                path: parts[2].to_string(),
+            });
+        }
+
+        if entries.is_empty() {
+            return Err(format!("Asset manifest {} has no entries for [{}]", path, section));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Assets specific to the level loaded from `maze_path`. Levels use the section
+    /// named after the maze file (maze.txt -> [maze]); a missing section means the
+    /// level only uses the shared [default] assets
+    pub fn for_level(path: &str, maze_path: &str) -> Self {
+        let section = Path::new(maze_path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(maze_path);
+
+        Self::load(path, section).unwrap_or_else(|e| {
+            debug!("{} - level uses shared assets only", e);
+            Self { entries: Vec::new() }
+        })
+    }
+
+    /// Built-in asset list used when the manifest file is missing or broken
+    pub fn builtin() -> Self {
+        let list = [
+            (AssetKind::Texture, "wall", "assets/textures/wall.png"),
+            (AssetKind::Texture, "wall_exit", "assets/textures/wall_exit.png"),
+            (AssetKind::Texture, "floor", "assets/textures/floor2.png"),
+            (AssetKind::Texture, "ceiling", "assets/textures/ceiling.png"),
+            (AssetKind::Texture, "door", "assets/textures/door.png"),
+            (AssetKind::Texture, "red_pill", "assets/textures/red_pill.png"),
+            (AssetKind::Texture, "blue_pill", "assets/textures/blue_pill.png"),
+            (AssetKind::Texture, "enemy", "assets/textures/enemy.png"),
+            (AssetKind::Texture, "companion", "assets/textures/companion.png"),
+            (AssetKind::Texture, "box", "assets/textures/box.png"),
+            (AssetKind::Texture, "desk", "assets/textures/desk.png"),
+            (AssetKind::Texture, "cabinet", "assets/textures/cabinet.png"),
+            (AssetKind::Texture, "radio", "assets/textures/radio.png"),
+            (AssetKind::Music, "ambient", "assets/audio/ambiental.wav"),
+            (AssetKind::Sound, "start", "assets/audio/start.wav"),
+            (AssetKind::Sound, "footstep", "assets/audio/footstep.wav"),
+            (AssetKind::Sound, "damage", "assets/audio/damage.wav"),
+            (AssetKind::Sound, "heartbeat", "assets/audio/heartbeat.wav"),
+            (AssetKind::Sound, "victory", "assets/audio/victory.wav"),
+            (AssetKind::Sound, "impact", "assets/audio/impact.wav"),
+            (AssetKind::Sound, "radio", "assets/audio/radio.wav"),
+            (AssetKind::Sound, "hum", "assets/audio/hum.wav"),
+            (AssetKind::Sound, "whisper", "assets/audio/whisper.wav"),
+            (AssetKind::Sound, "enemy_step", "assets/audio/footstep.wav"),
+            (AssetKind::Sound, "step_echo", "assets/audio/footstep.wav"),
+            (AssetKind::Sound, "impact_echo", "assets/audio/impact.wav"),
+            (AssetKind::Sound, "locked", "assets/audio/impact.wav"),
+        ];
+
+        Self {
+            entries: list
+                .iter()
+                .map(|(kind, name, path)| AssetEntry {
+                    kind: *kind,
+                    name: name.to_string(),
+                    path: path.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Load a manifest section, falling back to the built-in list on failure
+    pub fn load_or_builtin(path: &str, section: &str) -> Self {
+        Self::load(path, section).unwrap_or_else(|e| {
+            warn!("{} - using built-in asset list", e);
+            Self::builtin()
+        })
+    }
+}
+
+/// Result of decoding one texture on the worker thread
+type DecodedTexture = (String, String, Result<Texture, String>);
+
+pub struct AssetLoader {
+    receiver: Receiver<DecodedTexture>,
+    pending_textures: usize,
+    audio_queue: VecDeque<AssetEntry>,
+    total: usize,
+    done: usize,
+    pub current_item: String,
+    pub textures: HashMap<String, Texture>,
+    pub texture_errors: HashMap<String, String>,
+}
+
+impl AssetLoader {
+    /// Start loading everything listed in the manifest
+    pub fn start(manifest: &AssetManifest) -> Self {
+        let texture_entries: Vec<AssetEntry> = manifest
+            .entries
+            .iter()
+            .filter(|e| e.kind == AssetKind::Texture)
+            .cloned()
+            .collect();
+        let audio_queue: VecDeque<AssetEntry> = manifest
+            .entries
+            .iter()
+            .filter(|e| e.kind != AssetKind::Texture)
+            .cloned()
+            .collect();
+
+        // Decode textures on a worker thread
+        let (sender, receiver) = mpsc::channel();
+        let pending_textures = texture_entries.len();
+        thread::spawn(move || {
+            for entry in texture_entries {
+                let result = Texture::load(&entry.path);
+                if sender.send((entry.name, entry.path, result)).is_err() {
+                    break; // Loader was dropped
+                }
+            }
+        });
+
+        Self {
+            receiver,
+            pending_textures,
+            total: pending_textures + audio_queue.len(),
+            audio_queue,
+            done: 0,
+            current_item: String::new(),
+            textures: HashMap::new(),
+            texture_errors: HashMap::new(),
+        }
+    }
+
+    /// Collect decoded textures and load the next audio asset (call once per frame)
+    pub fn poll(&mut self, audio_manager: &mut AudioManager) {
+        loop {
+            let (name, path, result) = match self.receiver.try_recv() {
+                Ok(decoded) => decoded,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    // Worker died early, count whatever it didn't deliver as failed
+                    if self.pending_textures > 0 {
+                        warn!("Texture loader stopped with {} textures left", self.pending_textures);
+                        self.done += self.pending_textures;
+                        self.pending_textures = 0;
+                    }
+                    break;
+                }
+            };
+
+            match result {
+                Ok(texture) => {
+                    info!("Loaded texture: {} ({}x{})", name, texture.width, texture.height);
+                    self.textures.insert(name.clone(), texture);
+                }
+                Err(e) => {
+                    warn!("Failed to load {}: {}", path, e);
+                    self.texture_errors.insert(name.clone(), format!("{}: {}", path, e));
+                }
+            }
+            self.current_item = path;
+            self.pending_textures -= 1;
+            self.done += 1;
+        }
+
+        // Audio must be created on the main thread, one asset per frame keeps the screen responsive
+        if let Some(entry) = self.audio_queue.pop_front() {
+            audio_manager.load(&entry);
+            self.current_item = entry.path;
+            self.done += 1;
+        }
+    }
+
+    /// Loading progress from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.done as f32 / self.total as f32
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending_textures == 0 && self.audio_queue.is_empty()
+    }
+}
@@ -0,0 +1,37 @@
+// Attract mode for Backrooms Doom
+// Left alone on the main menu for a minute, the game plays itself like an
+// arcade cabinet: the bot (see bot.rs) walks the level from the start to the
+// exit, walks out through the opening exit (the same sequence a real escape
+// plays, see escape.rs) and starts over, under a blinking DEMO banner. Any key,
+// mouse movement or click goes straight back to the menu.
+
+use crate::bot::{Bot, World};
+use crate::maze::Maze;
+use crate::player::Player;
+
+pub const IDLE_TIME: f32 = 60.0;   // Seconds of no input on the menu before the demo starts
+const SPEED_FACTOR: f32 = 0.8;     // An unhurried walk
+
+pub struct Demo {
+    start: (f32, f32),
+    bot: Bot,
+}
+
+impl Demo {
+    pub fn new(maze: &Maze) -> Self {
+        Self { start: maze.start_pos, bot: Bot::with_speed(SPEED_FACTOR) }
+    }
+
+    /// Put the bot back at the start of the level
+    pub fn restart(&mut self, player: &mut Player) {
+        player.pos.x = self.start.0;
+        player.pos.y = self.start.1;
+        self.bot.replan();
+    }
+
+    /// Walk the bot straight to the exit. Returns true once it stands in front of it
+    pub fn update(&mut self, maze: &Maze, player: &mut Player, delta_time: f32) -> bool {
+        let world = World { maze, keys: Vec::new(), pills: Vec::new(), enemies: Vec::new(), health: 1.0, time_left: 0.0 };
+        self.bot.update(&world, player, delta_time)
+    }
+}
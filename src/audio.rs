@@ -1,146 +1,192 @@
 // Audio Manager for Backrooms Doom
 // Handles all audio playback using raylib-rs 5.5.1 API
+//
+// Audio is optional: without an output device the game runs silently, and the
+// manager keeps trying to open one every few seconds. raylib doesn't report a
+// device disappearing (headphones unplugged, a USB headset switched off), so a
+// lost device is noticed by the ambient music no longer advancing while it should
+// be playing. The device is then reopened and every sound loaded so far is loaded
+// again under the same handles.
+//
+// Footsteps and impacts echo in large open spaces: delayed, quieter copies are
+// layered under the dry sound through separate instances of the same samples,
+// later and louder the more open the room (see reverb.rs).
 
+use crate::assets::{AssetEntry, AssetKind, AssetManifest};
+use crate::reverb;
+use crate::resources::{Registry, SoundId, SoundKind};
+use log::{debug, info, warn};
 use raylib::prelude::*;
 use std::path::Path;
 
+const DEVICE_CHECK_INTERVAL: f32 = 3.0; // Seconds between output device checks
+const STALL_TIME: f32 = 1.5;            // Music stuck this long while playing = device gone
+
+/// Open the default output device. None when there is none or it failed to start
+pub fn open_device() -> Option<&'static RaylibAudio> {
+    let audio = RaylibAudio::init_audio_device().ok().filter(|audio| audio.is_audio_device_ready())?;
+    audio.set_master_volume(1.0);
+    // The token is zero-sized: leaking it costs nothing and lets sounds loaded after a
+    // reconnect borrow it for as long as the ones loaded at startup
+    Some(Box::leak(Box::new(audio)))
+}
+
 pub struct AudioManager<'a> {
     pub music_volume: f32,
     pub sfx_volume: f32,
     pub volume_multiplier: f32,
-    pub footstep_timer: f32,
     pub music_playing: bool,
+    pub music_intensity: f32, // 0.0 - 1.0, follows the enemy alert level
 
     // Loaded audio with lifetime bound to RaylibAudio
     pub ambient: Option<Music<'a>>,
-    pub start: Option<Sound<'a>>,
-    pub footstep: Option<Sound<'a>>,
-    pub damage: Option<Sound<'a>>,
-    pub heartbeat: Option<Sound<'a>>,
-    pub victory: Option<Sound<'a>>,
-}
-
-impl<'a> AudioManager<'a> {
-    pub fn new(audio: &'a RaylibAudio) -> Self {
-        let mut files_present = false;
-
-        // Load music
-        let ambient = if Path::new("assets/audio/ambiental.wav").exists() {
-            match audio.new_music("assets/audio/ambiental.wav") {
-                Ok(music) => {
-                    println!("Loaded: ambiental.wav");
-                    files_present = true;
-                    Some(music)
-                }
-                Err(e) => {
-                    println!("Could not load ambiental.wav: {}", e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
+    pub sounds: Registry<SoundKind, Sound<'a>>,
 
-        // Load sounds
-        let start = if Path::new("assets/audio/start.wav").exists() {
-            match audio.new_sound("assets/audio/start.wav") {
-                Ok(sound) => {
-                    println!("Loaded: start.wav");
-                    files_present = true;
-                    Some(sound)
-                }
-                Err(_) => {
-                    println!("Could not load start.wav");
-                    None
-                }
-            }
-        } else {
-            None
-        };
+    // Handles for the sounds the game plays, resolved when the manager is created
+    pub start: SoundId,
+    pub footstep: SoundId,
+    pub damage: SoundId,
+    pub heartbeat: SoundId,
+    pub victory: SoundId,
+    pub impact: SoundId,
+    pub radio: SoundId,
+    pub hum: SoundId,
+    pub whisper: SoundId,
+    pub enemy_step: SoundId,
+    pub step_echo: SoundId,
+    pub impact_echo: SoundId,
+    pub locked: SoundId,
 
-        let footstep = if Path::new("assets/audio/footstep.wav").exists() {
-            match audio.new_sound("assets/audio/footstep.wav") {
-                Ok(sound) => {
-                    println!("Loaded: footstep.wav");
-                    files_present = true;
-                    Some(sound)
-                }
-                Err(_) => {
-                    println!("Could not load footstep.wav");
-                    None
-                }
-            }
-        } else {
-            None
-        };
-
-        let damage = if Path::new("assets/audio/damage.wav").exists() {
-            match audio.new_sound("assets/audio/damage.wav") {
-                Ok(sound) => {
-                    println!("Loaded: damage.wav");
-                    files_present = true;
-                    Some(sound)
-                }
-                Err(_) => None,
-            }
-        } else {
-            None
-        };
-
-        let heartbeat = if Path::new("assets/audio/heartbeat.wav").exists() {
-            match audio.new_sound("assets/audio/heartbeat.wav") {
-                Ok(sound) => {
-                    println!("Loaded: heartbeat.wav");
-                    files_present = true;
-                    Some(sound)
-                }
-                Err(_) => None,
-            }
-        } else {
-            None
-        };
+    device: Option<&'a RaylibAudio>, // None while running without audio
+    loaded: Vec<AssetEntry>,         // Everything loaded, reloaded with a new device
+    paused: bool,
+    device_timer: f32,    // Until the next device check
+    music_position: f32,  // Last seen playback position of the ambient music
+    music_stalled: f32,   // Seconds it hasn't moved while playing
+    reverb: f32,          // Wet level of the room the player is in (0.0 - 1.0)
+    echoes: Vec<Echo>,    // Waiting to play
+}
 
-        let victory = if Path::new("assets/audio/victory.wav").exists() {
-            match audio.new_sound("assets/audio/victory.wav") {
-                Ok(sound) => {
-                    println!("Loaded: victory.wav");
-                    files_present = true;
-                    Some(sound)
-                }
-                Err(_) => {
-                    println!("Could not load victory.wav");
-                    None
-                }
-            }
-        } else {
-            None
-        };
+/// A delayed copy of a sound about to play
+struct Echo {
+    sound: SoundId,
+    delay: f32, // Seconds left
+    volume: f32,
+    pitch: f32,
+}
 
-        if !files_present {
-            println!("No audio files found in assets/audio/");
-            println!("Add WAV files to enable audio (see assets/audio/README.md)");
-        }
+impl<'a> AudioManager<'a> {
+    /// Create an audio manager with nothing loaded yet (see `load`). Without a device
+    /// nothing plays until `watch_device` manages to open one
+    pub fn new(device: Option<&'a RaylibAudio>) -> Self {
+        let mut sounds = Registry::new();
+        let start = sounds.handle("start");
+        let footstep = sounds.handle("footstep");
+        let damage = sounds.handle("damage");
+        let heartbeat = sounds.handle("heartbeat");
+        let victory = sounds.handle("victory");
+        let impact = sounds.handle("impact");
+        let radio = sounds.handle("radio");
+        let hum = sounds.handle("hum");
+        let whisper = sounds.handle("whisper");
+        let enemy_step = sounds.handle("enemy_step");
+        let step_echo = sounds.handle("step_echo");
+        let impact_echo = sounds.handle("impact_echo");
+        let locked = sounds.handle("locked");
 
         Self {
             music_volume: 0.6,
             sfx_volume: 0.7,
             volume_multiplier: 1.0,
-            footstep_timer: 0.0,
             music_playing: false,
-            ambient,
+            music_intensity: 0.0,
+            ambient: None,
+            sounds,
             start,
             footstep,
             damage,
             heartbeat,
             victory,
+            impact,
+            radio,
+            hum,
+            whisper,
+            enemy_step,
+            step_echo,
+            impact_echo,
+            locked,
+            device,
+            loaded: Vec::new(),
+            paused: false,
+            device_timer: DEVICE_CHECK_INTERVAL,
+            music_position: 0.0,
+            music_stalled: 0.0,
+            reverb: 0.0,
+            echoes: Vec::new(),
+        }
+    }
+
+    /// Load one manifest entry (sounds are stored under their manifest name)
+    pub fn load(&mut self, entry: &AssetEntry) {
+        if !Path::new(&entry.path).exists() {
+            warn!("Audio file not found: {}", entry.path);
+            return;
+        }
+        // Remembered even without a device, so it loads once one turns up
+        self.loaded.push(entry.clone());
+        let Some(audio) = self.device else {
+            return;
+        };
+
+        if entry.kind == AssetKind::Music {
+            match audio.new_music(&entry.path) {
+                Ok(music) => {
+                    info!("Loaded: {}", entry.path);
+                    match entry.name.as_str() {
+                        "ambient" => self.ambient = Some(music),
+                        other => warn!("Unknown music slot '{}'", other),
+                    }
+                }
+                Err(e) => warn!("Could not load {}: {}", entry.path, e),
+            }
+            return;
+        }
+
+        match audio.new_sound(&entry.path) {
+            Ok(sound) => {
+                info!("Loaded: {}", entry.path);
+                self.sounds.insert(&entry.name, sound);
+            }
+            Err(e) => warn!("Could not load {}: {}", entry.path, e),
+        }
+    }
+
+    /// Drop everything loaded and load `manifest` in its place (switching resource
+    /// packs). Sound handles stay valid, the ambient music restarts if it was playing
+    pub fn reload(&mut self, manifest: &AssetManifest) {
+        let was_playing = self.music_playing;
+        self.stop_music();
+        self.ambient = None;
+        self.sounds.clear();
+        self.loaded.clear();
+        self.echoes.clear();
+
+        for entry in manifest.entries.iter().filter(|e| e.kind != AssetKind::Texture) {
+            self.load(entry);
+        }
+        if was_playing {
+            self.play_background_music();
+        }
+        if self.paused {
+            self.set_paused(true);
         }
     }
 
     /// Play menu music (start.wav)
     pub fn play_menu_music(&self) {
-        if let Some(ref sound) = self.start {
+        if let Some(sound) = self.sounds.get(self.start) {
             sound.play();
-            println!("Playing menu music (start.wav)");
+            debug!("Playing menu music (start.wav)");
         }
     }
 
@@ -150,19 +196,64 @@ impl<'a> AudioManager<'a> {
             music.play_stream();
             music.set_volume(self.music_volume * self.volume_multiplier);
             self.music_playing = true;
-            println!("Playing background music");
+            debug!("Playing background music");
+        }
+    }
+
+    /// Push the ambient music louder and higher as enemies get alerted (0.0 - 1.0),
+    /// easing towards the target so changes swell instead of jumping. During a blackout
+    /// (`blackout` 0.0 - 1.0, already faded) it drops quieter and lower instead
+    pub fn update_music_intensity(&mut self, target: f32, blackout: f32, delta_time: f32) {
+        let rate = if target > self.music_intensity { 2.0 } else { 0.25 }; // Rise fast, settle slowly
+        self.music_intensity += (target - self.music_intensity) * (rate * delta_time).min(1.0);
+        if let Some(ref music) = self.ambient {
+            let volume = self.music_volume * (1.0 + 0.5 * self.music_intensity) * (1.0 - 0.4 * blackout);
+            music.set_volume((volume * self.volume_multiplier).min(1.0));
+            music.set_pitch((1.0 + 0.08 * self.music_intensity) * (1.0 - 0.15 * blackout));
         }
     }
 
     /// Stop background music
     pub fn stop_music(&mut self) {
+        self.music_playing = false;
         if let Some(ref mut music) = self.ambient {
             music.stop_stream();
-            self.music_playing = false;
-            println!("Stopped background music");
+            debug!("Stopped background music");
         }
     }
 
+    /// Set the music and sound effect volumes (0.0 - 1.0, from settings.toml)
+    pub fn set_volumes(&mut self, music: f32, sfx: f32) {
+        self.music_volume = music;
+        self.sfx_volume = sfx;
+        if let Some(ref music) = self.ambient {
+            music.set_volume(self.music_volume * self.volume_multiplier);
+        }
+    }
+
+    /// Pause or resume all playing audio (used while the window is unfocused)
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        if let Some(ref music) = self.ambient {
+            if self.music_playing {
+                if paused {
+                    music.pause_stream();
+                } else {
+                    music.resume_stream();
+                }
+            }
+        }
+
+        for sound in self.sounds.values() {
+            if paused {
+                sound.pause();
+            } else {
+                sound.resume();
+            }
+        }
+        debug!("Audio {}", if paused { "paused" } else { "resumed" });
+    }
+
     /// Update music stream (call every frame during gameplay)
     pub fn update_music(&mut self) {
         if self.music_playing {
@@ -172,64 +263,245 @@ impl<'a> AudioManager<'a> {
         }
     }
 
-    /// Update music volume based on distance to goal
-    pub fn update_ambient_volume(&mut self, distance_to_goal: f32) {
-        if self.music_playing {
-            if let Some(ref mut music) = self.ambient {
-                let intensity = 1.0 - (distance_to_goal / 20.0).min(1.0);
-                let volume = (self.music_volume + intensity * 0.4) * self.volume_multiplier;
-                music.set_volume(volume.min(1.0));
+    /// Keep an output device open (call every frame): open one if the game is running
+    /// without audio, reopen it when the music shows it stopped playing
+    pub fn watch_device(&mut self, delta_time: f32) {
+        match (&self.ambient, self.music_playing && !self.paused) {
+            (Some(music), true) => {
+                let position = music.get_time_played();
+                if position == self.music_position {
+                    self.music_stalled += delta_time;
+                } else {
+                    self.music_stalled = 0.0;
+                    self.music_position = position;
+                }
             }
+            _ => self.music_stalled = 0.0,
+        }
+
+        self.device_timer -= delta_time;
+        if self.device_timer > 0.0 {
+            return;
+        }
+        self.device_timer = DEVICE_CHECK_INTERVAL;
+
+        let lost = match self.device {
+            None => true,
+            Some(audio) => !audio.is_audio_device_ready() || self.music_stalled >= STALL_TIME,
+        };
+        if lost {
+            self.reopen_device();
         }
     }
 
-    /// Play footstep sound (with automatic timing) - only when moving
-    pub fn play_footstep(&mut self, delta_time: f32) {
-        self.footstep_timer += delta_time;
-        if self.footstep_timer >= 0.5 {
-            self.footstep_timer = 0.0;
-            if let Some(ref sound) = self.footstep {
-                sound.play();
-            }
+    /// Drop everything loaded on the old device, open a new one and load it all again
+    fn reopen_device(&mut self) {
+        if self.device.is_some() {
+            warn!("Audio output stopped, reopening the audio device");
+            self.ambient = None;
+            self.sounds.clear();
+            // SAFETY: everything loaded on the old device was dropped just above
+            unsafe { raylib::ffi::CloseAudioDevice() };
+        }
+
+        self.device = open_device();
+        if self.device.is_none() {
+            debug!("Still no audio device");
+            return;
+        }
+        info!("Audio device opened, reloading {} sounds", self.loaded.len());
+        for entry in std::mem::take(&mut self.loaded) {
+            self.load(&entry);
+        }
+        self.music_stalled = 0.0;
+        if self.music_playing {
+            self.play_background_music();
+        }
+        if self.paused {
+            self.set_paused(true);
         }
     }
 
-    /// Reset footstep timer (call when player stops moving)
-    pub fn reset_footstep_timer(&mut self) {
-        self.footstep_timer = 0.0;
+    /// Play footstep sound (call when a foot lands, see gait.rs)
+    pub fn play_footstep(&mut self) {
+        if let Some(sound) = self.sounds.get(self.footstep) {
+            sound.play();
+            self.play_echo(self.step_echo, 1.0, 1.0);
+        }
     }
 
     /// Stop footstep sound (call when player stops moving)
     pub fn stop_footstep(&self) {
-        if let Some(ref sound) = self.footstep {
+        if let Some(sound) = self.sounds.get(self.footstep) {
             sound.stop();
         }
     }
 
     /// Play damage sound with heartbeat
     pub fn play_damage(&self) {
-        if let Some(ref sound) = self.damage {
+        if let Some(sound) = self.sounds.get(self.damage) {
             sound.play();
         }
-        if let Some(ref sound) = self.heartbeat {
+        if let Some(sound) = self.sounds.get(self.heartbeat) {
+            sound.play();
+        }
+        debug!("Playing damage + heartbeat");
+    }
+
+    /// Play the impact layer for a landed attack (heavier hits sound louder and deeper)
+    pub fn play_impact(&mut self, strength: f32) {
+        if let Some(sound) = self.sounds.get(self.impact) {
+            let strength = strength.clamp(0.0, 1.0);
+            sound.set_volume(self.sfx_volume * self.volume_multiplier * (0.5 + 0.5 * strength));
+            sound.set_pitch(1.15 - 0.3 * strength);
+            sound.play();
+            self.play_echo(self.impact_echo, 0.5 + 0.5 * strength, 1.15 - 0.3 * strength);
+        }
+    }
+
+    /// Tick of the level tally counting up: the footstep sample, short and high
+    pub fn play_tick(&self, pitch: f32) {
+        if let Some(sound) = self.sounds.get(self.footstep) {
+            sound.set_volume(self.sfx_volume * self.volume_multiplier * 0.4);
+            sound.set_pitch(2.0 * pitch);
+            sound.play();
+        }
+    }
+
+    /// Play the rattle of a locked door (low and dull)
+    pub fn play_locked(&self) {
+        if let Some(sound) = self.sounds.get(self.locked) {
+            sound.set_volume(self.sfx_volume * self.volume_multiplier * 0.8);
+            sound.set_pitch(0.6);
+            sound.play();
+        }
+    }
+
+    /// Set how much the room the player is in echoes (0.0 - 1.0, see reverb.rs)
+    pub fn set_reverb(&mut self, wet: f32) {
+        self.reverb = wet;
+    }
+
+    /// Queue the echoes of a sound just played at (volume, pitch) for the current room
+    fn play_echo(&mut self, sound: SoundId, volume: f32, pitch: f32) {
+        let Some(taps) = reverb::taps(self.reverb) else {
+            return;
+        };
+        for (delay, tap_volume) in taps {
+            self.echoes.push(Echo { sound, delay, volume: volume * tap_volume, pitch });
+        }
+    }
+
+    /// Play the echoes that are due (call every frame)
+    pub fn update_echoes(&mut self, delta_time: f32) {
+        if self.paused {
+            return;
+        }
+        for echo in &mut self.echoes {
+            echo.delay -= delta_time;
+            if echo.delay > 0.0 {
+                continue;
+            }
+            if let Some(sound) = self.sounds.get(echo.sound) {
+                sound.set_volume(self.sfx_volume * self.volume_multiplier * echo.volume.clamp(0.0, 1.0));
+                sound.set_pitch(echo.pitch);
+                sound.play();
+            }
+        }
+        self.echoes.retain(|echo| echo.delay > 0.0);
+    }
+
+    /// Keep the placed radio looping at (volume, pan), or silence it with None.
+    /// Pan is 0.0 - 1.0 with 0.5 centered (raylib puts 1.0 fully on the left)
+    pub fn update_radio(&self, playback: Option<(f32, f32, f32)>) {
+        let Some(sound) = self.sounds.get(self.radio) else {
+            return;
+        };
+        match playback {
+            Some((volume, pan, pitch)) => {
+                sound.set_volume(self.sfx_volume * self.volume_multiplier * volume.clamp(0.0, 1.0));
+                sound.set_pan(pan.clamp(0.0, 1.0));
+                sound.set_pitch(pitch);
+                if !sound.is_playing() {
+                    sound.play();
+                }
+            }
+            None => {
+                if sound.is_playing() {
+                    sound.stop();
+                }
+            }
+        }
+    }
+
+    /// Keep the exit hum looping at (volume, pitch), or silence it with None
+    pub fn update_hum(&self, playback: Option<(f32, f32)>) {
+        let Some(sound) = self.sounds.get(self.hum) else {
+            return;
+        };
+        match playback {
+            Some((volume, pitch)) => {
+                sound.set_volume(self.sfx_volume * self.volume_multiplier * volume.clamp(0.0, 1.0));
+                sound.set_pitch(pitch);
+                if !sound.is_playing() {
+                    sound.play();
+                }
+            }
+            None => {
+                if sound.is_playing() {
+                    sound.stop();
+                }
+            }
+        }
+    }
+
+    /// Play an enemy footstep at (volume, pan, pitch), see footsteps.rs
+    pub fn play_enemy_step(&mut self, volume: f32, pan: f32, pitch: f32) {
+        if let Some(sound) = self.sounds.get(self.enemy_step) {
+            sound.set_volume(self.sfx_volume * self.volume_multiplier * volume.clamp(0.0, 1.0));
+            sound.set_pan(pan.clamp(0.0, 1.0));
+            sound.set_pitch(pitch);
             sound.play();
+            self.play_echo(self.step_echo, volume, pitch);
+        }
+    }
+
+    /// Follow the whispers at (volume, pan, pitch), starting a phrase when asked, or silence
+    /// them with None. Volume and pan also follow a phrase that's already playing
+    pub fn update_whisper(&self, playback: Option<(f32, f32, f32, bool)>) {
+        let Some(sound) = self.sounds.get(self.whisper) else {
+            return;
+        };
+        match playback {
+            Some((volume, pan, pitch, start)) => {
+                sound.set_volume(self.sfx_volume * self.volume_multiplier * volume.clamp(0.0, 1.0));
+                sound.set_pan(pan.clamp(0.0, 1.0));
+                sound.set_pitch(pitch);
+                if start {
+                    sound.play();
+                }
+            }
+            None => {
+                if sound.is_playing() {
+                    sound.stop();
+                }
+            }
         }
-        println!("Playing damage + heartbeat");
     }
 
     /// Play victory sound effect
     pub fn play_victory(&self) {
-        if let Some(ref sound) = self.victory {
+        if let Some(sound) = self.sounds.get(self.victory) {
             sound.play();
-            println!("Playing victory sound");
+            debug!("Playing victory sound");
         }
     }
 
     /// Play heartbeat sound (for idle penalty/anxiety)
     pub fn play_heartbeat(&self) {
-        if let Some(ref sound) = self.heartbeat {
+        if let Some(sound) = self.sounds.get(self.heartbeat) {
             sound.play();
-            println!("Playing heartbeat (idle penalty)");
+            debug!("Playing heartbeat (idle penalty)");
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,102 @@
+// First-run settings detection for Backrooms Doom
+// A new profile has no settings.toml yet. Rather than starting every machine on
+// the medium preset, the first launch looks at the monitor and times the software
+// renderer for about two seconds, then saves what it picked to the profile so it
+// only happens once:
+//   - display: borderless on monitors at least twice the base resolution both ways
+//     (a 640x480 window is a postage stamp there), windowed otherwise
+//   - quality: the highest preset (ray count and render scale) whose frames render
+//     within the budget at the framebuffer size that display gives, so a low-end
+//     laptop starts on low instead of at a slideshow
+// The full benchmark (B on the menu) measures presented frames over a longer run
+// and can be used afterwards for a finer recommendation.
+
+use crate::benchmark::CameraPath;
+use crate::config::{DisplayMode, QualityPreset};
+use crate::maze::Maze;
+
+const PRESET_TIME: f32 = 0.5;           // Seconds spent on each preset (2s for all four)
+const MIN_FRAMES: usize = 5;            // Frames measured per preset however slow they are
+const RENDER_BUDGET: f32 = 1.0 / 120.0; // Seconds per frame, half of a 60 FPS frame
+const PATH_SPEED: f32 = 2.0;            // Tiles per second along the camera path
+
+/// Display mode for a monitor of `monitor` pixels, given the renderer's base resolution
+pub fn display_mode_for(monitor: (i32, i32), base: (usize, usize)) -> DisplayMode {
+    if monitor.0 >= base.0 as i32 * 2 && monitor.1 >= base.1 as i32 * 2 {
+        DisplayMode::Borderless
+    } else {
+        DisplayMode::Windowed
+    }
+}
+
+pub struct Autodetect {
+    path: CameraPath,
+    preset_index: usize,
+    elapsed: f32,           // Seconds on the current preset
+    travelled: f32,         // Tiles along the camera path, carried across presets
+    render_times: Vec<f32>, // Seconds per frame on the current preset
+    pub passed: Option<QualityPreset>, // Highest preset within the budget so far
+}
+
+impl Autodetect {
+    pub fn new(maze: &Maze) -> Self {
+        Self {
+            path: CameraPath::new(maze),
+            preset_index: 0,
+            elapsed: 0.0,
+            travelled: 0.0,
+            render_times: Vec::new(),
+            passed: None,
+        }
+    }
+
+    /// Preset being timed, None once done
+    pub fn current_preset(&self) -> Option<QualityPreset> {
+        QualityPreset::ALL.get(self.preset_index).copied()
+    }
+
+    /// Camera position and angle for the next frame
+    pub fn camera(&self) -> (f32, f32, f32) {
+        self.path.at(self.travelled).unwrap_or_else(|| {
+            let (x, y) = self.path.start();
+            (x, y, self.travelled)
+        })
+    }
+
+    /// Record one rendered frame: how long it took to render and how long the whole
+    /// frame took. Higher presets are skipped as soon as one misses the budget
+    pub fn record(&mut self, render_time: f32, delta_time: f32) {
+        let Some(preset) = self.current_preset() else {
+            return;
+        };
+        self.render_times.push(render_time);
+        self.elapsed += delta_time;
+        self.travelled += delta_time * PATH_SPEED;
+        if self.elapsed < PRESET_TIME || self.render_times.len() < MIN_FRAMES {
+            return;
+        }
+
+        // Median, so a hitch while the window settles doesn't decide it
+        self.render_times.sort_by(f32::total_cmp);
+        let median = self.render_times[self.render_times.len() / 2];
+        if median <= RENDER_BUDGET {
+            self.passed = Some(preset);
+            self.preset_index += 1;
+        } else {
+            self.preset_index = QualityPreset::ALL.len();
+        }
+        self.render_times.clear();
+        self.elapsed = 0.0;
+    }
+
+    /// Progress from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        let done = self.preset_index as f32 + (self.elapsed / PRESET_TIME).min(1.0);
+        (done / QualityPreset::ALL.len() as f32).min(1.0)
+    }
+
+    /// The preset to start on (low if even that missed the budget)
+    pub fn recommended(&self) -> QualityPreset {
+        self.passed.unwrap_or(QualityPreset::Low)
+    }
+}
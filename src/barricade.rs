@@ -0,0 +1,87 @@
+// Door barricading for Backrooms Doom
+// An enemy that walks into a closed door starts shoving it open. The player can
+// brace the door (hold or mash E) at the cost of stamina; the pressure meter
+// decides whether the enemy breaks through or gives up
+
+const PUSH_RATE: f32 = 0.22;      // Pressure per second the enemy adds
+const SHOVE_INTERVAL: f32 = 0.6;  // Seconds between visible/audible shoves
+const SHOVE_PRESSURE: f32 = 0.06; // Extra pressure on each shove
+const HOLD_RATE: f32 = 0.3;       // Pressure per second removed while holding E
+const MASH_PUSH: f32 = 0.05;      // Pressure removed by each fresh press of E
+const ENEMY_PATIENCE: f32 = 7.0;  // Seconds of pushing before the enemy gives up
+const SHAKE_TIME: f32 = 0.25;     // Seconds the door rattles after a shove
+
+// Stamina cost of bracing
+pub const HOLD_STAMINA_DRAIN: f32 = 0.22; // Per second while holding
+pub const MASH_STAMINA_COST: f32 = 0.04;  // Per press
+
+#[derive(PartialEq)]
+pub enum Outcome {
+    Ongoing,
+    BrokeThrough, // Door bursts open
+    GaveUp,       // Enemy stops pushing
+}
+
+pub struct Barricade {
+    pub door: (usize, usize),
+    pub pressure: f32,  // 0.0 - 1.0, the door gives way at 1.0
+    pub timer: f32,     // Time spent pushing
+    pub shove_timer: f32,
+    pub shake_timer: f32,
+}
+
+impl Barricade {
+    pub fn new(door: (usize, usize)) -> Self {
+        Self {
+            door,
+            pressure: 0.0,
+            timer: 0.0,
+            shove_timer: SHOVE_INTERVAL,
+            shake_timer: 0.0,
+        }
+    }
+
+    /// Advance the struggle. `holding` is true while the player braces the door this frame.
+    /// Returns whether a shove happened and the current outcome
+    pub fn update(&mut self, holding: bool, delta_time: f32) -> (bool, Outcome) {
+        self.timer += delta_time;
+        self.shake_timer = (self.shake_timer - delta_time).max(0.0);
+        self.pressure += PUSH_RATE * delta_time;
+        if holding {
+            self.pressure -= HOLD_RATE * delta_time;
+        }
+
+        let mut shoved = false;
+        self.shove_timer -= delta_time;
+        if self.shove_timer <= 0.0 {
+            self.shove_timer = SHOVE_INTERVAL;
+            self.pressure += SHOVE_PRESSURE;
+            self.shake_timer = SHAKE_TIME;
+            shoved = true;
+        }
+        self.pressure = self.pressure.clamp(0.0, 1.0);
+
+        let outcome = if self.pressure >= 1.0 {
+            Outcome::BrokeThrough
+        } else if self.timer >= ENEMY_PATIENCE {
+            Outcome::GaveUp
+        } else {
+            Outcome::Ongoing
+        };
+        (shoved, outcome)
+    }
+
+    /// A fresh press of E pushes back a little
+    pub fn mash(&mut self) {
+        self.pressure = (self.pressure - MASH_PUSH).max(0.0);
+    }
+
+    /// Texture offset (fraction of the door width) for the rattle after a shove
+    pub fn shake(&self) -> f32 {
+        if self.shake_timer <= 0.0 {
+            return 0.0;
+        }
+        let strength = self.shake_timer / SHAKE_TIME;
+        (self.shake_timer * 90.0).sin() * 0.04 * strength
+    }
+}
@@ -0,0 +1,227 @@
+// Quality benchmark for Backrooms Doom
+// Flies the camera along a fixed path through the level at every quality
+// preset, measures average and 1% low FPS and recommends a preset
+
+use crate::config::{Config, QualityPreset};
+use crate::maze::Maze;
+use std::collections::VecDeque;
+
+const PRESET_DURATION: f32 = 6.0; // Seconds measured per preset
+const WARMUP: f32 = 0.5;          // Seconds ignored after switching preset
+const PATH_SPEED: f32 = 2.0;      // Tiles per second along the path
+
+// A preset is recommended if it holds these frame rates
+const RECOMMEND_AVG_FPS: f32 = 60.0;
+const RECOMMEND_LOW_FPS: f32 = 45.0;
+
+pub struct BenchmarkResult {
+    pub preset: QualityPreset,
+    pub avg_fps: f32,
+    pub low_fps: f32, // Average FPS of the slowest 1% of frames
+}
+
+/// Walkable route from the start to the exit for scripted cameras (the benchmark and the
+/// menu flythrough, see flythrough.rs)
+pub struct CameraPath {
+    points: Vec<(f32, f32)>,
+    length: f32,
+}
+
+impl CameraPath {
+    pub fn new(maze: &Maze) -> Self {
+        let points = find_path(maze);
+        let length = points
+            .windows(2)
+            .map(|w| ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt())
+            .sum();
+        Self { points, length }
+    }
+
+    /// First point of the path (the start)
+    pub fn start(&self) -> (f32, f32) {
+        self.points.first().copied().unwrap_or((1.5, 1.5))
+    }
+
+    /// Position and heading `travelled` tiles along the path, walking it back and forth.
+    /// None when there is no path to the exit
+    pub fn at(&self, travelled: f32) -> Option<(f32, f32, f32)> {
+        if self.points.len() < 2 || self.length <= 0.0 {
+            return None;
+        }
+
+        let travelled = travelled % (self.length * 2.0);
+        let (mut distance, reverse) = if travelled > self.length {
+            (self.length * 2.0 - travelled, true)
+        } else {
+            (travelled, false)
+        };
+
+        for segment in self.points.windows(2) {
+            let (from, to) = (segment[0], segment[1]);
+            let dx = to.0 - from.0;
+            let dy = to.1 - from.1;
+            let length = (dx * dx + dy * dy).sqrt();
+
+            if distance <= length {
+                let t = distance / length;
+                let angle = if reverse { (-dy).atan2(-dx) } else { dy.atan2(dx) };
+                return Some((from.0 + dx * t, from.1 + dy * t, angle));
+            }
+            distance -= length;
+        }
+
+        let (x, y) = *self.points.last()?;
+        Some((x, y, 0.0))
+    }
+}
+
+pub struct Benchmark {
+    path: CameraPath,
+    preset_index: usize,
+    elapsed: f32,
+    frame_times: Vec<f32>,
+    pub results: Vec<BenchmarkResult>,
+    pub previous_config: Config, // Settings to restore when the benchmark ends
+}
+
+impl Benchmark {
+    pub fn new(maze: &Maze, previous_config: Config) -> Self {
+        Self {
+            path: CameraPath::new(maze),
+            preset_index: 0,
+            elapsed: 0.0,
+            frame_times: Vec::new(),
+            results: Vec::new(),
+            previous_config,
+        }
+    }
+
+    /// Preset being measured, None once every preset is done
+    pub fn current_preset(&self) -> Option<QualityPreset> {
+        QualityPreset::ALL.get(self.preset_index).copied()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_preset().is_none()
+    }
+
+    /// Record one frame. Returns true when the benchmark moved on to the next preset
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        let Some(preset) = self.current_preset() else {
+            return false;
+        };
+
+        self.elapsed += delta_time;
+        if self.elapsed > WARMUP && delta_time > 0.0 {
+            self.frame_times.push(delta_time);
+        }
+
+        if self.elapsed < PRESET_DURATION + WARMUP {
+            return false;
+        }
+
+        self.results.push(summarize(preset, &self.frame_times));
+        self.frame_times.clear();
+        self.elapsed = 0.0;
+        self.preset_index += 1;
+        true
+    }
+
+    /// Camera position and angle along the path at the current time.
+    /// The path is walked back and forth so every preset sees the same frames
+    pub fn camera(&self) -> (f32, f32, f32) {
+        self.path.at(self.elapsed * PATH_SPEED).unwrap_or_else(|| {
+            // No path to the exit, spin in place instead
+            let (x, y) = self.path.start();
+            (x, y, self.elapsed * 0.8)
+        })
+    }
+
+    /// Progress through the whole benchmark from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        let per_preset = PRESET_DURATION + WARMUP;
+        let total = per_preset * QualityPreset::ALL.len() as f32;
+        ((self.preset_index as f32 * per_preset + self.elapsed) / total).min(1.0)
+    }
+
+    /// Highest preset that holds the target frame rates (Low if none does)
+    pub fn recommended(&self) -> QualityPreset {
+        self.results
+            .iter()
+            .filter(|r| r.avg_fps >= RECOMMEND_AVG_FPS && r.low_fps >= RECOMMEND_LOW_FPS)
+            .map(|r| r.preset)
+            .next_back()
+            .unwrap_or(QualityPreset::Low)
+    }
+}
+
+/// Average and 1% low FPS from a list of frame times
+fn summarize(preset: QualityPreset, frame_times: &[f32]) -> BenchmarkResult {
+    if frame_times.is_empty() {
+        return BenchmarkResult { preset, avg_fps: 0.0, low_fps: 0.0 };
+    }
+
+    let total: f32 = frame_times.iter().sum();
+    let avg_fps = frame_times.len() as f32 / total;
+
+    // Slowest 1% of frames (at least one)
+    let mut sorted = frame_times.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    let count = (sorted.len() / 100).max(1);
+    let slow_total: f32 = sorted[..count].iter().sum();
+    let low_fps = count as f32 / slow_total;
+
+    BenchmarkResult { preset, avg_fps, low_fps }
+}
+
+/// Shortest walkable path (tile centers) from the start to a tile next to the exit
+fn find_path(maze: &Maze) -> Vec<(f32, f32)> {
+    let tile = maze.tile_size;
+    let start = ((maze.start_pos.0 / tile) as usize, (maze.start_pos.1 / tile) as usize);
+    let goal = ((maze.goal_pos.0 / tile) as usize, (maze.goal_pos.1 / tile) as usize);
+    let center = |(x, y): (usize, usize)| ((x as f32 + 0.5) * tile, (y as f32 + 0.5) * tile);
+
+    let mut came_from: Vec<Option<(usize, usize)>> = vec![None; maze.width * maze.height];
+    let mut visited = vec![false; maze.width * maze.height];
+    let mut queue = VecDeque::new();
+
+    if start.0 >= maze.width || start.1 >= maze.height {
+        return vec![maze.start_pos];
+    }
+    visited[start.1 * maze.width + start.0] = true;
+    queue.push_back(start);
+
+    while let Some(cell) = queue.pop_front() {
+        if cell.0.abs_diff(goal.0) + cell.1.abs_diff(goal.1) == 1 {
+            // Walk back to the start
+            let mut path = vec![center(cell)];
+            let mut current = cell;
+            while let Some(previous) = came_from[current.1 * maze.width + current.0] {
+                path.push(center(previous));
+                current = previous;
+            }
+            path.reverse();
+            return path;
+        }
+
+        let neighbors = [
+            (cell.0.wrapping_sub(1), cell.1),
+            (cell.0 + 1, cell.1),
+            (cell.0, cell.1.wrapping_sub(1)),
+            (cell.0, cell.1 + 1),
+        ];
+        for (x, y) in neighbors {
+            if x >= maze.width || y >= maze.height || maze.is_wall(x, y) {
+                continue;
+            }
+            let index = y * maze.width + x;
+            if !visited[index] {
+                visited[index] = true;
+                came_from[index] = Some(cell);
+                queue.push_back((x, y));
+            }
+        }
+    }
+
+    vec![maze.start_pos]
+}
@@ -0,0 +1,153 @@
+// Blackouts for Backrooms Doom
+// A level event that cuts the power for a while. Blackouts are scheduled in the
+// maze file, one line each after the grid:
+//
+//   blackout 60 20
+//
+// = 60 seconds into the run, for 20 seconds (each fires once per run); the
+// console's `blackout [seconds]` starts one right away. While the power is out
+// the lights flicker and die, the level drops to a dim ambient light, and only
+// the emergency lights stay on, as red beacons sweeping round. Lights named
+// `emergency` in the maze are kept, plus every third other light switched to
+// emergency red, so any lit level gets a few. The ambient music drops low and
+// slow, and enemies move faster and roam around instead of standing idle.
+
+use crate::light::Light;
+use crate::maze::Maze;
+use crate::pathfinding;
+use raylib::prelude::Color;
+use rand::Rng;
+
+pub const DEFAULT_DURATION: f32 = 20.0; // Seconds, for the console command
+const FADE_TIME: f32 = 1.5;             // Seconds the lights take to go out and come back
+const AMBIENT: f32 = 0.3;               // Scene brightness with the power out
+const BEACON_SPEED: f32 = 3.0;          // Radians per second of the beacons' sweep
+const BEACON_SPACING: usize = 3;        // Every n-th ordinary light becomes a beacon
+const ENEMY_SPEED: f32 = 1.4;           // Enemy walking speed multiplier
+const ROAM_STEPS: usize = 6;            // How far idle enemies wander, in steps
+
+/// A blackout scheduled by the level
+#[derive(Clone, Copy)]
+pub struct BlackoutEvent {
+    pub start: f32, // Seconds into the run
+    pub duration: f32,
+}
+
+impl BlackoutEvent {
+    /// Parse the rest of a "blackout <start> <duration>" line (without the keyword)
+    pub fn parse(definition: &str) -> Result<Self, String> {
+        let numbers: Vec<&str> = definition.split_whitespace().collect();
+        let invalid = || format!("bad blackout '{}', expected <start seconds> <duration seconds>", definition.trim());
+        let [start, duration] = numbers[..] else {
+            return Err(invalid());
+        };
+        let start = start.parse::<f32>().map_err(|_| invalid())?;
+        let duration = duration.parse::<f32>().map_err(|_| invalid())?;
+        if !start.is_finite() || start < 0.0 || !duration.is_finite() || duration <= 0.0 {
+            return Err(invalid());
+        }
+        Ok(Self { start, duration })
+    }
+}
+
+pub struct Blackout {
+    scheduled: Vec<BlackoutEvent>, // Still to come this run
+    time_left: f32,                // Of the current blackout
+    elapsed: f32,
+}
+
+impl Blackout {
+    pub fn new(events: &[BlackoutEvent]) -> Self {
+        Self { scheduled: events.to_vec(), time_left: 0.0, elapsed: 0.0 }
+    }
+
+    /// Cut the power now for `duration` seconds
+    pub fn trigger(&mut self, duration: f32) {
+        if !self.is_active() {
+            self.elapsed = 0.0;
+        }
+        self.time_left = duration;
+    }
+
+    /// Run scheduled blackouts and count down the current one. Returns true when one starts
+    pub fn update(&mut self, run_time: f32, delta_time: f32) -> bool {
+        let was_active = self.is_active();
+        self.time_left = (self.time_left - delta_time).max(0.0);
+        self.elapsed += delta_time;
+        if let Some(index) = self.scheduled.iter().position(|event| run_time >= event.start) {
+            let event = self.scheduled.remove(index);
+            self.trigger(event.duration);
+        }
+        !was_active && self.is_active()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.time_left > 0.0
+    }
+
+    pub fn time_left(&self) -> f32 {
+        self.time_left
+    }
+
+    /// 0.0 with the power on, 1.0 with it out, fading in between
+    pub fn level(&self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+        (self.elapsed / FADE_TIME).min(self.time_left / FADE_TIME).min(1.0)
+    }
+
+    /// Ambient brightness of the scene (1.0 = normal), see light::tint_at
+    pub fn ambient(&self) -> f32 {
+        1.0 - (1.0 - AMBIENT) * self.level()
+    }
+
+    /// The level's lights as they shine right now: all of them normally, only the emergency
+    /// beacons during a blackout
+    pub fn scene_lights(&self, lights: &[Light]) -> Vec<Light> {
+        let level = self.level();
+        if level <= 0.0 {
+            return lights.to_vec();
+        }
+        let emergency_red = Color::new(255, 40, 30, 255);
+        let mut ordinary = 0;
+        lights
+            .iter()
+            .enumerate()
+            .map(|(index, light)| {
+                let beacon = light.emergency || {
+                    ordinary += 1;
+                    ordinary % BEACON_SPACING == 1
+                };
+                if !beacon {
+                    return Light { intensity: light.intensity * (1.0 - level), ..*light };
+                }
+                // A rotating beacon: bright while it faces this way, dim while it faces away
+                let sweep = 0.5 + 0.5 * (self.elapsed * BEACON_SPEED + index as f32 * 1.7).sin();
+                let color = if light.emergency { light.color } else { emergency_red };
+                Light { color, intensity: 1.0 - level + level * (0.2 + 0.8 * sweep * sweep), ..*light }
+            })
+            .collect()
+    }
+
+    /// Enemy walking speed multiplier
+    pub fn enemy_speed(&self) -> f32 {
+        1.0 + (ENEMY_SPEED - 1.0) * self.level()
+    }
+}
+
+/// Random reachable spot a few steps from (x, y) for an idle enemy to wander to
+pub fn roam_point(maze: &Maze, (x, y): (f32, f32), rng: &mut impl Rng) -> (f32, f32) {
+    let spots: Vec<usize> = pathfinding::distances(maze, (x, y))
+        .into_iter()
+        .enumerate()
+        .filter(|(_, steps)| steps.is_some_and(|steps| (2..=ROAM_STEPS).contains(&steps)))
+        .map(|(index, _)| index)
+        .collect();
+    if spots.is_empty() {
+        return (x, y);
+    }
+    let index = spots[rng.gen_range(0..spots.len())];
+    let tile = maze.tile_size;
+    (((index % maze.width) as f32 + 0.5) * tile, ((index / maze.width) as f32 + 0.5) * tile)
+}
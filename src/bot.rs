@@ -0,0 +1,302 @@
+// Bot player for Backrooms Doom
+// A bot that plays a level on its own: it picks up every key, grabs a blue pill when
+// it is hurt and can spare the time, steers around red pills, then heads for the
+// exit. Routes are A* over the maze (see pathfinding.rs) with extra cost on tiles
+// near living enemies, so it detours around them when the detour is short and
+// replans twice a second as they move. It can't open doors, so anything behind a
+// closed one is left alone.
+//
+// It drives the local player when switched on in the console (`bot on`), walks the
+// attract mode demo (see attract.rs), and plays whole runs headless for soak tests
+// and difficulty calibration:
+//
+//   cargo run --release -- --bot-soak [runs] [--seed N]
+
+use crate::enemy::{self, Enemy};
+use crate::events::EventQueue;
+use crate::health::{Health, Source};
+use crate::keys;
+use crate::maze::Maze;
+use crate::pathfinding;
+use crate::pill::{self, PillTable, PillType};
+use crate::player::Player;
+use crate::status::{Effect, StatusEffects};
+use log::{error, info};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const SPEED_FACTOR: f32 = 1.0;      // Full walking speed (the demo slows it down)
+const WAYPOINT_REACHED: f32 = 0.15;
+const REPLAN_TIME: f32 = 0.5;       // Seconds between route updates
+const DANGER_RADIUS: f32 = 3.0;     // Tiles around an enemy the bot would rather not cross
+const DANGER_COST: f32 = 12.0;      // Extra steps a tile right next to an enemy is worth
+const RED_PILL_COST: u32 = 20;      // Extra steps to walk over a red pill
+const BLUE_PILL_HEALTH: f32 = 0.6;  // Health fraction under which blue pills are worth it
+const BLUE_PILL_TIME: f32 = 60.0;   // Seconds that have to be left to spend 20 on a blue pill
+
+/// What the bot can see of the level this frame
+pub struct World<'a> {
+    pub maze: &'a Maze,
+    pub keys: Vec<(f32, f32)>,              // Keys still to pick up
+    pub pills: Vec<(f32, f32, PillType)>,   // Pills still lying around
+    pub enemies: Vec<(f32, f32)>,           // Living enemies
+    pub health: f32,                        // Fraction of max health
+    pub time_left: f32,                     // Seconds on the run timer
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Goal {
+    Key(f32, f32),
+    Pill(f32, f32),
+    Exit,
+}
+
+pub struct Bot {
+    pub goal: Option<Goal>,
+    route: Vec<(f32, f32)>,
+    next: usize,        // Waypoint being walked to
+    replan_timer: f32,
+    speed_factor: f32,
+}
+
+impl Bot {
+    pub fn new() -> Self {
+        Self { goal: None, route: Vec::new(), next: 0, replan_timer: 0.0, speed_factor: SPEED_FACTOR }
+    }
+
+    /// A bot walking at a fraction of the normal speed
+    pub fn with_speed(speed_factor: f32) -> Self {
+        Self { speed_factor, ..Self::new() }
+    }
+
+    /// Plan again on the next update (after the player was moved by something else)
+    pub fn replan(&mut self) {
+        self.replan_timer = 0.0;
+    }
+
+    /// Walk the player towards the current goal, choosing a new one and a new route
+    /// every so often. Returns true once it stands at the exit
+    pub fn update(&mut self, world: &World, player: &mut Player, delta_time: f32) -> bool {
+        let position = (player.pos.x, player.pos.y);
+        self.replan_timer -= delta_time;
+        if self.replan_timer <= 0.0 || self.next >= self.route.len() {
+            self.replan_timer = REPLAN_TIME;
+            self.plan(world, position);
+        }
+
+        if let Some(&(x, y)) = self.route.get(self.next) {
+            if player.walk_towards(world.maze, x, y, self.speed_factor, delta_time) <= WAYPOINT_REACHED {
+                self.next += 1;
+            }
+        }
+        world.keys.is_empty() && world.maze.is_goal(player.pos.x, player.pos.y, 1.0)
+    }
+
+    /// Pick the nearest goal worth going for and route to it around enemies
+    fn plan(&mut self, world: &World, from: (f32, f32)) {
+        let maze = world.maze;
+        let steps = pathfinding::distances(maze, from);
+        let steps_to = |(x, y): (f32, f32)| {
+            let (cx, cy) = pathfinding::cell(maze, (x, y));
+            (cx < maze.width && cy < maze.height).then(|| steps[cy * maze.width + cx]).flatten()
+        };
+
+        // Keys first (the exit stays locked without them), a blue pill when hurt, then the exit
+        let wants_pill = world.health < BLUE_PILL_HEALTH && world.time_left > BLUE_PILL_TIME;
+        let pills = world.pills.iter().filter(|(_, _, kind)| wants_pill && *kind == PillType::Blue);
+        let goal = world
+            .keys
+            .iter()
+            .map(|&(x, y)| Goal::Key(x, y))
+            .chain(pills.map(|&(x, y, _)| Goal::Pill(x, y)))
+            .filter_map(|goal| Some((goal, steps_to(goal_position(maze, goal, from)?)?)))
+            .min_by_key(|&(goal, steps)| (matches!(goal, Goal::Pill(..)), steps))
+            .map(|(goal, _)| goal)
+            .unwrap_or(Goal::Exit);
+
+        let Some(target) = goal_position(maze, goal, from) else {
+            self.goal = None;
+            self.route.clear();
+            return;
+        };
+        let danger = danger_map(world);
+        self.route = pathfinding::find_path_weighted(maze, from, target, |index| danger[index]).unwrap_or_default();
+        if goal == Goal::Exit {
+            // The exit is a wall: walk up against it from the tile in front
+            self.route.push(maze.goal_pos);
+        }
+        self.goal = Some(goal);
+        self.next = 0;
+    }
+}
+
+/// Where to walk for a goal (for the exit, the tile in front of it nearest to `from`)
+fn goal_position(maze: &Maze, goal: Goal, from: (f32, f32)) -> Option<(f32, f32)> {
+    match goal {
+        Goal::Key(x, y) | Goal::Pill(x, y) => Some((x, y)),
+        Goal::Exit => {
+            let (gx, gy) = pathfinding::cell(maze, maze.goal_pos);
+            let tile = maze.tile_size;
+            [(gx.wrapping_sub(1), gy), (gx + 1, gy), (gx, gy.wrapping_sub(1)), (gx, gy + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x < maze.width && y < maze.height && !maze.is_wall(x, y))
+                .map(|(x, y)| ((x as f32 + 0.5) * tile, (y as f32 + 0.5) * tile))
+                .min_by(|a, b| {
+                    let distance = |(x, y): (f32, f32)| (x - from.0).powi(2) + (y - from.1).powi(2);
+                    distance(*a).total_cmp(&distance(*b))
+                })
+        }
+    }
+}
+
+/// Extra cost per tile: high next to enemies, fading out over DANGER_RADIUS, and red pills
+fn danger_map(world: &World) -> Vec<u32> {
+    let maze = world.maze;
+    let mut cost = vec![0; maze.width * maze.height];
+    for y in 0..maze.height {
+        for x in 0..maze.width {
+            let center = ((x as f32 + 0.5) * maze.tile_size, (y as f32 + 0.5) * maze.tile_size);
+            let danger: f32 = world
+                .enemies
+                .iter()
+                .map(|&(ex, ey)| {
+                    let distance = ((ex - center.0).powi(2) + (ey - center.1).powi(2)).sqrt() / maze.tile_size;
+                    (1.0 - distance / DANGER_RADIUS).max(0.0) * DANGER_COST
+                })
+                .sum();
+            cost[y * maze.width + x] = danger.round() as u32;
+        }
+    }
+    for &(x, y, kind) in &world.pills {
+        let (cx, cy) = pathfinding::cell(maze, (x, y));
+        if kind == PillType::Red && cx < maze.width && cy < maze.height {
+            cost[cy * maze.width + cx] += RED_PILL_COST;
+        }
+    }
+    cost
+}
+
+// Headless soak test: whole runs of the bot against wandering enemies
+
+const SOAK_MAZE_PATH: &str = "maze.txt";
+const DEFAULT_RUNS: usize = 20;
+const SOAK_FRAME_TIME: f32 = 1.0 / 60.0;
+const SOAK_RUN_TIME: f32 = 180.0;   // The run timer, as in the game
+const SOAK_NOISE_CHANCE: f64 = 0.02;
+
+/// How one soak run ended
+struct SoakRun {
+    escaped: bool,
+    time_left: f32, // On the run timer
+    health: i32,    // Left at the end
+}
+
+/// Parse "--bot-soak [runs] [--seed N]" and play the runs. Returns None when the flag wasn't
+/// given, otherwise whether the bot escaped at least once (a level it never beats is broken
+/// or far too hard)
+pub fn run_from_args(args: &[String]) -> Option<bool> {
+    let index = args.iter().position(|arg| arg == "--bot-soak")?;
+    let runs = args.get(index + 1).and_then(|value| value.parse().ok()).unwrap_or(DEFAULT_RUNS);
+    let seed: u64 = args
+        .iter()
+        .position(|arg| arg == "--seed")
+        .and_then(|index| args.get(index + 1))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(rand::random);
+
+    let maze = match Maze::load_from_file(SOAK_MAZE_PATH, 1.0) {
+        Ok(maze) => maze,
+        Err(e) => {
+            error!("Bot soak could not start: {}", e);
+            return Some(false);
+        }
+    };
+    let pill_table = PillTable::load_or_builtin(pill::PILLS_PATH);
+    info!("Bot soak (seed {}): {} runs of {}", seed, runs, SOAK_MAZE_PATH);
+
+    let results: Vec<SoakRun> = (0..runs).map(|run| soak_run(&maze, &pill_table, seed.wrapping_add(run as u64))).collect();
+    let escaped: Vec<&SoakRun> = results.iter().filter(|run| run.escaped).collect();
+    let average = |values: Vec<f32>| if values.is_empty() { 0.0 } else { values.iter().sum::<f32>() / values.len() as f32 };
+    info!(
+        "Bot soak: {}/{} escaped, {:.0}s and {:.0} HP left on average when escaping",
+        escaped.len(),
+        results.len(),
+        average(escaped.iter().map(|run| run.time_left).collect()),
+        average(escaped.iter().map(|run| run.health as f32).collect()),
+    );
+    let caught = results.iter().filter(|run| !run.escaped && run.health <= 0).count();
+    let timed_out = results.len() - escaped.len() - caught;
+    if caught > 0 || timed_out > 0 {
+        info!("Bot soak: {} caught by enemies, {} out of time", caught, timed_out);
+    }
+    Some(!escaped.is_empty() || results.is_empty())
+}
+
+/// One run from the start: the bot plays, enemies wander towards its noise and strike on contact.
+/// Pills are placed and dosed from pills.toml; of their effects only poison and invulnerability
+/// matter here. Health changes go through Health like in the game, so hits get the same
+/// invulnerability window
+fn soak_run(maze: &Maze, pill_table: &PillTable, seed: u64) -> SoakRun {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (x, y) = maze.start_pos;
+    let mut player = Player::new(x, y);
+    let mut enemies: Vec<Enemy> = maze.enemy_positions.iter().map(|&(x, y)| Enemy::new(x, y)).collect();
+    let mut keys_left = maze.key_positions.clone();
+    let mut pills: Vec<(f32, f32, PillType)> = maze
+        .pill_positions
+        .iter()
+        .filter_map(|&(x, y)| pill_table.roll(&mut rng).map(|pill_type| (x, y, pill_type)))
+        .collect();
+    let mut status = StatusEffects::new();
+    let mut health = Health::new();
+    let mut events = EventQueue::new(); // Nothing reacts to them here
+    let mut bot = Bot::new();
+    let mut time_left = SOAK_RUN_TIME;
+
+    while time_left > 0.0 && player.is_alive() {
+        let (px, py) = (player.pos.x, player.pos.y);
+        let near = |(x, y): (f32, f32), radius: f32| ((x - px).powi(2) + (y - py).powi(2)).sqrt() < radius;
+        keys_left.retain(|&key| !near(key, keys::COLLECT_RADIUS));
+        let (taken, left): (Vec<_>, Vec<_>) = pills.iter().partition(|&&(x, y, _)| near((x, y), 0.6));
+        pills = left;
+        for (_, _, pill_type) in taken {
+            let (_, dose) = pill_table.take(pill_type, &mut rng);
+            health.heal(&mut player, dose.heal, Source::Pill, &mut events);
+            time_left = (time_left + dose.time).max(0.0);
+            for &(effect, strength, seconds) in &dose.effects {
+                status.apply(effect, strength, seconds);
+            }
+        }
+        let poison = status.update(SOAK_FRAME_TIME);
+        health.damage(&mut player, poison, Source::Poison, &mut events);
+        health.shield(status.time_left(Effect::Invulnerable));
+
+        let world = World {
+            maze,
+            keys: keys_left.clone(),
+            pills: pills.clone(),
+            enemies: enemies.iter().filter(|enemy| enemy.is_alive()).map(|enemy| (enemy.pos.x, enemy.pos.y)).collect(),
+            health: player.health as f32 / player.max_health as f32,
+            time_left,
+        };
+        if bot.update(&world, &mut player, SOAK_FRAME_TIME) {
+            return SoakRun { escaped: true, time_left, health: player.health };
+        }
+
+        let heard = rng.gen_bool(SOAK_NOISE_CHANCE);
+        for enemy in &mut enemies {
+            if heard {
+                enemy.hear(player.pos.x, player.pos.y);
+            }
+            enemy.update(SOAK_FRAME_TIME);
+            enemy.update_movement(maze, (0.0, 0.0), SOAK_FRAME_TIME);
+            if enemy.try_strike(player.pos.x, player.pos.y) {
+                health.damage(&mut player, enemy::CONTACT_DAMAGE, Source::Enemy, &mut events);
+            }
+        }
+        health.update(&mut player, SOAK_FRAME_TIME, &mut events);
+        events.clear();
+        time_left -= SOAK_FRAME_TIME;
+    }
+    SoakRun { escaped: false, time_left: time_left.max(0.0), health: player.health }
+}
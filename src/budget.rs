@@ -0,0 +1,91 @@
+// Entity budget for Backrooms Doom
+// Custom levels can pile up far more enemies, decals, floating texts and noise
+// rings than the built-in ones. The budget watches the smoothed frame time while
+// playing and shrinks the caps on those pools when the game drops under 30 FPS,
+// growing them back slowly once it runs comfortably again. GameState evicts the
+// least important instances over a cap first (see cleanup_entities): corpses and
+// far-off enemies that haven't seen the player, old footprints before chalk marks.
+
+const SHED_BELOW_FPS: f32 = 30.0;   // Cut the caps while the smoothed frame rate is below this
+const RECOVER_ABOVE_FPS: f32 = 45.0; // Raise them again above this
+const SMOOTHING: f32 = 2.0;          // How fast the smoothed frame time follows (per second)
+const SHED_RATE: f32 = 0.5;          // Caps lost per second while slow (fraction of the full caps)
+const RECOVER_RATE: f32 = 0.05;      // Caps regained per second while fast
+const MIN_SCALE: f32 = 0.25;         // Never cut below this fraction of the full caps
+const MAX_FRAME_TIME: f32 = 0.25;    // Longer frames are hitches (loading, window drag), not load
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Pool {
+    Enemies,
+    Decals,
+    FloatingTexts,
+    Noises,
+}
+
+impl Pool {
+    pub const ALL: [Pool; 4] = [Pool::Enemies, Pool::Decals, Pool::FloatingTexts, Pool::Noises];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Pool::Enemies => "enemies",
+            Pool::Decals => "decals",
+            Pool::FloatingTexts => "floating texts",
+            Pool::Noises => "noises",
+        }
+    }
+
+    /// Cap with the game running at full speed
+    fn full(self) -> usize {
+        match self {
+            Pool::Enemies => 32,
+            Pool::Decals => 256,
+            Pool::FloatingTexts => 32,
+            Pool::Noises => 24,
+        }
+    }
+
+    /// Cap never cut below, however slow the game runs
+    fn floor(self) -> usize {
+        match self {
+            Pool::Enemies => 8,
+            Pool::Decals => 32,
+            Pool::FloatingTexts => 4,
+            Pool::Noises => 4,
+        }
+    }
+}
+
+pub struct Budget {
+    frame_time: f32, // Smoothed, in seconds
+    scale: f32,      // 0.25 - 1.0 of the full caps
+}
+
+impl Budget {
+    pub fn new() -> Self {
+        Self { frame_time: 1.0 / 60.0, scale: 1.0 }
+    }
+
+    /// Follow the frame time of the frame just played and adjust the caps to it
+    pub fn update(&mut self, delta_time: f32) {
+        if delta_time <= 0.0 || delta_time > MAX_FRAME_TIME {
+            return;
+        }
+        self.frame_time += (delta_time - self.frame_time) * (SMOOTHING * delta_time).min(1.0);
+
+        if self.frame_time > 1.0 / SHED_BELOW_FPS {
+            self.scale = (self.scale - SHED_RATE * delta_time).max(MIN_SCALE);
+        } else if self.frame_time < 1.0 / RECOVER_ABOVE_FPS {
+            self.scale = (self.scale + RECOVER_RATE * delta_time).min(1.0);
+        }
+    }
+
+    /// How many instances of a pool may be alive right now
+    pub fn cap(&self, pool: Pool) -> usize {
+        ((pool.full() as f32 * self.scale).round() as usize).max(pool.floor())
+    }
+
+    /// Fraction of the full caps allowed, 0.25 - 1.0
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+}
@@ -1,22 +1,39 @@
+// Camera for Backrooms Doom
+// Mouse look turns the player and tilts the view up and down (the horizon moves,
+// see render_3d_view). The view bob follows the step cycle in gait.rs: the horizon
+// dips with each step, the view sways towards the foot landing and rolls a little
+// into a strafe. Its strength is a setting, and it can be turned off entirely for
+// players who get motion sick.
+
 use raylib::prelude::*;
+use crate::gait::Gait;
 use crate::player::Player;
 
+pub const BASE_SENSITIVITY: f32 = 0.003; // Turn per pixel of mouse movement at 1.0x (settings.toml)
+pub const MAX_PITCH: f32 = 0.35; // Furthest the horizon moves up or down, in view heights
+pub const MAX_BOB_INTENSITY: f32 = 2.0; // Multiplier on the view bob (settings.toml)
+const STRAFE_ROLL: f32 = 0.03; // Roll in radians when strafing at full speed
+const ROLL_RATE: f32 = 8.0;    // How fast the roll follows the strafe (per second)
+const MIN_ROLL: f32 = 0.0005;  // Less than this is drawn level
+
 pub struct Camera {
     pub sensitivity: f32,
     pub last_mouse_pos: Vector2,
     pub is_first_frame: bool,
+    pub invert_y: bool, // Mouse up looks down (settings.toml)
 }
 
 impl Camera {
-    pub fn new(sensitivity: f32) -> Self {
+    pub fn new(sensitivity: f32, invert_y: bool) -> Self {
         Self {
             sensitivity,
             last_mouse_pos: Vector2::zero(),
             is_first_frame: true,
+            invert_y,
         }
     }
 
-    /// Update player rotation based on mouse movement
+    /// Update player rotation and pitch based on mouse movement
     pub fn update(&mut self, rl: &RaylibHandle, player: &mut Player, delta_time: f32) {
         let mouse_pos = rl.get_mouse_position();
 
@@ -34,6 +51,13 @@ impl Camera {
             player.rotate(delta_x * self.sensitivity * delta_time);
         }
 
+        // Vertical movement tilts the view, moving the horizon (mouse up looks up)
+        let delta_y = mouse_pos.y - self.last_mouse_pos.y;
+        if delta_y.abs() > 0.1 {
+            let direction = if self.invert_y { 1.0 } else { -1.0 };
+            player.pitch = (player.pitch + direction * delta_y * self.sensitivity * delta_time).clamp(-MAX_PITCH, MAX_PITCH);
+        }
+
         self.last_mouse_pos = mouse_pos;
     }
 
@@ -42,3 +66,47 @@ impl Camera {
         self.is_first_frame = true;
     }
 }
+
+/// View bob driven by the walking speed: how far the horizon dips, the view sways
+/// and rolls this frame
+pub struct ViewBob {
+    horizon: f32, // Horizon shift in view heights (down is positive)
+    sway: f32,    // Sideways shift in view heights
+    roll: f32,    // Radians, leaning into the strafe
+}
+
+impl ViewBob {
+    pub fn new() -> Self {
+        Self { horizon: 0.0, sway: 0.0, roll: 0.0 }
+    }
+
+    /// Follow the step cycle and the sideways speed (-1.0 left to 1.0 right, fraction of
+    /// full speed). `intensity` scales everything, 0.0 keeps the view still
+    pub fn update(&mut self, gait: &Gait, strafe: f32, intensity: f32, delta_time: f32) {
+        let (sway, dip) = gait.bob();
+        self.sway = sway * intensity;
+        self.horizon = dip * intensity;
+
+        let target = strafe.clamp(-1.0, 1.0) * STRAFE_ROLL * intensity;
+        self.roll += (target - self.roll) * (ROLL_RATE * delta_time).min(1.0);
+    }
+
+    /// Horizon shift in view heights
+    pub fn horizon(&self) -> f32 {
+        self.horizon
+    }
+
+    /// Sideways shift in view heights
+    pub fn sway(&self) -> f32 {
+        self.sway
+    }
+
+    /// View roll in radians, 0.0 when too small to show
+    pub fn roll(&self) -> f32 {
+        if self.roll.abs() < MIN_ROLL {
+            0.0
+        } else {
+            self.roll
+        }
+    }
+}
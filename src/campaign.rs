@@ -0,0 +1,62 @@
+// Campaign for Backrooms Doom
+// A run can span several levels: levels/level1.txt, levels/level2.txt, ... are
+// played in order (numbering stops at the first missing file), and without any
+// the run is the single level in maze.txt as before. Reaching the exit of any
+// level but the last opens the level-complete screen; the next level starts
+// with the health the player had and a fresh timer plus a share of the time
+// that was left, so a quick, careful escape pays off later on. The run's time,
+// and its place on the leaderboard, cover the whole campaign.
+
+use std::path::Path;
+
+pub const LEVELS_DIR: &str = "levels";
+pub const LEVEL_TIME: f32 = 180.0; // Seconds on the clock at the start of each level
+const TIME_CARRIED: f32 = 0.5;     // Share of the time left on a level added to the next
+
+pub struct Campaign {
+    levels: Vec<String>, // Maze file of each level, in order
+    pub index: usize,    // Level being played
+}
+
+impl Campaign {
+    /// Find the numbered levels, or fall back to the single level in `fallback`
+    pub fn discover(fallback: &str) -> Self {
+        let mut levels = Vec::new();
+        for number in 1.. {
+            let path = format!("{}/level{}.txt", LEVELS_DIR, number);
+            if !Path::new(&path).exists() {
+                break;
+            }
+            levels.push(path);
+        }
+        if levels.is_empty() {
+            levels.push(fallback.to_string());
+        }
+        Self { levels, index: 0 }
+    }
+
+    /// Maze file of the level being played
+    pub fn current(&self) -> &str {
+        &self.levels[self.index]
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.index + 1 >= self.levels.len()
+    }
+
+    /// Move on to the next level (stays on the last one)
+    pub fn advance(&mut self) {
+        if !self.is_last() {
+            self.index += 1;
+        }
+    }
+}
+
+/// Time on the clock at the start of the next level, given the time left on this one
+pub fn next_level_time(time_left: f32) -> f32 {
+    LEVEL_TIME + time_left.max(0.0) * TIME_CARRIED
+}
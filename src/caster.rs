@@ -1,5 +1,17 @@
+use crate::doors::Doors;
+use crate::guard;
 use crate::maze::Maze;
 use crate::player::Player;
+use crate::translucent::Translucent;
+
+const MAX_LAYERS: usize = 8; // See-through surfaces kept per ray, the nearest ones
+
+/// A see-through surface a ray passed on its way to the wall (see translucent.rs)
+pub struct Layer {
+    pub distance: f32,
+    pub wall_x: f32, // Texture coordinate (0.0 to 1.0)
+    pub kind: Translucent,
+}
 
 pub struct RayHit {
     pub distance: f32,
@@ -8,6 +20,9 @@ pub struct RayHit {
     pub map_x: usize,
     pub map_y: usize,
     pub wall_type: char,  // Type of wall hit ('#' for normal, 'E' for exit)
+    pub hit_x: f32,       // World position where the ray met the wall
+    pub hit_y: f32,
+    pub layers: Vec<Layer>, // Nearest first; only collected when the maze has see-through tiles
 }
 
 pub struct RayCaster {
@@ -37,18 +52,29 @@ impl RayCaster {
     }
 
     /// Cast all rays and return hit information
-    pub fn cast_rays(&self, player: &Player, maze: &Maze) -> Vec<RayHit> {
+    pub fn cast_rays(&self, player: &Player, maze: &Maze, doors: &Doors) -> Vec<RayHit> {
+        let collect_layers = maze.has_translucent();
         self.ray_angles
             .iter()
             .map(|&ray_offset| {
                 let ray_angle = player.angle + ray_offset;
-                self.cast_single_ray(player.pos.x, player.pos.y, ray_angle, maze)
+                self.cast_single_ray(player.pos.x, player.pos.y, ray_angle, maze, doors, collect_layers)
             })
             .collect()
     }
 
-    /// Cast a single ray using DDA algorithm (optimized)
-    fn cast_single_ray(&self, origin_x: f32, origin_y: f32, angle: f32, maze: &Maze) -> RayHit {
+    /// Cast a single ray using DDA algorithm (optimized). With `collect_layers` the
+    /// see-through tiles it passes are recorded on the way. The part of a closed door
+    /// that has slid aside (see doors.rs) lets the ray through
+    fn cast_single_ray(
+        &self,
+        origin_x: f32,
+        origin_y: f32,
+        angle: f32,
+        maze: &Maze,
+        doors: &Doors,
+        collect_layers: bool,
+    ) -> RayHit {
         let dir_x = angle.cos();
         let dir_y = angle.sin();
 
@@ -84,9 +110,23 @@ impl RayCaster {
             side_dist_y = (map_y as f32 + 1.0 - origin_y / maze.tile_size) * delta_dist_y;
         }
 
+        // Perpendicular distance (avoids fisheye) and texture coordinate where the ray
+        // enters grid square (map_x, map_y)
+        let origin = (origin_x / maze.tile_size, origin_y / maze.tile_size);
+        let edge = |map_x: i32, map_y: i32, vertical: bool| {
+            let distance = if vertical {
+                (map_x as f32 - origin.0 + (1.0 - step_x as f32) / 2.0) / dir_x
+            } else {
+                (map_y as f32 - origin.1 + (1.0 - step_y as f32) / 2.0) / dir_y
+            };
+            let wall_x = if vertical { origin.1 + distance * dir_y } else { origin.0 + distance * dir_x };
+            (distance, wall_x - wall_x.floor())
+        };
+
         // DDA algorithm
         let mut hit = false;
         let mut hit_vertical = false;
+        let mut layers = Vec::new();
 
         while !hit {
             // Jump to next grid square
@@ -106,36 +146,34 @@ impl RayCaster {
                 break;
             }
 
-            if maze.is_wall(map_x as usize, map_y as usize) {
-                hit = true;
+            let (x, y) = (map_x as usize, map_y as usize);
+            if maze.is_opaque(x, y) {
+                let sliding = maze.door_at(x, y) == Some(false) && doors.open_amount(x, y) > 0.0;
+                hit = !sliding || edge(map_x, map_y, hit_vertical).1 >= doors.open_amount(x, y);
+            } else if let (true, Some(kind)) = (collect_layers && layers.len() < MAX_LAYERS, maze.translucency(x, y)) {
+                let (distance, wall_x) = edge(map_x, map_y, hit_vertical);
+                let distance = guard::depth(distance.abs() * maze.tile_size, self.max_depth, "layer distance");
+                layers.push(Layer { distance, wall_x, kind });
             }
         }
 
-        // Calculate distance (perpendicular to camera plane to avoid fisheye)
-        let distance = if hit_vertical {
-            (map_x as f32 - origin_x / maze.tile_size + (1.0 - step_x as f32) / 2.0) / dir_x
-        } else {
-            (map_y as f32 - origin_y / maze.tile_size + (1.0 - step_y as f32) / 2.0) / dir_y
-        };
-
-        // Calculate exact hit position for texture mapping
-        let wall_x = if hit_vertical {
-            origin_y / maze.tile_size + distance * dir_y
-        } else {
-            origin_x / maze.tile_size + distance * dir_x
-        };
-        let wall_x = wall_x - wall_x.floor(); // Get fractional part [0, 1]
+        // Distance and texture coordinate of the wall the ray stopped at
+        let (distance, wall_x) = edge(map_x, map_y, hit_vertical);
 
         // Get wall type for texture selection
         let wall_type = maze.get_wall_type(map_x as usize, map_y as usize);
+        let distance = guard::depth(distance.abs() * maze.tile_size, self.max_depth, "ray distance");
 
         RayHit {
-            distance: distance.abs() * maze.tile_size,
+            distance,
             wall_x,
             hit_vertical,
             map_x: map_x as usize,
             map_y: map_y as usize,
             wall_type,
+            hit_x: origin_x + dir_x * distance,
+            hit_y: origin_y + dir_y * distance,
+            layers,
         }
     }
 }
\ No newline at end of file
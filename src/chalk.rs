@@ -0,0 +1,64 @@
+// Chalk marks for Backrooms Doom
+// A stick of chalk with a limited number of marks. Marking the wall in front of
+// the player (or the floor underfoot when no wall is in reach) leaves a decal
+// that stays for the whole run (unless a puddle washes it off) and shows on the
+// minimap, so corridors that have already been walked can be recognised.
+// `unlimited_chalk` removes the limit.
+
+use crate::decal::{DecalKind, Decals};
+use crate::maze::Maze;
+
+pub const STARTING_MARKS: u32 = 12;
+const MARK_REACH: f32 = 1.0; // How far away a wall can be marked, in tiles
+
+pub struct Chalk {
+    pub marks: u32, // Marks left
+    pub unlimited: bool,
+}
+
+impl Chalk {
+    pub fn new(unlimited: bool) -> Self {
+        Self { marks: STARTING_MARKS, unlimited }
+    }
+
+    /// Marks left as 0.0 - 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.unlimited {
+            1.0
+        } else {
+            self.marks as f32 / STARTING_MARKS as f32
+        }
+    }
+
+    /// A fresh stick from the inventory: the marks back to full. Returns false when they are
+    pub fn refill(&mut self) -> bool {
+        if self.unlimited || self.marks >= STARTING_MARKS {
+            return false;
+        }
+        self.marks = STARTING_MARKS;
+        true
+    }
+
+    /// Mark the wall straight ahead of (x, y) if one is in reach, otherwise the floor.
+    /// Returns false when the chalk has run out
+    pub fn mark(&mut self, decals: &mut Decals, maze: &Maze, x: f32, y: f32, angle: f32) -> bool {
+        if !self.unlimited {
+            if self.marks == 0 {
+                return false;
+            }
+            self.marks -= 1;
+        }
+
+        let mut distance = 0.0;
+        while distance <= MARK_REACH {
+            let point = (x + angle.cos() * distance, y + angle.sin() * distance);
+            if !maze.is_walkable(point.0, point.1) {
+                decals.add_wall(DecalKind::Chalk, point, (x, y));
+                return true;
+            }
+            distance += 0.05;
+        }
+        decals.add_floor(DecalKind::Chalk, maze, x, y);
+        true
+    }
+}
@@ -0,0 +1,235 @@
+// Challenge rooms for Backrooms Doom
+// Optional side rooms off the main path, each holding a short timed challenge with a
+// reward. A room is a rectangle of tiles in the maze file, one line each after the grid:
+//
+//   challenge 25,1 29,3 orbs 5 15 time 20
+//   challenge 22,14 28,16 blackout 10 armor
+//
+// = two opposite corners (x,y counted from 0 at the top-left corner), the challenge and
+// its numbers, then the reward. Walking into the room starts it, once per level:
+// - orbs <count> <seconds>: orbs appear on the room's floor, collect them all in time
+// - blackout <seconds>: the power goes out, stay in the room until it comes back
+// Rewards are `time <seconds>` back on the run timer, or a perk for the rest of the
+// level: `armor` (half damage taken) or `regen` (slowly healing). Rooms still waiting
+// are outlined on the minimap, and the run's stats count the challenges won and failed.
+
+use crate::maze::Maze;
+use rand::seq::SliceRandom;
+use raylib::prelude::Color;
+
+pub const COLLECT_RADIUS: f32 = 0.5;
+pub const ARMOR: f32 = 0.5; // Damage taken with the armor perk
+pub const REGEN: f32 = 0.5; // Health per second with the regen perk
+
+pub const COLOR: Color = Color::new(120, 220, 255, 255);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Trial {
+    Orbs { count: usize, seconds: f32 },
+    Blackout { seconds: f32 },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Reward {
+    Time(f32), // Seconds added to the run timer
+    Armor,
+    Regen,
+}
+
+impl Reward {
+    /// Short text for the toast and floating text
+    pub fn label(&self) -> String {
+        match self {
+            Reward::Time(seconds) => format!("+{:.0}s", seconds),
+            Reward::Armor => "Armor".to_string(),
+            Reward::Regen => "Regeneration".to_string(),
+        }
+    }
+}
+
+/// A challenge room from the maze file. Corners are tiles, inclusive
+#[derive(Clone, Copy, Debug)]
+pub struct ChallengeRoom {
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+    pub trial: Trial,
+    pub reward: Reward,
+}
+
+impl ChallengeRoom {
+    /// Parse the rest of a "challenge x,y x,y <trial> <reward>" line (without the keyword)
+    pub fn parse(definition: &str) -> Result<Self, String> {
+        let words: Vec<&str> = definition.split_whitespace().collect();
+        let invalid = || {
+            format!(
+                "bad challenge '{}', expected x,y x,y then orbs <count> <seconds> or blackout <seconds>, then time <seconds>, armor or regen",
+                definition.trim()
+            )
+        };
+        let corner = |token: &str| -> Option<(usize, usize)> {
+            let (x, y) = token.split_once(',')?;
+            Some((x.parse().ok()?, y.parse().ok()?))
+        };
+        let seconds = |token: &str| token.parse::<f32>().ok().filter(|seconds| seconds.is_finite() && *seconds > 0.0);
+
+        let (Some(first), Some(second)) = (words.first(), words.get(1)) else {
+            return Err(invalid());
+        };
+        let (a, b) = (corner(first).ok_or_else(invalid)?, corner(second).ok_or_else(invalid)?);
+        let (trial, rest) = match words.get(2..) {
+            Some(["orbs", count, time, rest @ ..]) => {
+                let count = count.parse().ok().filter(|count| *count > 0).ok_or_else(invalid)?;
+                (Trial::Orbs { count, seconds: seconds(time).ok_or_else(invalid)? }, rest)
+            }
+            Some(["blackout", time, rest @ ..]) => (Trial::Blackout { seconds: seconds(time).ok_or_else(invalid)? }, rest),
+            _ => return Err(invalid()),
+        };
+        let reward = match rest {
+            ["time", time] => Reward::Time(seconds(time).ok_or_else(invalid)?),
+            ["armor"] => Reward::Armor,
+            ["regen"] => Reward::Regen,
+            _ => return Err(invalid()),
+        };
+        Ok(Self { x0: a.0.min(b.0), y0: a.1.min(b.1), x1: a.0.max(b.0), y1: a.1.max(b.1), trial, reward })
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x0..=self.x1).contains(&x) && (self.y0..=self.y1).contains(&y)
+    }
+
+    /// Walkable tiles inside the room
+    pub fn floor(&self, maze: &Maze) -> Vec<(usize, usize)> {
+        (self.y0..=self.y1)
+            .flat_map(|y| (self.x0..=self.x1).map(move |x| (x, y)))
+            .filter(|&(x, y)| !maze.is_wall(x, y))
+            .collect()
+    }
+
+    /// What to do, for the toast when it starts
+    pub fn describe(&self) -> String {
+        match self.trial {
+            Trial::Orbs { count, seconds } => format!("Challenge: collect {} orbs in {:.0}s", count, seconds),
+            Trial::Blackout { seconds } => format!("Challenge: stay in the room through {:.0}s of darkness", seconds),
+        }
+    }
+}
+
+/// Where a room's challenge stands
+enum Progress {
+    Waiting,
+    Running { time_left: f32, orbs: Vec<(f32, f32)>, collected: usize },
+    Won,
+    Failed,
+}
+
+/// What happened to a challenge this frame (rooms by index)
+pub enum Outcome {
+    Started(usize),
+    Orb { x: f32, y: f32 },
+    Won(usize),
+    Failed(usize),
+}
+
+pub struct Challenges {
+    rooms: Vec<(ChallengeRoom, Progress)>,
+    pub time: f32, // Seconds of animation, for the orbs' bob
+}
+
+impl Challenges {
+    pub fn new(rooms: &[ChallengeRoom]) -> Self {
+        Self { rooms: rooms.iter().map(|&room| (room, Progress::Waiting)).collect(), time: 0.0 }
+    }
+
+    pub fn room(&self, index: usize) -> &ChallengeRoom {
+        &self.rooms[index].0
+    }
+
+    /// Start a challenge when the player at (x, y) walks into its room, collect orbs, count
+    /// the running one down and settle it
+    pub fn update(&mut self, maze: &Maze, (x, y): (f32, f32), delta_time: f32) -> Vec<Outcome> {
+        self.time += delta_time;
+        let tile = ((x / maze.tile_size) as usize, (y / maze.tile_size) as usize);
+        let mut outcomes = Vec::new();
+        for (index, (room, progress)) in self.rooms.iter_mut().enumerate() {
+            let inside = room.contains(tile.0, tile.1);
+            match progress {
+                Progress::Waiting if inside => {
+                    let (seconds, orbs) = match room.trial {
+                        Trial::Orbs { count, seconds } => {
+                            let mut floor = room.floor(maze);
+                            floor.shuffle(&mut rand::thread_rng());
+                            let center = |(x, y): (usize, usize)| ((x as f32 + 0.5) * maze.tile_size, (y as f32 + 0.5) * maze.tile_size);
+                            // Not on the tile the player stands on, so they have to go for them
+                            (seconds, floor.into_iter().filter(|&spot| spot != tile).take(count).map(center).collect())
+                        }
+                        Trial::Blackout { seconds } => (seconds, Vec::new()),
+                    };
+                    *progress = Progress::Running { time_left: seconds, orbs, collected: 0 };
+                    outcomes.push(Outcome::Started(index));
+                }
+                Progress::Running { time_left, orbs, collected } => {
+                    if let Some(orb) = orbs.iter().position(|&(ox, oy)| ((ox - x).powi(2) + (oy - y).powi(2)).sqrt() < COLLECT_RADIUS) {
+                        let (ox, oy) = orbs.remove(orb);
+                        *collected += 1;
+                        outcomes.push(Outcome::Orb { x: ox, y: oy });
+                    }
+                    *time_left -= delta_time;
+                    let outcome = match room.trial {
+                        Trial::Orbs { .. } if orbs.is_empty() => Some(Outcome::Won(index)),
+                        Trial::Orbs { .. } if *time_left <= 0.0 => Some(Outcome::Failed(index)),
+                        Trial::Blackout { .. } if !inside => Some(Outcome::Failed(index)),
+                        Trial::Blackout { .. } if *time_left <= 0.0 => Some(Outcome::Won(index)),
+                        _ => None,
+                    };
+                    if let Some(outcome) = outcome {
+                        *progress = if matches!(outcome, Outcome::Won(_)) { Progress::Won } else { Progress::Failed };
+                        outcomes.push(outcome);
+                    }
+                }
+                _ => {}
+            }
+        }
+        outcomes
+    }
+
+    /// Challenges won so far, and the level's number of challenge rooms
+    pub fn won(&self) -> (usize, usize) {
+        (self.rooms.iter().filter(|(_, progress)| matches!(progress, Progress::Won)).count(), self.rooms.len())
+    }
+
+    /// The running challenge: its room, seconds left and orbs collected
+    pub fn running(&self) -> Option<(&ChallengeRoom, f32, usize)> {
+        self.rooms.iter().find_map(|(room, progress)| match progress {
+            Progress::Running { time_left, collected, .. } => Some((room, *time_left, *collected)),
+            _ => None,
+        })
+    }
+
+    /// Orbs lying in the running challenge's room
+    pub fn orbs(&self) -> Vec<(f32, f32)> {
+        self.rooms
+            .iter()
+            .filter_map(|(_, progress)| match progress {
+                Progress::Running { orbs, .. } => Some(orbs.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Corners of the rooms whose challenge hasn't been played yet, for the minimap
+    pub fn waiting(&self) -> Vec<(usize, usize, usize, usize)> {
+        self.rooms
+            .iter()
+            .filter(|(_, progress)| matches!(progress, Progress::Waiting))
+            .map(|(room, _)| (room.x0, room.y0, room.x1, room.y1))
+            .collect()
+    }
+}
+
+/// Whether the point (u, v), each -1.0 - 1.0 across the sprite, is part of an orb
+pub fn covers(u: f32, v: f32) -> bool {
+    u * u + v * v < 1.0
+}
@@ -0,0 +1,48 @@
+// Clock pickups for Backrooms Doom
+// Wall clocks lying on the floor ('T' in the maze file). Walking over one puts
+// BONUS seconds back on the run timer; the timer flashes green and the seconds float
+// up next to it (see UI::change_timer). Clocks are drawn as pale faces turning slowly,
+// and show on the minimap while the map is revealed.
+
+use crate::maze::Maze;
+use raylib::prelude::Color;
+
+pub const COLLECT_RADIUS: f32 = 0.5;
+pub const BONUS: f32 = 15.0; // Seconds a clock adds to the timer
+
+pub const COLOR: Color = Color::new(235, 235, 210, 255);
+
+pub struct Clocks {
+    clocks: Vec<(f32, f32)>, // Still lying in the level
+    pub time: f32,           // Seconds of animation, for the turning faces
+}
+
+impl Clocks {
+    pub fn new(maze: &Maze) -> Self {
+        Self { clocks: maze.clock_positions.clone(), time: 0.0 }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+
+    pub fn remaining(&self) -> &[(f32, f32)] {
+        &self.clocks
+    }
+
+    /// Pick up a clock within reach of (x, y). Returns where it was
+    pub fn collect_near(&mut self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let index = self.clocks.iter().position(|&(cx, cy)| ((cx - x).powi(2) + (cy - y).powi(2)).sqrt() < COLLECT_RADIUS)?;
+        Some(self.clocks.remove(index))
+    }
+}
+
+/// Whether the point (u, v), each -1.0 - 1.0 across the sprite, is part of the clock:
+/// the rim of the face and its two hands (the hour hand up, the minute hand right)
+pub fn covers(u: f32, v: f32) -> bool {
+    let radius = (u * u + v * v).sqrt();
+    let rim = (0.8..=1.0).contains(&radius);
+    let hour = u.abs() < 0.08 && (-0.5..=0.0).contains(&v);
+    let minute = v.abs() < 0.06 && (0.0..=0.65).contains(&u);
+    rim || hour || minute
+}
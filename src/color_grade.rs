@@ -0,0 +1,98 @@
+// Color grading for Backrooms Doom
+// Per-channel lift/gamma/gain curves baked into lookup tables plus a
+// saturation control, applied as the last step of the post-processing chain
+
+use raylib::prelude::*;
+
+pub struct ColorGrade {
+    lut_r: [u8; 256],
+    lut_g: [u8; 256],
+    lut_b: [u8; 256],
+    saturation: f32, // 1.0 = unchanged, 0.0 = grayscale
+}
+
+impl ColorGrade {
+    /// Bake per-channel curves (r, g, b) into lookup tables
+    pub fn from_curves(lift: [f32; 3], gamma: [f32; 3], gain: [f32; 3], saturation: f32) -> Self {
+        let bake = |channel: usize| {
+            let mut lut = [0u8; 256];
+            for (i, value) in lut.iter_mut().enumerate() {
+                let x = i as f32 / 255.0;
+                let lifted = lift[channel] + x * (1.0 - lift[channel]);
+                let curved = lifted.max(0.0).powf(1.0 / gamma[channel].max(0.01));
+                *value = (curved * gain[channel] * 255.0).clamp(0.0, 255.0) as u8;
+            }
+            lut
+        };
+
+        Self {
+            lut_r: bake(0),
+            lut_g: bake(1),
+            lut_b: bake(2),
+            saturation,
+        }
+    }
+
+    #[inline]
+    pub fn apply(&self, color: Color) -> Color {
+        let (mut r, mut g, mut b) = (color.r, color.g, color.b);
+
+        if self.saturation != 1.0 {
+            let luma = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+            let mix = |c: u8| (luma + (c as f32 - luma) * self.saturation).clamp(0.0, 255.0) as u8;
+            r = mix(r);
+            g = mix(g);
+            b = mix(b);
+        }
+
+        Color::new(
+            self.lut_r[r as usize],
+            self.lut_g[g as usize],
+            self.lut_b[b as usize],
+            color.a,
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Theme {
+    Level0,    // Classic yellow office rooms
+    Poolrooms, // Tiled pools under cold light
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Level0, Theme::Poolrooms];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Level0 => "level0",
+            Theme::Poolrooms => "poolrooms",
+        }
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Theme::Level0 => "Level 0",
+            Theme::Poolrooms => "Poolrooms",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Theme::ALL.iter().copied().find(|t| t.name() == value)
+    }
+
+    /// Next theme in the list (for cycling on the menu)
+    pub fn next(self) -> Self {
+        let index = Theme::ALL.iter().position(|t| *t == self).unwrap_or(0);
+        Theme::ALL[(index + 1) % Theme::ALL.len()]
+    }
+
+    pub fn grade(self) -> ColorGrade {
+        match self {
+            // Sickly yellow: warm highlights, green-tinted mids, crushed blues
+            Theme::Level0 => ColorGrade::from_curves([0.02, 0.03, 0.0], [1.05, 1.08, 0.85], [1.0, 0.97, 0.78], 0.85),
+            // Cold blue: lifted blue shadows, pulled down reds
+            Theme::Poolrooms => ColorGrade::from_curves([0.0, 0.03, 0.08], [0.9, 1.0, 1.1], [0.8, 0.95, 1.05], 0.8),
+        }
+    }
+}
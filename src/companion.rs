@@ -0,0 +1,150 @@
+// AI companion for Backrooms Doom
+// An optional bot that follows the player around with pathfinding, calls out
+// pills and the exit when it spots them, and can be sent ahead to scout a spot
+
+use crate::maze::Maze;
+use crate::pathfinding;
+use crate::player::Player;
+
+const FOLLOW_DISTANCE: f32 = 1.6;  // Stays this close to the player before walking over
+const WAYPOINT_REACHED: f32 = 0.15;
+const SCOUT_WAIT: f32 = 4.0;       // Seconds it waits at a scouted spot before coming back
+const CAPTION_TIME: f32 = 3.0;     // Seconds a caption stays on screen
+const CAPTION_COOLDOWN: f32 = 2.0; // Minimum gap between two callouts
+pub const SPOT_RANGE: f32 = 5.0;   // How far away it notices pills
+pub const SPEED_FACTOR: f32 = 0.9; // Slightly slower than the player
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Order {
+    Follow,
+    Scout { x: f32, y: f32 },
+    Wait { time_left: f32 }, // Holding position after reaching a scout marker
+}
+
+pub struct Companion {
+    pub player_index: usize, // Entry in GameState::players controlled by this bot
+    pub order: Order,
+    pub caption: Option<(String, f32)>, // Callout and how long it stays visible
+    path: Vec<(f32, f32)>,
+    path_goal: Option<(usize, usize)>, // Cell the path leads to, re-planned when the player leaves it
+    caption_cooldown: f32,
+    pointed_out: Vec<(f32, f32)>, // Things already called out, so each is only mentioned once
+}
+
+impl Companion {
+    pub fn new(player_index: usize) -> Self {
+        Self {
+            player_index,
+            order: Order::Follow,
+            caption: None,
+            path: Vec::new(),
+            path_goal: None,
+            caption_cooldown: 0.0,
+            pointed_out: Vec::new(),
+        }
+    }
+
+    /// Send the bot to (x, y). Returns false if there is no route there
+    pub fn scout(&mut self, maze: &Maze, bot: &Player, x: f32, y: f32) -> bool {
+        match pathfinding::find_path(maze, (bot.pos.x, bot.pos.y), (x, y)) {
+            Some(path) => {
+                self.path = path;
+                self.path_goal = None;
+                self.order = Order::Scout { x, y };
+                self.say("On my way.");
+                true
+            }
+            None => {
+                self.say("I can't get there.");
+                false
+            }
+        }
+    }
+
+    /// Stop scouting and come back to the player
+    pub fn recall(&mut self) {
+        self.order = Order::Follow;
+        self.path.clear();
+        self.path_goal = None;
+        self.say("Coming back.");
+    }
+
+    pub fn update(&mut self, maze: &Maze, bot: &mut Player, leader: &Player, delta_time: f32) {
+        self.caption_cooldown = (self.caption_cooldown - delta_time).max(0.0);
+        if let Some((_, time_left)) = &mut self.caption {
+            *time_left -= delta_time;
+            if *time_left <= 0.0 {
+                self.caption = None;
+            }
+        }
+
+        match self.order {
+            Order::Follow => {
+                let distance = ((leader.pos.x - bot.pos.x).powi(2) + (leader.pos.y - bot.pos.y).powi(2)).sqrt();
+                if distance <= FOLLOW_DISTANCE {
+                    self.path.clear();
+                    self.path_goal = None;
+                    return;
+                }
+
+                let goal = pathfinding::cell(maze, (leader.pos.x, leader.pos.y));
+                if self.path_goal != Some(goal) {
+                    self.path_goal = Some(goal);
+                    self.path = pathfinding::find_path(maze, (bot.pos.x, bot.pos.y), (leader.pos.x, leader.pos.y))
+                        .unwrap_or_default();
+                }
+                self.walk(maze, bot, delta_time);
+            }
+            Order::Scout { x, y } => {
+                if self.path.is_empty() {
+                    self.order = Order::Wait { time_left: SCOUT_WAIT };
+                    self.say("Made it. All quiet here.");
+                    return;
+                }
+                // Aim for the exact marker once on its tile
+                if self.path.len() == 1 {
+                    self.path[0] = (x, y);
+                }
+                self.walk(maze, bot, delta_time);
+            }
+            Order::Wait { time_left } => {
+                let time_left = time_left - delta_time;
+                if time_left <= 0.0 {
+                    self.recall();
+                } else {
+                    self.order = Order::Wait { time_left };
+                }
+            }
+        }
+    }
+
+    /// Step along the current route
+    fn walk(&mut self, maze: &Maze, bot: &mut Player, delta_time: f32) {
+        let Some(&(x, y)) = self.path.first() else {
+            return;
+        };
+        if bot.walk_towards(maze, x, y, SPEED_FACTOR, delta_time) <= WAYPOINT_REACHED {
+            self.path.remove(0);
+        }
+    }
+
+    /// Call out something at (x, y) unless it was already mentioned. Returns true if it spoke
+    pub fn point_out(&mut self, x: f32, y: f32, line: &str) -> bool {
+        let known = self
+            .pointed_out
+            .iter()
+            .any(|(px, py)| (px - x).abs() < 0.1 && (py - y).abs() < 0.1);
+        if known || self.caption_cooldown > 0.0 {
+            return false;
+        }
+
+        self.pointed_out.push((x, y));
+        self.say(line);
+        true
+    }
+
+    fn say(&mut self, line: &str) {
+        self.caption = Some((line.to_string(), CAPTION_TIME));
+        self.caption_cooldown = CAPTION_COOLDOWN;
+    }
+}
@@ -0,0 +1,525 @@
+// Settings for Backrooms Doom
+// Stored in settings.toml as simple "key = value" lines, missing keys keep their defaults
+
+use crate::camera;
+use crate::color_grade::Theme;
+use crate::controls::{Layout, Preset};
+use crate::hints::HintFrequency;
+use crate::hud;
+use crate::idle::IdlePenalty;
+use crate::options;
+use crate::packs;
+use crate::player::Physics;
+use crate::search;
+use crate::crosshair::{self, CrosshairConfig, CrosshairStyle};
+use crate::doors;
+use log::{info, warn};
+use std::fs;
+use std::path::Path;
+
+pub const CONFIG_PATH: &str = "settings.toml";
+const MIN_SCARE_COOLDOWN: f32 = 15.0; // Chat can't trigger scares back to back
+const MAX_SCARE_COOLDOWN: f32 = 3600.0;
+const MAX_CROSSHAIR_SIZE: f32 = 64.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FpsCap {
+    Fps30,
+    Fps60,
+    Fps120,
+    Uncapped,
+}
+
+impl FpsCap {
+    /// Frames per second to aim for, None when uncapped
+    pub fn target(self) -> Option<u32> {
+        match self {
+            FpsCap::Fps30 => Some(30),
+            FpsCap::Fps60 => Some(60),
+            FpsCap::Fps120 => Some(120),
+            FpsCap::Uncapped => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FpsCap::Fps30 => "30",
+            FpsCap::Fps60 => "60",
+            FpsCap::Fps120 => "120",
+            FpsCap::Uncapped => "uncapped",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "30" => Some(FpsCap::Fps30),
+            "60" => Some(FpsCap::Fps60),
+            "120" => Some(FpsCap::Fps120),
+            "uncapped" | "0" => Some(FpsCap::Uncapped),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FramePacing {
+    Raylib,  // Let raylib wait in EndDrawing (set_target_fps)
+    Precise, // Sleep + spin to the exact frame deadline ourselves
+}
+
+impl FramePacing {
+    pub fn name(self) -> &'static str {
+        match self {
+            FramePacing::Raylib => "raylib",
+            FramePacing::Precise => "precise",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "raylib" => Some(FramePacing::Raylib),
+            "precise" => Some(FramePacing::Precise),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DisplayMode {
+    Windowed,
+    Borderless, // Borderless window covering the monitor
+    Fullscreen, // Exclusive fullscreen
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [DisplayMode::Windowed, DisplayMode::Borderless, DisplayMode::Fullscreen];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "windowed",
+            DisplayMode::Borderless => "borderless",
+            DisplayMode::Fullscreen => "fullscreen",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "windowed" => Some(DisplayMode::Windowed),
+            "borderless" => Some(DisplayMode::Borderless),
+            "fullscreen" => Some(DisplayMode::Fullscreen),
+            _ => None,
+        }
+    }
+
+    /// The next (step 1) or previous (-1) mode, wrapping around (options screen)
+    pub fn cycle(self, step: i32) -> Self {
+        let count = Self::ALL.len() as i32;
+        let index = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0) as i32;
+        Self::ALL[(index + step).rem_euclid(count) as usize]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum QualityPreset {
+    Low,
+    Medium,
+    High,
+    Ultra,
+    Full, // One ray per framebuffer column, no blocky walls
+}
+
+impl QualityPreset {
+    pub const ALL: [QualityPreset; 5] = [
+        QualityPreset::Low,
+        QualityPreset::Medium,
+        QualityPreset::High,
+        QualityPreset::Ultra,
+        QualityPreset::Full,
+    ];
+
+    /// Rays cast per frame into a framebuffer `width` columns wide
+    pub fn num_rays(self, width: usize) -> usize {
+        match self {
+            QualityPreset::Low => 40,
+            QualityPreset::Medium => 80,
+            QualityPreset::High => 160,
+            QualityPreset::Ultra => 320,
+            QualityPreset::Full => width.max(1),
+        }
+    }
+
+    /// Framebuffer resolution relative to the base resolution
+    pub fn resolution_percent(self) -> usize {
+        match self {
+            QualityPreset::Low => 50,
+            _ => 100,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            QualityPreset::Low => "low",
+            QualityPreset::Medium => "medium",
+            QualityPreset::High => "high",
+            QualityPreset::Ultra => "ultra",
+            QualityPreset::Full => "full",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        QualityPreset::ALL.iter().copied().find(|q| q.name() == value)
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    pub vsync: bool,
+    pub fps_cap: FpsCap,
+    pub frame_pacing: FramePacing,
+    pub display_mode: DisplayMode,
+    pub monitor: i32,
+    pub quality: QualityPreset,
+    pub theme: Theme,
+    pub film_grain: bool,
+    pub grain_cap: f32, // Accessibility: hard upper limit for grain/static intensity (0.0 - 1.0)
+    pub companion: bool, // Spawn the AI companion at the start of each run
+    pub online_leaderboard: bool, // Opt-in: submit runs online (needs the online-leaderboard feature)
+    pub leaderboard_url: String,  // http:// endpoint for the online leaderboard
+    pub twitch_channel: String,   // Chat that votes on scares, empty disables it
+    pub scare_cooldown: f32,      // Seconds between chat votes
+    pub rumble: bool, // Controller vibration when a gamepad is connected
+    pub view_bob: bool, // Bob the view with the player's steps
+    pub view_bob_intensity: f32, // Multiplier on the bob, sway and strafe roll
+    pub menu_flythrough: bool, // Live 3D view of the level behind the main menu
+    pub intro_splash: bool, // Logo splash before the menu at startup
+    pub procedural_maze: bool, // Generate the level from the run seed instead of loading maze.txt
+    pub maze_seed: Option<u64>, // Seed every run with this (same maze, same pills), None = random
+    pub mouse_sensitivity: f32, // Multiplier on the base mouse turn rate
+    pub invert_y: bool,         // Mouse up looks down
+    pub music_volume: f32,      // 0.0 - 1.0
+    pub sfx_volume: f32,        // 0.0 - 1.0
+    pub fov: f32,               // Horizontal field of view in degrees
+    pub crosshair: CrosshairConfig,
+    pub hud_scale: f32, // Multiplier on the resolution-based HUD scale
+    pub hints: HintFrequency,
+    pub hardcore: bool, // One life, no saves, no hints, minimal HUD (applies from the next run)
+    pub exit_hum: Option<bool>, // Hot/cold hum near the exit, None = on unless hardcore
+    pub captions: bool,         // Captions for sound cues
+    pub high_contrast: bool,    // Outlined floor edges, glowing exit and pills (see contrast.rs)
+    pub narration: String,      // Menus read aloud: "off", "auto" (platform speech), "log" or a command
+    pub resource_pack: String,  // Texture/audio overlay from assets/packs/ (see packs.rs)
+    pub unlimited_chalk: bool,  // No limit on chalk marks
+    pub store_pills: bool,      // Pills walked over go into the inventory instead of being taken
+    pub search_time: f32,       // Seconds enemies search around where they lost the player
+    pub door_close_delay: f32,  // Seconds before an opened door slides shut by itself, 0 = never
+    pub physics: Physics,       // Player acceleration, friction and top speed
+    pub idle: IdlePenalty,      // Idle penalty time, damage and anxiety strength (ignored in hardcore)
+    pub controls: Preset,
+    pub keyboard_layout: Option<Layout>, // Key names shown in hints, None = detect
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            vsync: false,
+            fps_cap: FpsCap::Fps60,
+            frame_pacing: FramePacing::Raylib,
+            display_mode: DisplayMode::Windowed,
+            monitor: 0,
+            quality: QualityPreset::Medium,
+            theme: Theme::Level0,
+            film_grain: true,
+            grain_cap: 1.0,
+            companion: false,
+            online_leaderboard: false,
+            leaderboard_url: String::new(),
+            twitch_channel: String::new(),
+            scare_cooldown: 60.0,
+            rumble: true,
+            view_bob: true,
+            view_bob_intensity: 1.0,
+            menu_flythrough: true,
+            intro_splash: true,
+            procedural_maze: false,
+            maze_seed: None,
+            mouse_sensitivity: 1.0,
+            invert_y: false,
+            music_volume: 0.6,
+            sfx_volume: 0.7,
+            fov: 60.0,
+            crosshair: CrosshairConfig::default(),
+            hud_scale: 1.0,
+            hints: HintFrequency::Normal,
+            hardcore: false,
+            exit_hum: None,
+            captions: false,
+            high_contrast: false,
+            narration: "off".to_string(),
+            resource_pack: packs::DEFAULT_PACK.to_string(),
+            unlimited_chalk: false,
+            store_pills: false,
+            search_time: search::DEFAULT_SEARCH_TIME,
+            door_close_delay: doors::DEFAULT_CLOSE_DELAY,
+            physics: Physics::default(),
+            idle: IdlePenalty::default(),
+            controls: Preset::Wasd,
+            keyboard_layout: None,
+        }
+    }
+}
+
+impl Config {
+    /// Parse settings from "key = value" lines. A malformed line, or a value that doesn't
+    /// parse or is out of range, doesn't reject the file: that setting keeps its default
+    /// and the problem is returned, naming the key and what it accepts
+    pub fn parse(content: &str) -> (Self, Vec<String>) {
+        let mut config = Self::default();
+        let mut problems = Vec::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=').map(|(k, v)| (k.trim(), v.trim().trim_matches('"'))) else {
+                problems.push(format!("line {}: expected 'key = value', got '{}'", line_number + 1, line));
+                continue;
+            };
+            match config.apply(key, value) {
+                Ok(true) => {}
+                Ok(false) => warn!("{}: unknown setting '{}' ignored", CONFIG_PATH, key),
+                Err(accepted) => problems.push(format!(
+                    "line {}: {} = '{}' is invalid, it takes {} (using the default)",
+                    line_number + 1,
+                    key,
+                    value,
+                    accepted
+                )),
+            }
+        }
+
+        (config, problems)
+    }
+
+    /// Set one setting from its settings.toml key and value. Ok(false) for an unknown key,
+    /// Err with what the key accepts when the value doesn't fit
+    fn apply(&mut self, key: &str, value: &str) -> Result<bool, String> {
+        let flag = || value.parse::<bool>().map_err(|_| "true or false".to_string());
+        let number = |min: f32, max: f32| match value.parse::<f32>() {
+            Ok(number) if (min..=max).contains(&number) => Ok(number),
+            _ => Err(format!("a number from {} to {}", min, max)),
+        };
+        let one_of = |names: &[&str]| format!("one of {}", names.join(", "));
+
+        match key {
+            "vsync" => self.vsync = flag()?,
+            "fps_cap" => self.fps_cap = FpsCap::parse(value).ok_or_else(|| one_of(&["30", "60", "120", "uncapped"]))?,
+            "frame_pacing" => self.frame_pacing = FramePacing::parse(value).ok_or_else(|| one_of(&["raylib", "precise"]))?,
+            "display_mode" => {
+                self.display_mode = DisplayMode::parse(value).ok_or_else(|| one_of(&["windowed", "borderless", "fullscreen"]))?
+            }
+            "monitor" => self.monitor = value.parse().ok().filter(|&monitor| monitor >= 0).ok_or("a monitor index from 0")?,
+            "quality" => self.quality = QualityPreset::parse(value).ok_or_else(|| one_of(&QualityPreset::ALL.map(QualityPreset::name)))?,
+            "theme" => self.theme = Theme::parse(value).ok_or_else(|| one_of(&["level0", "poolrooms"]))?,
+            "film_grain" => self.film_grain = flag()?,
+            "grain_cap" => self.grain_cap = number(0.0, 1.0)?,
+            "companion" => self.companion = flag()?,
+            "online_leaderboard" => self.online_leaderboard = flag()?,
+            "leaderboard_url" => self.leaderboard_url = value.to_string(),
+            "twitch_channel" => self.twitch_channel = value.to_string(),
+            "scare_cooldown" => self.scare_cooldown = number(MIN_SCARE_COOLDOWN, MAX_SCARE_COOLDOWN)?,
+            "rumble" => self.rumble = flag()?,
+            "view_bob" => self.view_bob = flag()?,
+            "view_bob_intensity" => self.view_bob_intensity = number(0.0, camera::MAX_BOB_INTENSITY)?,
+            "menu_flythrough" => self.menu_flythrough = flag()?,
+            "intro_splash" => self.intro_splash = flag()?,
+            "procedural_maze" => self.procedural_maze = flag()?,
+            "maze_seed" => {
+                self.maze_seed = match value {
+                    "random" => None,
+                    _ => Some(value.parse().map_err(|_| "random or a whole number")?),
+                }
+            }
+            "mouse_sensitivity" => self.mouse_sensitivity = number(options::MIN_SENSITIVITY, options::MAX_SENSITIVITY)?,
+            "invert_y" => self.invert_y = flag()?,
+            "music_volume" => self.music_volume = number(0.0, 1.0)?,
+            "sfx_volume" => self.sfx_volume = number(0.0, 1.0)?,
+            "fov" => self.fov = number(options::MIN_FOV, options::MAX_FOV)?,
+            "crosshair_style" => {
+                self.crosshair.style = CrosshairStyle::parse(value).ok_or_else(|| one_of(&["cross", "dot", "circle", "cross_dot", "off"]))?
+            }
+            "crosshair_size" => self.crosshair.size = number(1.0, MAX_CROSSHAIR_SIZE)?,
+            "crosshair_gap" => self.crosshair.gap = number(0.0, MAX_CROSSHAIR_SIZE)?,
+            "crosshair_thickness" => self.crosshair.thickness = number(1.0, 8.0)?,
+            "crosshair_color" => self.crosshair.color = crosshair::parse_color(value).ok_or("a color as #rrggbb or #rrggbbaa")?,
+            "crosshair_dynamic" => self.crosshair.dynamic = flag()?,
+            "hud_scale" => self.hud_scale = number(hud::MIN_SCALE, hud::MAX_SCALE)?,
+            "hints" => self.hints = HintFrequency::parse(value).ok_or_else(|| one_of(&["off", "rare", "normal", "often"]))?,
+            "hardcore" => self.hardcore = flag()?,
+            "exit_hum" => {
+                self.exit_hum = match value {
+                    "auto" => None,
+                    _ => Some(value.parse().map_err(|_| "auto, true or false")?),
+                }
+            }
+            "captions" => self.captions = flag()?,
+            "high_contrast" => self.high_contrast = flag()?,
+            "narration" => self.narration = value.to_string(),
+            "resource_pack" => {
+                if !packs::exists(value) {
+                    return Err(one_of(&packs::available().iter().map(String::as_str).collect::<Vec<_>>()));
+                }
+                self.resource_pack = value.to_string();
+            }
+            "unlimited_chalk" => self.unlimited_chalk = flag()?,
+            "store_pills" => self.store_pills = flag()?,
+            "search_time" => self.search_time = number(0.0, search::MAX_SEARCH_TIME)?,
+            "door_close_delay" => self.door_close_delay = number(0.0, doors::MAX_CLOSE_DELAY)?,
+            "move_acceleration" | "move_friction" | "max_speed" | "water_friction" => {
+                let (min, max) = Physics::range(key).unwrap_or((0.0, 0.0));
+                self.physics.set(key, number(min, max)?);
+            }
+            "idle_time" | "idle_damage" | "anxiety_strength" => {
+                let (min, max) = IdlePenalty::range(key).unwrap_or((0.0, 0.0));
+                if value != "off" {
+                    number(min, max).map_err(|accepted| format!("off or {}", accepted))?;
+                }
+                self.idle.set(key, value);
+            }
+            "controls" => self.controls = Preset::parse(value).ok_or_else(|| one_of(&["wasd", "esdf", "lefty"]))?,
+            "keyboard_layout" => {
+                self.keyboard_layout = Layout::parse(value).ok_or_else(|| one_of(&["auto", "qwerty", "azerty", "dvorak"]))?
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Load settings from disk, with any problems found in them (see parse)
+    pub fn load(path: &str) -> Result<(Self, Vec<String>), String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Load settings, using the defaults if the file doesn't exist or can't be read. Problems
+    /// with single settings are logged and returned so they can be shown
+    pub fn load_checked(path: &str) -> (Self, Vec<String>) {
+        if !Path::new(path).exists() {
+            info!("No {} found, using default settings", path);
+            return (Self::default(), Vec::new());
+        }
+
+        match Self::load(path) {
+            Ok((config, problems)) => {
+                for problem in &problems {
+                    warn!("{}: {}", path, problem);
+                }
+                (config, problems)
+            }
+            Err(e) => {
+                warn!("{} - using default settings", e);
+                (Self::default(), vec![e])
+            }
+        }
+    }
+
+    /// Load settings like load_checked, without the list of problems
+    pub fn load_or_default(path: &str) -> Self {
+        Self::load_checked(path).0
+    }
+
+    /// Serialize settings to "key = value" lines
+    pub fn serialize(&self) -> String {
+        let mut out = String::from("# Backrooms Doom settings\n");
+        out.push_str(&format!("vsync = {}\n", self.vsync));
+        out.push_str(&format!("fps_cap = \"{}\"\n", self.fps_cap.name()));
+        out.push_str(&format!("frame_pacing = \"{}\"\n", self.frame_pacing.name()));
+        out.push_str(&format!("display_mode = \"{}\"\n", self.display_mode.name()));
+        out.push_str(&format!("monitor = {}\n", self.monitor));
+        out.push_str(&format!("quality = \"{}\"\n", self.quality.name()));
+        out.push_str(&format!("theme = \"{}\"\n", self.theme.name()));
+        out.push_str(&format!("film_grain = {}\n", self.film_grain));
+        out.push_str(&format!("grain_cap = {}\n", self.grain_cap));
+        out.push_str(&format!("companion = {}\n", self.companion));
+        out.push_str(&format!("online_leaderboard = {}\n", self.online_leaderboard));
+        out.push_str(&format!("leaderboard_url = \"{}\"\n", self.leaderboard_url));
+        out.push_str(&format!("twitch_channel = \"{}\"\n", self.twitch_channel));
+        out.push_str(&format!("scare_cooldown = {}\n", self.scare_cooldown));
+        out.push_str(&format!("rumble = {}\n", self.rumble));
+        out.push_str(&format!("view_bob = {}\n", self.view_bob));
+        out.push_str(&format!("view_bob_intensity = {}\n", self.view_bob_intensity));
+        out.push_str(&format!("menu_flythrough = {}\n", self.menu_flythrough));
+        out.push_str(&format!("intro_splash = {}\n", self.intro_splash));
+        out.push_str(&format!("procedural_maze = {}\n", self.procedural_maze));
+        let maze_seed = self.maze_seed.map_or("random".to_string(), |seed| seed.to_string());
+        out.push_str(&format!("maze_seed = \"{}\"\n", maze_seed));
+        out.push_str(&format!("mouse_sensitivity = {}\n", self.mouse_sensitivity));
+        out.push_str(&format!("invert_y = {}\n", self.invert_y));
+        out.push_str(&format!("music_volume = {}\n", self.music_volume));
+        out.push_str(&format!("sfx_volume = {}\n", self.sfx_volume));
+        out.push_str(&format!("fov = {}\n", self.fov));
+        out.push_str(&format!("crosshair_style = \"{}\"\n", self.crosshair.style.name()));
+        out.push_str(&format!("crosshair_size = {}\n", self.crosshair.size));
+        out.push_str(&format!("crosshair_gap = {}\n", self.crosshair.gap));
+        out.push_str(&format!("crosshair_thickness = {}\n", self.crosshair.thickness));
+        out.push_str(&format!("crosshair_color = \"{}\"\n", crosshair::color_to_hex(self.crosshair.color)));
+        out.push_str(&format!("crosshair_dynamic = {}\n", self.crosshair.dynamic));
+        out.push_str(&format!("hud_scale = {}\n", self.hud_scale));
+        out.push_str(&format!("hints = \"{}\"\n", self.hints.name()));
+        out.push_str(&format!("hardcore = {}\n", self.hardcore));
+        let exit_hum = self.exit_hum.map_or("auto".to_string(), |on| on.to_string());
+        out.push_str(&format!("exit_hum = \"{}\"\n", exit_hum));
+        out.push_str(&format!("captions = {}\n", self.captions));
+        out.push_str(&format!("high_contrast = {}\n", self.high_contrast));
+        out.push_str(&format!("narration = \"{}\"\n", self.narration));
+        out.push_str(&format!("resource_pack = \"{}\"\n", self.resource_pack));
+        out.push_str(&format!("unlimited_chalk = {}\n", self.unlimited_chalk));
+        out.push_str(&format!("store_pills = {}\n", self.store_pills));
+        out.push_str(&format!("search_time = {}\n", self.search_time));
+        out.push_str(&format!("door_close_delay = {}\n", self.door_close_delay));
+        out.push_str(&format!("move_acceleration = {}\n", self.physics.acceleration));
+        out.push_str(&format!("move_friction = {}\n", self.physics.friction));
+        out.push_str(&format!("max_speed = {}\n", self.physics.max_speed));
+        out.push_str(&format!("water_friction = {}\n", self.physics.water_friction));
+        let idle_time = self.idle.threshold.map_or("off".to_string(), |time| time.to_string());
+        out.push_str(&format!("idle_time = \"{}\"\n", idle_time));
+        out.push_str(&format!("idle_damage = {}\n", self.idle.damage));
+        out.push_str(&format!("anxiety_strength = {}\n", self.idle.anxiety));
+        out.push_str(&format!("controls = \"{}\"\n", self.controls.name()));
+        let layout = self.keyboard_layout.map_or("auto", |layout| layout.name());
+        out.push_str(&format!("keyboard_layout = \"{}\"\n", layout));
+        out
+    }
+
+    /// Write settings to disk
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.serialize()).map_err(|e| format!("Failed to write {}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_values_keep_their_default_and_other_keys_still_load() {
+        let content = "fov = 200\nmusic_volume = NaN\nsfx_volume = 0.25\nhints = sometimes\nhardcore = true\n";
+        let (config, problems) = Config::parse(content);
+        let default = Config::default();
+
+        assert_eq!(config.fov, default.fov);
+        assert_eq!(config.music_volume, default.music_volume);
+        assert!(config.hints == default.hints);
+        assert_eq!(config.sfx_volume, 0.25);
+        assert!(config.hardcore);
+
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].starts_with("line 1: fov = '200'"));
+        assert!(problems[0].contains(&format!("a number from {} to {}", options::MIN_FOV, options::MAX_FOV)));
+        assert!(problems[1].starts_with("line 2: music_volume = 'NaN'"));
+        assert!(problems[1].contains("a number from 0 to 1"));
+        assert!(problems[2].starts_with("line 4: hints = 'sometimes'"));
+        assert!(problems[2].contains("one of off, rare, normal, often"));
+    }
+}
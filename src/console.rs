@@ -0,0 +1,120 @@
+// Developer console for Backrooms Doom
+// Toggled with the grave key (`), shows recent log output and accepts commands
+
+use crate::logger;
+use raylib::prelude::*;
+
+const VISIBLE_LINES: usize = 12;
+
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    history: Vec<String>,          // Previously submitted commands
+    history_index: Option<usize>,  // Position while browsing history with UP/DOWN
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            history_index: None,
+        }
+    }
+
+    /// Handle console input. Returns a submitted command line, if any
+    pub fn update(&mut self, rl: &mut RaylibHandle) -> Option<String> {
+        if rl.is_key_pressed(KeyboardKey::KEY_GRAVE) {
+            self.open = !self.open;
+            // Drop the queued '`' character so it doesn't end up in the input
+            while rl.get_char_pressed().is_some() {}
+            return None;
+        }
+
+        if !self.open {
+            return None;
+        }
+
+        // Text input
+        while let Some(c) = rl.get_char_pressed() {
+            if !c.is_control() && c != '`' {
+                self.input.push(c);
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) || rl.is_key_pressed_repeat(KeyboardKey::KEY_BACKSPACE) {
+            self.input.pop();
+        }
+
+        // Browse command history
+        if rl.is_key_pressed(KeyboardKey::KEY_UP) && !self.history.is_empty() {
+            let index = match self.history_index {
+                Some(i) => i.saturating_sub(1),
+                None => self.history.len() - 1,
+            };
+            self.history_index = Some(index);
+            self.input = self.history[index].clone();
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+            if let Some(i) = self.history_index {
+                if i + 1 < self.history.len() {
+                    self.history_index = Some(i + 1);
+                    self.input = self.history[i + 1].clone();
+                } else {
+                    self.history_index = None;
+                    self.input.clear();
+                }
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            let command = self.input.trim().to_string();
+            self.input.clear();
+            self.history_index = None;
+            if !command.is_empty() {
+                self.history.push(command.clone());
+                return Some(command);
+            }
+        }
+
+        None
+    }
+
+    /// Render the console overlay on the top part of the screen
+    pub fn render(&self, d: &mut RaylibDrawHandle) {
+        if !self.open {
+            return;
+        }
+
+        let screen_width = d.get_screen_width();
+        let font_size = 14;
+        let line_height = font_size + 4;
+        let height = (VISIBLE_LINES as i32 + 1) * line_height + 10;
+
+        d.draw_rectangle(0, 0, screen_width, height, Color::new(0, 0, 0, 210));
+        d.draw_line(0, height, screen_width, height, Color::new(255, 220, 0, 255));
+
+        // Recent log output
+        for (i, line) in logger::recent_lines(VISIBLE_LINES).iter().enumerate() {
+            let color = if line.contains("ERROR") {
+                Color::new(255, 90, 90, 255)
+            } else if line.contains("WARN") {
+                Color::new(255, 200, 50, 255)
+            } else {
+                Color::new(200, 200, 200, 255)
+            };
+            d.draw_text(line, 5, 5 + i as i32 * line_height, font_size, color);
+        }
+
+        // Input line with blinking cursor
+        let cursor = if (d.get_time() * 2.0) as i64 % 2 == 0 { "_" } else { "" };
+        d.draw_text(
+            &format!("> {}{}", self.input, cursor),
+            5,
+            height - line_height,
+            font_size,
+            Color::new(255, 220, 0, 255),
+        );
+    }
+}
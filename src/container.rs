@@ -0,0 +1,125 @@
+// Searchable containers for Backrooms Doom
+// Desks ('d') and filing cabinets ('c') placed in the maze. Searching takes a
+// few seconds with movement locked, makes noise and rolls the container's loot table.
+// A needle sweeps the progress circle: pressing E while it is inside the lucky
+// zone speeds the search up, missing makes a loud noise
+
+use crate::player::Vector2;
+
+pub const SEARCH_RANGE: f32 = 1.0;        // How close the player must be to start searching
+const SEARCH_DURATION: f32 = 2.5;         // Seconds to search without any bonus
+const NEEDLE_SPEED: f32 = 1.3;            // Needle turns per second
+const LUCKY_ZONE: f32 = 0.12;             // Size of the lucky zone (fraction of a turn)
+const LUCKY_BONUS: f32 = 0.35;            // Progress gained by hitting the lucky zone
+const SEARCH_NOISE_INTERVAL: f32 = 0.8;     // Seconds between rummaging noises
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ContainerKind {
+    Desk,
+    Cabinet,
+}
+
+impl ContainerKind {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ContainerKind::Desk => "desk",
+            ContainerKind::Cabinet => "filing cabinet",
+        }
+    }
+
+    /// Loot table rolled when searched
+    pub fn loot_table(self) -> &'static str {
+        match self {
+            ContainerKind::Desk => "desk",
+            ContainerKind::Cabinet => "cabinet",
+        }
+    }
+}
+
+pub struct Container {
+    pub pos: Vector2,
+    pub kind: ContainerKind,
+    pub searched: bool,
+}
+
+impl Container {
+    pub fn new(x: f32, y: f32, kind: ContainerKind) -> Self {
+        Self {
+            pos: Vector2::new(x, y),
+            kind,
+            searched: false,
+        }
+    }
+}
+
+/// Outcome of pressing E during a search
+pub enum RummageResult {
+    Lucky,  // Needle was in the zone, search sped up
+    Missed, // Needle was outside, loud noise
+    Spent,  // The one attempt was already used
+}
+
+/// A search in progress
+pub struct Search {
+    pub container: usize,  // Index into the container list
+    pub progress: f32,     // 0.0 - 1.0
+    pub needle: f32,       // Needle position as a fraction of a turn
+    pub zone_start: f32,   // Lucky zone start as a fraction of a turn
+    pub attempt_used: bool,
+    pub noise_timer: f32,
+    pub start_health: i32, // Taking damage interrupts the search
+}
+
+impl Search {
+    pub fn new(container: usize, start_health: i32) -> Self {
+        Self {
+            container,
+            progress: 0.0,
+            needle: 0.0,
+            zone_start: 0.3 + rand::random::<f32>() * 0.55,
+            attempt_used: false,
+            noise_timer: 0.0,
+            start_health,
+        }
+    }
+
+    /// Advance the search. Returns true when it is complete
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        self.progress = (self.progress + delta_time / SEARCH_DURATION).min(1.0);
+        self.needle = (self.needle + delta_time * NEEDLE_SPEED) % 1.0;
+        self.noise_timer += delta_time;
+        self.progress >= 1.0
+    }
+
+    /// True once every SEARCH_NOISE_INTERVAL, when the rummaging makes a sound
+    pub fn noise_due(&mut self) -> bool {
+        if self.noise_timer >= SEARCH_NOISE_INTERVAL {
+            self.noise_timer -= SEARCH_NOISE_INTERVAL;
+            return true;
+        }
+        false
+    }
+
+    /// Try to hit the lucky zone (one attempt per search)
+    pub fn rummage(&mut self) -> RummageResult {
+        if self.attempt_used {
+            return RummageResult::Spent;
+        }
+        self.attempt_used = true;
+
+        if self.in_zone() {
+            self.progress = (self.progress + LUCKY_BONUS).min(1.0);
+            RummageResult::Lucky
+        } else {
+            RummageResult::Missed
+        }
+    }
+
+    pub fn in_zone(&self) -> bool {
+        self.needle >= self.zone_start && self.needle <= self.zone_start + LUCKY_ZONE
+    }
+
+    pub fn zone(&self) -> (f32, f32) {
+        (self.zone_start, self.zone_start + LUCKY_ZONE)
+    }
+}
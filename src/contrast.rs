@@ -0,0 +1,18 @@
+// High-contrast navigation for Backrooms Doom
+// The yellow-on-yellow look that makes the Backrooms unsettling also makes them
+// hard to read for players with low vision. With `high_contrast` on, the
+// renderer takes a few alternate shading paths: a bright line runs along the
+// foot of every wall so the edges of the walkable floor stand out, the exit is
+// drawn unshaded with a strong glow spilling above and below it, and pills get
+// a wide halo so they show up from across a room. Off by default; the normal
+// look is untouched when it's off.
+
+use raylib::prelude::Color;
+
+pub const EDGE_COLOR: Color = Color::new(0, 255, 255, 255); // Where floor meets wall
+pub const EDGE_WIDTH: usize = 2;                            // Pixels, at the framebuffer's resolution
+pub const EXIT_GLOW_COLOR: Color = Color::new(120, 255, 140, 255);
+pub const EXIT_GLOW_REACH: f32 = 0.35;  // Glow above and below the exit, relative to its height on screen
+pub const EXIT_GLOW_STRENGTH: f32 = 0.8;
+pub const PILL_GLOW_RADIUS: f32 = 3.0;  // Halo radius relative to the pill's size on screen
+pub const PILL_GLOW_STRENGTH: f32 = 0.6;
@@ -0,0 +1,305 @@
+// Control presets for Backrooms Doom
+// Gameplay input goes through a table of actions -> keys picked by a preset
+// (WASD, ESDF or left-handed IJKL), so every input check and on-screen hint
+// follows the chosen layout.
+//
+// raylib reports keys by their physical position on a US keyboard, so WASD is
+// already the right cluster on AZERTY or Dvorak keyboards, only the printed
+// letters differ. The keyboard layout setting therefore changes the key names
+// shown in hints (Z Q S D on AZERTY). In "auto" it's detected by comparing the
+// key pressed with the character it typed.
+
+use raylib::prelude::*;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Forward,
+    Back,
+    Left,
+    Right,
+    Attack,
+    Interact, // Doors, containers, radio pickup, bracing
+    Peek,
+    Crouch,
+    Throw,     // Radio
+    Companion, // Send / recall the companion
+    Mark,      // Chalk mark
+    Flashlight,
+    UseItem,   // Selected inventory slot
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Preset {
+    Wasd,
+    Esdf,
+    Lefty, // IJKL under the right hand, mouse in the left
+}
+
+impl Preset {
+    pub const ALL: [Preset; 3] = [Preset::Wasd, Preset::Esdf, Preset::Lefty];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Wasd => "wasd",
+            Preset::Esdf => "esdf",
+            Preset::Lefty => "lefty",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.name() == value)
+    }
+
+    /// The next (step 1) or previous (-1) preset, wrapping around (options screen)
+    pub fn cycle(self, step: i32) -> Self {
+        let count = Self::ALL.len() as i32;
+        let index = Self::ALL.iter().position(|&preset| preset == self).unwrap_or(0) as i32;
+        Self::ALL[(index + step).rem_euclid(count) as usize]
+    }
+
+    /// Physical keys for an action, the first one is shown in hints
+    pub fn keys(self, action: Action) -> &'static [KeyboardKey] {
+        use KeyboardKey::*;
+        match (self, action) {
+            (Preset::Wasd, Action::Forward) => &[KEY_W],
+            (Preset::Wasd, Action::Back) => &[KEY_S],
+            (Preset::Wasd, Action::Left) => &[KEY_A],
+            (Preset::Wasd, Action::Right) => &[KEY_D],
+            (Preset::Wasd, Action::Interact) => &[KEY_E],
+            (Preset::Wasd, Action::Peek) => &[KEY_Q],
+            (Preset::Wasd, Action::Crouch) => &[KEY_C, KEY_LEFT_CONTROL],
+            (Preset::Wasd, Action::Throw) => &[KEY_G],
+            (Preset::Wasd, Action::Companion) => &[KEY_R],
+            (Preset::Wasd, Action::Mark) => &[KEY_X],
+            (Preset::Wasd, Action::Flashlight) => &[KEY_F],
+            (Preset::Wasd, Action::UseItem) => &[KEY_V],
+
+            (Preset::Esdf, Action::Forward) => &[KEY_E],
+            (Preset::Esdf, Action::Back) => &[KEY_D],
+            (Preset::Esdf, Action::Left) => &[KEY_S],
+            (Preset::Esdf, Action::Right) => &[KEY_F],
+            (Preset::Esdf, Action::Interact) => &[KEY_R],
+            (Preset::Esdf, Action::Peek) => &[KEY_W],
+            (Preset::Esdf, Action::Crouch) => &[KEY_A, KEY_LEFT_CONTROL],
+            (Preset::Esdf, Action::Throw) => &[KEY_T],
+            (Preset::Esdf, Action::Companion) => &[KEY_V],
+            (Preset::Esdf, Action::Mark) => &[KEY_C],
+            (Preset::Esdf, Action::Flashlight) => &[KEY_G],
+            (Preset::Esdf, Action::UseItem) => &[KEY_B],
+
+            (Preset::Lefty, Action::Forward) => &[KEY_I],
+            (Preset::Lefty, Action::Back) => &[KEY_K],
+            (Preset::Lefty, Action::Left) => &[KEY_J],
+            (Preset::Lefty, Action::Right) => &[KEY_L],
+            (Preset::Lefty, Action::Interact) => &[KEY_O],
+            (Preset::Lefty, Action::Peek) => &[KEY_U],
+            (Preset::Lefty, Action::Crouch) => &[KEY_N, KEY_RIGHT_CONTROL],
+            (Preset::Lefty, Action::Throw) => &[KEY_H],
+            (Preset::Lefty, Action::Companion) => &[KEY_P],
+            (Preset::Lefty, Action::Mark) => &[KEY_M],
+            (Preset::Lefty, Action::Flashlight) => &[KEY_Y],
+            (Preset::Lefty, Action::UseItem) => &[KEY_COMMA],
+
+            (_, Action::Attack) => &[KEY_SPACE],
+        }
+    }
+
+    /// Keys for the versus hunter (forward, back, turn left, turn right), on the other side
+    /// of the keyboard from the escaper
+    pub fn hunter_keys(self) -> [KeyboardKey; 4] {
+        use KeyboardKey::*;
+        match self {
+            Preset::Wasd | Preset::Esdf => [KEY_I, KEY_K, KEY_J, KEY_L],
+            Preset::Lefty => [KEY_E, KEY_D, KEY_S, KEY_F],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Layout {
+    Qwerty,
+    Azerty,
+    Dvorak,
+}
+
+impl Layout {
+    const ALL: [Layout; 3] = [Layout::Qwerty, Layout::Azerty, Layout::Dvorak];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::Qwerty => "qwerty",
+            Layout::Azerty => "azerty",
+            Layout::Dvorak => "dvorak",
+        }
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Layout::Qwerty => "QWERTY",
+            Layout::Azerty => "AZERTY",
+            Layout::Dvorak => "Dvorak",
+        }
+    }
+
+    /// "auto" parses to None (detect from typing)
+    pub fn parse(value: &str) -> Option<Option<Self>> {
+        if value == "auto" {
+            return Some(None);
+        }
+        Self::ALL.into_iter().find(|layout| layout.name() == value).map(Some)
+    }
+
+    /// The next (step 1) or previous (-1) layout setting after `layout`, auto (None) first
+    /// (options screen)
+    pub fn cycle(layout: Option<Self>, step: i32) -> Option<Self> {
+        let choices: Vec<Option<Self>> = std::iter::once(None).chain(Self::ALL.into_iter().map(Some)).collect();
+        let index = choices.iter().position(|&choice| choice == layout).unwrap_or(0) as i32;
+        choices[(index + step).rem_euclid(choices.len() as i32) as usize]
+    }
+
+    /// Character printed on the key that sits where `qwerty` is on a US keyboard
+    fn translate(self, qwerty: char) -> char {
+        const AZERTY: [(char, char); 6] = [('q', 'a'), ('w', 'z'), ('a', 'q'), ('z', 'w'), (';', 'm'), ('m', ',')];
+        const DVORAK: [(char, char); 30] = [
+            ('q', '\''), ('w', ','), ('e', '.'), ('r', 'p'), ('t', 'y'), ('y', 'f'), ('u', 'g'), ('i', 'c'),
+            ('o', 'r'), ('p', 'l'), ('a', 'a'), ('s', 'o'), ('d', 'e'), ('f', 'u'), ('g', 'i'), ('h', 'd'),
+            ('j', 'h'), ('k', 't'), ('l', 'n'), (';', 's'), ('z', ';'), ('x', 'q'), ('c', 'j'), ('v', 'k'),
+            ('b', 'x'), ('n', 'b'), ('m', 'm'), (',', 'w'), ('.', 'v'), ('/', 'z'),
+        ];
+        let table: &[(char, char)] = match self {
+            Layout::Qwerty => &[],
+            Layout::Azerty => &AZERTY,
+            Layout::Dvorak => &DVORAK,
+        };
+        table.iter().find(|(from, _)| *from == qwerty).map_or(qwerty, |(_, to)| *to)
+    }
+}
+
+/// Character a key types on a US keyboard, for keys that type one
+fn qwerty_char(key: KeyboardKey) -> Option<char> {
+    use KeyboardKey::*;
+    const LETTERS: [KeyboardKey; 26] = [
+        KEY_A, KEY_B, KEY_C, KEY_D, KEY_E, KEY_F, KEY_G, KEY_H, KEY_I, KEY_J, KEY_K, KEY_L, KEY_M,
+        KEY_N, KEY_O, KEY_P, KEY_Q, KEY_R, KEY_S, KEY_T, KEY_U, KEY_V, KEY_W, KEY_X, KEY_Y, KEY_Z,
+    ];
+    if let Some(index) = LETTERS.iter().position(|letter| *letter == key) {
+        return Some((b'a' + index as u8) as char);
+    }
+    match key {
+        KEY_SEMICOLON => Some(';'),
+        KEY_COMMA => Some(','),
+        KEY_PERIOD => Some('.'),
+        KEY_SLASH => Some('/'),
+        KEY_APOSTROPHE => Some('\''),
+        _ => None,
+    }
+}
+
+pub struct Controls {
+    pub preset: Preset,
+    pub layout: Option<Layout>,   // None = auto
+    pub detected: Option<Layout>, // Guessed from typing, used when the layout is auto
+}
+
+impl Controls {
+    pub fn new(preset: Preset, layout: Option<Layout>) -> Self {
+        Self { preset, layout, detected: None }
+    }
+
+    pub fn down(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.preset.keys(action).iter().any(|key| rl.is_key_down(*key))
+    }
+
+    pub fn pressed(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.preset.keys(action).iter().any(|key| rl.is_key_pressed(*key))
+    }
+
+    /// Any movement key held
+    pub fn moving(&self, rl: &RaylibHandle) -> bool {
+        [Action::Forward, Action::Back, Action::Left, Action::Right]
+            .into_iter()
+            .any(|action| self.down(rl, action))
+    }
+
+    /// Any movement key pressed this frame
+    pub fn move_pressed(&self, rl: &RaylibHandle) -> bool {
+        [Action::Forward, Action::Back, Action::Left, Action::Right]
+            .into_iter()
+            .any(|action| self.pressed(rl, action))
+    }
+
+    /// Layout key names are shown for
+    pub fn active_layout(&self) -> Layout {
+        self.layout.or(self.detected).unwrap_or(Layout::Qwerty)
+    }
+
+    /// Name of the key bound to an action, as printed on the player's keyboard
+    pub fn label(&self, action: Action) -> String {
+        Self::key_label(self.preset.keys(action)[0], self.active_layout())
+    }
+
+    /// Movement keys in hint form, e.g. "WASD" or "ZQSD"
+    pub fn move_label(&self) -> String {
+        [Action::Forward, Action::Left, Action::Back, Action::Right]
+            .into_iter()
+            .map(|action| self.label(action))
+            .collect()
+    }
+
+    /// Versus hunter keys in hint form: (move, turn), e.g. ("I/K", "J/L")
+    pub fn hunter_labels(&self) -> (String, String) {
+        let [forward, back, left, right] = self.preset.hunter_keys().map(|key| Self::key_label(key, self.active_layout()));
+        (format!("{}/{}", forward, back), format!("{}/{}", left, right))
+    }
+
+    fn key_label(key: KeyboardKey, layout: Layout) -> String {
+        match qwerty_char(key) {
+            Some(c) => layout.translate(c).to_ascii_uppercase().to_string(),
+            None => match key {
+                KeyboardKey::KEY_SPACE => "SPACE".to_string(),
+                KeyboardKey::KEY_LEFT_CONTROL => "L-CTRL".to_string(),
+                KeyboardKey::KEY_RIGHT_CONTROL => "R-CTRL".to_string(),
+                _ => "?".to_string(),
+            },
+        }
+    }
+
+    /// Watch typed keys to guess the keyboard layout: a key at the US 'W' position typing 'z'
+    /// means AZERTY, typing ',' means Dvorak. Must not run while the console reads text.
+    /// Returns whether any key was pressed this frame
+    pub fn detect_layout(&mut self, rl: &mut RaylibHandle) -> bool {
+        // Drain both queues, only a single typing key with a single character can be paired up
+        let mut keys = Vec::new();
+        let mut any_key = false;
+        while let Some(key) = rl.get_key_pressed() {
+            any_key = true;
+            keys.extend(qwerty_char(key));
+        }
+        let mut typed = Vec::new();
+        while let Some(c) = rl.get_char_pressed() {
+            typed.push(c);
+        }
+        let (&[qwerty], &[typed]) = (&keys[..], &typed[..]) else {
+            return any_key;
+        };
+
+        // Only keys that differ between layouts tell them apart
+        let typed = typed.to_ascii_lowercase();
+        let matches: Vec<Layout> = Layout::ALL.into_iter().filter(|layout| layout.translate(qwerty) == typed).collect();
+        if let [layout] = matches[..] {
+            if self.detected != Some(layout) {
+                log::info!("Detected {} keyboard layout", layout.display_name());
+                self.detected = Some(layout);
+            }
+        }
+        any_key
+    }
+
+    /// A detected layout that differs from the one chosen in the settings
+    pub fn suggestion(&self) -> Option<Layout> {
+        match (self.layout, self.detected) {
+            (Some(chosen), Some(detected)) if chosen != detected => Some(detected),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// Crash handling for Backrooms Doom
+// Captures panic details and writes them to a crash log before exiting
+
+use log::error;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CRASH_LOG_DIR: &str = "logs";
+
+// Last panic captured by the hook (message + location)
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Install a panic hook that records the panic message so it can be
+/// written to the crash log once the frame has been unwound
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = info.payload().downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "unknown panic payload".to_string()
+        };
+
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "unknown location".to_string());
+
+        let report = format!("{} (at {})", message, location);
+        error!("PANIC: {}", report);
+
+        if let Ok(mut last) = LAST_PANIC.lock() {
+            *last = Some(report);
+        }
+    }));
+}
+
+/// Take the last recorded panic report (if any)
+pub fn take_last_panic() -> Option<String> {
+    LAST_PANIC.lock().ok().and_then(|mut last| last.take())
+}
+
+/// Write a crash log to logs/crash_<timestamp>.log and return its path
+pub fn write_crash_log(panic_report: &str, context: &[String]) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(CRASH_LOG_DIR)?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = PathBuf::from(CRASH_LOG_DIR).join(format!("crash_{}.log", timestamp));
+
+    let mut contents = String::new();
+    contents.push_str("Backrooms Doom crash report\n");
+    contents.push_str(&format!("Version: {}\n", env!("CARGO_PKG_VERSION")));
+    contents.push_str(&format!("Timestamp: {}\n", timestamp));
+    contents.push_str(&format!("Panic: {}\n", panic_report));
+    contents.push_str("\nGame context:\n");
+    for line in context {
+        contents.push_str(&format!("  {}\n", line));
+    }
+
+    fs::write(&path, contents)?;
+    Ok(path)
+}
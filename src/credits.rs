@@ -0,0 +1,91 @@
+// Credits for Backrooms Doom
+// The credits roll is defined in assets/credits.txt so names can be added without
+// recompiling: "[Section]" headings (Code, Art, Audio, Special Thanks...) each
+// followed by one line per credit. The screen is opened with C from the menu and
+// rolls automatically after escaping; ENTER or BACKSPACE skips it, holding SPACE
+// speeds it up, and it returns to the menu once the last line has scrolled off.
+
+use log::warn;
+use std::fs;
+
+pub const CREDITS_PATH: &str = "assets/credits.txt";
+
+pub const TITLE_SIZE: i32 = 28;     // Section heading font size
+pub const LINE_SIZE: i32 = 20;      // Credit line font size
+pub const LINE_SPACING: i32 = 8;    // Gap below every line
+pub const SECTION_GAP: i32 = 40;    // Gap between sections
+const SCROLL_SPEED: f32 = 40.0;     // Pixels per second
+const FAST_FORWARD: f32 = 5.0;      // Scroll multiplier while SPACE is held
+
+pub struct CreditSection {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+pub struct Credits {
+    pub sections: Vec<CreditSection>,
+    pub scroll: f32, // Pixels scrolled since the roll started
+}
+
+impl Credits {
+    /// Parse "[Section]" headings each followed by credit lines
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut sections: Vec<CreditSection> = Vec::new();
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(title) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                sections.push(CreditSection { title: title.trim().to_string(), lines: Vec::new() });
+                continue;
+            }
+            let Some(section) = sections.last_mut() else {
+                return Err(format!("line {}: credit outside of a [section]", line_number + 1));
+            };
+            section.lines.push(line.to_string());
+        }
+        if sections.is_empty() {
+            return Err("no [sections]".to_string());
+        }
+        Ok(Self { sections, scroll: 0.0 })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Self::parse(&content).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Credits used when assets/credits.txt is missing or invalid
+    pub fn builtin() -> Self {
+        Self::parse("[Code]\nnadissa1508\n[Built With]\nRust\nraylib\n").expect("built-in credits are valid")
+    }
+
+    pub fn load_or_builtin(path: &str) -> Self {
+        Self::load(path).unwrap_or_else(|e| {
+            warn!("{} - using built-in credits", e);
+            Self::builtin()
+        })
+    }
+
+    /// Start the roll from the bottom of the screen
+    pub fn restart(&mut self) {
+        self.scroll = 0.0;
+    }
+
+    /// Scroll on. Returns true once everything has scrolled off the top
+    pub fn update(&mut self, delta_time: f32, fast_forward: bool, screen_height: i32) -> bool {
+        let speed = if fast_forward { SCROLL_SPEED * FAST_FORWARD } else { SCROLL_SPEED };
+        self.scroll += speed * delta_time;
+        self.scroll > (self.height() + screen_height) as f32
+    }
+
+    /// Height of the whole roll in pixels
+    pub fn height(&self) -> i32 {
+        self.sections
+            .iter()
+            .map(|section| TITLE_SIZE + LINE_SPACING + section.lines.len() as i32 * (LINE_SIZE + LINE_SPACING))
+            .sum::<i32>()
+            + SECTION_GAP * (self.sections.len() as i32 - 1).max(0)
+    }
+}
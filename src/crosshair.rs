@@ -0,0 +1,122 @@
+// Crosshair for Backrooms Doom
+// The crosshair's look comes from settings (style, size, gap, thickness, color),
+// while CrosshairState tracks what changes frame to frame: dynamic spread that
+// opens up while moving and kicks out on each swing, and the hit marker flashed
+// when an attack connects. Both are drawn by UI::render_crosshair.
+
+use raylib::prelude::*;
+
+const MOVE_SPREAD: f32 = 0.5;      // Spread while walking (fraction of the size)
+const ATTACK_KICK: f32 = 1.0;      // Spread added by a swing
+const MAX_SPREAD: f32 = 1.5;
+const SPREAD_RECOVERY: f32 = 6.0;  // How fast spread settles, per second
+pub const HIT_MARKER_TIME: f32 = 0.2; // Seconds a hit marker stays up
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CrosshairStyle {
+    Cross,
+    Dot,
+    Circle,
+    CrossDot, // Cross with a dot in the gap
+    Off,
+}
+
+impl CrosshairStyle {
+    const ALL: [CrosshairStyle; 5] = [
+        CrosshairStyle::Cross,
+        CrosshairStyle::Dot,
+        CrosshairStyle::Circle,
+        CrosshairStyle::CrossDot,
+        CrosshairStyle::Off,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CrosshairStyle::Cross => "cross",
+            CrosshairStyle::Dot => "dot",
+            CrosshairStyle::Circle => "circle",
+            CrosshairStyle::CrossDot => "cross_dot",
+            CrosshairStyle::Off => "off",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.name() == value)
+    }
+
+    /// The style `step` places further along the list (negative goes back), wrapping around
+    pub fn cycle(self, step: i32) -> Self {
+        let count = Self::ALL.len() as i32;
+        let index = Self::ALL.iter().position(|&style| style == self).unwrap_or(0) as i32;
+        Self::ALL[(index + step).rem_euclid(count) as usize]
+    }
+}
+
+/// How the crosshair looks (stored in settings.toml)
+#[derive(Clone, Copy)]
+pub struct CrosshairConfig {
+    pub style: CrosshairStyle,
+    pub size: f32,      // Arm length in pixels
+    pub gap: f32,       // Empty space around the center in pixels
+    pub thickness: f32, // Line thickness in pixels
+    pub color: Color,
+    pub dynamic: bool,  // Spread while moving / attacking
+}
+
+impl Default for CrosshairConfig {
+    fn default() -> Self {
+        Self {
+            style: CrosshairStyle::Cross,
+            size: 10.0,
+            gap: 0.0,
+            thickness: 1.0,
+            color: Color::WHITE,
+            dynamic: true,
+        }
+    }
+}
+
+/// "#rrggbb" or "#rrggbbaa"
+pub fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::new(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+pub fn color_to_hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}{:02x}", color.r, color.g, color.b, color.a)
+}
+
+/// Per-frame crosshair state
+pub struct CrosshairState {
+    pub spread: f32,     // 0.0 = resting, scales the gap by the crosshair size
+    pub hit_marker: f32, // Seconds left on the hit marker
+    pub kill: bool,      // The last hit was a kill (marker drawn red)
+}
+
+impl CrosshairState {
+    pub fn new() -> Self {
+        Self { spread: 0.0, hit_marker: 0.0, kill: false }
+    }
+
+    pub fn update(&mut self, moving: bool, delta_time: f32) {
+        let rest = if moving { MOVE_SPREAD } else { 0.0 };
+        self.spread += (rest - self.spread) * (SPREAD_RECOVERY * delta_time).min(1.0);
+        self.hit_marker = (self.hit_marker - delta_time).max(0.0);
+    }
+
+    /// Kick the crosshair open for a swing
+    pub fn attacked(&mut self) {
+        self.spread = (self.spread + ATTACK_KICK).min(MAX_SPREAD);
+    }
+
+    /// Flash the hit marker for an attack that connected
+    pub fn hit(&mut self, kill: bool) {
+        self.hit_marker = HIT_MARKER_TIME;
+        self.kill = kill;
+    }
+}
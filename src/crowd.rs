@@ -0,0 +1,111 @@
+// Crowd movement for Backrooms Doom
+// Keeps a group of enemies heading the same way from walking single file.
+// Once a second, enemies walking somewhere get a route planned nearest-first
+// on a shared occupancy grid: tiles already on another enemy's route cost
+// extra, so the next one takes a parallel corridor when the detour is short
+// and the group closes in from several sides. Up close, a separation push
+// keeps them from standing on top of each other. Between those group plans an
+// enemy whose target moves to another cell (a new noise, the player stepping
+// out of sight) gets a route right away instead of walking into a wall for up
+// to a second.
+
+use crate::enemy::Enemy;
+use crate::maze::Maze;
+use crate::pathfinding;
+
+const REPLAN_TIME: f32 = 1.0;         // Seconds between route updates
+const OCCUPIED_COST: u32 = 3;         // Extra steps a tile on another enemy's route costs
+const SEPARATION_RADIUS: f32 = 0.8;   // Tiles
+const SEPARATION_STRENGTH: f32 = 0.8; // Push at zero distance, relative to walking speed
+
+pub struct Crowd {
+    replan_timer: f32,
+    pub occupancy: Vec<u32>, // Routes planned through each tile, indexed by y * width + x
+}
+
+impl Crowd {
+    pub fn new() -> Self {
+        Self { replan_timer: 0.0, occupancy: Vec::new() }
+    }
+
+    /// Re-plan the routes of enemies walking somewhere, closest to their target first
+    pub fn update(&mut self, maze: &Maze, enemies: &mut [Enemy], delta_time: f32) {
+        self.replan_timer -= delta_time;
+        if self.replan_timer > 0.0 {
+            // Routes are cached for their target cell, only moved targets need a new one
+            for enemy in enemies.iter_mut().filter(|enemy| enemy.is_alive()) {
+                let Some(target) = enemy.investigate else {
+                    continue;
+                };
+                if enemy.route_goal != Some(pathfinding::cell(maze, (target.x, target.y))) {
+                    self.plan(maze, enemy);
+                }
+            }
+            return;
+        }
+        self.replan_timer = REPLAN_TIME;
+
+        let mut walking: Vec<(usize, f32)> = enemies
+            .iter()
+            .enumerate()
+            .filter(|(_, enemy)| enemy.is_alive())
+            .filter_map(|(i, enemy)| {
+                let target = enemy.investigate?;
+                Some((i, (target.x - enemy.pos.x).powi(2) + (target.y - enemy.pos.y).powi(2)))
+            })
+            .collect();
+        walking.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        self.occupancy = vec![0; maze.width * maze.height];
+        for (i, _) in walking {
+            self.plan(maze, &mut enemies[i]);
+        }
+    }
+
+    /// Plan a route to the enemy's target around the routes already planned
+    fn plan(&mut self, maze: &Maze, enemy: &mut Enemy) {
+        let Some(target) = enemy.investigate else {
+            return;
+        };
+        if self.occupancy.len() != maze.width * maze.height {
+            self.occupancy = vec![0; maze.width * maze.height];
+        }
+        let occupancy = &self.occupancy;
+        let route = pathfinding::find_path_weighted(maze, (enemy.pos.x, enemy.pos.y), (target.x, target.y), |index| {
+            occupancy[index] * OCCUPIED_COST
+        });
+
+        // No route (closed doors in the way): walk straight at it and push the door
+        enemy.route = route.unwrap_or_default();
+        enemy.route_goal = Some(pathfinding::cell(maze, (target.x, target.y)));
+        for &(x, y) in &enemy.route {
+            let index = (y / maze.tile_size) as usize * maze.width + (x / maze.tile_size) as usize;
+            self.occupancy[index] += 1;
+        }
+    }
+}
+
+/// Push for the enemy at `index` away from other living enemies close by
+pub fn separation(enemies: &[Enemy], index: usize) -> (f32, f32) {
+    let me = &enemies[index];
+    let mut push = (0.0, 0.0);
+    for (i, other) in enemies.iter().enumerate() {
+        if i == index || !other.is_alive() {
+            continue;
+        }
+        let (dx, dy) = (me.pos.x - other.pos.x, me.pos.y - other.pos.y);
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance >= SEPARATION_RADIUS {
+            continue;
+        }
+        let strength = (1.0 - distance / SEPARATION_RADIUS) * SEPARATION_STRENGTH;
+        if distance > 0.001 {
+            push.0 += dx / distance * strength;
+            push.1 += dy / distance * strength;
+        } else {
+            // Exactly on top of each other: split along the index order
+            push.0 += if index < i { strength } else { -strength };
+        }
+    }
+    push
+}
@@ -0,0 +1,98 @@
+// Game-over sequences for Backrooms Doom
+// Each way of losing gets its own couple of seconds before the game-over
+// screen, picked from what ended the run:
+// - the timer running out shuts the lights off, band by band from the top;
+// - being caught by an entity (its strike, or the hunter in versus) throws its
+//   sprite at the camera for a frame and drowns the view in static;
+// - losing the last health to anything else collapses the player: the view
+//   tilts and drops towards the floor as it goes dark.
+// Each effect is a few keyframe tracks (see timeline.rs) applied in the
+// post-processing chain; ENTER skips to the game-over screen.
+
+use crate::timeline;
+
+pub const LIGHT_BANDS: usize = 6; // Rows of ceiling lights going out one after another
+
+const LIGHTS_OUT: [(f32, f32); 2] = [(0.3, 0.0), (1.8, 1.0)];                // Share of the bands dark
+const LUNGE: [(f32, f32); 3] = [(0.0, 0.4), (0.25, 2.5), (0.3, 0.0)];        // Sprite size (screen heights), gone after the frame
+const STATIC: [(f32, f32); 4] = [(0.2, 0.0), (0.3, 1.0), (1.2, 1.0), (1.8, 0.6)];
+const TILT: [(f32, f32); 2] = [(0.0, 0.0), (1.2, 0.35)];                     // Roll in radians
+const DROP: [(f32, f32); 2] = [(0.2, 0.0), (1.0, 0.3)];                      // Eye height lost, screen heights
+const FADE: [(f32, f32); 2] = [(0.6, 1.0), (1.8, 0.3)];                      // Brightness while collapsing
+const LENGTH: f32 = 2.0; // Seconds before the game-over screen shows
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum DeathCause {
+    Timer,  // The clock ran out
+    Caught, // An entity got the player
+    Health, // Out of health from anything else
+}
+
+impl DeathCause {
+    /// Title of the game-over screen
+    pub fn title(self) -> &'static str {
+        match self {
+            DeathCause::Timer => "TIME'S UP!",
+            DeathCause::Caught => "CAUGHT!",
+            DeathCause::Health => "YOU COLLAPSED",
+        }
+    }
+
+    /// Why the run ended, under the title
+    pub fn reason(self) -> &'static str {
+        match self {
+            DeathCause::Timer => "The clock ran out. You've been lost in the Backrooms forever...",
+            DeathCause::Caught => "Something in the halls got to you first.",
+            DeathCause::Health => "Your body gave out before you found the exit.",
+        }
+    }
+}
+
+pub struct DeathScene {
+    pub cause: DeathCause,
+    time: f32,
+}
+
+impl DeathScene {
+    pub fn new(cause: DeathCause) -> Self {
+        Self { cause, time: 0.0 }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+
+    /// Whether the sequence has played through
+    pub fn finished(&self) -> bool {
+        self.time >= LENGTH
+    }
+
+    /// Jump to the end of the sequence
+    pub fn skip(&mut self) {
+        self.time = self.time.max(LENGTH);
+    }
+
+    /// Light bands switched off, from the top (0 - LIGHT_BANDS)
+    pub fn bands_off(&self) -> usize {
+        (timeline::sample(&LIGHTS_OUT, self.time) * LIGHT_BANDS as f32) as usize
+    }
+
+    /// Size of the lunging sprite in screen heights, 0.0 once it has hit
+    pub fn lunge(&self) -> f32 {
+        timeline::sample(&LUNGE, self.time)
+    }
+
+    /// Strength of the static after the lunge (0.0 - 1.0)
+    pub fn static_level(&self) -> f32 {
+        timeline::sample(&STATIC, self.time)
+    }
+
+    /// (roll in radians, drop in screen heights, brightness) of the collapse
+    pub fn collapse(&self) -> (f32, f32, f32) {
+        (
+            timeline::sample(&TILT, self.time),
+            timeline::sample(&DROP, self.time),
+            timeline::sample(&FADE, self.time),
+        )
+    }
+}
@@ -0,0 +1,191 @@
+// Decals for Backrooms Doom
+// Flat marks left on the floor or on a wall: drawn into the 3D view as blended
+// shapes (lying flat on the floor, or upright at eye level on a wall), chalk
+// also as small icons on the minimap. Surface rules decide where each kind can
+// go and how long it lasts there: footprints only stay on dry floor, chalk
+// washes off in water after a while, the rest stays for the whole run.
+
+use crate::guard;
+use crate::maze::Maze;
+use raylib::prelude::*;
+
+const MAX_DECALS: usize = 256;
+const WALL_OFFSET: f32 = 0.05; // Wall decals sit this far in front of the wall so they aren't hidden by it
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Surface {
+    Floor,
+    Wall,
+    Water, // Floor covered by a puddle ('~' tiles)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DecalKind {
+    Chalk,
+    Footprint,    // Left by enemies
+    WetFootprint, // Left by the player after walking through water
+    Puddle,       // Marks out water tiles
+}
+
+impl DecalKind {
+    /// Whether this kind of decal can be left on a surface
+    fn sticks_to(self, surface: Surface) -> bool {
+        match self {
+            DecalKind::Chalk => true,
+            DecalKind::Footprint | DecalKind::WetFootprint => surface == Surface::Floor,
+            DecalKind::Puddle => surface == Surface::Water,
+        }
+    }
+
+    /// Seconds before the decal is gone from a surface, None = stays for the whole run
+    fn lifetime(self, surface: Surface) -> Option<f32> {
+        match (self, surface) {
+            (DecalKind::Chalk, Surface::Water) => Some(10.0),
+            (DecalKind::Footprint, _) => Some(30.0),
+            (DecalKind::WetFootprint, _) => Some(20.0),
+            _ => None,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            DecalKind::Chalk => Color::new(235, 235, 225, 255),
+            DecalKind::Footprint => Color::new(35, 28, 20, 255),
+            DecalKind::WetFootprint => Color::new(60, 80, 105, 255),
+            DecalKind::Puddle => Color::new(70, 95, 120, 255),
+        }
+    }
+
+    /// Width in tiles
+    pub fn size(self) -> f32 {
+        match self {
+            DecalKind::Chalk => 0.35,
+            DecalKind::Footprint | DecalKind::WetFootprint => 0.12,
+            DecalKind::Puddle => 0.9,
+        }
+    }
+
+    /// Drawn on the minimap
+    pub fn on_minimap(self) -> bool {
+        self == DecalKind::Chalk
+    }
+
+    /// Whether the point (u, v), each -1.0 - 1.0 across the decal, is part of the mark
+    pub fn covers(self, u: f32, v: f32) -> bool {
+        match self {
+            // An X
+            DecalKind::Chalk => u * u + v * v <= 1.0 && ((u - v).abs() < 0.3 || (u + v).abs() < 0.3),
+            // A narrow sole
+            DecalKind::Footprint | DecalKind::WetFootprint => (u / 0.45).powi(2) + v * v <= 1.0,
+            DecalKind::Puddle => u * u + v * v <= 1.0,
+        }
+    }
+}
+
+pub struct Decal {
+    pub x: f32,
+    pub y: f32,
+    pub kind: DecalKind,
+    pub surface: Surface,
+    pub age: f32,
+}
+
+impl Decal {
+    /// Opacity from 0.0 - 1.0, fading out over its lifetime
+    pub fn alpha(&self) -> f32 {
+        match self.kind.lifetime(self.surface) {
+            Some(lifetime) => (1.0 - self.age / lifetime).clamp(0.0, 1.0),
+            None => 1.0,
+        }
+    }
+}
+
+pub struct Decals {
+    pub list: Vec<Decal>,
+}
+
+impl Decals {
+    pub fn new() -> Self {
+        Self { list: Vec::new() }
+    }
+
+    /// Puddles on every water tile of a freshly loaded maze
+    pub fn for_maze(maze: &Maze) -> Self {
+        let mut decals = Self::new();
+        for (y, row) in maze.map.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                if *tile == '~' {
+                    let center = ((x as f32 + 0.5) * maze.tile_size, (y as f32 + 0.5) * maze.tile_size);
+                    decals.add_floor(DecalKind::Puddle, maze, center.0, center.1);
+                }
+            }
+        }
+        decals
+    }
+
+    /// Add a decal on the floor at (x, y), if it can go on that kind of floor
+    pub fn add_floor(&mut self, kind: DecalKind, maze: &Maze, x: f32, y: f32) {
+        let surface = if maze.is_water(x, y) { Surface::Water } else { Surface::Floor };
+        self.push(Decal { x, y, kind, surface, age: 0.0 });
+    }
+
+    /// Add a decal on the wall hit at (x, y), seen from (from_x, from_y)
+    pub fn add_wall(&mut self, kind: DecalKind, (x, y): (f32, f32), (from_x, from_y): (f32, f32)) {
+        let (dir_x, dir_y) = guard::normalize(x - from_x, y - from_y);
+        let (x, y) = (x - dir_x * WALL_OFFSET, y - dir_y * WALL_OFFSET);
+        self.push(Decal { x, y, kind, surface: Surface::Wall, age: 0.0 });
+    }
+
+    fn push(&mut self, decal: Decal) {
+        if !decal.kind.sticks_to(decal.surface) {
+            return;
+        }
+        // Make room by dropping the oldest fading decal, or the oldest one if none fade
+        if self.list.len() >= MAX_DECALS {
+            let oldest = self.list.iter().position(|d| d.kind.lifetime(d.surface).is_some()).unwrap_or(0);
+            self.list.remove(oldest);
+        }
+        self.list.push(decal);
+    }
+
+    /// Drop decals until at most `max` are left (entity budget, see budget.rs): fading
+    /// footprints go first, then puddles, chalk marks last, oldest first within a kind
+    pub fn trim(&mut self, max: usize) {
+        let importance = |kind: DecalKind| match kind {
+            DecalKind::Footprint => 0,
+            DecalKind::WetFootprint => 1,
+            DecalKind::Puddle => 2,
+            DecalKind::Chalk => 3,
+        };
+        while self.list.len() > max {
+            let Some(least) = (0..self.list.len()).min_by(|&a, &b| {
+                let (a, b) = (&self.list[a], &self.list[b]);
+                importance(a.kind).cmp(&importance(b.kind)).then(b.age.total_cmp(&a.age))
+            }) else {
+                break;
+            };
+            self.list.remove(least);
+        }
+    }
+
+    /// Age decals and drop the ones that have faded out
+    pub fn update(&mut self, delta_time: f32) {
+        for decal in &mut self.list {
+            decal.age += delta_time;
+        }
+        self.list.retain(|decal| decal.kind.lifetime(decal.surface).is_none_or(|lifetime| decal.age < lifetime));
+    }
+
+    /// Freshest decal of a kind within `range` of (x, y) but further than `min_distance`,
+    /// younger than `max_age`
+    pub fn freshest(&self, kind: DecalKind, (x, y): (f32, f32), min_distance: f32, range: f32, max_age: f32) -> Option<&Decal> {
+        self.list
+            .iter()
+            .filter(|decal| decal.kind == kind && decal.age < max_age)
+            .filter(|decal| {
+                let distance = ((decal.x - x).powi(2) + (decal.y - y).powi(2)).sqrt();
+                distance > min_distance && distance <= range
+            })
+            .min_by(|a, b| a.age.total_cmp(&b.age))
+    }
+}
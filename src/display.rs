@@ -0,0 +1,79 @@
+// Display modes for Backrooms Doom
+// Switches between windowed, borderless windowed and exclusive fullscreen
+// on a chosen monitor. raylib only offers toggles, so the applied mode is tracked here
+
+use crate::config::{Config, DisplayMode};
+use log::{info, warn};
+use raylib::prelude::*;
+
+pub struct Display {
+    mode: DisplayMode,           // Mode currently applied to the window
+    windowed_size: (i32, i32),   // Size to restore when going back to windowed
+}
+
+impl Display {
+    pub fn new(windowed_width: i32, windowed_height: i32) -> Self {
+        Self {
+            mode: DisplayMode::Windowed,
+            windowed_size: (windowed_width, windowed_height),
+        }
+    }
+
+    /// Apply the display mode and monitor from the settings
+    pub fn apply(&mut self, rl: &mut RaylibHandle, config: &Config) {
+        let monitor_count = get_monitor_count();
+        let monitor = if monitor_count > 0 {
+            if config.monitor >= monitor_count {
+                warn!("Monitor {} not found, using monitor 0", config.monitor);
+                0
+            } else {
+                config.monitor.max(0)
+            }
+        } else {
+            0
+        };
+
+        // Leave the current mode first, the raylib calls are toggles
+        match self.mode {
+            DisplayMode::Fullscreen => rl.toggle_fullscreen(),
+            DisplayMode::Borderless => rl.toggle_borderless_windowed(),
+            DisplayMode::Windowed => {}
+        }
+        rl.set_window_size(self.windowed_size.0, self.windowed_size.1);
+
+        // Move to the chosen monitor (centers the window there when windowed)
+        if monitor_count > 0 {
+            rl.set_window_monitor(monitor);
+        }
+
+        match config.display_mode {
+            DisplayMode::Fullscreen => {
+                if monitor_count > 0 {
+                    rl.set_window_size(get_monitor_width(monitor), get_monitor_height(monitor));
+                }
+                rl.toggle_fullscreen();
+            }
+            DisplayMode::Borderless => rl.toggle_borderless_windowed(),
+            DisplayMode::Windowed => {}
+        }
+        self.mode = config.display_mode;
+
+        info!("Display mode: {} on monitor {}", self.mode.name(), monitor);
+    }
+
+    /// Describe the connected monitors for the console
+    pub fn monitor_list() -> Vec<String> {
+        (0..get_monitor_count())
+            .map(|i| {
+                format!(
+                    "{}: {} ({}x{} @ {}Hz)",
+                    i,
+                    get_monitor_name(i).unwrap_or_else(|_| "unknown".to_string()),
+                    get_monitor_width(i),
+                    get_monitor_height(i),
+                    get_monitor_refresh_rate(i)
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,112 @@
+// Sliding doors for Backrooms Doom
+// The maze tile says whether a door lets things through ('/' open, 'D' closed);
+// this keeps how far each door has actually slid aside. Opening slides the panel
+// into the wall over half a second and only frees the tile once it is all the way
+// open, closing blocks the tile straight away and slides the panel back. Rays pass
+// the open part of a sliding door (see caster.rs). A door opened during play swings
+// shut by itself after a while, once nobody is standing in the doorway.
+
+use crate::maze::Maze;
+
+const SLIDE_TIME: f32 = 0.5; // Seconds to slide all the way open or shut
+
+pub const DEFAULT_CLOSE_DELAY: f32 = 5.0; // Seconds an opened door stays open
+pub const MAX_CLOSE_DELAY: f32 = 120.0;
+
+struct Door {
+    cell: (usize, usize),
+    open: f32,             // How far the panel has slid aside, 0.0 (shut) - 1.0
+    opening: bool,         // Where it is sliding towards
+    close_in: Option<f32>, // Seconds until it closes by itself
+}
+
+pub struct Doors {
+    doors: Vec<Door>,
+    close_delay: f32, // Seconds before an opened door closes by itself, 0 = never
+}
+
+impl Doors {
+    /// Every door in the maze, standing as the maze file left it
+    pub fn new(maze: &Maze, close_delay: f32) -> Self {
+        let mut doors = Vec::new();
+        for y in 0..maze.height {
+            for x in 0..maze.width {
+                if let Some(open) = maze.door_at(x, y) {
+                    let open_amount = if open { 1.0 } else { 0.0 };
+                    doors.push(Door { cell: (x, y), open: open_amount, opening: open, close_in: None });
+                }
+            }
+        }
+        Self { doors, close_delay }
+    }
+
+    /// Change how long doors opened from now on stay open (0 = until closed by hand)
+    pub fn set_close_delay(&mut self, close_delay: f32) {
+        self.close_delay = close_delay;
+    }
+
+    fn find(&mut self, x: usize, y: usize) -> Option<&mut Door> {
+        self.doors.iter_mut().find(|door| door.cell == (x, y))
+    }
+
+    /// How far the door at (x, y) has slid open, 0.0 - 1.0 (0.0 if there is no door)
+    pub fn open_amount(&self, x: usize, y: usize) -> f32 {
+        self.doors.iter().find(|door| door.cell == (x, y)).map_or(0.0, |door| door.open)
+    }
+
+    /// Whether the door at (x, y) is open or on its way open (None if there is no door)
+    pub fn is_opening(&self, x: usize, y: usize) -> Option<bool> {
+        self.doors.iter().find(|door| door.cell == (x, y)).map(|door| door.opening)
+    }
+
+    /// Start sliding the door open. The tile stays blocked until it gets there
+    pub fn open(&mut self, x: usize, y: usize) {
+        let close_delay = self.close_delay;
+        if let Some(door) = self.find(x, y) {
+            door.opening = true;
+            door.close_in = (close_delay > 0.0).then_some(close_delay);
+        }
+    }
+
+    /// Block the doorway and start sliding the door shut
+    pub fn close(&mut self, x: usize, y: usize, maze: &mut Maze) {
+        if let Some(door) = self.find(x, y) {
+            door.opening = false;
+            door.close_in = None;
+            maze.set_door(x, y, false);
+        }
+    }
+
+    /// Fling the door all the way open at once (an enemy breaking through)
+    pub fn burst(&mut self, x: usize, y: usize, maze: &mut Maze) {
+        self.open(x, y);
+        if let Some(door) = self.find(x, y) {
+            door.open = 1.0;
+            maze.set_door(x, y, true);
+        }
+    }
+
+    /// Slide the doors and count down the open ones. Returns the doors due to close
+    /// by themselves; they stay due until the caller finds the doorway clear
+    pub fn update(&mut self, delta_time: f32, maze: &mut Maze) -> Vec<(usize, usize)> {
+        let step = delta_time / SLIDE_TIME;
+        let mut due = Vec::new();
+        for door in &mut self.doors {
+            if door.opening {
+                door.open = (door.open + step).min(1.0);
+                if door.open >= 1.0 {
+                    maze.set_door(door.cell.0, door.cell.1, true);
+                    if let Some(close_in) = &mut door.close_in {
+                        *close_in -= delta_time;
+                        if *close_in <= 0.0 {
+                            due.push(door.cell);
+                        }
+                    }
+                }
+            } else {
+                door.open = (door.open - step).max(0.0);
+            }
+        }
+        due
+    }
+}
@@ -0,0 +1,61 @@
+// Doppler shift for Backrooms Doom
+// Sounds moving quickly towards the player play higher, and lower as they move
+// away, so a charge or a near miss reads by ear. Each positional source keeps
+// a Doppler tracker that follows its distance to the player from frame to frame;
+// the rate that distance changes is the relative radial velocity (it includes
+// the player's own movement, as it should). The pitch is the classic
+// speed / (speed + radial velocity), with a speed of sound far below the real
+// one so the few tiles per second things move here are audible at all.
+// Slow drifts are ignored so an ordinary walk doesn't warble, and the shift is
+// clamped so nothing turns into a chipmunk.
+
+const SOUND_SPEED: f32 = 14.0;   // Tiles per second, exaggerated
+const DEAD_ZONE: f32 = 1.2;      // Radial speeds below this (tiles per second) don't shift
+const SMOOTHING: f32 = 8.0;      // How fast the velocity follows the measurement (per second)
+const JUMP_SPEED: f32 = 20.0;    // Faster than anything moves: the source or listener jumped
+const MIN_PITCH: f32 = 0.8;
+const MAX_PITCH: f32 = 1.25;
+
+pub struct Doppler {
+    distance: Option<f32>, // Last distance to the listener, None until heard
+    pub velocity: f32,     // Smoothed radial velocity, positive = moving away
+}
+
+impl Doppler {
+    pub fn new() -> Self {
+        Self { distance: None, velocity: 0.0 }
+    }
+
+    /// Follow the source's current distance to the listener
+    pub fn update(&mut self, distance: f32, delta_time: f32) {
+        if delta_time <= 0.0 {
+            return;
+        }
+        if let Some(last) = self.distance {
+            let measured = (distance - last) / delta_time;
+            if measured.abs() > JUMP_SPEED {
+                self.velocity = 0.0;
+                self.distance = Some(distance);
+                return;
+            }
+            self.velocity += (measured - self.velocity) * (SMOOTHING * delta_time).min(1.0);
+        }
+        self.distance = Some(distance);
+    }
+
+    /// Forget the source's history, e.g. when it stops playing
+    pub fn reset(&mut self) {
+        self.distance = None;
+        self.velocity = 0.0;
+    }
+
+    /// Pitch multiplier for the current radial velocity (1.0 = unshifted)
+    pub fn pitch(&self) -> f32 {
+        let speed = self.velocity.abs() - DEAD_ZONE;
+        if speed <= 0.0 {
+            return 1.0;
+        }
+        let velocity = speed.copysign(self.velocity);
+        (SOUND_SPEED / (SOUND_SPEED + velocity)).clamp(MIN_PITCH, MAX_PITCH)
+    }
+}
@@ -1,5 +1,22 @@
+use crate::guard;
 use raylib::prelude::*;
 
+// Hit feedback when an attack lands (hit-stop = brief dip of the time scale)
+const HIT_STOP_DURATION: f32 = 0.07;   // Real seconds at full strength
+const HIT_STOP_TIME_SCALE: f32 = 0.05; // Simulation speed during the hit-stop
+const PUNCH_PIXELS: f32 = 6.0;         // Screen punch offset at full strength
+const PUNCH_RECOVERY: f32 = 14.0;      // How fast the punch springs back (per second)
+
+// Lights flickering out (chat scare)
+const FLICKER_DURATION: f32 = 2.5;
+const ANXIETY_DURATION: f32 = 2.0;
+const FLICKER_DARKNESS: f32 = 0.12; // Brightness while the lights are cut
+
+// Flashlight beam (see flashlight.rs)
+const FLASHLIGHT_CONE: f32 = 0.45;  // Half-width of the cone, as a fraction of half the screen
+const FLASHLIGHT_RANGE: f32 = 8.0;  // Distance at which the beam has faded out
+const FLASHLIGHT_BOOST: f32 = 1.0;  // Extra brightness at the center of the beam, up close
+
 pub struct Effects {
     pub fog_enabled: bool,
     pub fog_distance: f32,
@@ -10,7 +27,12 @@ pub struct Effects {
     // Anxiety effect fields
     pub anxiety_intensity: f32,  // 0.0 to 1.0
     pub anxiety_timer: f32,       // Duration of anxiety effect
+    pub anxiety_strength: f32,    // Scale on the anxiety effect from the settings (0.0 = off)
     pub screen_shake_offset: (f32, f32), // Random offset for screen shake
+    // Hit feedback
+    pub hit_stop_timer: f32,
+    pub punch_offset: (f32, f32), // Screen offset in pixels, decays back to zero
+    pub flicker_timer: f32,
 }
 
 impl Effects {
@@ -19,12 +41,16 @@ impl Effects {
             fog_enabled: false, // Fog disabled
             fog_distance: 15.0,
             fog_color: Color::new(80, 75, 50, 255), // Yellowish Backrooms fog (fluorescent lighting feel)
-            flashlight_enabled: false, // Flashlight disabled
+            flashlight_enabled: false, // Follows the player's flashlight (see flashlight.rs)
             flashlight_intensity: 1.0,
             damage_flash_timer: 0.0,
             anxiety_intensity: 0.0,
             anxiety_timer: 0.0,
+            anxiety_strength: 1.0,
             screen_shake_offset: (0.0, 0.0),
+            hit_stop_timer: 0.0,
+            punch_offset: (0.0, 0.0),
+            flicker_timer: 0.0,
         }
     }
 
@@ -44,22 +70,18 @@ impl Effects {
         )
     }
 
-    /// Apply flashlight effect (brightens center, darkens edges)
-    pub fn apply_flashlight(&self, color: Color, screen_x: usize, screen_width: usize) -> Color {
-        if !self.flashlight_enabled {
-            return color;
+    /// Extra brightness the flashlight adds to a wall column at `screen_x` whose wall is
+    /// `distance` away: strongest in the middle of the cone and on walls close by
+    pub fn flashlight_beam(&self, screen_x: usize, screen_width: usize, distance: f32) -> f32 {
+        if !self.flashlight_enabled || self.flashlight_intensity <= 0.0 {
+            return 0.0;
         }
 
         let center = screen_width as f32 / 2.0;
-        let distance_from_center = ((screen_x as f32 - center).abs() / center).clamp(0.0, 1.0);
-        let brightness = (1.0 - distance_from_center * 0.5) * self.flashlight_intensity;
-
-        Color::new(
-            (color.r as f32 * brightness) as u8,
-            (color.g as f32 * brightness) as u8,
-            (color.b as f32 * brightness) as u8,
-            255,
-        )
+        let off_center = (screen_x as f32 - center).abs() / (center * FLASHLIGHT_CONE);
+        let cone = (1.0 - off_center * off_center).max(0.0);
+        let falloff = (1.0 - distance / FLASHLIGHT_RANGE).max(0.0);
+        cone * falloff * falloff * FLASHLIGHT_BOOST * self.flashlight_intensity
     }
 
     /// Apply damage flash effect
@@ -78,8 +100,10 @@ impl Effects {
         )
     }
 
-    /// Update damage flash timer
+    /// Update timed effects (damage flash, anxiety, light flicker)
     pub fn update(&mut self, delta_time: f32) {
+        self.flicker_timer = (self.flicker_timer - delta_time).max(0.0);
+
         if self.damage_flash_timer > 0.0 {
             self.damage_flash_timer -= delta_time * 2.0;
             self.damage_flash_timer = self.damage_flash_timer.max(0.0);
@@ -91,7 +115,7 @@ impl Effects {
             self.anxiety_timer = self.anxiety_timer.max(0.0);
             
             // Fade out anxiety intensity as timer decreases
-            self.anxiety_intensity = (self.anxiety_timer / 2.0).min(1.0);
+            self.anxiety_intensity = (self.anxiety_timer / 2.0).min(1.0) * self.anxiety_strength;
             
             // Update screen shake with random offset
             if self.anxiety_intensity > 0.0 {
@@ -110,15 +134,87 @@ impl Effects {
         }
     }
 
+    /// Make the lights stutter and cut out for a moment
+    pub fn trigger_flicker(&mut self) {
+        self.flicker_timer = FLICKER_DURATION;
+    }
+
+    /// Scene brightness while the lights flicker (1.0 when they're steady)
+    pub fn flicker_brightness(&self) -> f32 {
+        if self.flicker_timer <= 0.0 {
+            return 1.0;
+        }
+        // Irregular on/off stutter, fully out for most of the middle
+        let t = self.flicker_timer;
+        let stutter = (t * 23.0).sin() + (t * 37.0).sin() * 0.6;
+        if !(FLICKER_DURATION * 0.2..=FLICKER_DURATION * 0.8).contains(&t) {
+            if stutter > 0.0 { 1.0 } else { FLICKER_DARKNESS }
+        } else {
+            FLICKER_DARKNESS
+        }
+    }
+
     /// Trigger damage flash
     pub fn trigger_damage_flash(&mut self) {
         self.damage_flash_timer = 0.3;
     }
 
+    /// Advance hit-stop and screen punch in real time.
+    /// Returns the delta time the simulation should use this frame
+    pub fn update_hit_feedback(&mut self, delta_time: f32) -> f32 {
+        let recovery = (1.0 - PUNCH_RECOVERY * delta_time).max(0.0);
+        let decay = |offset: f32| guard::finite_or(offset * recovery, 0.0);
+        self.punch_offset = (decay(self.punch_offset.0), decay(self.punch_offset.1));
+
+        if self.hit_stop_timer > 0.0 {
+            self.hit_stop_timer = (self.hit_stop_timer - delta_time).max(0.0);
+            return delta_time * HIT_STOP_TIME_SCALE;
+        }
+        delta_time
+    }
+
+    /// Freeze the action for a few frames (strength 0.0 - 1.0)
+    pub fn trigger_hit_stop(&mut self, strength: f32) {
+        self.hit_stop_timer = self.hit_stop_timer.max(HIT_STOP_DURATION * strength.clamp(0.0, 1.0));
+    }
+
+    /// Kick the view in a screen direction (x right, y down), strength 0.0 - 1.0
+    pub fn trigger_screen_punch(&mut self, direction: (f32, f32), strength: f32) {
+        let (dir_x, dir_y) = guard::normalize(direction.0, direction.1);
+        let pixels = PUNCH_PIXELS * strength.clamp(0.0, 1.0);
+        self.punch_offset = (dir_x * pixels, dir_y * pixels);
+    }
+
+    /// Whole-pixel offset to draw the 3D view at
+    pub fn view_offset(&self) -> (i32, i32) {
+        (self.punch_offset.0.round() as i32, self.punch_offset.1.round() as i32)
+    }
+
+    /// Fraction of the anxiety effect still to run (0.0 when inactive)
+    pub fn anxiety_remaining(&self) -> f32 {
+        self.anxiety_timer / ANXIETY_DURATION
+    }
+
+    /// Fraction of the light flicker still to run (0.0 when inactive)
+    pub fn flicker_remaining(&self) -> f32 {
+        self.flicker_timer / FLICKER_DURATION
+    }
+
     /// Trigger anxiety effect (idle penalty)
     pub fn trigger_anxiety_effect(&mut self) {
-        self.anxiety_timer = 2.0; // 2 seconds of anxiety effect
-        self.anxiety_intensity = 1.0;
+        if self.anxiety_strength <= 0.0 {
+            return;
+        }
+        self.anxiety_timer = ANXIETY_DURATION;
+        self.anxiety_intensity = self.anxiety_strength;
+    }
+
+    /// Keep the anxiety effect at least `level` (0.0 - 1.0) strong, e.g. while entities whisper
+    pub fn feel_dread(&mut self, level: f32) {
+        if self.anxiety_strength <= 0.0 {
+            return;
+        }
+        self.anxiety_timer = self.anxiety_timer.max(level.clamp(0.0, 1.0) * ANXIETY_DURATION);
     }
 
     /// Apply vignette effect (darkened edges) for anxiety
@@ -156,7 +252,7 @@ impl Effects {
 
     /// Apply distance-based shading
     pub fn calculate_distance_shading(&self, distance: f32, max_distance: f32) -> f32 {
-        let normalized = (distance / max_distance).clamp(0.0, 1.0);
+        let normalized = guard::finite_or(distance / max_distance.max(guard::MIN_DEPTH), 1.0).clamp(0.0, 1.0);
         1.0 - normalized * 0.25 // Very minimal darkening for Backrooms bright lighting
     }
 }
\ No newline at end of file
@@ -1,21 +1,359 @@
-// Enemy module (placeholder for future expansion)
-// Can be used to add hostile entities in the Backrooms
+// Enemy module for Backrooms Doom
+// Hostile entities placed with 'e' in the maze file. They patrol or wander, spot
+// the player by line of sight (stealth.rs), then run them down and strike on
+// contact, with a short recovery between strikes so a touch isn't instant death
 
+use crate::doppler::Doppler;
+use crate::guard;
+use crate::maze::Maze;
+use crate::patrol::{Patrol, PatrolState};
 use crate::player::Vector2;
+use crate::search::Search;
+use crate::sprite::{AnimState, Animator, Clip, SpriteSheet};
+use crate::stealth;
+use crate::tracks::{self, Strider};
+use rand::Rng;
+
+const HIT_FLASH_DURATION: f32 = 0.1;   // Seconds the sprite stays white after a hit
+const HEALTH_BAR_VISIBLE: f32 = 2.0;   // Seconds the health bar stays fully visible
+const HEALTH_BAR_FADE: f32 = 0.5;      // Seconds it then takes to fade out
+const ARRIVE_DISTANCE: f32 = 0.3;      // Close enough to a noise to stop investigating
+const PUSH_COOLDOWN: f32 = 6.0;        // Seconds after giving up on a door before pushing again
+pub const WALK_SPEED: f32 = 1.0;       // Tiles per second
+pub const CHASE_SPEED: f32 = 1.6;      // Tiles per second once the player is spotted
+pub const CONTACT_DAMAGE: i32 = 10;
+const CONTACT_RANGE: f32 = 0.5;        // Tiles between enemy and player for a strike
+const STRIKE_COOLDOWN: f32 = 1.2;      // Seconds between strikes
+
+// Death animation: sprite height per step as the enemy crumples to the floor over the die clip
+const DEATH_FRAMES: [f32; 4] = [0.85, 0.6, 0.38, 0.2];
+const DEATH_TIME: f32 = 0.48;
+const ATTACK_TIME: f32 = 0.4; // Seconds the attack clip plays after a strike
+
+// Clips on the enemy sprite sheet. enemy.png is a single frame, so every state shows it;
+// a wider strip of frames only needs its ranges set here
+pub const SHEET: SpriteSheet = SpriteSheet {
+    columns: 1,
+    idle: Clip::looping(0, 1, 2.0),
+    walk: Clip::looping(0, 1, 8.0),
+    attack: Clip::once(0, 1, 1.0 / ATTACK_TIME),
+    die: Clip::once(0, 1, 1.0 / DEATH_TIME),
+};
+
+// Corpse cleanup so long sessions don't pile up entities
+const CORPSE_LIFETIME: f32 = 90.0; // Seconds before a corpse despawns
+pub const MAX_CORPSES: usize = 8;      // Oldest corpses are removed beyond this
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum EnemyState {
+    Alive,
+    Dying,  // Playing the death animation
+    Corpse, // Last death frame, stays until cleaned up
+}
 
 pub struct Enemy {
     pub pos: Vector2,
     pub health: i32,
-    pub speed: f32,
+    pub max_health: i32,
+    pub speed: f32, // Tiles per second
+    pub hit_flash_timer: f32,
+    pub health_bar_timer: f32, // Time since the last hit, drives the health bar fade
+    pub state: EnemyState,
+    pub state_timer: f32, // Time spent in the current state
+    pub awareness: f32,   // 0.0 - 1.0, how close the enemy is to spotting the player
+    pub detected: bool,   // Has spotted the player
+    pub investigate: Option<Vector2>, // Last noise heard, walked towards until reached
+    pub route: Vec<(f32, f32)>,       // Tile centers on the way there, planned by the crowd (crowd.rs)
+    pub route_goal: Option<(usize, usize)>, // Cell the route leads to, re-planned when the target leaves it
+    pub push_cooldown: f32, // Time until the enemy will try to force a door again
+    pub stride: Strider,    // Footprints left while walking
+    pub trail_age: f32,     // Age of the last wet footprint followed, only fresher ones are followed next
+    pub facing: f32,        // Direction the enemy looks in (radians), mirrors the sprite
+    pub patrol: Option<PatrolState>,
+    pub search: Option<Search>, // Where the player was last seen, searched around after losing sight
+    pub doppler: Doppler,       // Pitch shift of its sounds as it closes in or moves off
+    pub strike_cooldown: f32,   // Time until it can strike the player again
+    pub animation: Animator,    // Clip on the sprite sheet (see SHEET)
 }
 
 impl Enemy {
-    #[allow(dead_code)]
     pub fn new(x: f32, y: f32) -> Self {
         Self {
             pos: Vector2::new(x, y),
             health: 100,
-            speed: 1.0,
+            max_health: 100,
+            speed: WALK_SPEED,
+            hit_flash_timer: 0.0,
+            health_bar_timer: HEALTH_BAR_VISIBLE + HEALTH_BAR_FADE,
+            state: EnemyState::Alive,
+            state_timer: 0.0,
+            awareness: 0.0,
+            detected: false,
+            investigate: None,
+            route: Vec::new(),
+            route_goal: None,
+            push_cooldown: 0.0,
+            stride: Strider::new(x, y),
+            trail_age: tracks::SCENT_TIME,
+            facing: 0.0,
+            patrol: None,
+            search: None,
+            doppler: Doppler::new(),
+            strike_cooldown: 0.0,
+            animation: Animator::new(&SHEET),
+        }
+    }
+
+    /// Give the enemy a patrol loop to walk when idle
+    pub fn with_patrol(mut self, patrol: &Patrol) -> Self {
+        self.patrol = Some(PatrolState::new(patrol));
+        self
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.hit_flash_timer = (self.hit_flash_timer - delta_time).max(0.0);
+        self.push_cooldown = (self.push_cooldown - delta_time).max(0.0);
+        self.strike_cooldown = (self.strike_cooldown - delta_time).max(0.0);
+        self.health_bar_timer += delta_time;
+        self.state_timer += delta_time;
+        self.animation.update(delta_time);
+
+        if self.state == EnemyState::Dying && self.animation.finished() {
+            self.state = EnemyState::Corpse;
+            self.state_timer = 0.0;
+        }
+        // A strike plays out, otherwise walking while heading somewhere and idle when not
+        let striking = self.animation.state() == AnimState::Attack && !self.animation.finished();
+        if self.is_alive() && !striking {
+            self.animation.play(if self.investigate.is_some() { AnimState::Walk } else { AnimState::Idle });
+        }
+    }
+
+    /// Apply damage. Returns true if this hit killed the enemy
+    pub fn take_damage(&mut self, amount: i32) -> bool {
+        if !self.is_alive() {
+            return false;
+        }
+
+        self.health = (self.health - amount).max(0);
+        self.hit_flash_timer = HIT_FLASH_DURATION;
+        self.health_bar_timer = 0.0;
+
+        if self.health == 0 {
+            self.state = EnemyState::Dying;
+            self.state_timer = 0.0;
+            self.animation.play(AnimState::Die);
+            return true;
+        }
+        false
+    }
+
+    /// Remember a noise at (x, y) to go and check out
+    pub fn hear(&mut self, x: f32, y: f32) {
+        if self.is_alive() {
+            self.investigate = Some(Vector2::new(x, y)); // The crowd re-plans if it's another cell
+        }
+    }
+
+    /// Forget the planned route so the crowd plans a new one
+    pub fn clear_route(&mut self) {
+        self.route.clear();
+        self.route_goal = None;
+    }
+
+    /// Where the enemy is walking right now: the next tile of its route, or straight at the
+    /// noise when it has no route
+    pub fn heading(&self) -> Option<Vector2> {
+        let target = self.investigate?;
+        Some(self.route.first().map_or(target, |&(x, y)| Vector2::new(x, y)))
+    }
+
+    /// Head for the player's position while they are in sight, and remember it to search
+    /// around for `search_time` seconds once they are gone
+    pub fn remember_player(&mut self, x: f32, y: f32, search_time: f32) {
+        self.hear(x, y);
+        self.search = Some(Search::new((x, y), search_time));
+    }
+
+    /// Search around the last known position of the player while there is nothing else to
+    /// walk to, until the search time runs out
+    pub fn update_search(&mut self, maze: &Maze, rng: &mut impl Rng, delta_time: f32) {
+        if !self.is_alive() {
+            self.search = None;
+            return;
+        }
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.update(delta_time);
+        if search.finished() {
+            self.search = None; // Gave up, back to the patrol
+            return;
+        }
+        if self.investigate.is_none() {
+            if let Some((x, y)) = search.idle(maze, rng, delta_time) {
+                self.hear(x, y);
+            }
+        }
+    }
+
+    /// Walk the patrol loop while there is nothing to investigate. Anything else the enemy
+    /// heads for (a noise, the player) interrupts the patrol until it is idle again
+    pub fn update_patrol(&mut self, delta_time: f32) {
+        if !self.is_alive() {
+            return;
+        }
+        let Some(patrol) = &mut self.patrol else {
+            return;
+        };
+        match self.investigate {
+            Some(target) if !self.detected && patrol.is_heading_to(target.x, target.y) => {}
+            Some(_) => patrol.interrupted = true,
+            None => {
+                if let Some((x, y)) = patrol.idle((self.pos.x, self.pos.y), &mut self.facing, delta_time) {
+                    self.investigate = Some(Vector2::new(x, y));
+                    self.clear_route();
+                }
+            }
+        }
+    }
+
+    /// Walk towards the last heard noise along the planned route, sliding along walls.
+    /// `separation` nudges the enemy away from others close by (see crowd.rs)
+    pub fn update_movement(&mut self, maze: &Maze, separation: (f32, f32), delta_time: f32) {
+        let Some(target) = self.investigate else {
+            return;
+        };
+        if !self.is_alive() {
+            self.investigate = None;
+            self.clear_route();
+            return;
+        }
+
+        let distance_to = |x: f32, y: f32| ((x - self.pos.x).powi(2) + (y - self.pos.y).powi(2)).sqrt();
+        if distance_to(target.x, target.y) <= ARRIVE_DISTANCE {
+            self.investigate = None;
+            self.clear_route();
+            return;
+        }
+
+        // Drop route tiles already reached
+        while self.route.first().is_some_and(|&(x, y)| distance_to(x, y) <= ARRIVE_DISTANCE) {
+            self.route.remove(0);
+        }
+        let Some(heading) = self.heading() else {
+            return;
+        };
+        let dx = heading.x - self.pos.x;
+        let dy = heading.y - self.pos.y;
+        let distance = (dx * dx + dy * dy).sqrt();
+        let (to_x, to_y) = guard::normalize(dx, dy);
+        let (dir_x, dir_y) = guard::normalize(to_x + separation.0, to_y + separation.1);
+
+        self.facing = dir_y.atan2(dir_x);
+
+        let step = (self.speed * delta_time).min(distance);
+        let new_x = self.pos.x + dir_x * step;
+        let new_y = self.pos.y + dir_y * step;
+        let blocked_x = !maze.is_walkable(new_x, self.pos.y);
+        if !blocked_x {
+            self.pos.x = new_x;
+        }
+        let blocked_y = !maze.is_walkable(self.pos.x, new_y);
+        if !blocked_y {
+            self.pos.y = new_y;
+        }
+
+        // Cornered with no way forward, give up on the noise
+        if blocked_x && blocked_y {
+            self.investigate = None;
+            self.clear_route();
+        }
+    }
+
+    /// Strike the player at (x, y) if they're within reach and the last strike has
+    /// worn off. Returns true when it lands
+    pub fn try_strike(&mut self, x: f32, y: f32) -> bool {
+        let distance = ((x - self.pos.x).powi(2) + (y - self.pos.y).powi(2)).sqrt();
+        if !self.is_alive() || distance > CONTACT_RANGE || self.strike_cooldown > 0.0 {
+            return false;
+        }
+        self.strike_cooldown = STRIKE_COOLDOWN;
+        self.animation.play(AnimState::Attack);
+        true
+    }
+
+    /// Heading somewhere and willing to force a closed door on the way
+    pub fn wants_to_push(&self) -> bool {
+        self.is_alive() && self.investigate.is_some() && self.push_cooldown <= 0.0
+    }
+
+    /// Stop pushing a door that held, and lose interest for a while
+    pub fn give_up_push(&mut self) {
+        self.investigate = None;
+        self.clear_route();
+        self.push_cooldown = PUSH_COOLDOWN;
+    }
+
+    /// Update awareness from a vision check. Returns true the moment the player is spotted
+    pub fn watch(&mut self, sees: bool, visibility: f32, delta_time: f32) -> bool {
+        self.awareness = stealth::update_awareness(self.awareness, sees, visibility, delta_time);
+        if self.awareness >= 1.0 && !self.detected {
+            self.detected = true;
+            return true;
+        }
+        if self.awareness <= 0.0 {
+            self.detected = false; // Lost track of the player
+        }
+        false
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.state == EnemyState::Alive
+    }
+
+    pub fn is_corpse(&self) -> bool {
+        self.state == EnemyState::Corpse
+    }
+
+    /// Corpse has been around long enough to despawn
+    pub fn is_expired(&self) -> bool {
+        self.is_corpse() && self.state_timer >= CORPSE_LIFETIME
+    }
+
+    /// Sprite height multiplier for the current animation frame
+    pub fn sprite_scale(&self) -> f32 {
+        match self.state {
+            EnemyState::Alive => 1.0,
+            EnemyState::Dying => {
+                let step = (self.animation.progress() * DEATH_FRAMES.len() as f32) as usize;
+                DEATH_FRAMES[step.min(DEATH_FRAMES.len() - 1)]
+            }
+            EnemyState::Corpse => DEATH_FRAMES[DEATH_FRAMES.len() - 1],
+        }
+    }
+
+    /// Brightness multiplier, dead enemies darken as they fall
+    pub fn brightness(&self) -> f32 {
+        match self.state {
+            EnemyState::Alive => 1.0,
+            EnemyState::Dying => 0.5 + 0.5 * self.sprite_scale(),
+            EnemyState::Corpse => 0.45,
+        }
+    }
+
+    pub fn is_flashing(&self) -> bool {
+        self.hit_flash_timer > 0.0
+    }
+
+    /// Health bar opacity (0.0 - 1.0), fading out after a while without taking damage
+    pub fn health_bar_alpha(&self) -> f32 {
+        if !self.is_alive() {
+            return 0.0;
+        }
+        if self.health_bar_timer <= HEALTH_BAR_VISIBLE {
+            return 1.0;
         }
+        (1.0 - (self.health_bar_timer - HEALTH_BAR_VISIBLE) / HEALTH_BAR_FADE).max(0.0)
     }
 }
@@ -0,0 +1,115 @@
+// Countdown escalation for Backrooms Doom
+// A level can get worse as its timer runs down. Steps are scripted in the maze
+// file, one line each after the grid, keyed on the seconds left on the timer:
+//
+//   escalate 90 flicker 3
+//   escalate 60 spawn 5,13
+//   escalate 30 hum 2
+//   escalate 20 blackout 10
+//
+// `flicker <n>` makes the lights flicker about n times a minute from then on,
+// `spawn x,y` brings in another entity at that tile (x,y counted from 0 at the
+// top-left corner), `hum <scale>` makes the exit hum carry that many times
+// further, and `blackout <seconds>` cuts the power (see blackout.rs). Each step
+// fires once per run, when the timer first drops to its time, as a
+// GameEvent::Escalation carrying the step's Escalate action; GameState carries it
+// out when it handles the frame's events. The flickers after a `flicker` step
+// are rolled every frame and don't go through the queue.
+
+use rand::Rng;
+
+const MAX_FLICKER_RATE: f32 = 30.0; // Flickers per minute
+const MAX_HUM_SCALE: f32 = 4.0;
+
+/// What a step does to the level
+#[derive(Clone, Copy)]
+pub enum Escalate {
+    Flicker(f32),      // Flickers per minute
+    Spawn(f32, f32),   // World position (tile center)
+    Hum(f32),          // Exit hum range multiplier
+    Blackout(f32),     // Seconds
+}
+
+#[derive(Clone, Copy)]
+pub struct EscalationStep {
+    pub at: f32, // Seconds left on the timer
+    pub action: Escalate,
+}
+
+impl EscalationStep {
+    /// Parse the rest of an "escalate <seconds left> <action> <value>" line (without the
+    /// keyword). Spawn positions are returned in tiles, the maze converts them
+    pub fn parse(definition: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = definition.split_whitespace().collect();
+        let [at, action, value] = parts[..] else {
+            return Err(format!(
+                "bad escalation '{}', expected <seconds left> <flicker|spawn|hum|blackout> <value>",
+                definition.trim()
+            ));
+        };
+        let at = at
+            .parse::<f32>()
+            .ok()
+            .filter(|at| at.is_finite() && *at > 0.0)
+            .ok_or_else(|| format!("bad escalation time '{}', expected seconds left on the timer", at))?;
+
+        let number = |max: f32| {
+            value
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite() && *value > 0.0 && *value <= max)
+                .ok_or_else(|| format!("bad {} value '{}', expected 0-{}", action, value, max))
+        };
+        let action = match action {
+            "flicker" => Escalate::Flicker(number(MAX_FLICKER_RATE)?),
+            "hum" => Escalate::Hum(number(MAX_HUM_SCALE)?),
+            "blackout" => Escalate::Blackout(number(f32::MAX)?),
+            "spawn" => {
+                let invalid = || format!("bad spawn position '{}', expected x,y", value);
+                let (x, y) = value.split_once(',').ok_or_else(invalid)?;
+                let x = x.parse::<usize>().map_err(|_| invalid())?;
+                let y = y.parse::<usize>().map_err(|_| invalid())?;
+                Escalate::Spawn(x as f32, y as f32)
+            }
+            _ => return Err(format!("unknown escalation '{}', expected flicker, spawn, hum or blackout", action)),
+        };
+        Ok(Self { at, action })
+    }
+}
+
+pub struct Escalation {
+    pending: Vec<EscalationStep>, // Still to come this run
+    flicker_rate: f32,            // Flickers per minute, 0 until a flicker step fires
+    flicker_timer: f32,           // Until the next flicker
+}
+
+impl Escalation {
+    pub fn new(steps: &[EscalationStep]) -> Self {
+        Self { pending: steps.to_vec(), flicker_rate: 0.0, flicker_timer: 0.0 }
+    }
+
+    /// The next step due with `time_left` seconds on the timer, if any
+    pub fn update(&mut self, time_left: f32) -> Option<Escalate> {
+        let index = self.pending.iter().position(|step| time_left <= step.at)?;
+        let step = self.pending.remove(index);
+        if let Escalate::Flicker(rate) = step.action {
+            self.flicker_rate = rate;
+            self.flicker_timer = 0.0;
+        }
+        Some(step.action)
+    }
+
+    /// Whether the lights should flicker this frame. Intervals are jittered so it doesn't
+    /// feel like a metronome
+    pub fn flicker_due(&mut self, rng: &mut impl Rng, delta_time: f32) -> bool {
+        if self.flicker_rate <= 0.0 {
+            return false;
+        }
+        self.flicker_timer -= delta_time;
+        if self.flicker_timer > 0.0 {
+            return false;
+        }
+        self.flicker_timer = 60.0 / self.flicker_rate * rng.gen_range(0.5..1.5);
+        true
+    }
+}
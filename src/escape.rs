@@ -0,0 +1,79 @@
+// Escape sequence for Backrooms Doom
+// Reaching the exit of the last level doesn't cut straight to the victory
+// screen. The exit door slides open, white light blooms out of the doorway
+// until it fills the view, and the player walks up to the threshold on their
+// own, turning to face the light, before the victory screen takes over. The
+// sequence is a set of keyframe tracks (see timeline.rs); ENTER skips it.
+
+use crate::maze::Maze;
+use crate::timeline;
+use std::f32::consts::PI;
+
+const DOOR: [(f32, f32); 2] = [(0.2, 0.0), (1.2, 1.0)];              // Share of the door slid aside
+const WALK: [(f32, f32); 2] = [(0.6, 0.0), (2.4, 1.0)];              // Share of the way to the threshold
+const BLOOM: [(f32, f32); 3] = [(0.4, 0.0), (2.0, 0.5), (2.9, 1.0)]; // Light washing out the view
+const HOLD: f32 = 0.4;       // Seconds of plain white before the victory screen
+const THRESHOLD: f32 = 0.15; // How close to the door plane the walk ends (tiles)
+
+pub struct Escape {
+    time: f32,
+    pub door: (usize, usize), // Exit tile
+    from: (f32, f32, f32),    // Player x, y and angle when the sequence started
+    to: (f32, f32, f32),      // ...and at the threshold, facing the door
+}
+
+impl Escape {
+    /// Start the sequence with the player at (x, y) looking along `angle`
+    pub fn new(maze: &Maze, x: f32, y: f32, angle: f32) -> Self {
+        let tile = maze.tile_size;
+        let door = ((maze.goal_pos.0 / tile) as usize, (maze.goal_pos.1 / tile) as usize);
+
+        // The door is in a wall: walk in from the open side facing it
+        let (dx, dy) = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .find(|&(dx, dy)| {
+                let (nx, ny) = (door.0 as i32 + dx, door.1 as i32 + dy);
+                nx >= 0 && ny >= 0 && !maze.is_wall(nx as usize, ny as usize)
+            })
+            .unwrap_or((0, 1));
+        let reach = 0.5 + THRESHOLD;
+        let to_x = maze.goal_pos.0 + dx as f32 * reach * tile;
+        let to_y = maze.goal_pos.1 + dy as f32 * reach * tile;
+        let facing = (-dy as f32).atan2(-dx as f32);
+
+        // Turn the short way round
+        let mut turn = (facing - angle).rem_euclid(2.0 * PI);
+        if turn > PI {
+            turn -= 2.0 * PI;
+        }
+
+        Self { time: 0.0, door, from: (x, y, angle), to: (to_x, to_y, angle + turn) }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+
+    /// Whether the sequence has played through
+    pub fn finished(&self) -> bool {
+        self.time >= timeline::end(&BLOOM) + HOLD
+    }
+
+    /// Share of the door slid aside (0.0 closed - 1.0 open)
+    pub fn door_open(&self) -> f32 {
+        timeline::sample(&DOOR, self.time)
+    }
+
+    /// Strength of the light washing out the view (0.0 - 1.0 pure white)
+    pub fn bloom(&self) -> f32 {
+        timeline::sample(&BLOOM, self.time)
+    }
+
+    /// Player x, y and angle along the walk, eased in and out
+    pub fn pose(&self) -> (f32, f32, f32) {
+        let t = timeline::sample(&WALK, self.time);
+        let t = t * t * (3.0 - 2.0 * t);
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+        (lerp(self.from.0, self.to.0), lerp(self.from.1, self.to.1), lerp(self.from.2, self.to.2))
+    }
+}
@@ -0,0 +1,88 @@
+// Gameplay events for Backrooms Doom
+// Systems push events while updating and the game reacts to all of them in one
+// place afterwards, so feedback (effects, sounds, text) is tuned centrally
+
+use crate::escalation::Escalate;
+use crate::health::Source;
+use crate::twitch::Scare;
+use raylib::prelude::Color;
+
+pub enum GameEvent {
+    /// A player attack connected at (x, y). Strength is 0.0 - 1.0 and scales the feedback
+    AttackLanded { x: f32, y: f32, strength: f32 },
+    /// An enemy died at (x, y)
+    EnemyKilled { x: f32, y: f32 },
+    /// A player attack hit the wall at (x, y)
+    WallHit { x: f32, y: f32 },
+    /// The player picked up the key at (x, y)
+    KeyCollected { x: f32, y: f32 },
+    /// The player picked up the flashlight battery at (x, y)
+    BatteryCollected { x: f32, y: f32 },
+    /// The player picked up the clock at (x, y)
+    ClockCollected { x: f32, y: f32 },
+    /// The player touched the exit at (x, y) without every key
+    ExitLocked { x: f32, y: f32 },
+    /// A breakable prop was destroyed at (x, y)
+    PropBroken { x: f32, y: f32 },
+    /// Something made a noise at (x, y) audible within radius tiles
+    Noise { x: f32, y: f32, radius: f32 },
+    /// An enemy at (x, y) spotted the player
+    PlayerSpotted { x: f32, y: f32 },
+    /// An enemy at (x, y) struck the player on contact
+    PlayerStruck { x: f32, y: f32, damage: i32 },
+    /// The player lost health (after modifiers and clamping, see health.rs)
+    Damaged { amount: i32, source: Source },
+    /// The player gained health
+    Healed { amount: i32, source: Source },
+    /// The player's health dropped under health::LOW_HEALTH
+    LowHealth,
+    /// The player's health reached 0
+    Died { source: Source },
+    /// An enemy shoved the door at (x, y) it is trying to force open
+    DoorShoved { x: f32, y: f32 },
+    /// An enemy broke through the door at (x, y)
+    DoorBurst { x: f32, y: f32 },
+    /// The door at (x, y) held and the enemy gave up
+    DoorHeld { x: f32, y: f32 },
+    /// A short message for the player, shown as a toast (see toast.rs)
+    Toast { text: String, color: Color },
+    /// The player walked into a challenge room and its challenge started (see challenge.rs)
+    ChallengeStarted { room: usize },
+    /// The player picked up a challenge orb at (x, y)
+    OrbCollected { x: f32, y: f32 },
+    /// The player finished a room's challenge in time
+    ChallengeWon { room: usize },
+    /// The player ran out of time or left the room
+    ChallengeFailed { room: usize },
+    /// Twitch chat voted for a scare from the safe list
+    ChatScare { scare: Scare },
+    /// The level's escalation script reached a step as the timer ran down
+    Escalation { action: Escalate },
+}
+
+pub struct EventQueue {
+    events: Vec<GameEvent>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn push(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    /// Take every queued event, leaving the queue empty
+    pub fn drain(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
@@ -0,0 +1,100 @@
+// Hot/cold exit hum for Backrooms Doom
+// A low electrical hum that tells the player how close the exit is: it fades in
+// within HUM_RANGE steps of walking distance, then gets louder and higher the
+// closer they get. Distance is measured along the corridors (a breadth-first
+// distance map from the exit), so a wall between the player and the exit
+// doesn't make it sound close. Crossing into a new proximity band can show a
+// caption for players who can't hear it.
+//
+// On by default and off in hardcore mode, unless `exit_hum` forces it either way.
+// A level's escalation script can make it carry further as the timer runs out.
+
+use crate::maze::Maze;
+use crate::pathfinding;
+
+const HUM_RANGE: f32 = 25.0;    // Steps from the exit where the hum becomes audible
+const MIN_VOLUME: f32 = 0.05;   // At the edge of the range
+const MAX_VOLUME: f32 = 0.8;    // Next to the exit
+const LOW_PITCH: f32 = 0.8;
+const HIGH_PITCH: f32 = 1.35;
+const REFRESH_TIME: f32 = 1.0;  // Seconds between distance map rebuilds (doors open and close)
+const CAPTION_TIME: f32 = 3.0;
+
+// Proximity thresholds for the captions (0.0 = out of range, 1.0 = at the exit)
+const BANDS: [f32; 3] = [0.0, 0.45, 0.8];
+
+pub struct ExitHum {
+    steps: Vec<Option<usize>>, // Walking distance to the exit per cell
+    refresh_timer: f32,
+    pub proximity: f32,        // 0.0 - 1.0
+    range_scale: f32,          // HUM_RANGE multiplier (escalation.rs)
+    band: usize,
+    pub caption: Option<(&'static str, f32)>, // Caption on screen and time left
+}
+
+impl ExitHum {
+    pub fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            refresh_timer: 0.0,
+            proximity: 0.0,
+            range_scale: 1.0,
+            band: 0,
+            caption: None,
+        }
+    }
+
+    pub fn update(&mut self, maze: &Maze, x: f32, y: f32, delta_time: f32) {
+        self.refresh_timer -= delta_time;
+        if self.refresh_timer <= 0.0 || self.steps.len() != maze.width * maze.height {
+            self.steps = pathfinding::distances(maze, maze.goal_pos);
+            self.refresh_timer = REFRESH_TIME;
+        }
+
+        // Walking distance, or a straight line when the exit is shut off (closed doors)
+        let (cell_x, cell_y) = ((x / maze.tile_size) as usize, (y / maze.tile_size) as usize);
+        let walking = (cell_x < maze.width && cell_y < maze.height)
+            .then(|| self.steps[cell_y * maze.width + cell_x])
+            .flatten();
+        let distance = walking.map_or_else(
+            || ((x - maze.goal_pos.0).powi(2) + (y - maze.goal_pos.1).powi(2)).sqrt() / maze.tile_size,
+            |steps| steps as f32,
+        );
+        self.proximity = (1.0 - distance / (HUM_RANGE * self.range_scale)).clamp(0.0, 1.0);
+
+        if let Some((_, time_left)) = &mut self.caption {
+            *time_left -= delta_time;
+            if *time_left <= 0.0 {
+                self.caption = None;
+            }
+        }
+        let band = BANDS.iter().filter(|threshold| self.proximity > **threshold).count();
+        if band != self.band {
+            let text = match (band, band > self.band) {
+                (0, _) => "[The hum fades away]",
+                (1, true) => "[A faint electrical hum]",
+                (1, false) => "[The hum grows quieter]",
+                (2, true) => "[The hum grows louder]",
+                (2, false) => "[The hum drops lower]",
+                _ => "[The hum is loud here - the exit is close]",
+            };
+            self.caption = Some((text, CAPTION_TIME));
+            self.band = band;
+        }
+    }
+
+    /// Make the hum audible `scale` times as far from the exit
+    pub fn set_range_scale(&mut self, scale: f32) {
+        self.range_scale = scale;
+    }
+
+    /// (volume, pitch) to play the hum at, None when out of range
+    pub fn playback(&self) -> Option<(f32, f32)> {
+        if self.proximity <= 0.0 {
+            return None;
+        }
+        let p = self.proximity;
+        let volume = MIN_VOLUME + (MAX_VOLUME - MIN_VOLUME) * p * p;
+        Some((volume, LOW_PITCH + (HIGH_PITCH - LOW_PITCH) * p))
+    }
+}
@@ -0,0 +1,109 @@
+// Flashlight for Backrooms Doom
+// The player carries a flashlight (toggled with the flashlight key) that lights up the
+// walls in a cone around the middle of the view, strongest on walls close by (the
+// beam itself is Effects::flashlight_beam). It runs on a battery that drains while it
+// is on; under a fifth of a charge the beam starts to flicker, and when the battery is
+// flat it goes out and won't come back on. Batteries ('B' in the maze file) lie around
+// the level; picked up, they go into the inventory (see inventory.rs), or top it up
+// straight away when there is no room.
+
+use crate::maze::Maze;
+use rand::Rng;
+use raylib::prelude::*;
+
+pub const COLLECT_RADIUS: f32 = 0.5;
+pub const BATTERY_CHARGE: f32 = 0.5; // Charge in a battery pickup (a full battery is 1.0)
+const DRAIN_TIME: f32 = 120.0;       // Seconds a full battery lasts with the light on
+const LOW_BATTERY: f32 = 0.2;        // Charge under which the beam flickers
+
+pub const COLOR: Color = Color::new(150, 220, 90, 255);
+
+pub struct Flashlight {
+    pub on: bool,
+    battery: f32,                 // Charge left, 0.0 - 1.0
+    batteries: Vec<(f32, f32)>,   // Battery pickups still lying in the level
+    flicker: f32,                 // Current brightness of a flickering beam
+    flicker_timer: f32,           // Until the flicker picks a new brightness
+}
+
+impl Flashlight {
+    /// Switched off with a full battery, and the level's batteries to pick up
+    pub fn new(maze: &Maze) -> Self {
+        Self { on: false, battery: 1.0, batteries: maze.battery_positions.clone(), flicker: 1.0, flicker_timer: 0.0 }
+    }
+
+    /// Switch the light on or off. A flat battery keeps it off; returns whether it is on
+    pub fn toggle(&mut self) -> bool {
+        self.on = !self.on && self.battery > 0.0;
+        self.on
+    }
+
+    pub fn battery(&self) -> f32 {
+        self.battery
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.battery < LOW_BATTERY
+    }
+
+    /// Drain the battery while the light is on and flicker it when low
+    pub fn update(&mut self, delta_time: f32) {
+        if !self.on {
+            return;
+        }
+        self.battery = (self.battery - delta_time / DRAIN_TIME).max(0.0);
+        if self.battery <= 0.0 {
+            self.on = false;
+        }
+
+        self.flicker_timer -= delta_time;
+        if self.flicker_timer <= 0.0 {
+            let mut rng = rand::thread_rng();
+            // The flatter the battery, the more often the beam drops out
+            let dropout = 1.0 - self.battery / LOW_BATTERY;
+            self.flicker = if rng.gen::<f32>() < 0.3 + 0.5 * dropout { rng.gen_range(0.1..0.6) } else { 1.0 };
+            self.flicker_timer = rng.gen_range(0.04..0.25);
+        }
+    }
+
+    /// Beam strength, 0.0 (off) - 1.0
+    pub fn intensity(&self) -> f32 {
+        match (self.on, self.is_low()) {
+            (false, _) => 0.0,
+            (true, false) => 1.0,
+            (true, true) => self.flicker,
+        }
+    }
+
+    /// Battery pickups still lying in the level
+    pub fn batteries(&self) -> &[(f32, f32)] {
+        &self.batteries
+    }
+
+    /// Pick up a battery within reach of (x, y), to charge with or keep in the inventory.
+    /// Returns where it was
+    pub fn collect_near(&mut self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let index = self
+            .batteries
+            .iter()
+            .position(|&(bx, by)| ((bx - x).powi(2) + (by - y).powi(2)).sqrt() < COLLECT_RADIUS)?;
+        Some(self.batteries.remove(index))
+    }
+
+    /// Put a battery in: BATTERY_CHARGE more charge. Returns false when it was already full
+    pub fn charge(&mut self) -> bool {
+        if self.battery >= 1.0 {
+            return false;
+        }
+        self.battery = (self.battery + BATTERY_CHARGE).min(1.0);
+        true
+    }
+}
+
+/// Whether the point (u, v), each -1.0 - 1.0 across the sprite, is part of the battery:
+/// an upright cell with a nub on top
+pub fn covers(u: f32, v: f32) -> bool {
+    let cell = u.abs() < 0.45 && (-0.7..=1.0).contains(&v);
+    let nub = u.abs() < 0.18 && (-0.9..-0.7).contains(&v);
+    cell || nub
+}
@@ -0,0 +1,51 @@
+// Menu flythrough for Backrooms Doom
+// Behind the main menu the camera drifts slowly through the level, along the
+// route from the start to the exit and back, rendered with the normal raycaster
+// and then blurred and darkened so the menu text stays readable. The heading
+// eases round corners and wanders a little, like someone lost looking around.
+// Because it keeps rendering for as long as the menu is open it doubles as a
+// soak test of the renderer: leave the menu running with --strict-math and any
+// NaN in the render paths panics (see guard.rs).
+//
+// `menu_flythrough = false` in settings.toml brings back the plain black menu.
+
+use crate::benchmark::CameraPath;
+use crate::maze::Maze;
+use std::f32::consts::{PI, TAU};
+
+const SPEED: f32 = 0.6;        // Tiles per second
+const TURN_RATE: f32 = 1.5;    // How fast the heading follows the path (per second)
+const LOOK_AROUND: f32 = 0.35; // Radians of slow side-to-side looking
+const LOOK_PERIOD: f32 = 9.0;  // Seconds per look left and right
+pub const BLUR_RADIUS: usize = 2; // Pixels
+pub const DARKEN: f32 = 0.4;      // Brightness of the background
+
+pub struct Flythrough {
+    path: CameraPath,
+    time: f32,
+    angle: f32,
+}
+
+impl Flythrough {
+    pub fn new(maze: &Maze) -> Self {
+        let path = CameraPath::new(maze);
+        let angle = path.at(0.0).map_or(0.0, |(_, _, angle)| angle);
+        Self { path, time: 0.0, angle }
+    }
+
+    /// Advance the camera. Returns its position and view angle
+    pub fn update(&mut self, delta_time: f32) -> (f32, f32, f32) {
+        self.time += delta_time;
+        let Some((x, y, heading)) = self.path.at(self.time * SPEED) else {
+            // No way to the exit: stand at the start and turn slowly
+            let (x, y) = self.path.start();
+            return (x, y, self.time * 0.2);
+        };
+
+        let look = (self.time * TAU / LOOK_PERIOD).sin() * LOOK_AROUND;
+        let target = heading + look;
+        let difference = (target - self.angle + PI).rem_euclid(TAU) - PI;
+        self.angle = (self.angle + difference * (TURN_RATE * delta_time).min(1.0)).rem_euclid(TAU);
+        (x, y, self.angle)
+    }
+}
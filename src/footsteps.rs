@@ -0,0 +1,59 @@
+// Enemy footsteps for Backrooms Doom
+// Entities are heard walking before they're seen. A step sounds each time an
+// enemy completes a stride (the same stride that leaves its footprints, see
+// tracks.rs), so the cadence follows how fast it actually moves. Each step is
+// placed relative to the player: quieter with distance, muffled by walls, and
+// panned to the side it comes from. Steps on water splash (higher and louder),
+// and an enemy chasing the player stamps harder so a chase is unmistakable.
+// Pitch wobbles a little per step so a walk doesn't sound like a metronome, on
+// top of the enemy's Doppler shift (doppler.rs).
+
+use crate::maze::Maze;
+use crate::stealth;
+use rand::Rng;
+
+const HEARING_RANGE: f32 = 9.0;  // Tiles
+const OCCLUDED: f32 = 0.5;       // Loudness left through walls
+const WALK_VOLUME: f32 = 0.55;   // Relative to a chase step
+const CARPET_PITCH: f32 = 0.8;   // Heavier than the player's own steps
+const WATER_PITCH: f32 = 1.2;
+const WATER_VOLUME: f32 = 1.2;
+const CHASE_PITCH: f32 = 0.9;    // Multiplier, stamping steps sound lower
+const PITCH_JITTER: f32 = 0.06;
+
+#[derive(Clone, Copy)]
+pub struct StepSound {
+    pub volume: f32, // 0.0 - 1.0
+    pub pan: f32,    // 0.0 - 1.0, 0.5 centered (raylib puts 1.0 fully on the left)
+    pub pitch: f32,
+}
+
+/// The step an enemy at `source` just took, heard from `listener` (x, y, view angle),
+/// with its Doppler `shift`. None when it's too far away to hear
+pub fn enemy_step(maze: &Maze, listener: (f32, f32, f32), source: (f32, f32), chasing: bool, shift: f32, rng: &mut impl Rng) -> Option<StepSound> {
+    let (x, y, angle) = listener;
+    let (dx, dy) = (source.0 - x, source.1 - y);
+    let distance = (dx * dx + dy * dy).sqrt() / maze.tile_size;
+    if distance >= HEARING_RANGE {
+        return None;
+    }
+
+    let falloff = 1.0 - distance / HEARING_RANGE;
+    let walls = if stealth::line_of_sight(maze, (x, y), source) { 1.0 } else { OCCLUDED };
+    let gait = if chasing { 1.0 } else { WALK_VOLUME };
+    let (surface_volume, surface_pitch) = if maze.is_water(source.0, source.1) {
+        (WATER_VOLUME, WATER_PITCH)
+    } else {
+        (1.0, CARPET_PITCH)
+    };
+    let gait_pitch = if chasing { CHASE_PITCH } else { 1.0 };
+    let pitch = (surface_pitch * gait_pitch + rng.gen_range(-PITCH_JITTER..PITCH_JITTER)) * shift;
+
+    // Positive side = step is to the player's right
+    let side = (dy.atan2(dx) - angle).sin();
+    Some(StepSound {
+        volume: (falloff * falloff * walls * gait * surface_volume).min(1.0),
+        pan: 0.5 - 0.5 * side,
+        pitch,
+    })
+}
@@ -0,0 +1,69 @@
+// Frame pacing for Backrooms Doom
+// Applies the VSync / FPS cap settings and, in precise mode, waits for the
+// frame deadline ourselves instead of relying on raylib's set_target_fps
+
+use crate::config::{Config, FramePacing};
+use raylib::prelude::*;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Wake up this long before the deadline and spin the rest (sleep is not precise enough)
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+
+pub struct FramePacer {
+    next_frame: Instant,
+}
+
+impl FramePacer {
+    pub fn new() -> Self {
+        Self {
+            next_frame: Instant::now(),
+        }
+    }
+
+    /// Push VSync and the FPS cap from the settings to raylib
+    pub fn apply(rl: &mut RaylibHandle, config: &Config) {
+        let vsync = WindowState::default().set_vsync_hint(true);
+        if config.vsync {
+            rl.set_window_state(vsync);
+        } else {
+            rl.clear_window_state(vsync);
+        }
+
+        // In precise mode raylib must not wait on its own
+        let target = match config.frame_pacing {
+            FramePacing::Raylib => config.fps_cap.target().unwrap_or(0),
+            FramePacing::Precise => 0,
+        };
+        rl.set_target_fps(target);
+    }
+
+    /// Wait until the next frame is due (call after the frame has been drawn)
+    pub fn wait(&mut self, config: &Config) {
+        let fps = match (config.frame_pacing, config.fps_cap.target()) {
+            (FramePacing::Precise, Some(fps)) => fps,
+            _ => {
+                self.next_frame = Instant::now();
+                return;
+            }
+        };
+
+        let frame_time = Duration::from_secs_f64(1.0 / fps as f64);
+        self.next_frame += frame_time;
+
+        let now = Instant::now();
+        if self.next_frame <= now {
+            // Running behind, don't try to catch up with a burst of short frames
+            self.next_frame = now;
+            return;
+        }
+
+        let remaining = self.next_frame - now;
+        if remaining > SPIN_MARGIN {
+            thread::sleep(remaining - SPIN_MARGIN);
+        }
+        while Instant::now() < self.next_frame {
+            std::hint::spin_loop();
+        }
+    }
+}
@@ -1,12 +1,21 @@
+use crate::color_grade::ColorGrade;
+use crate::light;
+use crate::sprite::Frame;
+use crate::textures::Texture;
+use log::warn;
 use raylib::prelude::*;
 
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
     buffer: Vec<Color>,
-    // Cache for texture rendering
-    image: Option<Image>,
+    depth: Vec<f32>,  // View depth of the wall in each column (1D z-buffer for sprites)
+    pub ambient: f32, // Sprite brightness multiplier, lowered during blackouts
+    pub horizon: f32, // Screen row of the horizon, moved by looking up and down
+    // GPU copy of the buffer, drawn as one quad (see prepare and render)
     texture: Option<Texture2D>,
+    upload: Vec<u8>,       // Buffer as RGBA bytes, reused every frame
+    texture_failed: bool, // Texture couldn't be created, draw pixel by pixel instead
 }
 
 impl Framebuffer {
@@ -15,11 +24,20 @@ impl Framebuffer {
             width,
             height,
             buffer: vec![Color::BLACK; width * height],
-            image: None,
+            depth: vec![f32::INFINITY; width],
+            ambient: 1.0,
+            horizon: height as f32 / 2.0,
             texture: None,
+            upload: Vec::new(),
+            texture_failed: false,
         }
     }
 
+    /// Raw pixels, row by row
+    pub fn pixels(&self) -> &[Color] {
+        &self.buffer
+    }
+
     /// Clear the framebuffer with a color
     pub fn clear(&mut self, color: Color) {
         self.buffer.fill(color);
@@ -33,6 +51,141 @@ impl Framebuffer {
         }
     }
 
+    /// Mix a color over the existing pixel (alpha 0.0 - 1.0), with bounds checking
+    #[inline]
+    pub fn blend_pixel(&mut self, x: usize, y: usize, color: Color, alpha: f32) {
+        if x < self.width && y < self.height {
+            let index = y * self.width + x;
+            let under = self.buffer[index];
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * alpha) as u8;
+            self.buffer[index] = Color::new(mix(under.r, color.r), mix(under.g, color.g), mix(under.b, color.b), 255);
+        }
+    }
+
+    /// Blend a flat shape into a screen rectangle. `covers(u, v)` gets the position inside
+    /// the rectangle as -1.0 - 1.0 on each axis and says whether that pixel is part of the shape
+    pub fn blend_shape(&mut self, rect: (f32, f32, f32, f32), color: Color, alpha: f32, covers: impl Fn(f32, f32) -> bool) {
+        let (left, top, width, height) = rect;
+        if width < 1.0 || height < 1.0 {
+            return;
+        }
+        for py in 0..(height as usize) {
+            let y = top as i32 + py as i32;
+            if y < 0 || y as usize >= self.height {
+                continue;
+            }
+            let v = (py as f32 + 0.5) / height * 2.0 - 1.0;
+            for px in 0..(width as usize) {
+                let x = left as i32 + px as i32;
+                if x < 0 || x as usize >= self.width {
+                    continue;
+                }
+                let u = (px as f32 + 0.5) / width * 2.0 - 1.0;
+                if covers(u, v) {
+                    self.blend_pixel(x as usize, y as usize, color, alpha);
+                }
+            }
+        }
+    }
+
+    /// Soft round glow of `radius` pixels around (x, y), fading out from `strength` (0.0 - 1.0)
+    /// at the center. Skips columns where a wall is in front of view depth `depth`
+    pub fn glow(&mut self, center: (f32, f32), radius: f32, color: Color, strength: f32, depth: f32) {
+        if radius < 1.0 {
+            return;
+        }
+        let (cx, cy) = center;
+        let left = (cx - radius).max(0.0) as usize;
+        let right = ((cx + radius) as usize).min(self.width);
+        let top = (cy - radius).max(0.0) as usize;
+        let bottom = ((cy + radius) as usize).min(self.height);
+        for x in left..right {
+            if self.occluded(x as i32, depth) {
+                continue;
+            }
+            for y in top..bottom {
+                let distance = ((x as f32 - cx).powi(2) + (y as f32 - cy).powi(2)).sqrt() / radius;
+                if distance < 1.0 {
+                    self.blend_pixel(x, y, color, strength * (1.0 - distance).powi(2));
+                }
+            }
+        }
+    }
+
+    /// Glow spilling `reach` pixels above `start` and below `end` in column x, fading out
+    /// from `strength` (0.0 - 1.0) next to the span
+    pub fn glow_column(&mut self, x: usize, (start, end): (usize, usize), reach: usize, color: Color, strength: f32) {
+        for step in 0..reach {
+            let alpha = strength * (1.0 - step as f32 / reach as f32).powi(2);
+            if let Some(y) = start.checked_sub(step + 1) {
+                self.blend_pixel(x, y, color, alpha);
+            }
+            self.blend_pixel(x, end + step, color, alpha);
+        }
+    }
+
+    /// Forget the last frame's walls: nothing in any column hides a sprite
+    pub fn clear_depth(&mut self) {
+        self.depth.fill(f32::INFINITY);
+    }
+
+    /// Record the view depth of the wall drawn in column x
+    #[inline]
+    pub fn set_depth(&mut self, x: usize, depth: f32) {
+        if x < self.width {
+            self.depth[x] = depth;
+        }
+    }
+
+    /// Whether something at view depth `depth` in column x is behind that column's wall
+    /// (columns off the screen count as hidden)
+    #[inline]
+    pub fn occluded(&self, x: i32, depth: f32) -> bool {
+        x < 0 || x as usize >= self.width || depth >= self.depth[x as usize]
+    }
+
+    /// Draw a billboard sprite at view depth `depth` scaled into a screen rectangle, skipping
+    /// transparent texels and columns where a wall is in front. flash draws every opaque texel
+    /// white, brightness darkens the sprite (1.0 = unchanged)
+    pub fn draw_sprite(&mut self, tex: &Texture, rect: (f32, f32, f32, f32), flash: bool, brightness: f32, depth: f32) {
+        self.draw_sprite_frame(tex, Frame::WHOLE, rect, flash, brightness, depth);
+    }
+
+    /// draw_sprite for one frame of a sprite sheet, flipped left to right if it is mirrored
+    pub fn draw_sprite_frame(&mut self, tex: &Texture, frame: Frame, rect: (f32, f32, f32, f32), flash: bool, brightness: f32, depth: f32) {
+        let (left, top, width, height) = rect;
+        let frame_width = (tex.width / frame.count.max(1)).max(1);
+        let frame_left = frame.index * frame_width;
+        for py in 0..(height as usize) {
+            let y = top as i32 + py as i32;
+            if y < 0 || y as usize >= self.height {
+                continue;
+            }
+            let tex_y = (py as f32 / height * tex.height as f32) as usize;
+
+            for px in 0..(width as usize) {
+                let x = left as i32 + px as i32;
+                if self.occluded(x, depth) {
+                    continue;
+                }
+                let tex_x = (px as f32 / width * frame_width as f32) as usize;
+                let tex_x = frame_left + if frame.mirrored { frame_width.saturating_sub(tex_x + 1) } else { tex_x.min(frame_width - 1) };
+
+                let color = tex.sample_point(tex_x, tex_y);
+                if color.a < 10 {
+                    continue;
+                }
+                let color = if flash {
+                    Color::WHITE
+                } else {
+                    let shade = |c: u8| (c as f32 * brightness * self.ambient) as u8;
+                    Color::new(shade(color.r), shade(color.g), shade(color.b), 255)
+                };
+                self.buffer[y as usize * self.width + x as usize] = color;
+            }
+        }
+    }
+
     /// Set a pixel without bounds checking (faster, use carefully)
     #[inline]
     pub unsafe fn set_pixel_unchecked(&mut self, x: usize, y: usize, color: Color) {
@@ -92,14 +245,99 @@ impl Framebuffer {
         }
     }
 
-    /// Render the framebuffer to the screen using raylib (optimized with draw_pixel)
-    pub fn render(&self, d: &mut RaylibDrawHandle, scale: i32) {
+    /// Apply animated film grain and VHS static. `intensity` is 0.0 to 1.0,
+    /// `frame` changes the noise pattern every frame
+    pub fn apply_film_grain(&mut self, intensity: f32, frame: u32) {
+        if intensity <= 0.0 {
+            return;
+        }
+
+        let grain_amount = intensity * 60.0;
+        // VHS tracking band slowly rolling down the screen
+        let band_y = (frame as usize * 3) % (self.height + 40);
+
+        for y in 0..self.height {
+            // Occasional horizontally shifted scanline
+            let row_noise = noise_hash(0, y as u32, frame);
+            let shift = if (row_noise & 0xff) as f32 / 255.0 < intensity * 0.05 {
+                1 + (row_noise >> 8) as usize % 4
+            } else {
+                0
+            };
+            let in_band = y + 40 >= band_y && y < band_y;
+
+            for x in (0..self.width).rev() {
+                let index = y * self.width + x;
+                let mut color = if shift > 0 && x >= shift {
+                    self.buffer[index - shift]
+                } else {
+                    self.buffer[index]
+                };
+
+                // Signed luma noise
+                let n = (noise_hash(x as u32, y as u32, frame) & 0xff) as f32 / 255.0 - 0.5;
+                let mut offset = n * grain_amount;
+                if in_band {
+                    offset += n.abs() * intensity * 120.0; // Bright static in the tracking band
+                }
+
+                let apply = |c: u8| (c as f32 + offset).clamp(0.0, 255.0) as u8;
+                color = Color::new(apply(color.r), apply(color.g), apply(color.b), color.a);
+                self.buffer[index] = color;
+            }
+        }
+    }
+
+    /// Apply a color grade to every pixel (last step of the post-processing chain)
+    pub fn apply_color_grade(&mut self, grade: &ColorGrade) {
+        for pixel in self.buffer.iter_mut() {
+            *pixel = grade.apply(*pixel);
+        }
+    }
+
+    /// Create the texture the buffer is uploaded to, if it doesn't exist yet. Call
+    /// once per frame before drawing starts (textures can't be made while drawing)
+    pub fn prepare(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) {
+        if self.texture.is_some() || self.texture_failed {
+            return;
+        }
+        let image = Image::gen_image_color(self.width as i32, self.height as i32, Color::BLACK);
+        match rl.load_texture_from_image(thread, &image) {
+            Ok(texture) => self.texture = Some(texture),
+            Err(e) => {
+                warn!("Framebuffer texture unavailable, drawing pixel by pixel: {}", e);
+                self.texture_failed = true;
+            }
+        }
+    }
+
+    /// Render the framebuffer to the screen, scaled up by `scale`: uploaded to the
+    /// texture and drawn as one quad, or pixel by pixel before prepare has run
+    pub fn render(&mut self, d: &mut RaylibDrawHandle, scale: i32, offset: (i32, i32)) {
+        let (offset_x, offset_y) = offset;
+        if let Some(texture) = &mut self.texture {
+            self.upload.clear();
+            self.upload.extend(self.buffer.iter().flat_map(|c| [c.r, c.g, c.b, 255]));
+            if texture.update_texture(&self.upload).is_ok() {
+                let (width, height) = (self.width as f32, self.height as f32);
+                d.draw_texture_pro(
+                    &*texture,
+                    Rectangle::new(0.0, 0.0, width, height),
+                    Rectangle::new(offset_x as f32, offset_y as f32, width * scale as f32, height * scale as f32),
+                    Vector2::new(0.0, 0.0),
+                    0.0,
+                    Color::WHITE,
+                );
+                return;
+            }
+        }
+
         if scale == 1 {
             // Fast path for 1:1 rendering - use draw_pixel
             for y in 0..self.height {
                 for x in 0..self.width {
                     let color = self.buffer[y * self.width + x];
-                    d.draw_pixel(x as i32, y as i32, color);
+                    d.draw_pixel(x as i32 + offset_x, y as i32 + offset_y, color);
                 }
             }
         } else {
@@ -108,8 +346,8 @@ impl Framebuffer {
                 for x in 0..self.width {
                     let color = self.buffer[y * self.width + x];
                     d.draw_rectangle(
-                        (x as i32) * scale,
-                        (y as i32) * scale,
+                        (x as i32) * scale + offset_x,
+                        (y as i32) * scale + offset_y,
                         scale,
                         scale,
                         color,
@@ -149,14 +387,168 @@ impl Framebuffer {
         }
     }
 
-    /// Optimized render using Image (faster for larger screens)
-    #[allow(dead_code)]
-    pub fn to_image(&self) -> Image {
-        let img = Image::gen_image_color(self.width as i32, self.height as i32, Color::BLACK);
+    /// Apply the low-health vignette: a red tint hugging the screen edges.
+    /// Unlike the anxiety vignette it tints instead of darkening and falls off
+    /// much faster, leaving the center of the screen untouched
+    pub fn apply_damage_vignette(&mut self, strength: f32) {
+        if strength <= 0.0 {
+            return;
+        }
+
+        let center_x = self.width as f32 / 2.0;
+        let center_y = self.height as f32 / 2.0;
+        let tint = Color::new(140, 0, 0, 255);
+
+        for y in 0..self.height {
+            let dy = (y as f32 - center_y) / center_y;
+            for x in 0..self.width {
+                let dx = (x as f32 - center_x) / center_x;
+                // Square-ish falloff so the corners and edges carry the effect
+                let edge = (dx.abs().max(dy.abs()) * 0.6 + (dx * dx + dy * dy).sqrt() * 0.4).min(1.0);
+                let amount = (edge.powi(3) * strength).min(0.85);
+                if amount <= 0.01 {
+                    continue;
+                }
 
-        // Note: Direct pixel manipulation would require unsafe access
-        // For now, this is a placeholder implementation
-        img
+                let index = y * self.width + x;
+                let color = self.buffer[index];
+                let blend = |c: u8, t: u8| (c as f32 + (t as f32 - c as f32) * amount) as u8;
+                self.buffer[index] = Color::new(
+                    blend(color.r, tint.r),
+                    blend(color.g, tint.g),
+                    blend(color.b, tint.b),
+                    255,
+                );
+            }
+        }
+    }
+
+    /// Mask everything but a narrow vertical slit (looking through a cracked door),
+    /// darkening towards the slit edges
+    pub fn apply_peek_mask(&mut self, slit_fraction: f32) {
+        let center_x = self.width as f32 / 2.0;
+        let center_y = self.height as f32 / 2.0;
+        let half_slit = (self.width as f32 * slit_fraction / 2.0).max(1.0);
+
+        for y in 0..self.height {
+            let dy = (y as f32 - center_y) / center_y;
+            for x in 0..self.width {
+                let dx = (x as f32 - center_x).abs() / half_slit;
+                // 1.0 in the middle of the slit, 0.0 at and beyond its edge
+                let open = ((1.0 - dx) * 2.5).clamp(0.0, 1.0) * (1.0 - dy * dy * 0.6);
+                let index = y * self.width + x;
+                if open <= 0.0 {
+                    self.buffer[index] = Color::BLACK;
+                    continue;
+                }
+                let color = self.buffer[index];
+                let shade = |c: u8| (c as f32 * open) as u8;
+                self.buffer[index] = Color::new(shade(color.r), shade(color.g), shade(color.b), 255);
+            }
+        }
+    }
+
+    /// Scale every pixel's brightness (lights flickering out)
+    pub fn apply_brightness(&mut self, factor: f32) {
+        let shade = |c: u8| (c as f32 * factor) as u8;
+        for color in &mut self.buffer {
+            *color = Color::new(shade(color.r), shade(color.g), shade(color.b), 255);
+        }
+    }
+
+    /// Wash the image out towards white, bright pixels first (0.0 - 1.0 all white)
+    pub fn apply_bloom(&mut self, amount: f32) {
+        if amount <= 0.0 {
+            return;
+        }
+        for color in &mut self.buffer {
+            let luma = (color.r as f32 + color.g as f32 + color.b as f32) / (3.0 * 255.0);
+            let alpha = (amount + (1.0 - amount) * amount * luma).min(1.0);
+            let mix = |c: u8| (c as f32 + (255.0 - c as f32) * alpha) as u8;
+            *color = Color::new(mix(color.r), mix(color.g), mix(color.b), 255);
+        }
+    }
+
+    /// Black out the top `off` of `bands` horizontal bands (lights going out row by row)
+    pub fn apply_bands_off(&mut self, bands: usize, off: usize) {
+        let rows = (self.height * off.min(bands)) / bands.max(1);
+        self.buffer[..rows * self.width].fill(Color::BLACK);
+    }
+
+    /// Roll the image by `roll` radians around its center and raise it by `drop` screen
+    /// heights, as if the camera were falling sideways. Uncovered pixels go black
+    pub fn apply_tilt(&mut self, roll: f32, drop: f32) {
+        if roll == 0.0 && drop == 0.0 {
+            return;
+        }
+        let (width, height) = (self.width as f32, self.height as f32);
+        let (sin, cos) = roll.sin_cos();
+        let shift = drop * height;
+        let source = self.buffer.clone();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (dx, dy) = (x as f32 - width / 2.0, y as f32 - height / 2.0);
+                let src_x = dx * cos - dy * sin + width / 2.0;
+                let src_y = dx * sin + dy * cos + height / 2.0 + shift;
+                let color = if src_x >= 0.0 && src_x < width && src_y >= 0.0 && src_y < height {
+                    source[src_y as usize * self.width + src_x as usize]
+                } else {
+                    Color::BLACK
+                };
+                self.buffer[y * self.width + x] = color;
+            }
+        }
+    }
+
+    /// Roll the image by `roll` radians around its center, zoomed in just enough that
+    /// the corners stay covered (the view bob leaning into a strafe)
+    pub fn apply_roll(&mut self, roll: f32) {
+        if roll == 0.0 {
+            return;
+        }
+        let (width, height) = (self.width as f32, self.height as f32);
+        let (sin, cos) = roll.sin_cos();
+        let zoom = cos + sin.abs() * (width / height).max(height / width);
+        let source = self.buffer.clone();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (dx, dy) = (x as f32 - width / 2.0, y as f32 - height / 2.0);
+                let src_x = ((dx * cos - dy * sin) / zoom + width / 2.0).clamp(0.0, width - 1.0);
+                let src_y = ((dx * sin + dy * cos) / zoom + height / 2.0).clamp(0.0, height - 1.0);
+                self.buffer[y * self.width + x] = source[src_y as usize * self.width + src_x as usize];
+            }
+        }
+    }
+
+    /// Box blur `radius` pixels wide, a horizontal then a vertical pass (menu background)
+    pub fn apply_blur(&mut self, radius: usize) {
+        if radius == 0 {
+            return;
+        }
+        let (width, height) = (self.width, self.height);
+        let horizontal = Self::blur_pass(&self.buffer, width, height, radius, (1, 0));
+        self.buffer = Self::blur_pass(&horizontal, width, height, radius, (0, 1));
+    }
+
+    /// Average every pixel with its neighbors up to `radius` away along `step`
+    fn blur_pass(source: &[Color], width: usize, height: usize, radius: usize, step: (usize, usize)) -> Vec<Color> {
+        let mut out = Vec::with_capacity(source.len());
+        for y in 0..height {
+            for x in 0..width {
+                let (along, limit) = if step.0 == 1 { (x, width) } else { (y, height) };
+                let (from, to) = (along.saturating_sub(radius), (along + radius).min(limit - 1));
+                let mut sum = [0u32; 3];
+                for i in from..=to {
+                    let pixel = if step.0 == 1 { source[y * width + i] } else { source[i * width + x] };
+                    sum[0] += pixel.r as u32;
+                    sum[1] += pixel.g as u32;
+                    sum[2] += pixel.b as u32;
+                }
+                let count = (to - from + 1) as u32;
+                out.push(Color::new((sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8, 255));
+            }
+        }
+        out
     }
 
     /// Apply fog effect based on distance
@@ -195,19 +587,9 @@ impl Framebuffer {
         let x_start = x_start.min(self.width);
         let x_end = x_end.min(self.width);
 
-        // Improved floor texture mapping with proper perspective
-        // Calculate row distance from player
-        let row_distance = (self.height as f32 / 2.0) / (y as f32 - self.height as f32 / 2.0).max(1.0);
-        
         for x in x_start..x_end {
-            // Calculate the angle for this column
-            let camera_x = 2.0 * x as f32 / self.width as f32 - 1.0;
-            let ray_angle = player_angle + camera_x * 0.5; // FOV factor
-            
-            // Calculate floor position
-            let floor_x = player_x + ray_angle.cos() * row_distance * 0.5;
-            let floor_y = player_y + ray_angle.sin() * row_distance * 0.5;
-            
+            let (floor_x, floor_y) = self.floor_position(x, y, player_x, player_y, player_angle);
+
             // Sample texture with tiling
             let tex_u = (floor_x.abs() * 2.0) as usize % tex_width;
             let tex_v = (floor_y.abs() * 2.0) as usize % tex_height;
@@ -228,6 +610,85 @@ impl Framebuffer {
         }
     }
 
+    /// World position of the floor seen at screen pixel (x, y), below the horizon
+    pub fn floor_position(&self, x: usize, y: usize, player_x: f32, player_y: f32, player_angle: f32) -> (f32, f32) {
+        // Improved floor texture mapping with proper perspective
+        // Calculate row distance from player
+        let row_distance = (self.height as f32 / 2.0) / (y as f32 - self.horizon).max(1.0);
+
+        // Calculate the angle for this column
+        let camera_x = 2.0 * x as f32 / self.width as f32 - 1.0;
+        let ray_angle = player_angle + camera_x * 0.5; // FOV factor
+
+        (player_x + ray_angle.cos() * row_distance * 0.5, player_y + ray_angle.sin() * row_distance * 0.5)
+    }
+
+    /// Multiply a pixel by a light tint (see light.rs)
+    pub fn tint_pixel(&mut self, x: usize, y: usize, tint: [f32; 3]) {
+        if let Some(pixel) = self.buffer.get_mut(y * self.width + x) {
+            *pixel = light::apply(*pixel, tint);
+        }
+    }
+
+    /// Fake reflection on a wet floor: mirror the wall column drawn between `wall_top` and
+    /// `wall_bottom` into the floor below it. `wetness` holds how reflective each floor row from
+    /// `wall_bottom` down is (0.0 = dry); the reflection fades away from the wall, bright pixels
+    /// (lights, lit walls) reflect the most, and `phase` ripples it
+    pub fn reflect_wall(&mut self, x: usize, wall_top: usize, wall_bottom: usize, wetness: &[f32], phase: f32) {
+        let wall_height = wall_bottom.saturating_sub(wall_top);
+        if x >= self.width || wall_height == 0 {
+            return;
+        }
+        for (row, &wet) in wetness.iter().enumerate().take(wall_height) {
+            let y = wall_bottom + row;
+            if wet <= 0.0 || y >= self.height {
+                continue;
+            }
+            let ripple = ((row as f32 * 0.7 + phase * 3.0).sin() * 1.5) as isize;
+            let source = (wall_bottom as isize - 1 - row as isize + ripple).clamp(wall_top as isize, wall_bottom as isize - 1);
+            let Some(&color) = self.buffer.get(source as usize * self.width + x) else {
+                continue;
+            };
+            let luminance = (color.r as f32 + color.g as f32 + color.b as f32) / (3.0 * 255.0);
+            let fade = 1.0 - row as f32 / wall_height as f32;
+            self.blend_pixel(x, y, color, wet * fade * (0.3 + 0.7 * luminance));
+        }
+    }
+
+    /// Warp the pixels inside `rect` (left, top, width, height) by up to `amplitude` pixels with
+    /// a smooth noise field, strongest in the middle and fading out to the edges. `drift` moves
+    /// the noise along so the warp shimmers over time (heat haze, see haze.rs)
+    pub fn apply_distortion(&mut self, rect: (f32, f32, f32, f32), amplitude: f32, drift: f32) {
+        let (left, top, width, height) = rect;
+        let x_start = left.max(0.0) as usize;
+        let y_start = top.max(0.0) as usize;
+        let x_end = ((left + width).max(0.0) as usize).min(self.width);
+        let y_end = ((top + height).max(0.0) as usize).min(self.height);
+        if x_start >= x_end || y_start >= y_end || amplitude < 0.5 {
+            return;
+        }
+
+        let source = self.buffer.clone();
+        let (center_x, center_y) = (left + width / 2.0, top + height / 2.0);
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                // Elliptical falloff from the middle of the region
+                let dx = (x as f32 - center_x) / (width / 2.0);
+                let dy = (y as f32 - center_y) / (height / 2.0);
+                let falloff = 1.0 - (dx * dx + dy * dy).min(1.0);
+                if falloff <= 0.0 {
+                    continue;
+                }
+                let (nx, ny) = (x as f32 * 0.08, y as f32 * 0.05 + drift);
+                let offset_x = (value_noise(nx, ny) - 0.5) * 2.0 * amplitude * falloff;
+                let offset_y = (value_noise(nx + 17.0, ny - drift * 0.5) - 0.5) * amplitude * falloff;
+                let sample_x = (x as f32 + offset_x).clamp(0.0, self.width as f32 - 1.0) as usize;
+                let sample_y = (y as f32 + offset_y).clamp(0.0, self.height as f32 - 1.0) as usize;
+                self.buffer[y * self.width + x] = source[sample_y * self.width + sample_x];
+            }
+        }
+    }
+
     /// Draw textured ceiling span (similar to floor but can be simpler)
     pub fn draw_textured_ceiling_span(
         &mut self,
@@ -266,3 +727,22 @@ impl Framebuffer {
         }
     }
 }
+
+/// Smooth 2D value noise in 0.0 - 1.0, interpolated between hashed lattice points
+fn value_noise(x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (fx, fy) = (smooth(x - x0), smooth(y - y0));
+    let corner = |cx: f32, cy: f32| (noise_hash(cx as i32 as u32, cy as i32 as u32, 0) & 0xffff) as f32 / 65535.0;
+    let top = corner(x0, y0) + (corner(x0 + 1.0, y0) - corner(x0, y0)) * fx;
+    let bottom = corner(x0, y0 + 1.0) + (corner(x0 + 1.0, y0 + 1.0) - corner(x0, y0 + 1.0)) * fx;
+    top + (bottom - top) * fy
+}
+
+/// Cheap integer hash used for per-pixel noise
+#[inline]
+fn noise_hash(x: u32, y: u32, frame: u32) -> u32 {
+    let mut h = x.wrapping_mul(374_761_393) ^ y.wrapping_mul(668_265_263) ^ frame.wrapping_mul(2_246_822_519);
+    h = (h ^ (h >> 13)).wrapping_mul(1_274_126_177);
+    h ^ (h >> 16)
+}
@@ -0,0 +1,70 @@
+// Walking gait for Backrooms Doom
+// One step cycle drives everything that follows the player's feet: the view bob,
+// the footstep sound and a light controller tap. The cycle advances with the
+// distance actually walked rather than with time, so a step lands exactly when
+// the bob bottoms out, whether walking, crouching, sliding to a stop or pushed
+// back by a wall (which doesn't count as walking at all).
+
+const STEP_LENGTH: f32 = 1.5;    // Tiles walked per step (a step every 0.5s at full speed)
+const BOB_HEIGHT: f32 = 0.012;   // Vertical bob, as a fraction of the screen height
+const BOB_SWAY: f32 = 0.008;     // Sideways sway towards the foot being stepped on
+const SETTLE_RATE: f32 = 6.0;    // How fast the bob follows changes in speed (per second)
+const MIN_WALK_SPEED: f32 = 0.1; // Slower than this (fraction of full speed) is standing still
+
+pub struct Gait {
+    phase: f32,      // 0.0 - 1.0 through the current step, a foot lands at 0.0
+    amount: f32,     // 0.0 - 1.0 bob strength, follows the walking speed
+    left_foot: bool, // Foot the current step lands on
+    stepped: bool,   // A foot landed this frame
+}
+
+impl Gait {
+    pub fn new() -> Self {
+        Self { phase: 0.5, amount: 0.0, left_foot: false, stepped: false }
+    }
+
+    /// Advance by `moved` tiles walked this frame at `speed` (fraction of full speed)
+    pub fn update(&mut self, moved: f32, speed: f32, delta_time: f32) {
+        self.stepped = false;
+        let walking = speed >= MIN_WALK_SPEED;
+        let target = if walking { speed.min(1.0) } else { 0.0 };
+        self.amount += (target - self.amount) * (SETTLE_RATE * delta_time).min(1.0);
+
+        if walking {
+            self.phase += moved.min(STEP_LENGTH) / STEP_LENGTH; // A teleport is not a thousand steps
+            if self.phase >= 1.0 {
+                self.phase = self.phase.fract();
+                self.left_foot = !self.left_foot;
+                self.stepped = true;
+            }
+        } else if self.amount < 0.01 {
+            // Come to rest mid-stride, so the first step after starting again is half a stride away
+            self.phase = 0.5;
+        }
+    }
+
+    /// Whether a foot landed this frame (play the footstep)
+    pub fn stepped(&self) -> bool {
+        self.stepped
+    }
+
+    /// How hard the last step landed, 0.0 - 1.0
+    pub fn strength(&self) -> f32 {
+        self.amount
+    }
+
+    /// Whether the player is walking (or still settling after stopping)
+    pub fn walking(&self) -> bool {
+        self.amount >= MIN_WALK_SPEED
+    }
+
+    /// View offset (x, y) as fractions of the screen height: lowest when a foot lands,
+    /// highest halfway through the step, swaying towards the foot being stepped on
+    pub fn bob(&self) -> (f32, f32) {
+        let angle = self.phase * std::f32::consts::TAU;
+        let down = (angle.cos() + 1.0) / 2.0;
+        let side = if self.left_foot { -1.0 } else { 1.0 };
+        let sway = (angle / 2.0).sin() * side;
+        (sway * BOB_SWAY * self.amount, down * BOB_HEIGHT * self.amount)
+    }
+}
@@ -1,22 +1,236 @@
-use crate::player::Player;
+use crate::alert::{self, Alerts};
+use crate::assets::{self, AssetManifest};
+use crate::attract::{self, Demo};
+use crate::player::{self, Player};
+use crate::profile::Profile;
 use crate::maze::Maze;
-use crate::textures::TextureManager;
+use crate::textures::{Texture, TextureManager};
 use crate::audio::AudioManager;
+use crate::splash::Splash;
 use crate::sprite::Sprite;
+use crate::campaign::{self, Campaign};
 use crate::caster::RayCaster;
-use crate::camera::Camera;
+use crate::chalk::Chalk;
+use crate::challenge::{self, Challenges, Reward, Trial};
+use crate::clock::{self, Clocks};
+use crate::barricade::{self, Barricade, Outcome};
+use crate::benchmark::Benchmark;
+use crate::blackout::{self, Blackout};
+use crate::bot::{self, Bot};
+use crate::budget::{Budget, Pool};
+use crate::camera::{self, Camera, ViewBob};
+use crate::color_grade::{ColorGrade, Theme};
+use crate::companion::{self, Companion, Order};
+use crate::contrast;
+use crate::idle::{self, IdlePenalty};
+use crate::inventory::{self, Item};
+use crate::keys::{self, Keys};
+use crate::container::{Container, ContainerKind, RummageResult, Search, SEARCH_RANGE};
+use crate::crosshair::{self, CrosshairState, CrosshairStyle};
+use crate::credits::{self, Credits};
+use crate::crowd::{self, Crowd};
+use crate::controls::{Action, Controls, Layout, Preset};
+use crate::config::{self, Config, DisplayMode, FpsCap, FramePacing, QualityPreset};
+use crate::display::Display;
+use crate::doors::{self, Doors};
+use crate::flashlight::{self, Flashlight};
+use crate::gait::Gait;
+use crate::glitch::Glitch;
+use crate::grime::{Grime, GrimeKind};
+use crate::guard;
+use crate::health::{Health, Modifier, Source};
+use crate::heatmap::{Heatmap, HeatmapMode};
+use crate::hints::{HintFrequency, HintInput, Hints};
+use crate::hud::{self, Anchor, HudLayout, StatusItem};
+use crate::console::Console;
+use crate::death::{self, DeathCause, DeathScene};
+use crate::decal::{DecalKind, Decals, Surface};
+use crate::logger;
 use crate::minimap::Minimap;
 use crate::ui::UI;
+use crate::versus::{self, Side, Versus};
+use crate::whisper::{self, Whispers};
 use crate::effects::Effects;
+use crate::escape::Escape;
+use crate::enemy::{self, Enemy};
+use crate::escalation::{Escalate, Escalation};
+use crate::leaderboard::{self, Leaderboard, RunRecord};
+use crate::light;
+use crate::loot::{self, LootTables};
+use crate::noise::Noise;
+use crate::options::{Options, Setting};
+use crate::packs;
+use crate::narration::Narrator;
+#[cfg(feature = "online-leaderboard")]
+use crate::online::OnlineLeaderboard;
+use crate::exit_hum::ExitHum;
+use crate::events::{EventQueue, GameEvent};
+use crate::flythrough::{self, Flythrough};
+use crate::footsteps::{self, StepSound};
+use crate::frame_pacing::FramePacer;
 use crate::framebuffer::Framebuffer;
-use crate::pill::{Pill, PillType, FloatingText};
+use crate::pill::{self, Pill, PillTable, PillType, FloatingText};
+use crate::prop::Prop;
+use crate::radio::{self, Radio};
+use crate::reverb::Acoustics;
+use crate::rumble::{self, Haptics, RumbleCurve};
+use crate::resources::TextureId;
+use crate::save::SaveData;
+use crate::schedule::{Schedule, Stage};
+use crate::search;
+use crate::stealth;
+use crate::status::{Effect, StatusEffects};
+use crate::translucent::Translucent;
+use crate::tally::{LevelLog, Tally, Tick};
+use crate::tips::{self, RunStats};
+use crate::tracks::{self, Strider};
+use crate::twitch::{Scare, TwitchChat};
+use log::{error, info, warn};
 use raylib::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const MAZE_PATH: &str = "maze.txt";
+const GENERATED_SIZE: (usize, usize) = (31, 21); // Tiles, for procedural mazes
+
+// Minimap layout (top-right corner of the screen)
+const MINIMAP_SIZE: i32 = 100;
+const MINIMAP_MARGIN: i32 = 10;
+
+// Film grain intensity when calm, and how much anxiety / idling add on top
+const GRAIN_BASE: f32 = 0.06;
+const GRAIN_ANXIETY: f32 = 0.6;
+const GRAIN_IDLE: f32 = 0.15;
+const GRAIN_GLITCH: f32 = 0.7;
+
+// Low-health vignette strength at 0 HP, and the health fraction where it starts pulsing
+const DAMAGE_VIGNETTE_MAX: f32 = 1.2;
+const DAMAGE_VIGNETTE_PULSE_BELOW: f32 = 0.3;
+
+// Feedback strength (0.0 - 1.0) for a swing that hits a wall / an enemy
+const WALL_HIT_STRENGTH: f32 = 0.4;
+const ENEMY_HIT_STRENGTH: f32 = 1.0;
+
+// Billboard sizes relative to a wall, and hitbox radius in tiles
+const ENEMY_SPRITE_HEIGHT: f32 = 0.9;
+const ENEMY_RADIUS: f32 = 0.3;
+const PROP_SPRITE_HEIGHT: f32 = 0.35;
+const PROP_RADIUS: f32 = 0.25;
+const DESK_SPRITE_HEIGHT: f32 = 0.4;
+const CABINET_SPRITE_HEIGHT: f32 = 0.65;
+const RADIO_SPRITE_HEIGHT: f32 = 0.18;
+const KEY_SPRITE_HEIGHT: f32 = 0.15;
+const KEY_SPIN_RATE: f32 = 2.0; // Radians per second the keys turn
+const CLOCK_SPRITE_HEIGHT: f32 = 0.25; // Clock pickups, as a fraction of the wall height
+const CLOCK_TURN_RATE: f32 = 0.8;      // Radians per second the clocks turn
+const ITEM_SPRITE_HEIGHT: f32 = 0.15;  // Inventory items on the floor
+const FLASHLIGHT_COLOR: [f32; 3] = [1.0, 0.95, 0.8]; // Warm white of the beam on the walls
+const BATTERY_SPRITE_HEIGHT: f32 = 0.2; // Battery pickups, as a fraction of the wall height
+const ORB_SPRITE_HEIGHT: f32 = 0.15;    // Challenge orbs, floating at chest height
+const COMPANION_SPRITE_HEIGHT: f32 = 0.85;
+// Flat decals and haze lie on or against a wall: tested at their center, this much in front
+const FLAT_DEPTH_SLACK: f32 = 0.3;
+
+// Number keys picking the inventory slots, 1 - 5
+const INVENTORY_KEYS: [KeyboardKey; inventory::SLOTS] =
+    [KeyboardKey::KEY_ONE, KeyboardKey::KEY_TWO, KeyboardKey::KEY_THREE, KeyboardKey::KEY_FOUR, KeyboardKey::KEY_FIVE];
+
+// Chat "glimpse" scare: how long the entity stays visible and how far down the hall it appears
+const GLIMPSE_TIME: f32 = 1.2;
+const GLIMPSE_MIN_DISTANCE: f32 = 2.5;
+const GLIMPSE_MAX_DISTANCE: f32 = 7.0;
+
+// Farthest a scout marker can be placed along the view direction
+const SCOUT_MARKER_RANGE: f32 = 8.0;
+
+// Doors: reach for opening/peeking, how far past the door the peek camera sits,
+// and the width of the visible slit as a fraction of the screen
+const DOOR_REACH: f32 = 1.3;
+const PEEK_DEPTH: f32 = 0.65;
+const PEEK_SLIT: f32 = 0.22;
+
+// Wet floor reflections: strength on water tiles, and on every floor tile in the Poolrooms
+const WATER_REFLECTION: f32 = 0.55;
+const POOLROOMS_REFLECTION: f32 = 0.2;
+
+// Door barricading: how close an enemy must be to keep pushing, how close the player must be
+// to brace the door, and the damage taken when standing next to a door that bursts open
+const PUSH_REACH: f32 = 1.0;
+const BRACE_REACH: f32 = 1.6;
+const DOOR_BURST_DAMAGE: i32 = 10;
+
+// Status effects from hazards: smoke stings the eyes while standing in it, a strike staggers
+const SMOKE_BLUR: f32 = 0.5;
+const SMOKE_BLUR_TIME: f32 = 1.0;
+const STRIKE_SLOW: f32 = 0.3;
+const STRIKE_SLOW_TIME: f32 = 1.5;
+
+// Moving slower than this fraction of full speed while holding a movement key means
+// the player is pushing into a wall (for hints)
+const WALL_PUSH_SPEED: f32 = 0.2;
+
+// Door slams further away than this (tiles) aren't felt on the controller
+const DOOR_RUMBLE_RANGE: f32 = 8.0;
+
+// Hearing range in tiles of the noises a search makes
+const SEARCH_NOISE_RADIUS: f32 = 4.0;
+const MISSED_RUMMAGE_NOISE_RADIUS: f32 = 8.0;
+
+/// What a melee swing connected with
+enum AttackTarget {
+    Enemy(usize),
+    Prop(usize),
+}
+
+// Seconds of "Resuming..." countdown after the window regains focus
+const RESUME_COUNTDOWN: f32 = 3.0;
+
+/// Texture handles used by the renderer, resolved once when the game is created
+pub struct TextureIds {
+    pub wall: TextureId,
+    pub wall_exit: TextureId,
+    pub floor: TextureId,
+    pub ceiling: TextureId,
+    pub red_pill: TextureId,  // Level texture, may be unloaded
+    pub blue_pill: TextureId, // Level texture, may be unloaded
+    pub enemy: TextureId,     // Level texture, may be unloaded
+    pub companion: TextureId, // Level texture, may be unloaded
+    pub prop: TextureId,      // Level texture, may be unloaded
+    pub desk: TextureId,      // Level texture, may be unloaded
+    pub cabinet: TextureId,   // Level texture, may be unloaded
+    pub door: TextureId,      // Falls back to the wall texture if missing
+    pub radio: TextureId,     // Level texture, may be unloaded
+}
+
+impl TextureIds {
+    /// Resolve all handles, failing if a texture the renderer cannot work without is missing
+    fn resolve(textures: &mut TextureManager) -> Result<Self, String> {
+        Ok(Self {
+            wall: textures.require("wall")?,
+            wall_exit: textures.require("wall_exit")?,
+            floor: textures.require("floor")?,
+            ceiling: textures.require("ceiling")?,
+            red_pill: textures.id("red_pill"),
+            blue_pill: textures.id("blue_pill"),
+            enemy: textures.id("enemy"),
+            companion: textures.id("companion"),
+            prop: textures.id("box"),
+            desk: textures.id("desk"),
+            cabinet: textures.id("cabinet"),
+            door: textures.id("door"),
+            radio: textures.id("radio"),
+        })
+    }
+}
 
 pub struct GameState<'a> {
-    pub player: Player,
+    pub players: Vec<Player>, // Every player entity in the level
+    pub local_index: usize,   // Player this client controls, renders and shows the HUD for
+    pub companion: Option<Companion>, // AI bot driving one of the players (see config.companion)
+    pub versus: Option<Versus>,       // Hotseat versus round: a second player hunts the local one
     pub maze: Maze,
     pub textures: TextureManager,
+    pub texture_ids: TextureIds,
     pub audio: AudioManager<'a>,
     pub sprites: Vec<Sprite>,
     pub raycaster: RayCaster,
@@ -30,7 +244,90 @@ pub struct GameState<'a> {
     pub game_timer: f32, // Timer in seconds (starts at 180.0 for 3 minutes)
     pub idle_timer: f32,  // Tracks time since last movement
     pub pills: Vec<Pill>,
+    pub items: Vec<(f32, f32, Item)>, // Inventory items lying on the floor (loot drops)
+    pub enemies: Vec<Enemy>,
+    pub props: Vec<Prop>,
+    pub containers: Vec<Container>,
+    pub search: Option<Search>, // Container search in progress
+    pub noises: Vec<Noise>,     // Recent noises the player made
+    pub budget: Budget,         // Caps on live entities from the frame time (see budget.rs)
+    pub visibility: f32,        // Stealth meter, 0.0 (hidden) - 1.0 (exposed)
+    pub peek_origin: Option<(f32, f32)>, // Camera position while peeking through a closed door
+    pub frame_start: (f32, f32),         // Local player position before this frame's movement
+    schedule: Schedule<GameState<'a>>,   // Systems run each frame of play (see playing_schedule)
+    pub barricade: Option<Barricade>,    // Enemy forcing a closed door
+    pub doors: Doors,                    // How far each door has slid open (see doors.rs)
+    pub loot_tables: LootTables,
+    pub pill_table: PillTable, // What each color of pill does and how often it is placed
+    pub radio: Radio, // Noise lure, carried or placed in the level
+    pub keys: Keys,   // Keys to the exit in this level (see keys.rs)
+    pub clocks: Clocks, // Clock pickups putting time back on the timer (see clock.rs)
+    pub flashlight: Flashlight, // Battery and the level's battery pickups (see flashlight.rs)
+    pub challenges: Challenges, // The level's challenge rooms (see challenge.rs)
+    pub health: Health, // Damage, healing and invulnerability for the local player (see health.rs)
+    pub status: StatusEffects, // Timed effects on the local player from pills and hazards (see status.rs)
+    pub level_log: LevelLog,   // Floor walked and damage taken this level, for the tally
+    tally: Option<Tally>,      // End-of-level score screen (see tally.rs)
     pub floating_texts: Vec<FloatingText>,
+    pub error_message: String, // Shown on the error screen
+    pub error_retry: bool,     // ENTER on the error screen retries the level (false: it only needs reading)
+    error_continue: State,     // Where ENTER goes from a report that only needs reading
+    pub console: Console,
+    pub config: Config,
+    pub focus_paused: bool, // Window is in the background, simulation is frozen
+    pub resume_timer: f32,  // Countdown before the simulation continues after refocus
+    pub display: Display,
+    pub base_resolution: (usize, usize), // Framebuffer size the renderer was tuned for
+    pub screen_size: (i32, i32),         // Window size the layout was computed for
+    pub render_scale: i32,               // Framebuffer pixel size on screen
+    pub benchmark: Option<Benchmark>,
+    pub color_grade: ColorGrade, // Baked from the active theme
+    pub frame_counter: u32,      // Animates post-processing noise
+    pub events: EventQueue,
+    pub run_seed: u64,  // Seeds the level's random layout (pill types) for this run
+    pub campaign: Campaign, // Levels of the run and the one being played
+    pub escape: Option<Escape>, // Walk out through the exit at the end of the run
+    pub death: Option<DeathScene>, // Sequence before the game-over screen
+    pub stats: RunStats, // What happened this run, for the game-over tips
+    pub run_time: f32,  // Seconds played in the current run
+    pub leaderboard: Leaderboard,
+    pub last_rank: Option<usize>, // Local leaderboard place of the last finished run
+    #[cfg(feature = "online-leaderboard")]
+    pub online: Option<OnlineLeaderboard>, // Only set when the player opted in
+    pub twitch: Option<TwitchChat>,        // Only set when a channel is configured
+    pub glimpse: Option<(f32, f32, f32)>,  // Chat scare entity: x, y, seconds left
+    pub controls: Controls,
+    pub haptics: Haptics,
+    pub crosshair: CrosshairState,
+    pub hud: HudLayout,
+    pub hints: Hints,
+    pub exit_hum: ExitHum,
+    pub decals: Decals,
+    pub grime: Grime, // Wear built up on the walls this level (see grime.rs)
+    pub chalk: Chalk,
+    pub wet_feet: f32,   // Seconds of wet footprints left
+    pub stride: Strider, // Local player's footprints
+    pub heatmap: Heatmap,
+    pub crowd: Crowd,
+    pub alerts: Alerts,
+    pub hardcore_run: bool, // Current run started in hardcore mode
+    pub blackout: Blackout,
+    pub glitch: Glitch,
+    pub escalation: Escalation, // The level's countdown script, steps fired so far
+    pub gait: Gait,             // Local player's step cycle: view bob, footsteps
+    pub view_bob: ViewBob,      // Horizon dip, sway and strafe roll from the gait (see camera.rs)
+    pub flythrough: Flythrough, // Camera drifting through the level behind the menu
+    pub credits: Credits,       // Credits roll (assets/credits.txt)
+    pub options: Options,       // Options screen opened from the menu
+    pub narrator: Narrator,     // Reads screens and focused menu items aloud
+    pub demo: Option<Demo>,     // Attract mode bot, while the demo plays
+    pub bot: Option<Bot>,       // Plays for the local player, switched on from the console (see bot.rs)
+    pub menu_idle: f32,         // Seconds on the menu without input, starts the demo
+    pub splash: Splash,         // Intro logo sequence before the menu
+    pub profile: Profile,       // Whose settings, leaderboard and autosave these are
+    pub whispers: Whispers,     // Unseen entities nearby, heard before they're seen
+    pub enemy_step: Option<StepSound>, // Loudest enemy footstep this frame (played in main.rs)
+    pub acoustics: Acoustics,   // How much the space around the player echoes
 }
 
 #[derive(PartialEq, Copy, Clone)]
@@ -39,34 +336,54 @@ pub enum State {
     Playing,
     Victory,
     GameOver,
+    Error,
+    Benchmark,
+    Leaderboard,
+    Credits,
+    Splash,
+    LevelComplete, // Between two levels of the campaign
+    Escaping,      // Walking out through the exit door before the victory screen
+    Options,       // Sensitivity, volume, field of view and crosshair, from the menu
+    Demo,          // Attract mode: a bot plays the level after the menu sat idle
 }
 
 impl<'a> GameState<'a> {
-    pub fn new(screen_width: usize, screen_height: usize, audio: &'a RaylibAudio) -> Result<Self, String> {
-        // Load maze
-        let maze = Maze::load_from_file("maze.txt", 1.0)?;
+    pub fn new(
+        screen_width: usize,
+        screen_height: usize,
+        mut textures: TextureManager,
+        mut audio_manager: AudioManager<'a>,
+        config: Config,
+        profile: Profile,
+    ) -> Result<Self, String> {
+        // Load (or generate) the first level of the campaign
+        let campaign = Campaign::discover(MAZE_PATH);
+        let run_seed = Self::new_run_seed(&config);
+        let maze = Self::load_maze(&config, campaign.current(), run_seed)?;
 
-        // Create player at start position
-        let player = Player::new(maze.start_pos.0, maze.start_pos.1);
+        // Create the local player at the start position
+        let players = Self::spawn_players(&maze);
 
-        // Initialize systems
-        let textures = TextureManager::new(64); // Very small textures for maximum performance
-        let audio_manager = AudioManager::new(audio);
-        // Optimize: Use very few rays for maximum performance (80 rays for 640px = 8px per ray)
-        let num_rays = 80;
-        let raycaster = RayCaster::new(std::f32::consts::PI / 3.0, num_rays, 20.0);
-        let camera = Camera::new(0.003);
+        // Initialize systems (textures and audio were preloaded by the loading screen)
+        let texture_ids = TextureIds::resolve(&mut textures)?;
+        textures.load_level(&Self::level_assets(campaign.current(), &config.resource_pack));
+        textures.set_grade(maze.texture_grade);
+        // Ray count comes from the quality preset (80 rays for 640px = 8px per ray on medium)
+        let raycaster = RayCaster::new(config.fov.to_radians(), config.quality.num_rays(screen_width), 20.0);
+        let camera = Camera::new(camera::BASE_SENSITIVITY * config.mouse_sensitivity, config.invert_y);
+        audio_manager.set_volumes(config.music_volume, config.sfx_volume);
+        let narrator = Narrator::from_setting(&config.narration);
 
         // Position minimap in top-right corner (very small for maximum performance)
-        let minimap_size = 100;
-        let margin = 10;
-        let minimap_x = screen_width as i32 - minimap_size - margin;
-        let minimap_y = margin;
-        let minimap = Minimap::new(minimap_size, 8.0, (minimap_x, minimap_y));
+        let minimap_x = screen_width as i32 - MINIMAP_SIZE - MINIMAP_MARGIN;
+        let minimap = Minimap::new(MINIMAP_SIZE, 8.0, (minimap_x, MINIMAP_MARGIN));
 
         let ui = UI::new(24);
         let effects = Effects::new();
+        let color_grade = config.theme.grade();
         let framebuffer = Framebuffer::new(screen_width, screen_height);
+        let grime = Grime::new(maze.tile_size);
+        let doors = Doors::new(&maze, config.door_close_delay);
 
         // Create flickering light sprites for atmosphere
         let mut sprites = Vec::new();
@@ -74,22 +391,35 @@ impl<'a> GameState<'a> {
         sprites.push(Sprite::new_flickering_light(10.0, 10.0));
 
         // Create pills from maze pill_positions
-        let mut pills = Vec::new();
-        let mut rng = rand::thread_rng();
-        for (x, y) in &maze.pill_positions {
-            // Randomly assign red or blue pill type
-            let pill_type = if rng.gen_bool(0.5) {
-                PillType::Red
-            } else {
-                PillType::Blue
-            };
-            pills.push(Pill::new(*x, *y, pill_type));
-        }
+        let pill_table = PillTable::load_or_builtin(pill::PILLS_PATH);
+        let pills = Self::spawn_pills(&maze, &pill_table, run_seed);
+        let keys = Keys::new(&maze);
+        let clocks = Clocks::new(&maze);
+        let flashlight = Flashlight::new(&maze);
+        let challenges = Challenges::new(&maze.challenges);
+        let level_log = LevelLog::new(&maze);
 
-        Ok(Self {
-            player,
+        // Nothing is ever sent unless the player opted in and set an endpoint
+        #[cfg(feature = "online-leaderboard")]
+        let online = (config.online_leaderboard && !config.leaderboard_url.is_empty())
+            .then(|| OnlineLeaderboard::new(&config.leaderboard_url));
+        let flythrough = Flythrough::new(&maze);
+        let twitch = (!config.twitch_channel.is_empty())
+            .then(|| TwitchChat::connect(&config.twitch_channel, config.scare_cooldown));
+        let controls = Controls::new(config.controls, config.keyboard_layout);
+        let chalk = Chalk::new(config.unlimited_chalk);
+        let enemies = Self::spawn_enemies(&maze);
+        let props = Self::spawn_props(&maze);
+        let containers = Self::spawn_containers(&maze);
+
+        let mut game = Self {
+            players,
+            local_index: 0,
+            companion: None,
+            versus: None,
             maze,
             textures,
+            texture_ids,
             audio: audio_manager,
             sprites,
             raycaster,
@@ -98,276 +428,3341 @@ impl<'a> GameState<'a> {
             ui,
             effects,
             framebuffer,
-            state: State::Menu,
+            state: if config.intro_splash { State::Splash } else { State::Menu },
             time_in_darkness: 0.0,
             game_timer: 180.0, // 3 minutes = 180 seconds
             idle_timer: 0.0,   // Starts at 0, no idle penalty yet
             pills,
+            items: Vec::new(),
+            enemies,
+            props,
+            containers,
+            search: None,
+            noises: Vec::new(),
+            budget: Budget::new(),
+            visibility: 0.0,
+            peek_origin: None,
+            frame_start: (0.0, 0.0),
+            schedule: Self::playing_schedule(),
+            barricade: None,
+            doors,
+            loot_tables: LootTables::load_or_builtin(loot::LOOT_PATH),
+            pill_table,
+            radio: Radio::new(),
+            keys,
+            clocks,
+            flashlight,
+            challenges,
+            health: Health::new(),
+            status: StatusEffects::new(),
+            level_log,
+            tally: None,
             floating_texts: Vec::new(),
-        })
+            error_message: String::new(),
+            error_retry: true,
+            error_continue: State::Menu,
+            console: Console::new(),
+            config,
+            focus_paused: false,
+            resume_timer: 0.0,
+            display: Display::new(screen_width as i32, screen_height as i32),
+            base_resolution: (screen_width, screen_height),
+            screen_size: (screen_width as i32, screen_height as i32),
+            render_scale: 1,
+            benchmark: None,
+            color_grade,
+            frame_counter: 0,
+            events: EventQueue::new(),
+            run_seed,
+            campaign,
+            escape: None,
+            death: None,
+            stats: RunStats::default(),
+            run_time: 0.0,
+            leaderboard: Leaderboard::load(&profile.file(leaderboard::LEADERBOARD_PATH)),
+            last_rank: None,
+            #[cfg(feature = "online-leaderboard")]
+            online,
+            twitch,
+            glimpse: None,
+            controls,
+            haptics: Haptics::new(),
+            crosshair: CrosshairState::new(),
+            hud: HudLayout::new(screen_width as i32, screen_height as i32, 1.0),
+            hints: Hints::new(),
+            exit_hum: ExitHum::new(),
+            decals: Decals::new(),
+            grime,
+            chalk,
+            wet_feet: 0.0,
+            stride: Strider::new(0.0, 0.0),
+            heatmap: Heatmap::new(),
+            crowd: Crowd::new(),
+            alerts: Alerts::new(),
+            hardcore_run: false,
+            blackout: Blackout::new(&[]),
+            glitch: Glitch::new(),
+            escalation: Escalation::new(&[]),
+            gait: Gait::new(),
+            view_bob: ViewBob::new(),
+            flythrough,
+            credits: Credits::load_or_builtin(credits::CREDITS_PATH),
+            options: Options::new(),
+            narrator,
+            demo: None,
+            bot: None,
+            menu_idle: 0.0,
+            splash: Splash::new(),
+            profile,
+            whispers: Whispers::new(),
+            enemy_step: None,
+            acoustics: Acoustics::new(),
+        };
+
+        // Size the framebuffer for the quality preset
+        game.relayout(screen_width as i32, screen_height as i32);
+        Ok(game)
     }
 
-    pub fn update(&mut self, rl: &RaylibHandle, delta_time: f32) {
-        match self.state {
-            State::Menu => {
-                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
-                    // Transition to playing (audio handled in main.rs)
-                    self.state = State::Playing;
-                    self.camera.reset();
-                    // Reset timer when starting a new game
-                    self.game_timer = 180.0;
+    /// Create the player entities for a level (currently just the local player at the start)
+    fn spawn_players(maze: &Maze) -> Vec<Player> {
+        vec![Player::new(maze.start_pos.0, maze.start_pos.1)]
+    }
+
+    /// Put every player back at the start of the level
+    fn reset_players(&mut self) {
+        self.players = Self::spawn_players(&self.maze);
+        self.local_index = 0;
+        self.companion = None;
+        self.versus = None;
+    }
+
+    /// Record a finished run on the local leaderboard and submit it online if opted in
+    fn finish_run(&mut self) {
+        let record = RunRecord::new(self.run_seed, self.run_time, self.game_timer, self.player().health, self.hardcore_run);
+        self.last_rank = self.leaderboard.add(record);
+        if let Err(e) = self.leaderboard.save(&self.profile.file(leaderboard::LEADERBOARD_PATH)) {
+            warn!("{}", e);
+        }
+        info!("Run finished in {:.2}s (seed {}, score {})", record.time, record.seed, record.score);
+
+        #[cfg(feature = "online-leaderboard")]
+        if let Some(online) = &mut self.online {
+            online.submit(record);
+        }
+    }
+
+    /// Open the leaderboard screen, syncing with the server when online submission is enabled
+    fn open_leaderboard(&mut self) {
+        #[cfg(feature = "online-leaderboard")]
+        if let Some(online) = &mut self.online {
+            online.flush_queue();
+            online.fetch_top();
+        }
+        self.state = State::Leaderboard;
+    }
+
+    /// Pills, keys, clocks and enemies for the minimap while the reveal effect runs
+    fn revealed_marks(&self) -> Vec<(f32, f32, Color)> {
+        let pills = self.pills.iter().filter(|pill| !pill.collected).map(|pill| (pill.pos.x, pill.pos.y, pill.get_color()));
+        let keys = self.keys.remaining().map(|(x, y)| (x, y, Color::GOLD));
+        let clocks = self.clocks.remaining().iter().map(|&(x, y)| (x, y, clock::COLOR));
+        let enemies = self.enemies.iter().filter(|enemy| enemy.is_alive()).map(|enemy| (enemy.pos.x, enemy.pos.y, Color::RED));
+        pills.chain(keys).chain(clocks).chain(enemies).collect()
+    }
+
+    /// Add up the level just finished on the tally screen
+    fn start_tally(&mut self) {
+        let explored = self.level_log.explored(&self.maze);
+        let tally = Tally::new(self.game_timer, explored, self.level_log.damage_taken, self.challenges.won());
+        info!("Level tally: {:.0}% explored, {} HP lost", explored * 100.0, self.level_log.damage_taken);
+        self.tally = Some(tally);
+        self.state = State::LevelComplete;
+    }
+
+    /// Start the attract mode demo from the level's start
+    fn start_demo(&mut self) {
+        info!("Menu idle for {:.0}s, starting the demo", attract::IDLE_TIME);
+        let mut demo = Demo::new(&self.maze);
+        demo.restart(&mut self.players[self.local_index]);
+        self.demo = Some(demo);
+        self.escape = None;
+        self.state = State::Demo;
+    }
+
+    /// Leave the demo for the menu
+    fn stop_demo(&mut self) {
+        self.demo = None;
+        self.escape = None;
+        self.menu_idle = 0.0;
+        self.state = State::Menu;
+    }
+
+    /// Roll the credits from the top
+    fn open_credits(&mut self) {
+        self.credits.restart();
+        self.state = State::Credits;
+    }
+
+    /// Online status line for the leaderboard screen, with the global list if there is one
+    fn online_status(&self) -> (String, Option<&[RunRecord]>) {
+        #[cfg(feature = "online-leaderboard")]
+        {
+            match &self.online {
+                Some(online) => (online.status.clone(), Some(&online.global)),
+                None => ("Online leaderboard off (opt in with online_leaderboard in settings.toml)".to_string(), None),
+            }
+        }
+        #[cfg(not(feature = "online-leaderboard"))]
+        {
+            let status = if self.config.online_leaderboard {
+                "This build has no online leaderboard (build with --features online-leaderboard)"
+            } else {
+                "Local runs only"
+            };
+            (status.to_string(), None)
+        }
+    }
+
+    /// Start a run with a second player controlling the hunting entity
+    fn start_versus_run(&mut self) {
+        self.start_new_run();
+        if self.state != State::Playing {
+            return;
+        }
+
+        // The hunter replaces the companion for this round
+        self.reset_players();
+        let (x, y) = Versus::spawn_point(&self.maze);
+        self.players.push(Player::new(x, y));
+        self.versus = Some(Versus::new(self.players.len() - 1));
+        info!("Versus round started, hunter spawned at ({:.1}, {:.1})", x, y);
+    }
+
+    /// Move the hunter and check whether it caught the escaper
+    fn update_versus(&mut self, rl: &RaylibHandle, delta_time: f32) {
+        let Some(versus) = &mut self.versus else {
+            return;
+        };
+        let hunter = &mut self.players[versus.hunter_index];
+        Versus::update_hunter(rl, self.controls.preset.hunter_keys(), hunter, &self.maze, delta_time);
+
+        let (hunter_x, hunter_y) = (hunter.pos.x, hunter.pos.y);
+        let escaper = &self.players[self.local_index];
+        let distance = ((escaper.pos.x - hunter_x).powi(2) + (escaper.pos.y - hunter_y).powi(2)).sqrt();
+        if distance <= versus::CATCH_DISTANCE {
+            versus.winner = Some(Side::Hunter);
+            self.game_over(DeathCause::Caught);
+        }
+    }
+
+    /// End the run, starting the game-over sequence for what ended it
+    fn game_over(&mut self, cause: DeathCause) {
+        info!("Game over: {}", cause.title());
+        self.death = Some(DeathScene::new(cause));
+        self.state = State::GameOver;
+    }
+
+    /// Where the hunter can see the escaper on its map: nearby or in a clear line of sight
+    fn hunter_sense(&self, versus: &Versus) -> Option<(f32, f32)> {
+        let hunter = &self.players[versus.hunter_index];
+        let escaper = self.player();
+        let from = (hunter.pos.x, hunter.pos.y);
+        let to = (escaper.pos.x, escaper.pos.y);
+        let distance = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        (distance <= versus::SENSE_RANGE || stealth::can_see(&self.maze, from, to, self.visibility)).then_some(to)
+    }
+
+    /// Add the AI companion as an extra player next to the local one
+    fn spawn_companion(&mut self) {
+        let leader = self.player();
+        self.players.push(Player::new(leader.pos.x, leader.pos.y));
+        self.companion = Some(Companion::new(self.players.len() - 1));
+    }
+
+    /// The player this client controls
+    pub fn player(&self) -> &Player {
+        &self.players[self.local_index]
+    }
+
+    /// Create pills from maze pill_positions
+    fn spawn_pills(maze: &Maze, table: &PillTable, seed: u64) -> Vec<Pill> {
+        let mut pills = Vec::new();
+        let mut rng = StdRng::seed_from_u64(seed);
+        for (x, y) in &maze.pill_positions {
+            // Pick the color by the weights in pills.toml
+            if let Some(pill_type) = table.roll(&mut rng) {
+                pills.push(Pill::new(*x, *y, pill_type));
+            }
+        }
+        pills
+    }
+
+    /// Create enemies from maze enemy_positions
+    fn spawn_enemies(maze: &Maze) -> Vec<Enemy> {
+        maze.enemy_positions
+            .iter()
+            .map(|&(x, y)| {
+                let enemy = Enemy::new(x, y);
+                // Patrols start on the spawn of the enemy that walks them
+                match maze.patrols.iter().find(|patrol| patrol.waypoints[0].x == x && patrol.waypoints[0].y == y) {
+                    Some(patrol) => enemy.with_patrol(patrol),
+                    None => enemy,
+                }
+            })
+            .collect()
+    }
+
+    /// Create breakable props from maze prop_positions
+    fn spawn_props(maze: &Maze) -> Vec<Prop> {
+        maze.prop_positions.iter().map(|(x, y)| Prop::new(*x, *y)).collect()
+    }
+
+    /// Create searchable containers from maze desk/cabinet positions
+    fn spawn_containers(maze: &Maze) -> Vec<Container> {
+        let desks = maze.desk_positions.iter().map(|(x, y)| Container::new(*x, *y, ContainerKind::Desk));
+        let cabinets = maze.cabinet_positions.iter().map(|(x, y)| Container::new(*x, *y, ContainerKind::Cabinet));
+        desks.chain(cabinets).collect()
+    }
+
+    /// Seed for a new run: the one fixed in settings.toml, or a random one
+    fn new_run_seed(config: &Config) -> u64 {
+        config.maze_seed.unwrap_or_else(rand::random)
+    }
+
+    /// A level's maze: generated from `seed` with procedural mazes on or when the file is
+    /// missing, loaded from `path` otherwise
+    fn load_maze(config: &Config, path: &str, seed: u64) -> Result<Maze, String> {
+        if config.procedural_maze || !std::path::Path::new(path).exists() {
+            info!("Generating a {}x{} maze from seed {}", GENERATED_SIZE.0, GENERATED_SIZE.1, seed);
+            return Ok(Maze::generate(GENERATED_SIZE.0, GENERATED_SIZE.1, seed, 1.0));
+        }
+        Maze::load_from_file(path, 1.0)
+    }
+
+    /// Level textures for a maze file, read through the resource pack. Levels without their
+    /// own manifest section use maze.txt's
+    fn level_assets(path: &str, pack: &str) -> AssetManifest {
+        let mut assets = AssetManifest::for_level(assets::MANIFEST_PATH, path);
+        if assets.entries.is_empty() {
+            assets = AssetManifest::for_level(assets::MANIFEST_PATH, MAZE_PATH);
+        }
+        packs::apply(pack, assets)
+    }
+
+    /// Reload the shared and current level assets from the configured resource pack
+    /// (see packs.rs). The managers reload in place, so `texture_ids` and the audio
+    /// handles keep pointing at the right assets
+    fn apply_resource_pack(&mut self) {
+        let pack = self.config.resource_pack.clone();
+        let shared = packs::apply(&pack, AssetManifest::load_or_builtin(assets::MANIFEST_PATH, "default"));
+        self.textures.reload(&shared);
+        self.textures.reload(&Self::level_assets(self.campaign.current(), &pack));
+        self.audio.reload(&shared);
+        self.audio.set_volumes(self.config.music_volume, self.config.sfx_volume);
+        info!("Resource pack '{}' loaded", pack);
+    }
+
+    /// Seed of the level being played: the run seed, offset by the level number
+    fn level_seed(&self) -> u64 {
+        self.run_seed.wrapping_add(self.campaign.index as u64)
+    }
+
+    /// Start a run from the first level of the campaign
+    fn start_new_run(&mut self) {
+        self.campaign = Campaign::discover(MAZE_PATH); // Picks up levels added since the last run
+        self.run_seed = Self::new_run_seed(&self.config);
+        self.run_time = 0.0;
+        self.stats = RunStats::default();
+        self.hardcore_run = self.config.hardcore;
+        self.hints = Hints::new();
+        self.load_level(None, campaign::LEVEL_TIME);
+    }
+
+    /// Move on to the next level of the campaign, keeping the player's health and
+    /// inventory and carrying part of the time left over
+    fn start_next_level(&mut self) {
+        let health = self.player().health;
+        let inventory = self.player().inventory.clone();
+        let timer = campaign::next_level_time(self.game_timer);
+        self.campaign.advance();
+        self.load_level(Some(health), timer);
+        self.players[self.local_index].inventory = inventory;
+    }
+
+    /// Load (or generate) the campaign's current level and reset everything in it, switching
+    /// to the error screen on failure. `health` carries over from the previous level (None = full)
+    fn load_level(&mut self, health: Option<i32>, timer: f32) {
+        let path = self.campaign.current().to_string();
+        match Self::load_maze(&self.config, &path, self.level_seed()) {
+            Ok(maze) => {
+                info!("Level {}/{}: {}", self.campaign.index + 1, self.campaign.level_count(), path);
+                self.maze = maze;
+                self.textures.load_level(&Self::level_assets(&path, &self.config.resource_pack));
+                self.textures.set_grade(self.maze.texture_grade);
+                self.flythrough = Flythrough::new(&self.maze);
+                self.reset_players();
+                if let Some(health) = health {
+                    self.players[self.local_index].health = health;
+                }
+                if self.config.companion {
+                    self.spawn_companion();
+                }
+                self.pills = Self::spawn_pills(&self.maze, &self.pill_table, self.level_seed());
+                self.items.clear();
+                self.enemies = Self::spawn_enemies(&self.maze);
+                self.props = Self::spawn_props(&self.maze);
+                self.containers = Self::spawn_containers(&self.maze);
+                self.search = None;
+                self.noises.clear();
+                self.peek_origin = None;
+                self.barricade = None;
+                self.doors = Doors::new(&self.maze, self.config.door_close_delay);
+                self.radio = Radio::new();
+                self.keys = Keys::new(&self.maze);
+                self.clocks = Clocks::new(&self.maze);
+                self.flashlight = Flashlight::new(&self.maze);
+                self.challenges = Challenges::new(&self.maze.challenges);
+                self.health = Health::new();
+                self.status.clear();
+                self.level_log = LevelLog::new(&self.maze);
+                self.tally = None;
+                if let Some(bot) = &mut self.bot {
+                    bot.replan();
+                }
+                self.glimpse = None;
+                self.exit_hum = ExitHum::new();
+                self.whispers = Whispers::new();
+                self.acoustics = Acoustics::new();
+                self.decals = Decals::for_maze(&self.maze);
+                self.grime = Grime::new(self.maze.tile_size);
+                self.chalk = Chalk::new(self.config.unlimited_chalk);
+                self.wet_feet = 0.0;
+                self.stride = Strider::new(self.player().pos.x, self.player().pos.y);
+                self.crowd = Crowd::new();
+                self.alerts = Alerts::new();
+                self.blackout = Blackout::new(&self.maze.blackouts);
+                self.glitch = Glitch::new();
+                self.escalation = Escalation::new(&self.maze.escalation);
+                self.gait = Gait::new();
+                self.view_bob = ViewBob::new();
+                self.floating_texts.clear();
+                self.events.clear();
+                self.ui.toasts.clear();
+                if self.keys.total() > 0 {
+                    let text = format!("New objective: find {} key{}", self.keys.total(), if self.keys.total() == 1 { "" } else { "s" });
+                    self.events.push(GameEvent::Toast { text, color: keys::COLOR });
+                }
+                self.camera.reset();
+                self.game_timer = timer;
+                self.idle_timer = 0.0;
+                self.state = State::Playing;
+                if !self.maze.skipped.is_empty() {
+                    let problems = self.maze.skipped.clone();
+                    self.show_level_problems(&path, &problems);
                 }
             }
-            State::Playing => {
-                // Update game timer - count down
-                self.game_timer -= delta_time;
-                
-                // Check if time ran out
-                if self.game_timer <= 0.0 {
-                    self.game_timer = 0.0;
-                    self.state = State::GameOver;
-                    return; // Don't process player input if game over
-                }
-
-                // Track idle time and apply penalty
-                let is_moving = rl.is_key_down(KeyboardKey::KEY_W)
-                    || rl.is_key_down(KeyboardKey::KEY_S)
-                    || rl.is_key_down(KeyboardKey::KEY_A)
-                    || rl.is_key_down(KeyboardKey::KEY_D);
-
-                if is_moving {
-                    // Player is moving, reset idle timer
-                    self.idle_timer = 0.0;
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Switch to the error screen with a description of what failed
+    pub fn show_error(&mut self, message: String) {
+        error!("{}", message);
+        self.error_message = message;
+        self.error_retry = true;
+        self.state = State::Error;
+    }
+
+    /// Show the problems found in the settings file on the error screen. Those settings
+    /// already fell back to their defaults, so ENTER just carries on to the menu
+    pub fn show_settings_problems(&mut self, path: &str, problems: &[String]) {
+        self.error_message = format!("Some settings in {} were not accepted: {}", path, problems.join("; "));
+        self.error_retry = false;
+        self.error_continue = State::Menu;
+        self.state = State::Error;
+    }
+
+    /// Report the lines of a level file that were skipped as invalid (the level plays
+    /// without them); ENTER goes on into the level
+    fn show_level_problems(&mut self, path: &str, problems: &[String]) {
+        self.error_message = format!("Some lines in {} were skipped: {}", path, problems.join("; "));
+        self.error_retry = false;
+        self.error_continue = State::Playing;
+        self.state = State::Error;
+    }
+
+    /// Write an autosave of the current run
+    pub fn autosave(&self, path: &str) -> Result<(), String> {
+        SaveData {
+            player_x: self.player().pos.x,
+            player_y: self.player().pos.y,
+            player_angle: self.player().angle,
+            health: self.player().health,
+            game_timer: self.game_timer,
+            hardcore: self.hardcore_run,
+        }
+        .write_to_file(path)
+    }
+
+    /// Describe the current game state for crash logs
+    pub fn crash_context(&self) -> Vec<String> {
+        let state = match self.state {
+            State::Menu => "Menu",
+            State::Playing => "Playing",
+            State::Victory => "Victory",
+            State::GameOver => "GameOver",
+            State::Error => "Error",
+            State::Benchmark => "Benchmark",
+            State::Leaderboard => "Leaderboard",
+            State::Credits => "Credits",
+            State::Splash => "Splash",
+            State::LevelComplete => "LevelComplete",
+            State::Escaping => "Escaping",
+            State::Options => "Options",
+            State::Demo => "Demo",
+        };
+        vec![
+            format!("State: {}", state),
+            format!("Player pos: ({:.3}, {:.3})", self.player().pos.x, self.player().pos.y),
+            format!("Player angle: {:.3}", self.player().angle),
+            format!("Health: {}/{}", self.player().health, self.player().max_health),
+            format!("Timer: {:.2}", self.game_timer),
+            format!("Maze: {}x{}", self.maze.width, self.maze.height),
+        ]
+    }
+
+    /// Tell the narrator which screen is showing and what has focus on it. Gameplay
+    /// and the animated sequences say nothing
+    pub fn narrate(&mut self) {
+        let (screen, item) = match self.state {
+            State::Menu => (
+                "Main menu. ENTER to start, O for options, V for versus, L for the leaderboard, \
+                 C for credits, B for the benchmark, ESCAPE to quit"
+                    .to_string(),
+                None,
+            ),
+            State::Options => (
+                "Options. UP and DOWN to select, LEFT and RIGHT to change, ENTER to go back".to_string(),
+                Some(self.options.focus_label(&self.config)),
+            ),
+            State::Leaderboard => ("Leaderboard. ENTER to go back".to_string(), None),
+            State::Credits => ("Credits. ENTER to go back".to_string(), None),
+            State::Victory => ("You escaped! Press ENTER to continue".to_string(), None),
+            State::LevelComplete => {
+                let cleared = format!("Level {} of {} cleared", self.campaign.index + 1, self.campaign.level_count());
+                match self.tally.as_ref().filter(|tally| tally.finished()) {
+                    Some(tally) => {
+                        let medal = tally.medal().map_or("no medal".to_string(), |(medal, _)| format!("{} medal", medal.name().to_lowercase()));
+                        let next = if self.campaign.is_last() { "continue" } else { "the next level" };
+                        (format!("{}. {} points, {}. Press ENTER for {}", cleared, tally.total(), medal, next), None)
+                    }
+                    None => (cleared, None),
+                }
+            }
+            State::GameOver if self.death.as_ref().is_none_or(|scene| scene.finished()) => {
+                let cause = self.death.as_ref().map_or(DeathCause::Timer, |scene| scene.cause);
+                (format!("{}. {}. Press ENTER to try again", cause.title(), cause.reason()), None)
+            }
+            State::Error if self.error_retry => (
+                format!("Something went wrong. {}. ENTER to retry, BACKSPACE for the menu", self.error_message),
+                None,
+            ),
+            State::Error => (format!("{}. ENTER to continue", self.error_message), None),
+            State::Demo => ("Demo. Press any key".to_string(), None),
+            State::Benchmark => ("Benchmark running. BACKSPACE to stop".to_string(), None),
+            _ => (String::new(), None),
+        };
+        self.narrator.update(&screen, item.as_deref());
+    }
+
+    /// Apply the display mode and monitor from the settings to the window
+    pub fn apply_display(&mut self, rl: &mut RaylibHandle) {
+        self.display.apply(rl, &self.config);
+    }
+
+    /// Lay out the framebuffer and minimap for a new window size. The framebuffer
+    /// keeps roughly the base resolution (scaled by the quality preset) and is
+    /// scaled up by whole pixels to fill the screen
+    pub fn relayout(&mut self, screen_width: i32, screen_height: i32) {
+        let percent = self.config.quality.resolution_percent();
+        let base_width = (self.base_resolution.0 * percent / 100).max(1);
+        let base_height = (self.base_resolution.1 * percent / 100).max(1);
+        let scale = (screen_width / base_width as i32)
+            .min(screen_height / base_height as i32)
+            .max(1);
+
+        self.render_scale = scale;
+        self.framebuffer = Framebuffer::new(
+            (screen_width / scale).max(1) as usize,
+            (screen_height / scale).max(1) as usize,
+        );
+        self.screen_size = (screen_width, screen_height);
+        self.hud = HudLayout::new(screen_width, screen_height, self.config.hud_scale);
+        self.minimap.position = self.hud.place(Anchor::TopRight, (MINIMAP_SIZE, MINIMAP_SIZE), (0.0, 0.0));
+        self.rebuild_raycaster();
+
+        info!(
+            "Layout {}x{}: framebuffer {}x{} at {}x scale",
+            screen_width, screen_height, self.framebuffer.width, self.framebuffer.height, scale
+        );
+    }
+
+    /// Switch the renderer to a quality preset (ray count and framebuffer resolution)
+    fn apply_quality(&mut self, quality: QualityPreset) {
+        self.config.quality = quality;
+        self.relayout(self.screen_size.0, self.screen_size.1);
+    }
+
+    /// Cast rays for the field of view and quality preset in the settings, sized to the framebuffer
+    fn rebuild_raycaster(&mut self) {
+        let num_rays = self.config.quality.num_rays(self.framebuffer.width);
+        self.raycaster = RayCaster::new(self.config.fov.to_radians(), num_rays, 20.0);
+    }
+
+    /// Put a setting just changed on the options screen into effect and persist it
+    fn apply_setting(&mut self, rl: &mut RaylibHandle, setting: Setting) {
+        match setting {
+            Setting::Sensitivity => self.camera.sensitivity = camera::BASE_SENSITIVITY * self.config.mouse_sensitivity,
+            Setting::InvertY => self.camera.invert_y = self.config.invert_y,
+            Setting::MusicVolume | Setting::SfxVolume => self.audio.set_volumes(self.config.music_volume, self.config.sfx_volume),
+            Setting::Fov => self.rebuild_raycaster(),
+            Setting::Crosshair | Setting::HighContrast => {}
+            Setting::Narration => self.narrator = Narrator::from_setting(&self.config.narration),
+            Setting::ResourcePack => self.apply_resource_pack(),
+            Setting::Controls => self.controls.preset = self.config.controls,
+            Setting::KeyboardLayout => self.controls.layout = self.config.keyboard_layout,
+            Setting::DisplayMode | Setting::Monitor => self.apply_display(rl),
+        }
+        info!("{} set to {}", setting.label(), setting.value(&self.config));
+
+        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Render one frame at `quality` from (x, y, angle) without showing it. Returns the time
+    /// it took in seconds (first-run detection, see autodetect.rs)
+    pub fn time_render(&mut self, quality: QualityPreset, (x, y, angle): (f32, f32, f32)) -> f32 {
+        if self.config.quality != quality {
+            self.apply_quality(quality);
+        }
+        let player = &mut self.players[self.local_index];
+        player.pos = player::Vector2::new(x, y);
+        player.angle = angle;
+
+        let start = std::time::Instant::now();
+        self.render_3d_view();
+        self.post_process();
+        start.elapsed().as_secs_f32()
+    }
+
+    /// Keep the preset picked on first run and save the detected settings
+    pub fn finish_detection(&mut self, quality: QualityPreset) {
+        self.apply_quality(quality);
+        self.reset_players();
+        info!("First run: quality preset {}", quality.name());
+        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Start the quality benchmark with an uncapped frame rate
+    fn start_benchmark(&mut self, rl: &mut RaylibHandle) {
+        info!("Starting quality benchmark");
+        let benchmark = Benchmark::new(&self.maze, self.config.clone());
+
+        self.config.vsync = false;
+        self.config.fps_cap = FpsCap::Uncapped;
+        FramePacer::apply(rl, &self.config);
+
+        if let Some(preset) = benchmark.current_preset() {
+            self.apply_quality(preset);
+        }
+        self.reset_players();
+        self.benchmark = Some(benchmark);
+        self.state = State::Benchmark;
+    }
+
+    /// Leave the benchmark, restoring the previous settings and optionally
+    /// switching to the recommended preset
+    fn finish_benchmark(&mut self, rl: &mut RaylibHandle, use_recommended: bool) {
+        let Some(benchmark) = self.benchmark.take() else {
+            return;
+        };
+
+        let use_recommended = use_recommended && benchmark.is_finished();
+        let quality = if use_recommended {
+            benchmark.recommended()
+        } else {
+            benchmark.previous_config.quality
+        };
+
+        self.config = benchmark.previous_config;
+        self.apply_quality(quality);
+        FramePacer::apply(rl, &self.config);
+
+        if use_recommended {
+            info!("Quality preset set to {}", quality.name());
+            if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                warn!("{}", e);
+            }
+        }
+        self.state = State::Menu;
+    }
+
+    /// Change a display setting, apply it right away and persist it
+    fn change_display_settings(&mut self, rl: &mut RaylibHandle, change: impl FnOnce(&mut Config)) {
+        change(&mut self.config);
+        self.apply_display(rl);
+
+        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Run a command typed into the developer console
+    fn execute_console_command(&mut self, rl: &mut RaylibHandle, line: &str) {
+        info!("> {}", line);
+        let mut parts = line.split_whitespace();
+
+        match parts.next() {
+            Some("help") => {
+                info!("Commands: help, clear, log <off|error|warn|info|debug|trace>");
+                info!("          vsync <on|off>, fps <30|60|120|uncapped>, pacing <raylib|precise>");
+                info!("          display <windowed|borderless|fullscreen>, monitor [index]");
+                info!("          quality <low|medium|high|ultra|full>, theme <level0|poolrooms>, grain <on|off|0.0-1.0>");
+                info!("          companion <on|off>, rumble <on|off>, bob <on|off|0.0-2.0>, flythrough <on|off>, maze <file|generated>, seed <random|number>, controls <wasd|esdf|lefty>, layout <auto|qwerty|azerty|dvorak>");
+                info!("          crosshair <cross|dot|circle|cross_dot|off>, crosshair_color <#rrggbb>, hud_scale <0.5-2.0>");
+                info!("          hints <off|rare|normal|often>, hardcore <on|off>, hum <auto|on|off>, captions <on|off>");
+                info!("          narration <off|auto|log|command...>, contrast <on|off>, pack [name]");
+                info!("          chalk <unlimited|limited>, heatmap <off|scent|threat|routes>, search <0-{}>, blackout [seconds]", search::MAX_SEARCH_TIME);
+                info!("          doors <0-{}> (seconds before opened doors close, 0 = never)", doors::MAX_CLOSE_DELAY);
+                info!("          health <regen <hp per second> [seconds]|god|normal> (developer, not saved)");
+                info!("          status <speed|slow|blur|poison|invulnerable|reveal> <strength> <seconds>|clear (developer)");
+                info!("          pills <store|take>, give <medkit|battery|chalk|<color>_pill> (developer)");
+                info!("          systems (update order, stage by stage), bot <on|off> (plays for you)");
+                info!("          physics <move_acceleration|move_friction|max_speed|water_friction> <value>");
+                info!("          idle <idle_time|idle_damage|anxiety_strength> <value|off>");
+            }
+            Some("clear") => logger::clear_recent(),
+            Some("log") => match parts.next().and_then(logger::parse_level) {
+                Some(level) => {
+                    logger::set_level(level);
+                    info!("Log level set to {}", level);
+                }
+                None => warn!("Usage: log <off|error|warn|info|debug|trace>"),
+            },
+            Some("vsync") => match parts.next() {
+                Some("on") => self.change_frame_settings(rl, |c| c.vsync = true),
+                Some("off") => self.change_frame_settings(rl, |c| c.vsync = false),
+                _ => warn!("Usage: vsync <on|off>"),
+            },
+            Some("fps") => match parts.next().and_then(FpsCap::parse) {
+                Some(cap) => self.change_frame_settings(rl, |c| c.fps_cap = cap),
+                None => warn!("Usage: fps <30|60|120|uncapped>"),
+            },
+            Some("display") => match parts.next().and_then(DisplayMode::parse) {
+                Some(mode) => self.change_display_settings(rl, |c| c.display_mode = mode),
+                None => warn!("Usage: display <windowed|borderless|fullscreen>"),
+            },
+            Some("monitor") => match parts.next().map(|n| n.parse::<i32>().ok().filter(|&monitor| monitor >= 0)) {
+                Some(Some(monitor)) => self.change_display_settings(rl, |c| c.monitor = monitor),
+                Some(None) => warn!("Usage: monitor [index]"),
+                None => {
+                    for line in Display::monitor_list() {
+                        info!("{}", line);
+                    }
+                }
+            },
+            Some("quality") => match parts.next().and_then(QualityPreset::parse) {
+                Some(quality) => {
+                    self.apply_quality(quality);
+                    info!("Quality preset set to {}", quality.name());
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                None => warn!("Usage: quality <low|medium|high|ultra|full>"),
+            },
+            Some("theme") => match parts.next().and_then(Theme::parse) {
+                Some(theme) => self.set_theme(theme),
+                None => warn!("Usage: theme <level0|poolrooms>"),
+            },
+            Some("grain") => match parts.next() {
+                Some("on") => self.change_grain_settings(|c| c.film_grain = true),
+                Some("off") => self.change_grain_settings(|c| c.film_grain = false),
+                Some(value) => match value.parse::<f32>() {
+                    Ok(cap) => self.change_grain_settings(|c| c.grain_cap = cap.clamp(0.0, 1.0)),
+                    Err(_) => warn!("Usage: grain <on|off|cap 0.0-1.0>"),
+                },
+                None => warn!("Usage: grain <on|off|cap 0.0-1.0>"),
+            },
+            Some("companion") => match parts.next() {
+                Some(value @ ("on" | "off")) => {
+                    self.config.companion = value == "on";
+                    info!("Companion {} (applies to the next run)", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: companion <on|off>"),
+            },
+            Some("hud_scale") => match parts.next().map(|value| value.parse::<f32>()) {
+                Some(Ok(scale)) => {
+                    self.config.hud_scale = scale.clamp(hud::MIN_SCALE, hud::MAX_SCALE);
+                    self.relayout(self.screen_size.0, self.screen_size.1);
+                    info!("HUD scale set to {}", self.config.hud_scale);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: hud_scale <{}-{}>", hud::MIN_SCALE, hud::MAX_SCALE),
+            },
+            Some("hints") => match parts.next().and_then(HintFrequency::parse) {
+                Some(frequency) => {
+                    self.config.hints = frequency;
+                    info!("Hints: {}", frequency.name());
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                None => warn!("Usage: hints <off|rare|normal|often>"),
+            },
+            Some("hardcore") => match parts.next() {
+                Some(value @ ("on" | "off")) => {
+                    self.config.hardcore = value == "on";
+                    info!("Hardcore mode {} (one life, no saves, no hints, minimal HUD; applies to the next run)", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: hardcore <on|off>"),
+            },
+            Some("hum") => match parts.next() {
+                Some(value @ ("auto" | "on" | "off")) => {
+                    self.config.exit_hum = match value {
+                        "on" => Some(true),
+                        "off" => Some(false),
+                        _ => None,
+                    };
+                    info!("Exit hum {} ({})", value, if self.exit_hum_enabled() { "playing" } else { "silent" });
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: hum <auto|on|off>"),
+            },
+            Some("captions") => match parts.next() {
+                Some(value @ ("on" | "off")) => {
+                    self.config.captions = value == "on";
+                    info!("Sound captions {}", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: captions <on|off>"),
+            },
+            Some("contrast") => match parts.next() {
+                Some(value @ ("on" | "off")) => {
+                    self.config.high_contrast = value == "on";
+                    info!("High contrast {}", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: contrast <on|off>"),
+            },
+            Some("pack") => match parts.next() {
+                Some(name) if packs::exists(name) => {
+                    self.config.resource_pack = name.to_string();
+                    self.apply_resource_pack();
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                Some(name) => warn!("No resource pack '{}' in {}", name, packs::PACKS_DIR),
+                None => info!("Resource packs: {} (using {})", packs::available().join(", "), self.config.resource_pack),
+            },
+            Some("narration") => {
+                let setting = parts.collect::<Vec<_>>().join(" ");
+                if setting.is_empty() {
+                    warn!("Usage: narration <off|auto|log|command...>");
                 } else {
-                    // Player is idle, increment timer
-                    self.idle_timer += delta_time;
-                    
-                    // Check if idle for more than 5 seconds
-                    if self.idle_timer >= 5.0 {
-                        // Apply idle penalty
-                        self.player.take_damage(10);
-                        
-                        // Trigger anxiety effect
-                        self.effects.trigger_anxiety_effect();
-                        
-                        // Reset idle timer to prevent continuous damage
-                        self.idle_timer = 0.0;
-                        
-                        // Note: Heartbeat sound will be played in main.rs
+                    self.narrator = Narrator::from_setting(&setting);
+                    info!("Narration set to {}", setting);
+                    self.config.narration = setting;
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+            }
+            Some("pills") => match parts.next() {
+                Some(value @ ("store" | "take")) => {
+                    self.config.store_pills = value == "store";
+                    info!("Pills walked over are {}", if self.config.store_pills { "stored in the inventory" } else { "taken right away" });
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: pills <store|take>"),
+            },
+            Some("give") => match parts.next().and_then(Item::parse) {
+                Some(item) => {
+                    if self.players[self.local_index].inventory.add(item) {
+                        info!("Added {} to the inventory", item.label());
+                    } else {
+                        warn!("No room for {} in the inventory", item.label());
+                    }
+                }
+                None => warn!("Usage: give <medkit|battery|chalk|<color>_pill>"),
+            },
+            Some("chalk") => match parts.next() {
+                Some(value @ ("unlimited" | "limited")) => {
+                    self.config.unlimited_chalk = value == "unlimited";
+                    self.chalk.unlimited = self.config.unlimited_chalk;
+                    info!("Chalk marks {}", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: chalk <unlimited|limited>"),
+            },
+            Some("health") => match (parts.next(), parts.next().map(str::parse::<f32>), parts.next().map(str::parse::<f32>)) {
+                (Some("regen"), Some(Ok(per_second)), duration) => {
+                    let duration = duration.and_then(Result::ok);
+                    self.health.add_modifier(Modifier::Regen(per_second), duration);
+                    info!("Regenerating {} HP/s{}", per_second, duration.map_or(String::new(), |d| format!(" for {}s", d)));
+                }
+                (Some("god"), None, None) => {
+                    self.health.add_modifier(Modifier::DamageTaken(0.0), None);
+                    info!("No damage taken");
+                }
+                (Some("normal"), None, None) => {
+                    self.health.clear_modifiers();
+                    info!("Health modifiers cleared");
+                }
+                _ => warn!("Usage: health <regen <hp per second> [seconds]|god|normal>"),
+            },
+            Some("status") => match (parts.next(), parts.next().map(str::parse::<f32>), parts.next().map(str::parse::<f32>)) {
+                (Some("clear"), None, None) => {
+                    self.status.clear();
+                    info!("Status effects cleared");
+                }
+                (Some(name), Some(Ok(strength)), Some(Ok(seconds))) => match Effect::from_name(name) {
+                    Some(effect) => {
+                        self.status.apply(effect, strength, seconds);
+                        info!("{} ({}) for {}s", effect.label(), strength, seconds);
+                    }
+                    None => warn!("Unknown status effect '{}'", name),
+                },
+                _ => warn!("Usage: status <speed|slow|blur|poison|invulnerable|reveal> <strength> <seconds>|clear"),
+            },
+            Some("search") => match parts.next().map(|value| value.parse::<f32>()) {
+                Some(Ok(time)) => {
+                    self.config.search_time = time.clamp(0.0, search::MAX_SEARCH_TIME);
+                    info!("Enemies search for {}s after losing the player", self.config.search_time);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: search <0-{}>", search::MAX_SEARCH_TIME),
+            },
+            Some("doors") => match parts.next().map(|value| value.parse::<f32>()) {
+                Some(Ok(delay)) => {
+                    self.config.door_close_delay = delay.clamp(0.0, doors::MAX_CLOSE_DELAY);
+                    self.doors.set_close_delay(self.config.door_close_delay);
+                    info!("Opened doors close after {}s (0 = never)", self.config.door_close_delay);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: doors <0-{}>", doors::MAX_CLOSE_DELAY),
+            },
+            Some("heatmap") => match parts.next().and_then(HeatmapMode::parse) {
+                Some(mode) => {
+                    self.heatmap.set_mode(mode);
+                    info!("Heatmap {}", mode.name());
+                }
+                None => warn!("Usage: heatmap <off|scent|threat|routes>"),
+            },
+            Some("bot") => match parts.next() {
+                Some("on") => {
+                    self.bot = Some(Bot::new());
+                    info!("Bot on: it plays the local player until 'bot off'");
+                }
+                Some("off") => {
+                    self.bot = None;
+                    info!("Bot off");
+                }
+                _ => warn!("Usage: bot <on|off>"),
+            },
+            Some("systems") => {
+                for stage in Stage::ALL {
+                    info!("{}: {}", stage, self.schedule.order(stage).join(", "));
+                }
+            }
+            Some("blackout") => match parts.next().map(|value| value.parse::<f32>()) {
+                Some(Ok(seconds)) if seconds > 0.0 => {
+                    self.blackout.trigger(seconds);
+                    info!("Blackout for {}s", seconds);
+                }
+                None => {
+                    self.blackout.trigger(blackout::DEFAULT_DURATION);
+                    info!("Blackout for {}s", blackout::DEFAULT_DURATION);
+                }
+                _ => warn!("Usage: blackout [seconds]"),
+            },
+            Some("physics") => match (parts.next(), parts.next().and_then(|value| value.parse::<f32>().ok())) {
+                (Some(name), Some(value)) => {
+                    if self.config.physics.set(name, value) {
+                        info!("Player physics: {} changed", name);
+                        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                            warn!("{}", e);
+                        }
+                    } else {
+                        warn!("Unknown physics setting '{}'", name);
+                    }
+                }
+                _ => warn!("Usage: physics <move_acceleration|move_friction|max_speed|water_friction> <value>"),
+            },
+            Some("idle") => match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) => {
+                    if self.config.idle.set(name, value) {
+                        info!("Idle penalty: {} set to {}", name, value);
+                        if self.hardcore_run {
+                            info!("Hardcore runs keep the default idle penalty");
+                        }
+                        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                            warn!("{}", e);
+                        }
+                    } else {
+                        warn!("Invalid idle setting '{} {}'", name, value);
+                    }
+                }
+                _ => warn!("Usage: idle <idle_time|idle_damage|anxiety_strength> <value|off> (time up to {}s, damage up to {})", idle::MAX_THRESHOLD, idle::MAX_DAMAGE),
+            },
+            Some("crosshair") => match parts.next().and_then(CrosshairStyle::parse) {
+                Some(style) => self.change_crosshair_settings(|c| c.crosshair.style = style),
+                None => warn!("Usage: crosshair <cross|dot|circle|cross_dot|off>"),
+            },
+            Some("crosshair_color") => match parts.next().and_then(crosshair::parse_color) {
+                Some(color) => self.change_crosshair_settings(|c| c.crosshair.color = color),
+                None => warn!("Usage: crosshair_color <#rrggbb|#rrggbbaa>"),
+            },
+            Some("rumble") => match parts.next() {
+                Some(value @ ("on" | "off")) => {
+                    self.config.rumble = value == "on";
+                    info!("Controller rumble {}", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: rumble <on|off>"),
+            },
+            Some("bob") => match parts.next() {
+                Some(value @ ("on" | "off")) => {
+                    self.config.view_bob = value == "on";
+                    info!("View bob {}", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                Some(value) => match value.parse::<f32>() {
+                    Ok(intensity) => {
+                        self.config.view_bob_intensity = intensity.clamp(0.0, camera::MAX_BOB_INTENSITY);
+                        info!("View bob intensity {:.2}", self.config.view_bob_intensity);
+                        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                            warn!("{}", e);
+                        }
+                    }
+                    Err(_) => warn!("Usage: bob <on|off|intensity 0.0-{}>", camera::MAX_BOB_INTENSITY),
+                },
+                None => warn!("Usage: bob <on|off|intensity 0.0-{}>", camera::MAX_BOB_INTENSITY),
+            },
+            Some("maze") => match parts.next() {
+                Some(value @ ("file" | "generated")) => {
+                    self.config.procedural_maze = value == "generated";
+                    info!("Maze: {} (from the next run)", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: maze <file|generated>"),
+            },
+            Some("seed") => {
+                let seed = match parts.next() {
+                    Some("random") => Some(None),
+                    Some(value) => value.parse().ok().map(Some),
+                    None => None,
+                };
+                match seed {
+                    Some(seed) => {
+                        self.config.maze_seed = seed;
+                        match seed {
+                            Some(seed) => info!("Runs use seed {} (from the next run)", seed),
+                            None => info!("Runs use a random seed (from the next run)"),
+                        }
+                        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                            warn!("{}", e);
+                        }
+                    }
+                    None => warn!("Usage: seed <random|number> (this run: {})", self.run_seed),
+                }
+            }
+            Some("flythrough") => match parts.next() {
+                Some(value @ ("on" | "off")) => {
+                    self.config.menu_flythrough = value == "on";
+                    info!("Menu flythrough {}", value);
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                _ => warn!("Usage: flythrough <on|off>"),
+            },
+            Some("controls") => match parts.next().and_then(Preset::parse) {
+                Some(preset) => {
+                    self.controls.preset = preset;
+                    self.config.controls = preset;
+                    info!("Controls set to {} (move with {})", preset.name(), self.controls.move_label());
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
+                    }
+                }
+                None => warn!("Usage: controls <wasd|esdf|lefty>"),
+            },
+            Some("layout") => match parts.next().and_then(Layout::parse) {
+                Some(layout) => {
+                    self.controls.layout = layout;
+                    self.config.keyboard_layout = layout;
+                    info!("Keyboard layout set to {}", layout.map_or("auto", |layout| layout.name()));
+                    if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+                        warn!("{}", e);
                     }
                 }
+                None => warn!("Usage: layout <auto|qwerty|azerty|dvorak>"),
+            },
+            Some("pacing") => match parts.next().and_then(FramePacing::parse) {
+                Some(pacing) => self.change_frame_settings(rl, |c| c.frame_pacing = pacing),
+                None => warn!("Usage: pacing <raylib|precise>"),
+            },
+            Some(other) => warn!("Unknown command '{}' (type 'help')", other),
+            None => {}
+        }
+    }
+
+    /// Change a crosshair setting and persist it
+    fn change_crosshair_settings(&mut self, change: impl FnOnce(&mut Config)) {
+        change(&mut self.config);
+        info!(
+            "Crosshair: {} {} (size {}, gap {}, thickness {}, dynamic {})",
+            self.config.crosshair.style.name(),
+            crosshair::color_to_hex(self.config.crosshair.color),
+            self.config.crosshair.size,
+            self.config.crosshair.gap,
+            self.config.crosshair.thickness,
+            if self.config.crosshair.dynamic { "on" } else { "off" }
+        );
+        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Change a frame pacing setting, apply it right away and persist it
+    fn change_frame_settings(&mut self, rl: &mut RaylibHandle, change: impl FnOnce(&mut Config)) {
+        change(&mut self.config);
+        FramePacer::apply(rl, &self.config);
+        info!(
+            "VSync {}, FPS cap {}, {} pacing",
+            if self.config.vsync { "on" } else { "off" },
+            self.config.fps_cap.name(),
+            self.config.frame_pacing.name()
+        );
+
+        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Freeze the run while the window is unfocused and count down after it
+    /// regains focus. Returns true while the simulation should not advance
+    fn update_focus_pause(&mut self, rl: &RaylibHandle, delta_time: f32) -> bool {
+        if !rl.is_window_focused() {
+            if !self.focus_paused {
+                info!("Window lost focus, pausing run");
+                self.focus_paused = true;
+                self.audio.set_paused(true);
+            }
+            self.resume_timer = RESUME_COUNTDOWN;
+            return true;
+        }
+
+        if self.focus_paused {
+            info!("Window focused, resuming in {:.0}s", RESUME_COUNTDOWN);
+            self.focus_paused = false;
+        }
+
+        if self.resume_timer > 0.0 {
+            self.resume_timer -= delta_time;
+            if self.resume_timer <= 0.0 {
+                self.resume_timer = 0.0;
+                self.audio.set_paused(false);
+            }
+            return true;
+        }
+
+        false
+    }
+
+    /// True while the run is frozen by an unfocused window or the resume countdown
+    pub fn is_focus_paused(&self) -> bool {
+        self.state == State::Playing && (self.focus_paused || self.resume_timer > 0.0)
+    }
+
+    pub fn update(&mut self, rl: &mut RaylibHandle, delta_time: f32) {
+        // Developer console takes over input while open
+        if let Some(command) = self.console.update(rl) {
+            self.execute_console_command(rl, &command);
+        }
+        if self.console.open {
+            return;
+        }
+
+        let health = self.player().health as f32 / self.player().max_health as f32;
+        self.ui.update(delta_time, self.state == State::Menu, health);
+
+        // Guess the keyboard layout from typing, for key names in hints
+        let key_pressed = self.controls.detect_layout(rl);
+        let mouse_delta = rl.get_mouse_delta();
+        let any_input = key_pressed
+            || mouse_delta.x != 0.0
+            || mouse_delta.y != 0.0
+            || rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+
+        // Alt-tabbing must not cost the player their run
+        if self.state == State::Playing && self.update_focus_pause(rl, delta_time) {
+            return;
+        }
+
+        // Pick up results of background leaderboard requests
+        #[cfg(feature = "online-leaderboard")]
+        if let Some(online) = &mut self.online {
+            online.poll();
+        }
+
+        match self.state {
+            State::Menu => {
+                // The menu background looks through the local player's eyes
+                if self.config.menu_flythrough {
+                    let (x, y, angle) = self.flythrough.update(delta_time);
+                    let player = &mut self.players[self.local_index];
+                    player.pos = player::Vector2::new(x, y);
+                    player.angle = angle;
+                }
+
+                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    // Transition to playing (audio handled in main.rs)
+                    self.start_new_run();
+                } else if rl.is_key_pressed(KeyboardKey::KEY_B) {
+                    self.start_benchmark(rl);
+                } else if rl.is_key_pressed(KeyboardKey::KEY_T) {
+                    self.set_theme(self.config.theme.next());
+                } else if rl.is_key_pressed(KeyboardKey::KEY_V) {
+                    self.start_versus_run();
+                } else if rl.is_key_pressed(KeyboardKey::KEY_L) {
+                    self.open_leaderboard();
+                } else if rl.is_key_pressed(KeyboardKey::KEY_C) {
+                    self.open_credits();
+                } else if rl.is_key_pressed(KeyboardKey::KEY_O) {
+                    self.state = State::Options;
+                }
+
+                // Left alone long enough, the game plays itself
+                self.menu_idle = if any_input { 0.0 } else { self.menu_idle + delta_time };
+                if self.state == State::Menu && self.menu_idle >= attract::IDLE_TIME {
+                    self.start_demo();
+                }
+            }
+            State::Demo => {
+                if any_input {
+                    self.stop_demo();
+                    return;
+                }
+                let Some(demo) = &mut self.demo else {
+                    return;
+                };
+                let player = &mut self.players[self.local_index];
+                match &mut self.escape {
+                    Some(escape) => {
+                        escape.update(delta_time);
+                        let (x, y, angle) = escape.pose();
+                        player.pos.x = x;
+                        player.pos.y = y;
+                        player.angle = angle;
+                        if escape.finished() {
+                            self.escape = None;
+                            demo.restart(player);
+                        }
+                    }
+                    None => {
+                        if demo.update(&self.maze, player, delta_time) {
+                            self.escape = Some(Escape::new(&self.maze, player.pos.x, player.pos.y, player.angle));
+                        }
+                    }
+                }
+                for pill in &mut self.pills {
+                    pill.update(delta_time);
+                }
+            }
+            State::Options => {
+                if let Some(setting) = self.options.update(rl, &mut self.config) {
+                    self.apply_setting(rl, setting);
+                }
+                if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) || rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    self.state = State::Menu;
+                }
+            }
+            State::Leaderboard => {
+                if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) || rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    self.state = State::Menu;
+                }
+            }
+            State::Splash => {
+                if self.splash.update(delta_time) {
+                    // The impact thud doubles as the logo sting
+                    self.audio.play_impact(1.0);
+                }
+                let skipped = rl.is_key_pressed(KeyboardKey::KEY_ENTER)
+                    || rl.is_key_pressed(KeyboardKey::KEY_SPACE)
+                    || rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+                if skipped || self.splash.finished() {
+                    self.state = State::Menu;
+                }
+            }
+            State::Credits => {
+                let fast_forward = rl.is_key_down(KeyboardKey::KEY_SPACE);
+                let finished = self.credits.update(delta_time, fast_forward, rl.get_screen_height());
+                if finished || rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) || rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    self.state = State::Menu;
+                }
+            }
+            State::Benchmark => {
+                let mut next_preset = None;
+                if let Some(benchmark) = &mut self.benchmark {
+                    if benchmark.update(delta_time) {
+                        next_preset = benchmark.current_preset();
+                    }
+                    let (x, y, angle) = benchmark.camera();
+                    let player = &mut self.players[self.local_index];
+                    player.pos.x = x;
+                    player.pos.y = y;
+                    player.angle = angle;
+                }
+                if let Some(preset) = next_preset {
+                    self.apply_quality(preset);
+                }
+
+                let finished = self.benchmark.as_ref().is_none_or(|b| b.is_finished());
+                if finished && rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    self.finish_benchmark(rl, true);
+                } else if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+                    self.finish_benchmark(rl, false);
+                }
+            }
+            State::Playing => {
+                // Hit-stop slows the whole simulation for a few frames
+                let delta_time = self.effects.update_hit_feedback(delta_time);
+
+                // One frame of play, system by system, until the run ends (see playing_schedule)
+                let schedule = std::mem::replace(&mut self.schedule, Schedule::new());
+                schedule.run(self, rl, delta_time, |game| game.state == State::Playing);
+                self.schedule = schedule;
+            }
+            State::Victory => {
+                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    // Reset game; escaping rolls the credits on the way back to the menu
+                    self.reset_players();
+                    if self.versus.is_none() {
+                        self.open_credits();
+                    } else {
+                        self.state = State::Menu;
+                    }
+                }
+            }
+            State::LevelComplete => {
+                let tick = self.tally.as_mut().and_then(|tally| tally.update(delta_time));
+                match tick {
+                    Some(Tick::Count(pitch)) => self.audio.play_tick(pitch),
+                    Some(Tick::Medal) if self.tally.as_ref().is_some_and(|tally| tally.medal().is_some()) => self.audio.play_impact(1.0),
+                    _ => {}
+                }
+                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    match &mut self.tally {
+                        Some(tally) if !tally.finished() => tally.skip(),
+                        _ if self.campaign.is_last() => {
+                            // The last level's tally follows the escape sequence
+                            self.state = State::Victory;
+                            self.finish_run();
+                        }
+                        _ => self.start_next_level(),
+                    }
+                }
+            }
+            State::Escaping => {
+                let finished = match &mut self.escape {
+                    Some(escape) => {
+                        escape.update(delta_time);
+                        let (x, y, angle) = escape.pose();
+                        let player = &mut self.players[self.local_index];
+                        player.pos.x = x;
+                        player.pos.y = y;
+                        player.angle = angle;
+                        escape.finished()
+                    }
+                    None => true,
+                };
+                if finished || rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    self.escape = None;
+                    self.start_tally();
+                }
+            }
+            State::GameOver => {
+                if let Some(scene) = self.death.as_mut().filter(|scene| !scene.finished()) {
+                    scene.update(delta_time);
+                    if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                        scene.skip();
+                    }
+                } else if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    // Reset game and return to menu
+                    self.death = None;
+                    self.reset_players();
+                    self.game_timer = 180.0;
+                    self.state = State::Menu;
+                }
+            }
+            State::Error => {
+                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) && !self.error_retry {
+                    self.state = self.error_continue;
+                } else if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    // Retry loading the level
+                    self.start_new_run();
+                } else if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+                    self.state = State::Menu;
+                }
+            }
+        }
+    }
+
+    /// The systems that make up a frame of play, stage by stage (see schedule.rs). A new
+    /// feature adds its system to the stage it belongs in
+    fn playing_schedule() -> Schedule<Self> {
+        Schedule::<Self>::new()
+            .add(Stage::Input, "look", |game, rl, dt| game.update_look(rl, dt))
+            .add(Stage::Input, "actions", |game, rl, dt| game.update_actions(rl, dt))
+            .add(Stage::Input, "bot", |game, _, dt| game.update_bot(dt))
+            .add(Stage::Simulation, "clock", |game, _, dt| game.update_clock(dt))
+            .add(Stage::Simulation, "idle", |game, rl, dt| game.update_idle(rl, dt))
+            .add(Stage::Simulation, "companion", |game, _, dt| game.update_companion(dt))
+            .add(Stage::Simulation, "versus", |game, rl, dt| game.update_versus(rl, dt))
+            .add(Stage::Simulation, "motion", |game, _, dt| game.update_motion(dt))
+            .add(Stage::Simulation, "hints", |game, rl, dt| game.update_hints(rl, dt))
+            .add(Stage::Simulation, "ambience", |game, _, dt| game.update_ambience(dt))
+            .add(Stage::Simulation, "level_events", |game, _, dt| game.update_level_events(dt))
+            .add(Stage::Simulation, "footprints", |game, _, dt| game.update_footprints(dt))
+            .add(Stage::Simulation, "radio", |game, _, dt| game.update_radio(dt))
+            .add(Stage::Simulation, "enemies", |game, _, dt| game.update_enemies(dt))
+            .add(Stage::Simulation, "flashlight", |game, _, dt| game.update_flashlight(dt))
+            .add(Stage::Simulation, "status", |game, _, dt| game.update_status(dt))
+            .add(Stage::Collision, "positions", |game, _, _| game.check_positions())
+            .add(Stage::Collision, "explored", |game, _, _| game.update_explored())
+            .add(Stage::Collision, "barricade", |game, rl, dt| game.update_barricade(rl, dt))
+            .add(Stage::Collision, "doors", |game, _, dt| game.update_doors(dt))
+            .add(Stage::Collision, "pickups", |game, _, _| game.update_pickups())
+            .add(Stage::Collision, "challenges", |game, _, dt| game.update_challenges(dt))
+            .add(Stage::Post, "upkeep", |game, _, dt| game.update_upkeep(dt))
+            .add(Stage::Post, "feedback", |game, _, dt| game.update_feedback(dt))
+            .add(Stage::Post, "exit", |game, _, _| game.update_exit())
+            .add(Stage::Post, "menu_key", |game, rl, _| game.update_menu_key(rl))
+    }
+
+    /// Aim, crouch and the crosshair; remembers where the frame started for the motion system
+    fn update_look(&mut self, rl: &RaylibHandle, delta_time: f32) {
+        // Crosshair spread settles back while standing still
+        self.crosshair.update(self.controls.moving(rl), delta_time);
+
+        // Update camera rotation (looking around is allowed while searching)
+        self.camera.update(rl, &mut self.players[self.local_index], delta_time);
+
+        // Crouch while held: slower and harder to see
+        self.players[self.local_index].crouching = self.controls.down(rl, Action::Crouch);
+        self.frame_start = (self.player().pos.x, self.player().pos.y);
+
+        // Movement tuning from the settings, so console changes apply right away
+        for player in &mut self.players {
+            player.physics = self.config.physics;
+        }
+    }
+
+    /// Movement, attacks and the action keys (a container search locks them, the bot takes over)
+    fn update_actions(&mut self, rl: &RaylibHandle, delta_time: f32) {
+        if self.bot.is_some() {
+            return;
+        }
+
+        // Searching a container locks movement and attacks
+        if self.search.is_some() {
+            self.players[self.local_index].stop();
+            self.update_search(rl, delta_time);
+        } else {
+            // Handle player movement (input only drives the local player)
+            let player = &mut self.players[self.local_index];
+            if self.controls.down(rl, Action::Forward) {
+                player.move_forward();
+            }
+            if self.controls.down(rl, Action::Back) {
+                player.move_backward();
+            }
+            if self.controls.down(rl, Action::Left) {
+                player.move_left();
+            }
+            if self.controls.down(rl, Action::Right) {
+                player.move_right();
+            }
+            player.update_movement(&self.maze, delta_time);
+
+            // Keyboard rotation
+            if rl.is_key_down(KeyboardKey::KEY_LEFT) {
+                player.rotate(-player.rot_speed * delta_time);
+            }
+            if rl.is_key_down(KeyboardKey::KEY_RIGHT) {
+                player.rotate(player.rot_speed * delta_time);
+            }
+
+            // Melee attack
+            player.update_attack(delta_time);
+            let attack_pressed = self.controls.pressed(rl, Action::Attack)
+                || rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+            if attack_pressed && player.try_attack() {
+                self.crosshair.attacked();
+                let wall_hit = self.player().attack_wall_hit(&self.maze);
+                let wall_distance = wall_hit.map_or(f32::MAX, |(x, y)| {
+                    ((x - self.player().pos.x).powi(2) + (y - self.player().pos.y).powi(2)).sqrt()
+                });
+
+                match self.attack_target(wall_distance) {
+                    Some(AttackTarget::Enemy(index)) => {
+                        let enemy = &mut self.enemies[index];
+                        let killed = enemy.take_damage(player::ATTACK_DAMAGE);
+                        let (x, y) = (enemy.pos.x, enemy.pos.y);
+                        self.crosshair.hit(killed);
+                        self.events.push(GameEvent::AttackLanded { x, y, strength: ENEMY_HIT_STRENGTH });
+                        if killed {
+                            self.events.push(GameEvent::EnemyKilled { x, y });
+                        }
+                    }
+                    Some(AttackTarget::Prop(index)) => {
+                        let prop = &mut self.props[index];
+                        let broken = prop.take_damage(player::ATTACK_DAMAGE);
+                        let (x, y) = (prop.pos.x, prop.pos.y);
+                        self.crosshair.hit(false);
+                        self.events.push(GameEvent::AttackLanded { x, y, strength: WALL_HIT_STRENGTH });
+                        if broken {
+                            self.events.push(GameEvent::PropBroken { x, y });
+                        }
+                    }
+                    None => {
+                        if let Some((x, y)) = wall_hit {
+                            self.events.push(GameEvent::AttackLanded { x, y, strength: WALL_HIT_STRENGTH });
+                            self.events.push(GameEvent::WallHit { x, y });
+                        }
+                    }
+                }
+            }
+
+            // Pick up the radio, start searching a nearby container, otherwise use the door in front
+            if self.controls.pressed(rl, Action::Interact) {
+                if self.radio.in_reach(self.player().pos.x, self.player().pos.y) {
+                    self.radio.pick_up();
+                } else if let Some(index) = self.nearby_container() {
+                    self.search = Some(Search::new(index, self.player().health));
+                } else if let Some((x, y, open)) = self.door_in_front() {
+                    // A door being forced is braced instead (see update_barricade)
+                    if self.barricade.as_ref().is_none_or(|b| b.door != (x, y)) {
+                        self.toggle_door(x, y, open);
+                    }
+                }
+            }
+
+            // Flashlight on / off (stays off once the battery is flat)
+            if self.controls.pressed(rl, Action::Flashlight) && !self.flashlight.toggle() && self.flashlight.battery() <= 0.0 {
+                let (x, y) = (self.player().pos.x, self.player().pos.y);
+                self.floating_texts.push(FloatingText::new("Battery dead".to_string(), x, y, Color::LIGHTGRAY));
+            }
+
+            // Send the companion to the spot in view, or call it back
+            if self.controls.pressed(rl, Action::Companion) {
+                self.command_companion();
+            }
+
+            // Toss the radio ahead to lure enemies away
+            if self.controls.pressed(rl, Action::Throw) && !self.radio.placed {
+                self.radio.place(&self.maze, self.player().pos.x, self.player().pos.y, self.player().angle);
+            }
+
+            // Number keys pick an inventory slot, the use key uses what is in it
+            for (slot, key) in INVENTORY_KEYS.into_iter().enumerate() {
+                if rl.is_key_pressed(key) {
+                    self.players[self.local_index].inventory.select(slot);
+                }
+            }
+            if self.controls.pressed(rl, Action::UseItem) {
+                self.use_item();
+            }
+
+            // Chalk mark on the wall ahead or the floor underfoot
+            if self.controls.pressed(rl, Action::Mark) {
+                let (x, y, angle) = (self.player().pos.x, self.player().pos.y, self.player().angle);
+                if !self.chalk.mark(&mut self.decals, &self.maze, x, y, angle) {
+                    self.floating_texts.push(FloatingText::new("Out of chalk".to_string(), x, y, Color::LIGHTGRAY));
+                }
+            }
+        }
+
+        // Hold the peek key at a closed door to peek through the crack
+        self.peek_origin = None;
+        if self.controls.down(rl, Action::Peek) && self.search.is_none() {
+            if let Some((x, y, false)) = self.door_in_front() {
+                self.peek_origin = self.peek_position(x, y);
+            }
+        }
+    }
+
+    /// Let the bot walk the local player to its next goal
+    fn update_bot(&mut self, delta_time: f32) {
+        let Some(bot) = &mut self.bot else {
+            return;
+        };
+        let player = &mut self.players[self.local_index];
+        let world = bot::World {
+            maze: &self.maze,
+            keys: self.keys.remaining().collect(),
+            pills: self.pills.iter().filter(|pill| !pill.collected).map(|pill| (pill.pos.x, pill.pos.y, pill.pill_type)).collect(),
+            enemies: self.enemies.iter().filter(|enemy| enemy.is_alive()).map(|enemy| (enemy.pos.x, enemy.pos.y)).collect(),
+            health: player.health as f32 / player.max_health as f32,
+            time_left: self.game_timer,
+        };
+        bot.update(&world, player, delta_time);
+    }
+
+    /// Count the run timer down; running out ends the run
+    fn update_clock(&mut self, delta_time: f32) {
+        // Update game timer - count down
+        self.game_timer -= delta_time;
+        self.run_time += delta_time;
+
+        // Check if time ran out
+        if self.game_timer <= 0.0 {
+            self.game_timer = 0.0;
+            self.game_over(DeathCause::Timer);
+            // In versus the hunter wins when time runs out
+            if let Some(versus) = &mut self.versus {
+                versus.winner = Some(Side::Hunter);
+            }
+        }
+    }
+
+    /// Idle penalty for standing still
+    fn update_idle(&mut self, rl: &RaylibHandle, delta_time: f32) {
+        // Track idle time and apply penalty
+        // (searching a container counts as activity)
+        let is_moving = self.controls.moving(rl) || self.search.is_some() || self.bot.is_some();
+        let idle = self.idle_penalty();
+        self.effects.anxiety_strength = idle.anxiety;
+
+        if let (Some(threshold), false) = (idle.threshold, is_moving) {
+            // Player is idle, increment timer
+            self.idle_timer += delta_time;
+
+            // Check if idle for longer than the threshold
+            if self.idle_timer >= threshold {
+                // Apply idle penalty
+                self.health.damage(&mut self.players[self.local_index], idle.damage, Source::Idle, &mut self.events);
+                self.stats.idle_penalties += 1;
+
+                // Trigger anxiety effect
+                self.effects.trigger_anxiety_effect();
+
+                // Reset idle timer to prevent continuous damage
+                self.idle_timer = 0.0;
+
+                // Note: Heartbeat sound will be played in main.rs
+            }
+        } else {
+            // Player is moving (or the penalty is off), reset idle timer
+            self.idle_timer = 0.0;
+        }
+    }
+
+    /// Distance the local player moved this frame, and the speed as a fraction of the top speed
+    fn frame_motion(&self, delta_time: f32) -> (f32, f32) {
+        let (x, y) = self.frame_start;
+        let moved = ((self.player().pos.x - x).powi(2) + (self.player().pos.y - y).powi(2)).sqrt();
+        let speed = if delta_time > 0.0 { moved / delta_time / self.player().physics.max_speed } else { 0.0 };
+        (moved, speed)
+    }
+
+    /// Stealth, step cycle and view bob from how the player moved
+    fn update_motion(&mut self, delta_time: f32) {
+        // Stealth: visibility from light, posture and speed, then enemy vision
+        let (moved, speed) = self.frame_motion(delta_time);
+        self.update_stealth(speed, delta_time);
+        self.gait.update(moved, speed, delta_time);
+        let player = self.player();
+        let strafe = (player.velocity.y * player.angle.cos() - player.velocity.x * player.angle.sin()) / player.physics.max_speed;
+        let intensity = if self.config.view_bob { self.config.view_bob_intensity } else { 0.0 };
+        self.view_bob.update(&self.gait, strafe, intensity, delta_time);
+    }
+
+    /// Hints for a player who seems stuck
+    fn update_hints(&mut self, rl: &RaylibHandle, delta_time: f32) {
+        // Never in hardcore mode
+        let (_, speed) = self.frame_motion(delta_time);
+        let hint_input = HintInput {
+            cell: (self.player().pos.x.floor() as i32, self.player().pos.y.floor() as i32),
+            pushing_into_wall: self.controls.moving(rl) && self.search.is_none() && speed < WALL_PUSH_SPEED,
+            exit_distance: ((self.player().pos.x - self.maze.goal_pos.0).powi(2)
+                + (self.player().pos.y - self.maze.goal_pos.1).powi(2))
+            .sqrt(),
+        };
+        let frequency = if self.hardcore_run { HintFrequency::Off } else { self.config.hints };
+        self.hints.update(&hint_input, frequency, delta_time);
+    }
+
+    /// Sound and atmosphere around the player: exit hum, room echo, whispers, decals, glitches
+    fn update_ambience(&mut self, delta_time: f32) {
+        // Exit hum proximity (played in main.rs)
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+        self.exit_hum.update(&self.maze, x, y, delta_time);
+        self.acoustics.update(&self.maze, x, y, delta_time);
+
+        // Whispers from entities close by but out of sight (played in main.rs)
+        let sources: Vec<(f32, f32, f32)> = self.enemies.iter().filter(|e| e.is_alive()).map(|e| (e.pos.x, e.pos.y, e.doppler.pitch())).collect();
+        let angle = self.player().angle;
+        self.whispers.update(&self.maze, (x, y, angle), self.raycaster.fov, &sources, &mut rand::thread_rng(), delta_time);
+        self.effects.feel_dread(self.whispers.intensity * whisper::DREAD);
+        self.decals.update(delta_time);
+        self.alerts.update(delta_time);
+        let tile = ((self.player().pos.x / self.maze.tile_size) as usize, (self.player().pos.y / self.maze.tile_size) as usize);
+        self.glitch.update(&self.maze.glitch_zones, tile, delta_time);
+    }
+
+    /// Blackouts, escalation steps and chat scares
+    fn update_level_events(&mut self, delta_time: f32) {
+        let power_out = self.blackout.is_active();
+        if self.blackout.update(self.run_time, delta_time) {
+            info!("Blackout: the power is out for {:.0}s", self.blackout.time_left());
+            self.effects.trigger_flicker();
+        } else if power_out && !self.blackout.is_active() {
+            self.events.push(GameEvent::Toast { text: "Power restored".to_string(), color: Color::new(255, 240, 180, 255) });
+        }
+        if let Some(action) = self.escalation.update(self.game_timer) {
+            self.events.push(GameEvent::Escalation { action });
+        }
+        if self.escalation.flicker_due(&mut rand::thread_rng(), delta_time) {
+            self.effects.trigger_flicker();
+        }
+
+        // Chat votes run on the game clock, so pausing also pauses the poll
+        if let Some(scare) = self.twitch.as_mut().and_then(|chat| chat.update(delta_time)) {
+            self.events.push(GameEvent::ChatScare { scare });
+        }
+        if let Some((_, _, time_left)) = &mut self.glimpse {
+            *time_left -= delta_time;
+            if *time_left <= 0.0 {
+                self.glimpse = None;
+            }
+        }
+    }
+
+    /// The player's wet trail after walking through water
+    fn update_footprints(&mut self, delta_time: f32) {
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+
+        // Wet footprints for a while after walking through water
+        if self.maze.is_water(x, y) {
+            self.wet_feet = tracks::WET_TIME;
+        } else {
+            self.wet_feet = (self.wet_feet - delta_time).max(0.0);
+        }
+        if let Some((print_x, print_y)) = self.stride.step(x, y) {
+            if self.wet_feet > 0.0 {
+                self.decals.add_floor(DecalKind::WetFootprint, &self.maze, print_x, print_y);
+            }
+        }
+    }
+
+    /// The radio lure's noise pulses and doppler
+    fn update_radio(&mut self, delta_time: f32) {
+        // Radio keeps pulsing noise while it has battery
+        if self.radio.update(delta_time) {
+            let (x, y) = (self.radio.pos.x, self.radio.pos.y);
+            self.events.push(GameEvent::Noise { x, y, radius: radio::NOISE_RADIUS });
+        }
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+        let radio_distance = ((self.radio.pos.x - x).powi(2) + (self.radio.pos.y - y).powi(2)).sqrt();
+        self.radio.doppler.update(radio_distance, delta_time);
+    }
+
+    /// Enemies: movement, senses, strikes and their footprints
+    fn update_enemies(&mut self, delta_time: f32) {
+        // Update enemies (death animations, corpse timers, walking to noises)
+        // Groups spread over parallel routes instead of walking single file
+        self.crowd.update(&self.maze, &mut self.enemies, delta_time);
+        let separation: Vec<(f32, f32)> = (0..self.enemies.len()).map(|i| crowd::separation(&self.enemies, i)).collect();
+        let listener = (self.player().pos.x, self.player().pos.y, self.player().angle);
+        for (enemy, separation) in self.enemies.iter_mut().zip(separation) {
+            let gait = if enemy.detected { enemy::CHASE_SPEED } else { enemy::WALK_SPEED };
+            enemy.speed = gait * self.blackout.enemy_speed();
+            enemy.update(delta_time);
+            enemy.update_search(&self.maze, &mut rand::thread_rng(), delta_time);
+
+            // Idle inside an alert zone: search around where the player was last seen
+            if enemy.is_alive() && enemy.investigate.is_none() && !enemy.detected {
+                if let Some(zone) = self.alerts.searching_at(enemy.pos.x, enemy.pos.y) {
+                    let (x, y) = alert::search_point(&self.maze, zone, &mut rand::thread_rng());
+                    enemy.hear(x, y);
+                }
+            }
+            // Blackouts stir up enemies that have nothing to do
+            if self.blackout.is_active() && enemy.is_alive() && enemy.investigate.is_none() && enemy.patrol.is_none() {
+                let (x, y) = blackout::roam_point(&self.maze, (enemy.pos.x, enemy.pos.y), &mut rand::thread_rng());
+                enemy.hear(x, y);
+            }
+            enemy.update_patrol(delta_time);
+            enemy.update_movement(&self.maze, separation, delta_time);
+            if !enemy.is_alive() {
+                continue;
+            }
+            let distance = ((enemy.pos.x - listener.0).powi(2) + (enemy.pos.y - listener.1).powi(2)).sqrt();
+            enemy.doppler.update(distance, delta_time);
+            if enemy.try_strike(listener.0, listener.1) {
+                let (x, y) = (enemy.pos.x, enemy.pos.y);
+                self.events.push(GameEvent::PlayerStruck { x, y, damage: enemy::CONTACT_DAMAGE });
+            }
+
+            // Footprints (and footsteps) behind it, and the player's wet trail to follow when idle
+            if let Some((print_x, print_y)) = enemy.stride.step(enemy.pos.x, enemy.pos.y) {
+                self.decals.add_floor(DecalKind::Footprint, &self.maze, print_x, print_y);
+                let source = (enemy.pos.x, enemy.pos.y);
+                let shift = enemy.doppler.pitch();
+                let step = footsteps::enemy_step(&self.maze, listener, source, enemy.detected, shift, &mut rand::thread_rng());
+                if step.is_some_and(|step| self.enemy_step.is_none_or(|loudest| step.volume > loudest.volume)) {
+                    self.enemy_step = step;
+                }
+            }
+            enemy.trail_age += delta_time;
+            if enemy.investigate.is_none() {
+                let (x, y) = (enemy.pos.x, enemy.pos.y);
+                if let Some((print_x, print_y, age)) = tracks::follow_wet_prints(&self.decals, x, y, enemy.trail_age) {
+                    enemy.hear(print_x, print_y);
+                    enemy.trail_age = age;
+                }
+            }
+        }
+    }
+
+    /// Flashlight battery, and the beam the renderer draws from it
+    fn update_flashlight(&mut self, delta_time: f32) {
+        self.flashlight.update(delta_time);
+        self.effects.flashlight_enabled = self.flashlight.on;
+        self.effects.flashlight_intensity = self.flashlight.intensity();
+    }
+
+    /// Hazards underfoot, poison, and what the running status effects do to the player
+    fn update_status(&mut self, delta_time: f32) {
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+        let tile = ((x / self.maze.tile_size) as usize, (y / self.maze.tile_size) as usize);
+        if self.maze.translucency(tile.0, tile.1) == Some(Translucent::Smoke) {
+            self.status.apply(Effect::Blur, SMOKE_BLUR, SMOKE_BLUR_TIME);
+        }
+        let poison = self.status.update(delta_time);
+        self.health.damage(&mut self.players[self.local_index], poison, Source::Poison, &mut self.events);
+        self.health.shield(self.status.time_left(Effect::Invulnerable));
+        self.players[self.local_index].speed_multiplier = self.status.speed_multiplier();
+    }
+
+    /// The floor the player walked, for the exploration line of the tally
+    fn update_explored(&mut self) {
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+        self.level_log.visit(&self.maze, x, y);
+    }
+
+    /// Keys, batteries and pills the player walked over
+    fn update_pickups(&mut self) {
+        let (player_x, player_y) = (self.player().pos.x, self.player().pos.y);
+        if let Some((x, y)) = self.keys.collect_near(player_x, player_y) {
+            self.events.push(GameEvent::KeyCollected { x, y });
+        }
+        if let Some((x, y)) = self.flashlight.collect_near(player_x, player_y) {
+            self.events.push(GameEvent::BatteryCollected { x, y });
+        }
+        if let Some((x, y)) = self.clocks.collect_near(player_x, player_y) {
+            self.events.push(GameEvent::ClockCollected { x, y });
+        }
+        if let Some(index) = self.items.iter().position(|&(x, y, _)| ((x - player_x).powi(2) + (y - player_y).powi(2)).sqrt() < inventory::COLLECT_RADIUS) {
+            // A full inventory leaves it lying there
+            let (x, y, item) = self.items[index];
+            if self.players[self.local_index].inventory.add(item) {
+                self.items.remove(index);
+                self.floating_texts.push(FloatingText::new(format!("+ {}", item.label()), x, y, item.color()));
+            }
+        }
+
+        let mut taken = Vec::new();
+        let inventory = &mut self.players[self.local_index].inventory;
+        for pill in &mut self.pills {
+            if !pill.collected && pill.can_collect(player_x, player_y, 0.6) {
+                pill.collected = true;
+                if self.config.store_pills && inventory.add(Item::Pill(pill.pill_type)) {
+                    let text = format!("{} stored", Item::Pill(pill.pill_type).label());
+                    self.floating_texts.push(FloatingText::new(text, pill.pos.x, pill.pos.y, pill.get_color()));
+                } else {
+                    taken.push((pill.pill_type, pill.pos.x, pill.pos.y));
+                }
+            }
+        }
+        for (pill_type, x, y) in taken {
+            self.take_pill(pill_type, x, y);
+        }
+    }
+
+    /// Take a pill of `pill_type` at (x, y): picked up, or used from the inventory
+    fn take_pill(&mut self, pill_type: PillType, x: f32, y: f32) {
+        // A random pill turns out to be another color
+        let (taken, dose) = self.pill_table.take(pill_type, &mut rand::thread_rng());
+        match taken {
+            PillType::Red => self.stats.red_pills += 1,
+            PillType::Blue => self.stats.blue_pills += 1,
+            _ => {}
+        }
+        if taken != pill_type {
+            self.floating_texts.push(FloatingText::new(format!("{} pill", taken.name()), x, y - 0.3, pill_type.color()));
+        }
+        if dose.heal > 0 {
+            self.health.heal(&mut self.players[self.local_index], dose.heal, Source::Pill, &mut self.events);
+            self.floating_texts.push(FloatingText::new(format!("+{} HP", dose.heal), x, y, Color::SKYBLUE));
+        }
+        if dose.time != 0.0 {
+            self.game_timer = (self.game_timer + dose.time).max(0.0);
+            self.ui.change_timer(dose.time);
+            self.floating_texts.push(FloatingText::new(format!("{:+.0}s", dose.time), x, y + 0.3, Color::ORANGE));
+        }
+        for (i, &(effect, strength, seconds)) in dose.effects.iter().enumerate() {
+            self.status.apply(effect, strength, seconds);
+            if effect == Effect::Poison {
+                self.effects.trigger_anxiety_effect();
+            }
+            self.floating_texts.push(FloatingText::new(effect.label().to_string(), x, y + 0.3 * i as f32, effect.color()));
+        }
+    }
+
+    /// Use one item from the selected inventory slot. Items that would do nothing stay
+    fn use_item(&mut self) {
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+        let Some(item) = self.player().inventory.selected_item() else {
+            return;
+        };
+        let used = match item {
+            Item::Medkit => {
+                let healed = self.health.heal(&mut self.players[self.local_index], inventory::MEDKIT_HEAL, Source::Medkit, &mut self.events);
+                if healed > 0 {
+                    self.floating_texts.push(FloatingText::new(format!("+{} HP", healed), x, y, Color::SKYBLUE));
+                }
+                healed > 0
+            }
+            Item::Battery => {
+                let charged = self.flashlight.charge();
+                if charged {
+                    let text = format!("Battery {}%", (self.flashlight.battery() * 100.0).round());
+                    self.floating_texts.push(FloatingText::new(text, x, y, flashlight::COLOR));
+                }
+                charged
+            }
+            Item::Chalk => self.chalk.refill(),
+            Item::Pill(pill_type) => {
+                self.take_pill(pill_type, x, y);
+                true
+            }
+        };
+        if used {
+            self.players[self.local_index].inventory.take_selected();
+        } else {
+            self.floating_texts.push(FloatingText::new(format!("{} not needed", item.label()), x, y, Color::LIGHTGRAY));
+        }
+    }
+
+    /// Challenge rooms the player walked into, and the orbs they picked up in them
+    fn update_challenges(&mut self, delta_time: f32) {
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+        for outcome in self.challenges.update(&self.maze, (x, y), delta_time) {
+            self.events.push(match outcome {
+                challenge::Outcome::Started(room) => GameEvent::ChallengeStarted { room },
+                challenge::Outcome::Orb { x, y } => GameEvent::OrbCollected { x, y },
+                challenge::Outcome::Won(room) => GameEvent::ChallengeWon { room },
+                challenge::Outcome::Failed(room) => GameEvent::ChallengeFailed { room },
+            });
+        }
+    }
+
+    /// Short-lived things ticking down: props, noises, sprites, floating texts, the entity budget
+    fn update_upkeep(&mut self, delta_time: f32) {
+        self.heatmap.update(&self.maze, &self.decals, &self.enemies, &self.crowd, delta_time);
+        for prop in &mut self.props {
+            prop.update(delta_time);
+        }
+        for noise in &mut self.noises {
+            noise.update(delta_time);
+        }
+        self.noises.retain(|noise| !noise.is_expired());
+        self.budget.update(delta_time);
+        self.cleanup_entities();
+
+        // Update sprites
+        for sprite in &mut self.sprites {
+            sprite.update(delta_time);
+        }
+
+        // Update pills (glow animation) and keys (spin, exit rattle cooldown)
+        self.keys.update(delta_time);
+        self.clocks.update(delta_time);
+        for pill in &mut self.pills {
+            pill.update(delta_time);
+        }
+
+        // Update floating texts
+        self.floating_texts.retain_mut(|text| {
+            text.update(delta_time);
+            text.lifetime > 0.0
+        });
+    }
+
+    /// Timed effects and health, then everything the frame's events set off
+    fn update_feedback(&mut self, delta_time: f32) {
+        // Update effects
+        self.effects.update(delta_time);
+        self.health.update(&mut self.players[self.local_index], delta_time, &mut self.events);
+        self.handle_events();
+    }
+
+    /// Reaching the exit
+    fn update_exit(&mut self) {
+        // Check if player reached goal (unless the hunter got them first). The exit
+        // stays locked until every key in the level has been picked up
+        let at_goal = self.maze.is_goal(self.player().pos.x, self.player().pos.y, 1.0);
+        if self.state == State::Playing && at_goal && !self.keys.all_collected() {
+            if self.keys.rattle() {
+                let (x, y) = self.maze.goal_pos;
+                self.events.push(GameEvent::ExitLocked { x, y });
+            }
+        } else if self.state == State::Playing && at_goal {
+            if let Some(versus) = &mut self.versus {
+                self.state = State::Victory;
+                versus.winner = Some(Side::Escaper);
+            } else if !self.campaign.is_last() {
+                info!("Level {} complete with {:.0}s left", self.campaign.index + 1, self.game_timer);
+                self.start_tally();
+            } else {
+                let player = self.player();
+                self.escape = Some(Escape::new(&self.maze, player.pos.x, player.pos.y, player.angle));
+                self.state = State::Escaping;
+            }
+        }
+    }
+
+    /// ESC goes back to the menu
+    fn update_menu_key(&mut self, rl: &RaylibHandle) {
+        // Escape to menu
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            self.state = State::Menu;
+        }
+    }
+
+    /// Nearest living enemy or intact prop in front of the player, within reach and closer than the wall
+    fn attack_target(&self, wall_distance: f32) -> Option<AttackTarget> {
+        let enemies = self
+            .enemies
+            .iter()
+            .enumerate()
+            .filter(|(_, enemy)| enemy.is_alive())
+            .map(|(i, enemy)| (AttackTarget::Enemy(i), enemy.pos, ENEMY_RADIUS));
+        let props = self
+            .props
+            .iter()
+            .enumerate()
+            .filter(|(_, prop)| !prop.is_broken())
+            .map(|(i, prop)| (AttackTarget::Prop(i), prop.pos, PROP_RADIUS));
+
+        let mut best: Option<(AttackTarget, f32)> = None;
+        for (target, pos, radius) in enemies.chain(props) {
+            let dx = pos.x - self.player().pos.x;
+            let dy = pos.y - self.player().pos.y;
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance > player::ATTACK_RANGE + radius || distance > wall_distance + radius {
+                continue;
+            }
+
+            let difference = dy.atan2(dx) - self.player().angle;
+            let off_center = difference.sin().atan2(difference.cos()); // Wrapped to [-PI, PI]
+            if off_center.abs() > player::ATTACK_ARC {
+                continue;
+            }
+
+            if best.as_ref().is_none_or(|(_, best_distance)| distance < *best_distance) {
+                best = Some((target, distance));
+            }
+        }
+        best.map(|(target, _)| target)
+    }
+
+    /// Despawn expired corpses, keep at most MAX_CORPSES, drop collected pills and
+    /// evict whatever is over the entity budget
+    fn cleanup_entities(&mut self) {
+        self.enemies.retain(|enemy| !enemy.is_expired());
+
+        let corpses = self.enemies.iter().filter(|enemy| enemy.is_corpse()).count();
+        if corpses > enemy::MAX_CORPSES {
+            // Oldest corpses have the longest state timer
+            let mut ages: Vec<f32> = self
+                .enemies
+                .iter()
+                .filter(|enemy| enemy.is_corpse())
+                .map(|enemy| enemy.state_timer)
+                .collect();
+            ages.sort_by(|a, b| b.total_cmp(a));
+            let cutoff = ages[corpses - enemy::MAX_CORPSES - 1];
+            self.enemies.retain(|enemy| !enemy.is_corpse() || enemy.state_timer < cutoff);
+        }
+
+        self.pills.retain(|pill| !pill.collected);
+        self.props.retain(|prop| !prop.is_broken());
+        self.enforce_budget();
+    }
+
+    /// Evict the least important instances over each entity budget cap: corpses, then the
+    /// enemies furthest away that haven't spotted the player; the oldest texts and noises
+    fn enforce_budget(&mut self) {
+        let max_enemies = self.budget.cap(Pool::Enemies);
+        if self.enemies.len() > max_enemies {
+            let (x, y) = (self.player().pos.x, self.player().pos.y);
+            let importance = |enemy: &Enemy| {
+                let distance = ((enemy.pos.x - x).powi(2) + (enemy.pos.y - y).powi(2)).sqrt();
+                match (enemy.is_alive(), enemy.detected) {
+                    (false, _) => -1.0 / (1.0 + enemy.state_timer), // Oldest corpse first
+                    (true, false) => 1.0 / (1.0 + distance),        // Furthest first
+                    (true, true) => 2.0,                            // Hunting the player, kept
+                }
+            };
+            let mut order: Vec<usize> = (0..self.enemies.len()).collect();
+            order.sort_by(|&a, &b| importance(&self.enemies[a]).total_cmp(&importance(&self.enemies[b])));
+            let mut keep = vec![true; self.enemies.len()];
+            for &index in &order[..self.enemies.len() - max_enemies] {
+                keep[index] = false;
+            }
+            info!("Entity budget: evicting {} enemies", self.enemies.len() - max_enemies);
+            let mut keep = keep.into_iter();
+            self.enemies.retain(|_| keep.next().unwrap_or(true));
+        }
+
+        self.decals.trim(self.budget.cap(Pool::Decals));
+        let excess = self.floating_texts.len().saturating_sub(self.budget.cap(Pool::FloatingTexts));
+        self.floating_texts.drain(..excess);
+        let excess = self.noises.len().saturating_sub(self.budget.cap(Pool::Noises));
+        self.noises.drain(..excess);
+    }
+
+    /// Catch a NaN or infinite position before it spreads (see guard.rs). In strict mode this
+    /// panics, otherwise players go back to the start and enemies to the exit
+    fn check_positions(&mut self) {
+        let (start, goal) = (self.maze.start_pos, self.maze.goal_pos);
+        for (index, player) in self.players.iter_mut().enumerate() {
+            if !guard::position_ok("player", index, player.pos.x, player.pos.y) {
+                player.pos = player::Vector2::new(start.0, start.1);
+            }
+        }
+        for (index, enemy) in self.enemies.iter_mut().enumerate() {
+            if !guard::position_ok("enemy", index, enemy.pos.x, enemy.pos.y) {
+                enemy.pos = player::Vector2::new(goal.0, goal.1);
+                enemy.investigate = None;
+                enemy.clear_route();
+            }
+        }
+    }
+
+    /// Update the visibility meter and let every living enemy look for the player
+    fn update_stealth(&mut self, speed: f32, delta_time: f32) {
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+        let light = stealth::light_level(x, y, &self.sprites) * self.blackout.ambient();
+        let target = stealth::visibility(light, self.player().crouching, speed);
+        self.visibility = stealth::smooth(self.visibility, target, delta_time);
+
+        let mut sightings = Vec::new();
+
+        for enemy in self.enemies.iter_mut().filter(|enemy| enemy.is_alive()) {
+            let sees = stealth::can_see(&self.maze, (enemy.pos.x, enemy.pos.y), (x, y), self.visibility);
+            if enemy.watch(sees, self.visibility, delta_time) {
+                self.events.push(GameEvent::PlayerSpotted { x: enemy.pos.x, y: enemy.pos.y });
+            }
+            // Keep heading for where the player was last seen
+            if enemy.detected && sees {
+                enemy.remember_player(x, y, self.config.search_time);
+                sightings.push((enemy.pos.x, enemy.pos.y));
+            }
+        }
+
+        // Every sighting alerts the zone around the enemy, which calls the others in
+        for spotter in sightings {
+            if let Some(zone) = self.alerts.raise(spotter, (x, y)) {
+                for enemy in self.enemies.iter_mut().filter(|enemy| enemy.is_alive() && !enemy.detected) {
+                    if zone.contains(enemy.pos.x, enemy.pos.y) {
+                        enemy.hear(x, y);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Scout the point the local player is looking at, or recall a companion that is away
+    fn command_companion(&mut self) {
+        let Some(companion) = &mut self.companion else {
+            return;
+        };
+        if companion.order != Order::Follow {
+            companion.recall();
+            return;
+        }
+
+        // Last walkable point along the view ray, a little short of the wall
+        let player = &self.players[self.local_index];
+        let mut marker = (player.pos.x, player.pos.y);
+        let mut distance = 0.5;
+        while distance <= SCOUT_MARKER_RANGE {
+            let point = (player.pos.x + player.angle.cos() * distance, player.pos.y + player.angle.sin() * distance);
+            if !self.maze.is_walkable(point.0, point.1) {
+                break;
+            }
+            marker = (player.pos.x + player.angle.cos() * (distance - 0.4), player.pos.y + player.angle.sin() * (distance - 0.4));
+            distance += 0.1;
+        }
+
+        let bot = &self.players[companion.player_index];
+        if companion.scout(&self.maze, bot, marker.0, marker.1) {
+            self.floating_texts.push(FloatingText::new("Scout here".to_string(), marker.0, marker.1, Color::GREEN));
+        }
+    }
+
+    /// Move the companion and let it call out pills and the exit it can see
+    fn update_companion(&mut self, delta_time: f32) {
+        let Some(companion) = &mut self.companion else {
+            return;
+        };
+        let leader = self.players[self.local_index].clone();
+        let bot = &mut self.players[companion.player_index];
+        companion.update(&self.maze, bot, &leader, delta_time);
+
+        let from = (bot.pos.x, bot.pos.y);
+        if stealth::can_see(&self.maze, from, self.maze.goal_pos, 1.0) {
+            let (x, y) = self.maze.goal_pos;
+            companion.point_out(x, y, "I can see the exit!");
+        }
+        for pill in self.pills.iter().filter(|pill| !pill.collected) {
+            let distance = ((pill.pos.x - from.0).powi(2) + (pill.pos.y - from.1).powi(2)).sqrt();
+            if distance <= companion::SPOT_RANGE && stealth::can_see(&self.maze, from, (pill.pos.x, pill.pos.y), 1.0) {
+                let line = match pill.pill_type {
+                    PillType::Red => "Red pill over here. Careful with those.",
+                    PillType::Blue => "There's a blue pill here.",
+                    PillType::Green => "Green pill here. That one gets your legs going.",
+                    PillType::Yellow => "Yellow pill. Might help us get our bearings.",
+                    PillType::Purple => "A purple pill... no telling what that does.",
+                };
+                if companion.point_out(pill.pos.x, pill.pos.y, line) {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Volume, pan and pitch for the radio loop heard from the player's position, None when silent
+    pub fn radio_playback(&self) -> Option<(f32, f32, f32)> {
+        if self.state != State::Playing || self.is_focus_paused() || !self.radio.is_playing() {
+            return None;
+        }
+
+        let dx = self.radio.pos.x - self.player().pos.x;
+        let dy = self.radio.pos.y - self.player().pos.y;
+        let distance = (dx * dx + dy * dy).sqrt();
+        let volume = 1.0 - distance / radio::HEARING_RANGE;
+        if volume <= 0.0 {
+            return None;
+        }
+
+        // Positive side = radio is to the player's right
+        let side = (dy.atan2(dx) - self.player().angle).sin();
+        Some((volume, 0.5 - 0.5 * side, self.radio.doppler.pitch()))
+    }
+
+    /// Echo level for footsteps and impacts in the player's surroundings (0.0 - 1.0)
+    pub fn reverb(&self) -> f32 {
+        if self.state != State::Playing || self.is_focus_paused() {
+            return 0.0;
+        }
+        self.acoustics.wet
+    }
+
+    /// (volume, pan, pitch, start a new phrase) for the whispers, None when silent
+    pub fn whisper_playback(&self) -> Option<(f32, f32, f32, bool)> {
+        if self.state != State::Playing || self.is_focus_paused() {
+            return None;
+        }
+        self.whispers.playback()
+    }
+
+    /// Highest awareness any living enemy has of the player (0.0 - 1.0)
+    fn max_awareness(&self) -> f32 {
+        self.enemies
+            .iter()
+            .filter(|enemy| enemy.is_alive())
+            .map(|enemy| enemy.awareness)
+            .fold(0.0, f32::max)
+    }
+
+    /// First door along the view direction within reach: (grid x, grid y, is open)
+    fn door_in_front(&self) -> Option<(usize, usize, bool)> {
+        let tile = self.maze.tile_size;
+        let mut distance = 0.0;
+        while distance <= DOOR_REACH {
+            let x = ((self.player().pos.x + self.player().angle.cos() * distance) / tile) as usize;
+            let y = ((self.player().pos.y + self.player().angle.sin() * distance) / tile) as usize;
+            if let Some(open) = self.doors.is_opening(x, y).or(self.maze.door_at(x, y)) {
+                return Some((x, y, open));
+            }
+            if self.maze.is_wall(x, y) {
+                return None;
+            }
+            distance += 0.05;
+        }
+        None
+    }
+
+    /// Someone is standing in the doorway at grid position (x, y)
+    fn doorway_occupied(&self, x: usize, y: usize) -> bool {
+        let tile = self.maze.tile_size;
+        let overlaps = |px: f32, py: f32, radius: f32| {
+            px + radius > x as f32 * tile
+                && px - radius < (x + 1) as f32 * tile
+                && py + radius > y as f32 * tile
+                && py - radius < (y + 1) as f32 * tile
+        };
+        overlaps(self.player().pos.x, self.player().pos.y, self.player().collision_radius)
+            || self.enemies.iter().any(|enemy| enemy.is_alive() && overlaps(enemy.pos.x, enemy.pos.y, ENEMY_RADIUS))
+    }
+
+    /// Open or close a door (it slides, see doors.rs). Closing fails while someone stands in the doorway
+    fn toggle_door(&mut self, x: usize, y: usize, open: bool) {
+        if open {
+            if self.doorway_occupied(x, y) {
+                return;
+            }
+            self.doors.close(x, y, &mut self.maze);
+        } else {
+            self.doors.open(x, y);
+        }
+
+        // Every use leaves a hand on the side the player pushed from
+        let from = (self.player().pos.x, self.player().pos.y);
+        self.grime.add(GrimeKind::Handprint, (x, y), from, from);
+        if open {
+            self.rumble_from(x as f32 + 0.5, y as f32 + 0.5, rumble::DOOR_SLAM, 0.6);
+        }
+    }
+
+    /// Closed door an enemy on its way somewhere has walked into: (grid x, grid y)
+    fn find_door_push(&self) -> Option<(usize, usize)> {
+        let tile = self.maze.tile_size;
+        for enemy in self.enemies.iter().filter(|enemy| enemy.wants_to_push()) {
+            let Some(target) = enemy.heading() else {
+                continue;
+            };
+            let dx = target.x - enemy.pos.x;
+            let dy = target.y - enemy.pos.y;
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance <= 0.0 {
+                continue;
+            }
+
+            // Probe ahead, and along each axis since movement slides per axis
+            let (step_x, step_y) = (dx / distance * 0.5, dy / distance * 0.5);
+            for (probe_x, probe_y) in [(step_x, step_y), (step_x, 0.0), (0.0, step_y)] {
+                let x = ((enemy.pos.x + probe_x) / tile) as usize;
+                let y = ((enemy.pos.y + probe_y) / tile) as usize;
+                if self.maze.door_at(x, y) == Some(false) {
+                    return Some((x, y));
+                }
+            }
+        }
+        None
+    }
+
+    /// Start, advance and resolve an enemy forcing a door while the player braces it
+    fn update_barricade(&mut self, rl: &RaylibHandle, delta_time: f32) {
+        if self.barricade.is_none() {
+            self.barricade = self.find_door_push().map(Barricade::new);
+        }
+        let Some(barricade) = &mut self.barricade else {
+            self.players[self.local_index].regen_stamina(delta_time);
+            return;
+        };
+
+        let tile = self.maze.tile_size;
+        let (door_x, door_y) = barricade.door;
+        let (x, y) = ((door_x as f32 + 0.5) * tile, (door_y as f32 + 0.5) * tile);
+        let distance_to = |px: f32, py: f32| ((px - x).powi(2) + (py - y).powi(2)).sqrt();
+
+        // The door was opened from this side, or the enemy wandered off or died
+        let pusher_near = self
+            .enemies
+            .iter()
+            .any(|enemy| enemy.is_alive() && distance_to(enemy.pos.x, enemy.pos.y) <= PUSH_REACH);
+        if self.maze.door_at(door_x, door_y) != Some(false) || !pusher_near {
+            self.barricade = None;
+            return;
+        }
+
+        // Hold interact to brace, each fresh press shoves back a little more
+        let player = &mut self.players[self.local_index];
+        let mut holding = false;
+        if self.search.is_none() && distance_to(player.pos.x, player.pos.y) <= BRACE_REACH {
+            if self.controls.pressed(rl, Action::Interact) && player.use_stamina(barricade::MASH_STAMINA_COST) {
+                barricade.mash();
+            }
+            holding = self.controls.down(rl, Action::Interact)
+                && player.use_stamina(barricade::HOLD_STAMINA_DRAIN * delta_time);
+        }
+        if !holding {
+            player.regen_stamina(delta_time);
+        }
+
+        let (shoved, outcome) = barricade.update(holding, delta_time);
+        if shoved {
+            self.events.push(GameEvent::DoorShoved { x, y });
+        }
+        match outcome {
+            Outcome::Ongoing => {}
+            Outcome::BrokeThrough => {
+                self.doors.burst(door_x, door_y, &mut self.maze);
+                self.barricade = None;
+                self.events.push(GameEvent::DoorBurst { x, y });
+            }
+            Outcome::GaveUp => {
+                for enemy in self.enemies.iter_mut() {
+                    if enemy.is_alive() && distance_to(enemy.pos.x, enemy.pos.y) <= PUSH_REACH {
+                        enemy.give_up_push();
+                    }
+                }
+                self.barricade = None;
+                self.events.push(GameEvent::DoorHeld { x, y });
+            }
+        }
+    }
+
+    /// Slide the doors, closing the ones left open long enough once their doorway is clear
+    fn update_doors(&mut self, delta_time: f32) {
+        for (x, y) in self.doors.update(delta_time, &mut self.maze) {
+            if !self.doorway_occupied(x, y) {
+                self.doors.close(x, y, &mut self.maze);
+            }
+        }
+    }
+
+    /// Player is close enough to brace the door being forced
+    fn can_brace(&self) -> bool {
+        self.barricade.as_ref().is_some_and(|barricade| {
+            let tile = self.maze.tile_size;
+            let x = (barricade.door.0 as f32 + 0.5) * tile;
+            let y = (barricade.door.1 as f32 + 0.5) * tile;
+            ((self.player().pos.x - x).powi(2) + (self.player().pos.y - y).powi(2)).sqrt() <= BRACE_REACH
+        })
+    }
+
+    /// Camera position just past a closed door, on the far side from the player.
+    /// None when the door opens onto a wall or another door, so there's nothing to see
+    fn peek_position(&self, x: usize, y: usize) -> Option<(f32, f32)> {
+        let tile = self.maze.tile_size;
+        let center = ((x as f32 + 0.5) * tile, (y as f32 + 0.5) * tile);
+        let dx = center.0 - self.player().pos.x;
+        let dy = center.1 - self.player().pos.y;
+
+        // Step through along the door's axis so the camera lands in the next tile
+        let position = if dx.abs() > dy.abs() {
+            (center.0 + dx.signum() * PEEK_DEPTH * tile, center.1)
+        } else {
+            (center.0, center.1 + dy.signum() * PEEK_DEPTH * tile)
+        };
+        self.maze.is_walkable(position.0, position.1).then_some(position)
+    }
+
+    /// Closest unsearched container within reach
+    fn nearby_container(&self) -> Option<usize> {
+        self.containers
+            .iter()
+            .enumerate()
+            .filter(|(_, container)| !container.searched)
+            .map(|(i, container)| {
+                let dx = container.pos.x - self.player().pos.x;
+                let dy = container.pos.y - self.player().pos.y;
+                (i, (dx * dx + dy * dy).sqrt())
+            })
+            .filter(|(_, distance)| *distance <= SEARCH_RANGE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Advance the container search: interruptions, the lucky zone, noise and loot
+    fn update_search(&mut self, rl: &RaylibHandle, delta_time: f32) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        let container = &mut self.containers[search.container];
+        let (x, y) = (container.pos.x, container.pos.y);
+
+        // Trying to move or getting hurt stops the search
+        let moved = self.controls.move_pressed(rl);
+        if moved || self.players[self.local_index].health < search.start_health {
+            self.floating_texts.push(FloatingText::new("Interrupted".to_string(), x, y, Color::ORANGE));
+            self.search = None;
+            return;
+        }
+
+        if self.controls.pressed(rl, Action::Interact) {
+            if let RummageResult::Missed = search.rummage() {
+                self.events.push(GameEvent::Noise { x, y, radius: MISSED_RUMMAGE_NOISE_RADIUS });
+            }
+        }
+        if search.noise_due() {
+            self.events.push(GameEvent::Noise { x, y, radius: SEARCH_NOISE_RADIUS });
+        }
+
+        if search.update(delta_time) {
+            container.searched = true;
+            let table = container.kind.loot_table();
+            self.search = None;
+            self.drop_loot(table, x, y);
+        }
+    }
+
+    /// Roll a loot table and scatter the items around (x, y)
+    fn drop_loot(&mut self, table: &str, x: f32, y: f32) {
+        let items = self.loot_tables.roll(table);
+        let count = items.len();
+        for (i, item) in items.into_iter().enumerate() {
+            // Spread multiple drops in a small circle so they don't overlap
+            let (offset_x, offset_y) = if count > 1 {
+                let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                (angle.cos() * 0.25, angle.sin() * 0.25)
+            } else {
+                (0.0, 0.0)
+            };
+            match item {
+                Item::Pill(pill_type) => self.pills.push(Pill::new(x + offset_x, y + offset_y, pill_type)),
+                item => self.items.push((x + offset_x, y + offset_y, item)),
+            }
+        }
+    }
+
+    /// React to everything that happened this frame, including what the reactions set off
+    fn handle_events(&mut self) {
+        while !self.events.is_empty() {
+            self.handle_event_batch();
+        }
+    }
+
+    fn handle_event_batch(&mut self) {
+        for event in self.events.drain() {
+            match event {
+                GameEvent::AttackLanded { x, y, strength } => {
+                    self.effects.trigger_hit_stop(strength);
+                    self.punch_from(x, y, strength);
+                    self.audio.play_impact(strength);
+                }
+                GameEvent::EnemyKilled { x, y } => {
+                    self.stats.kills += 1;
+                    self.drop_loot("enemy", x, y);
+                }
+                GameEvent::PropBroken { x, y } => self.drop_loot("prop", x, y),
+                GameEvent::KeyCollected { x, y } => {
+                    let text = format!("Key {}/{}", self.keys.collected(), self.keys.total());
+                    self.floating_texts.push(FloatingText::new(text, x, y, keys::COLOR));
+                    self.ui.toasts.push("Key acquired", keys::COLOR);
+                    if self.keys.all_collected() {
+                        self.ui.toasts.push("The exit is unlocked", keys::COLOR);
+                    }
+                }
+                GameEvent::BatteryCollected { x, y } => {
+                    // Kept for later when there is room, otherwise straight into the flashlight
+                    let text = if self.players[self.local_index].inventory.add(Item::Battery) {
+                        "+ Battery".to_string()
+                    } else {
+                        self.flashlight.charge();
+                        format!("Battery {}%", (self.flashlight.battery() * 100.0).round())
+                    };
+                    self.floating_texts.push(FloatingText::new(text, x, y, flashlight::COLOR));
+                }
+                GameEvent::ClockCollected { x, y } => {
+                    self.game_timer += clock::BONUS;
+                    self.ui.change_timer(clock::BONUS);
+                    self.floating_texts.push(FloatingText::new(format!("+{:.0}s", clock::BONUS), x, y, clock::COLOR));
+                }
+                GameEvent::Toast { text, color } => self.ui.toasts.push(text, color),
+                GameEvent::ExitLocked { x, y } => {
+                    let missing = self.keys.total() - self.keys.collected();
+                    let text = format!("Locked - {} key{} missing", missing, if missing == 1 { "" } else { "s" });
+                    self.floating_texts.push(FloatingText::new(text, x, y, keys::COLOR));
+                    self.audio.play_locked();
+                }
+                GameEvent::WallHit { x, y } => {
+                    let tile = self.maze.tile_size;
+                    let cell = ((x / tile) as usize, (y / tile) as usize);
+                    let from = (self.player().pos.x, self.player().pos.y);
+                    self.grime.add(GrimeKind::Scuff, cell, (x, y), from);
+                }
+                GameEvent::Noise { x, y, radius } => {
+                    // Every living enemy in earshot goes to check it out
+                    for enemy in &mut self.enemies {
+                        if ((enemy.pos.x - x).powi(2) + (enemy.pos.y - y).powi(2)).sqrt() <= radius {
+                            enemy.hear(x, y);
+                        }
+                    }
+                    self.noises.push(Noise::new(x, y, radius));
+                }
+                GameEvent::PlayerSpotted { x, y } => {
+                    self.effects.trigger_anxiety_effect();
+                    self.floating_texts.push(FloatingText::new("!".to_string(), x, y, Color::RED));
+                }
+                GameEvent::PlayerStruck { x, y, damage } => {
+                    let lost = self.health.damage(&mut self.players[self.local_index], damage, Source::Enemy, &mut self.events);
+                    if lost == 0 {
+                        continue; // Still reeling from the last hit
+                    }
+                    self.audio.play_damage();
+                    self.effects.trigger_anxiety_effect();
+                    self.status.apply(Effect::Slow, STRIKE_SLOW, STRIKE_SLOW_TIME);
+                    self.punch_from(x, y, 0.8);
+                    let (player_x, player_y) = (self.player().pos.x, self.player().pos.y);
+                    self.floating_texts.push(FloatingText::new(format!("-{} HP", lost), player_x, player_y, Color::RED));
+                }
+                GameEvent::Damaged { amount, source } => {
+                    self.haptics.damage(amount);
+                    self.level_log.damage_taken += amount;
+                    match source {
+                        Source::Enemy => self.stats.strikes += 1,
+                        Source::DoorBurst => self.stats.door_bursts += 1,
+                        _ => {}
+                    }
+                }
+                GameEvent::Healed { amount, source } => {
+                    // Pickups show their own floating text
+                    if source == Source::Regen {
+                        let (x, y) = (self.player().pos.x, self.player().pos.y);
+                        self.floating_texts.push(FloatingText::new(format!("+{}", amount), x, y, Color::GREEN));
+                    }
+                }
+                GameEvent::LowHealth => self.ui.toasts.push("Low health", Color::new(255, 80, 80, 255)),
+                GameEvent::Died { source } => {
+                    // Hardcore runs have one life; elsewhere the run goes on until the timer runs out
+                    if self.hardcore_run && self.state == State::Playing {
+                        info!("Hardcore run over: out of health ({:?})", source);
+                        let cause = if source == Source::Enemy { DeathCause::Caught } else { DeathCause::Health };
+                        self.game_over(cause);
+                    }
+                }
+                GameEvent::DoorShoved { x, y } => {
+                    // Claw marks on the side the enemy is shoving from
+                    let distance_to = |enemy: &Enemy| (enemy.pos.x - x).powi(2) + (enemy.pos.y - y).powi(2);
+                    let pusher = self
+                        .enemies
+                        .iter()
+                        .filter(|enemy| enemy.is_alive())
+                        .min_by(|a, b| distance_to(a).total_cmp(&distance_to(b)));
+                    if let Some(enemy) = pusher {
+                        let tile = self.maze.tile_size;
+                        let cell = ((x / tile) as usize, (y / tile) as usize);
+                        let from = (enemy.pos.x, enemy.pos.y);
+                        self.grime.add(GrimeKind::Scratch, cell, from, from);
+                    }
+                    self.audio.play_impact(0.5);
+                    self.rumble_from(x, y, rumble::DOOR_SLAM, 0.5);
+                    if self.can_brace() {
+                        self.punch_from(x, y, 0.35);
+                    }
+                }
+                GameEvent::DoorBurst { x, y } => {
+                    self.audio.play_impact(1.0);
+                    self.rumble_from(x, y, rumble::DOOR_SLAM, 1.0);
+                    self.effects.trigger_anxiety_effect();
+                    let distance = ((self.player().pos.x - x).powi(2) + (self.player().pos.y - y).powi(2)).sqrt();
+                    if distance <= BRACE_REACH {
+                        self.health.damage(&mut self.players[self.local_index], DOOR_BURST_DAMAGE, Source::DoorBurst, &mut self.events);
+                        self.punch_from(x, y, 1.0);
+                    }
+                    self.floating_texts.push(FloatingText::new("CRASH".to_string(), x, y, Color::ORANGE));
+                }
+                GameEvent::DoorHeld { x, y } => {
+                    self.floating_texts.push(FloatingText::new("Held".to_string(), x, y, Color::SKYBLUE));
+                }
+                GameEvent::ChatScare { scare } => self.apply_scare(scare),
+                GameEvent::Escalation { action } => self.apply_escalation(action),
+                GameEvent::ChallengeStarted { room } => {
+                    let room = *self.challenges.room(room);
+                    if let Trial::Blackout { seconds } = room.trial {
+                        self.blackout.trigger(seconds);
+                        self.effects.trigger_flicker();
+                    }
+                    self.ui.toasts.push(room.describe(), challenge::COLOR);
+                }
+                GameEvent::OrbCollected { x, y } => {
+                    if let Some((room, _, collected)) = self.challenges.running() {
+                        if let Trial::Orbs { count, .. } = room.trial {
+                            let text = format!("Orb {}/{}", collected, count);
+                            self.floating_texts.push(FloatingText::new(text, x, y, challenge::COLOR));
+                        }
+                    }
+                }
+                GameEvent::ChallengeWon { room } => {
+                    let reward = self.challenges.room(room).reward;
+                    self.stats.challenges_won += 1;
+                    match reward {
+                        Reward::Time(seconds) => {
+                            self.game_timer += seconds;
+                            self.ui.change_timer(seconds);
+                        }
+                        Reward::Armor => self.health.add_modifier(Modifier::DamageTaken(challenge::ARMOR), None),
+                        Reward::Regen => self.health.add_modifier(Modifier::Regen(challenge::REGEN), None),
+                    }
+                    let (x, y) = (self.player().pos.x, self.player().pos.y);
+                    self.floating_texts.push(FloatingText::new(reward.label(), x, y, challenge::COLOR));
+                    self.ui.toasts.push(format!("Challenge complete: {}", reward.label()), challenge::COLOR);
+                }
+                GameEvent::ChallengeFailed { room } => {
+                    self.stats.challenges_failed += 1;
+                    let text = match self.challenges.room(room).trial {
+                        Trial::Orbs { .. } => "Challenge failed: out of time",
+                        Trial::Blackout { .. } => "Challenge failed: you left the room",
+                    };
+                    self.ui.toasts.push(text, Color::new(200, 200, 200, 255));
+                }
+            }
+        }
+    }
+
+    /// Carry out the scare chat voted for. None of them can hurt the player
+    fn apply_scare(&mut self, scare: Scare) {
+        let (x, y, angle) = (self.player().pos.x, self.player().pos.y, self.player().angle);
+        match scare {
+            Scare::Flicker => self.effects.trigger_flicker(),
+            Scare::Glimpse => {
+                // Somewhere down the hall in front of the player, if there's room for it
+                let mut distance = 0.0;
+                while distance < GLIMPSE_MAX_DISTANCE
+                    && self.maze.is_walkable(x + angle.cos() * (distance + 0.5), y + angle.sin() * (distance + 0.5))
+                {
+                    distance += 0.1;
+                }
+                if distance >= GLIMPSE_MIN_DISTANCE {
+                    self.glimpse = Some((x + angle.cos() * distance, y + angle.sin() * distance, GLIMPSE_TIME));
+                    self.effects.trigger_anxiety_effect();
+                } else {
+                    // Facing a wall: nothing to see, so the lights go instead
+                    self.effects.trigger_flicker();
+                }
+            }
+            Scare::Pill => {
+                // Just ahead, so it isn't picked up the instant it lands
+                let (ahead_x, ahead_y) = (x + angle.cos() * 0.8, y + angle.sin() * 0.8);
+                let (drop_x, drop_y) = if self.maze.is_walkable(ahead_x, ahead_y) { (ahead_x, ahead_y) } else { (x, y) };
+                if let Some(pill_type) = self.pill_table.roll(&mut rand::thread_rng()) {
+                    self.pills.push(Pill::new(drop_x, drop_y, pill_type));
+                }
+            }
+        }
+        let text = format!("Chat: !{}", scare.command());
+        self.floating_texts.push(FloatingText::new(text, x, y, Color::new(170, 120, 255, 255)));
+    }
+
+    /// Carry out a step of the level's escalation script
+    fn apply_escalation(&mut self, action: Escalate) {
+        match action {
+            Escalate::Flicker(rate) => {
+                info!("Escalation: the lights flicker about {:.0} times a minute", rate);
+                self.effects.trigger_flicker();
+            }
+            Escalate::Spawn(x, y) => {
+                info!("Escalation: another entity appeared at ({:.1}, {:.1})", x, y);
+                self.enemies.push(Enemy::new(x, y));
+            }
+            Escalate::Hum(scale) => {
+                info!("Escalation: the exit hum carries {:.1}x as far", scale);
+                self.exit_hum.set_range_scale(scale);
+            }
+            Escalate::Blackout(duration) => {
+                info!("Escalation: the power is out for {:.0}s", duration);
+                self.blackout.trigger(duration);
+                self.effects.trigger_flicker();
+            }
+        }
+    }
+
+    /// Punch the view away from the side (x, y) is on
+    fn punch_from(&mut self, x: f32, y: f32, strength: f32) {
+        let angle_to_hit = (y - self.player().pos.y).atan2(x - self.player().pos.x);
+        let side = (angle_to_hit - self.player().angle).sin();
+        self.effects.trigger_screen_punch((-side, 1.0), strength);
+    }
+
+    /// Where to draw the 3D view: the screen punch plus the view bob's sway, in screen pixels
+    /// (the bob's dip moves the horizon instead, see render_3d_view)
+    fn view_offset(&self) -> (i32, i32) {
+        let (punch_x, punch_y) = self.effects.view_offset();
+        let height = (self.framebuffer.height as i32 * self.render_scale) as f32;
+        (punch_x + (self.view_bob.sway() * height).round() as i32, punch_y)
+    }
+
+    /// Idle penalty the current run plays by (the settings, or the defaults in hardcore)
+    fn idle_penalty(&self) -> IdlePenalty {
+        self.config.idle.for_run(self.hardcore_run)
+    }
+
+    /// The hot/cold exit hum plays unless turned off, by default everywhere but hardcore mode
+    pub fn exit_hum_enabled(&self) -> bool {
+        self.config.exit_hum.unwrap_or(!self.hardcore_run)
+    }
+
+    /// Entries for the HUD status cluster: gauges and cooldowns first, then whatever effects are active
+    fn status_items(&self) -> Vec<StatusItem> {
+        let player = self.player();
+        let mut items = vec![StatusItem::new("Stamina", player.stamina, Color::new(255, 220, 0, 255))];
+
+        let ready = player.attack_ready();
+        let swing_color = if ready >= 1.0 { Color::WHITE } else { Color::new(140, 140, 140, 255) };
+        items.push(StatusItem::new("Swing", ready, swing_color));
+
+        let charge = self.radio.charge();
+        let radio_color = if charge > 0.25 { Color::new(120, 200, 90, 255) } else { Color::new(220, 80, 60, 255) };
+        let radio_label = if self.radio.placed {
+            "Radio".to_string()
+        } else {
+            format!("Radio [{}]", self.controls.label(Action::Throw))
+        };
+        items.push(StatusItem::new(radio_label, charge, radio_color));
+
+        let battery = self.flashlight.battery();
+        let battery_color = if self.flashlight.is_low() { Color::new(220, 80, 60, 255) } else { flashlight::COLOR };
+        let battery_label = format!("Light {}% [{}]", (battery * 100.0).round(), self.controls.label(Action::Flashlight));
+        items.push(StatusItem::new(battery_label, battery, battery_color));
+
+        if let Some((room, time_left, collected)) = self.challenges.running() {
+            let (label, fraction) = match room.trial {
+                Trial::Orbs { count, seconds } => (format!("Orbs {}/{} {:.0}s", collected, count, time_left.ceil()), time_left / seconds),
+                Trial::Blackout { seconds } => (format!("Hold out {:.0}s", time_left.ceil()), time_left / seconds),
+            };
+            items.push(StatusItem::new(label, fraction, challenge::COLOR));
+        }
+
+        for (effect, copies, fraction) in self.status.hud() {
+            let label = if copies > 1 { format!("{} x{}", effect.label(), copies) } else { effect.label().to_string() };
+            items.push(StatusItem::new(label, fraction, effect.color()).with_icon(effect.icon()));
+        }
+
+        if self.keys.total() > 0 {
+            let (collected, total) = (self.keys.collected(), self.keys.total());
+            items.push(StatusItem::new(format!("Keys {}/{}", collected, total), collected as f32 / total as f32, keys::COLOR));
+        }
+
+        if !self.chalk.unlimited {
+            let chalk_label = format!("Chalk {} [{}]", self.chalk.marks, self.controls.label(Action::Mark));
+            items.push(StatusItem::new(chalk_label, self.chalk.fraction(), Color::new(235, 235, 225, 255)));
+        }
+
+        if self.effects.anxiety_timer > 0.0 {
+            items.push(StatusItem::new("Anxiety", self.effects.anxiety_remaining(), Color::new(220, 50, 50, 255)));
+        }
+        if self.effects.flicker_timer > 0.0 {
+            items.push(StatusItem::new("Lights", self.effects.flicker_remaining(), Color::new(170, 120, 255, 255)));
+        }
+        if self.blackout.is_active() {
+            items.push(StatusItem::new("Blackout", self.blackout.level(), Color::new(255, 40, 30, 255)));
+        }
+        if player.crouching {
+            items.push(StatusItem::new("Crouch", 1.0, Color::new(90, 160, 255, 255)));
+        }
+        let alert = self.alerts.intensity();
+        if alert > 0.0 {
+            items.push(StatusItem::new("Alert", alert, Color::new(255, 120, 40, 255)));
+        }
+        let levels = self.campaign.level_count();
+        if levels > 1 {
+            let level = self.campaign.index + 1;
+            items.push(StatusItem::new(format!("Level {}/{}", level, levels), level as f32 / levels as f32, Color::new(230, 210, 120, 255)));
+        }
+        items
+    }
+
+    /// Rumble for something at (x, y), fading out with distance from the player
+    fn rumble_from(&mut self, x: f32, y: f32, curve: RumbleCurve, strength: f32) {
+        let distance = ((x - self.player().pos.x).powi(2) + (y - self.player().pos.y).powi(2)).sqrt();
+        self.haptics.play(curve, strength * (1.0 - distance / DOOR_RUMBLE_RANGE));
+    }
+
+    /// Distance to the closest living enemy that is walking somewhere
+    pub fn closest_walking_enemy(&self) -> Option<f32> {
+        let (x, y) = (self.player().pos.x, self.player().pos.y);
+        self.enemies
+            .iter()
+            .filter(|enemy| enemy.is_alive() && enemy.investigate.is_some())
+            .map(|enemy| ((enemy.pos.x - x).powi(2) + (enemy.pos.y - y).powi(2)).sqrt())
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    pub fn render(&mut self, d: &mut RaylibDrawHandle) {
+        match self.state {
+            State::Menu => {
+                if self.config.menu_flythrough {
+                    self.render_3d_view();
+                    self.post_process();
+                    self.framebuffer.apply_blur(flythrough::BLUR_RADIUS);
+                    self.framebuffer.apply_brightness(flythrough::DARKEN);
+                    self.framebuffer.render(d, self.render_scale, (0, 0));
+                }
+                self.ui.render_menu(d, d.get_screen_width(), d.get_screen_height(), &self.controls, &self.profile.name);
+
+                // Graded texture swatches so the theme can be previewed before playing
+                let swatches: Vec<&Texture> = [self.texture_ids.wall, self.texture_ids.floor, self.texture_ids.ceiling]
+                    .iter()
+                    .filter_map(|id| self.textures.get(*id))
+                    .collect();
+                self.ui.render_theme_preview(d, self.config.theme.display_name(), &swatches, &self.color_grade);
+            }
+            State::Playing => {
+                if let Some((x, y)) = self.peek_origin {
+                    // Render from the far side of the door, then put the player back
+                    let position = self.player().pos;
+                    self.players[self.local_index].pos = player::Vector2::new(x, y);
+                    self.render_3d_view();
+                    self.players[self.local_index].pos = position;
+                } else {
+                    self.render_3d_view();
+                }
+                self.post_process();
+                self.framebuffer.render(d, self.render_scale, self.view_offset());
+
+                // Hardcore runs get a minimal HUD: no minimap, stealth eye or status bars (the
+                // minimap comes back while a reveal effect runs)
+                let revealed = self.status.has(Effect::Reveal);
+                if !self.hardcore_run || revealed {
+                    // The escaper doesn't get to see the hunter on their minimap
+                    if self.versus.is_some() {
+                        self.minimap.render(d, &self.maze, std::slice::from_ref(self.player()), 0, &self.glitch);
+                    } else {
+                        self.minimap.render(d, &self.maze, &self.players, self.local_index, &self.glitch);
+                    }
+                    self.minimap.render_compass(d, self.glitch.compass_heading(self.player().angle));
+                    self.minimap.render_noises(d, &self.maze, &self.noises);
+                    self.minimap.render_decals(d, &self.maze, &self.decals.list);
+                    self.minimap.render_rooms(d, &self.maze, &self.challenges.waiting(), challenge::COLOR);
+                    if revealed {
+                        self.minimap.render_marks(d, &self.maze, &self.revealed_marks());
+                    }
+                }
+                self.ui.render_hud(d, &self.hud, self.player(), d.get_fps());
+                self.ui.render_crosshair(d, &self.config.crosshair, &self.crosshair);
+                // Render timer overlay
+                self.ui.render_timer(d, self.game_timer);
+                self.ui.render_toasts(d, d.get_screen_width());
+                // Render floating texts
+                self.render_floating_texts(d);
+
+                // Container search progress, or the hint to start one
+                let (screen_width, screen_height) = (d.get_screen_width(), d.get_screen_height());
+                if !self.hardcore_run {
+                    self.ui.render_stealth_eye(d, screen_width, screen_height, self.visibility, self.max_awareness());
+                    self.ui.render_status_cluster(d, &self.hud, &self.status_items());
+                    self.ui.render_hotbar(d, &self.hud, &self.player().inventory, &self.controls.label(Action::UseItem));
+                }
+                if let Some(chat) = &self.twitch {
+                    let top = MINIMAP_SIZE + MINIMAP_MARGIN * 3;
+                    self.ui.render_chat_vote(d, screen_width, top, chat);
+                }
+                // Whispers take the caption line over the hum, they're the more urgent of the two
+                let hum_caption = self.exit_hum.caption.filter(|_| self.exit_hum_enabled());
+                if let (Some((caption, _)), true) = (self.whispers.caption.or(hum_caption), self.config.captions) {
+                    self.ui.render_sound_caption(d, screen_width, screen_height, caption);
+                }
+                if let Some((hint, time_left)) = self.hints.current {
+                    self.ui.render_hint(d, screen_width, screen_height, hint, time_left);
+                }
+                if let Some((line, _)) = self.companion.as_ref().and_then(|c| c.caption.as_ref()) {
+                    self.ui.render_caption(d, screen_width, screen_height, "Companion", line);
+                }
+                if let (Some(barricade), true) = (&self.barricade, self.can_brace()) {
+                    self.ui.render_barricade(d, screen_width, screen_height, barricade.pressure, self.player().stamina, &self.controls);
+                } else if let Some(search) = &self.search {
+                    let label = self.containers[search.container].kind.display_name();
+                    self.ui.render_search(d, screen_width, screen_height, search, label, &self.controls);
+                } else if self.radio.in_reach(self.player().pos.x, self.player().pos.y) {
+                    let text = format!("{} - Pick up radio", self.controls.label(Action::Interact));
+                    self.ui.render_prompt(d, screen_width, screen_height, &text);
+                } else if let Some(index) = self.nearby_container() {
+                    let text = format!("{} - Search {}", self.controls.label(Action::Interact), self.containers[index].kind.display_name());
+                    self.ui.render_prompt(d, screen_width, screen_height, &text);
+                } else if let Some((_, _, open)) = self.door_in_front() {
+                    let interact = self.controls.label(Action::Interact);
+                    let text = if open {
+                        format!("{} - Close door", interact)
+                    } else {
+                        format!("{} - Open door    {} (hold) - Peek", interact, self.controls.label(Action::Peek))
+                    };
+                    self.ui.render_prompt(d, screen_width, screen_height, &text);
+                }
+
+                if let Some(versus) = &self.versus {
+                    let hunter = &self.players[versus.hunter_index];
+                    let panel_size = screen_height / 3;
+                    let panel = (screen_width - panel_size - MINIMAP_MARGIN, screen_height - panel_size - MINIMAP_MARGIN, panel_size);
+                    let escaper = self.hunter_sense(versus);
+                    self.minimap.render_hunter_view(d, &self.maze, hunter, escaper, &self.noises, panel);
+                    let (move_keys, turn_keys) = self.controls.hunter_labels();
+                    let hint = format!("ENTITY  {} move  {} turn", move_keys, turn_keys);
+                    d.draw_text(&hint, panel.0 + 4, panel.1 + panel.2 - 16, 12, Color::new(230, 200, 200, 255));
+                }
+
+                if self.heatmap.mode != HeatmapMode::Off {
+                    self.minimap.render_heatmap(d, &self.maze, &self.heatmap, &self.enemies, self.player(), (screen_width, screen_height));
+                }
+
+                if self.is_focus_paused() {
+                    let countdown = if self.focus_paused { None } else { Some(self.resume_timer) };
+                    self.ui.render_focus_pause(d, d.get_screen_width(), d.get_screen_height(), countdown);
+                }
+            }
+            State::Victory => {
+                self.render_3d_view();
+                self.post_process();
+                self.framebuffer.render(d, self.render_scale, (0, 0));
+                self.ui.render_victory(d, d.get_screen_width(), d.get_screen_height());
+                if self.versus.is_none() {
+                    self.ui.render_run_summary(d, d.get_screen_width(), d.get_screen_height(), self.run_time, self.run_seed, self.last_rank);
+                    if self.hardcore_run {
+                        self.ui.render_hardcore_badge(d, d.get_screen_width(), d.get_screen_height());
+                    }
+                    // Only runs that went through a challenge room have a record to show
+                    if self.stats.challenges_won + self.stats.challenges_failed > 0 {
+                        let (won, failed) = (self.stats.challenges_won, self.stats.challenges_failed);
+                        self.ui.render_challenge_record(d, d.get_screen_width(), d.get_screen_height(), won, failed);
+                    }
+                }
+                if let Some(winner) = self.versus.as_ref().and_then(|v| v.winner) {
+                    self.ui.render_versus_result(d, d.get_screen_width(), d.get_screen_height(), winner.display_name());
+                }
+            }
+            State::Escaping => {
+                self.render_3d_view();
+                self.post_process();
+                self.framebuffer.render(d, self.render_scale, (0, 0));
+            }
+            State::LevelComplete => {
+                self.render_3d_view();
+                self.post_process();
+                self.framebuffer.render(d, self.render_scale, (0, 0));
+                let level = (self.campaign.index + 1, self.campaign.level_count());
+                let carried = campaign::next_level_time(self.game_timer) - campaign::LEVEL_TIME;
+                let carried = (!self.campaign.is_last()).then_some((self.player().health, carried));
+                if let Some(tally) = &self.tally {
+                    self.ui.render_level_complete(d, d.get_screen_width(), d.get_screen_height(), level, tally, carried);
+                }
+            }
+            State::GameOver => {
+                self.render_3d_view();
+                self.post_process();
+                self.framebuffer.render(d, self.render_scale, (0, 0));
+                // The game-over screen waits for the sequence to play through
+                if self.death.as_ref().is_none_or(|scene| scene.finished()) {
+                    let cause = self.death.as_ref().map_or(DeathCause::Timer, |scene| scene.cause);
+                    let tips = if self.versus.is_none() { tips::tips(cause, &self.stats) } else { Vec::new() };
+                    self.ui.render_game_over(d, d.get_screen_width(), d.get_screen_height(), cause, &tips);
+                    if let Some(winner) = self.versus.as_ref().and_then(|v| v.winner) {
+                        self.ui.render_versus_result(d, d.get_screen_width(), d.get_screen_height(), winner.display_name());
+                    }
+                }
+            }
+            State::Benchmark => {
+                if self.benchmark.as_ref().is_some_and(|b| !b.is_finished()) {
+                    self.render_3d_view();
+                    self.post_process();
+                    self.framebuffer.render(d, self.render_scale, (0, 0));
+                }
+                if let Some(benchmark) = &self.benchmark {
+                    self.ui.render_benchmark(d, d.get_screen_width(), d.get_screen_height(), benchmark);
+                }
+            }
+            State::Leaderboard => {
+                let (status, global) = self.online_status();
+                self.ui.render_leaderboard(d, d.get_screen_width(), d.get_screen_height(), &self.leaderboard.entries, global, &status);
+            }
+            State::Splash => {
+                self.ui.render_splash(d, d.get_screen_width(), d.get_screen_height(), self.splash.opacity());
+            }
+            State::Demo => {
+                self.render_3d_view();
+                self.post_process();
+                self.framebuffer.render(d, self.render_scale, (0, 0));
+                self.ui.render_demo(d, d.get_screen_width(), d.get_screen_height());
+            }
+            State::Options => {
+                self.ui.render_options(d, d.get_screen_width(), d.get_screen_height(), &self.options, &self.config);
+            }
+            State::Credits => {
+                self.ui.render_credits(d, d.get_screen_width(), d.get_screen_height(), &self.credits);
+            }
+            State::Error => {
+                self.ui.render_error(
+                    d,
+                    d.get_screen_width(),
+                    d.get_screen_height(),
+                    &self.error_message,
+                    if self.error_retry { "ENTER - Retry    BACKSPACE - Back to menu" } else { "ENTER - Continue" },
+                );
+            }
+        }
+
+        self.console.render(d);
+    }
 
-                // Update camera rotation
-                self.camera.update(rl, &mut self.player, delta_time);
+    /// Post-processing chain applied to the framebuffer after the 3D view is drawn
+    fn post_process(&mut self) {
+        // View bob leaning into a strafe (turns the whole 3D view, so it goes first)
+        if self.state == State::Playing {
+            self.framebuffer.apply_roll(self.view_bob.roll());
+        }
 
-                // Handle player movement
-                if rl.is_key_down(KeyboardKey::KEY_W) {
-                    self.player.move_forward(&self.maze, delta_time);
-                }
-                if rl.is_key_down(KeyboardKey::KEY_S) {
-                    self.player.move_backward(&self.maze, delta_time);
-                }
-                if rl.is_key_down(KeyboardKey::KEY_A) {
-                    self.player.move_left(&self.maze, delta_time);
-                }
-                if rl.is_key_down(KeyboardKey::KEY_D) {
-                    self.player.move_right(&self.maze, delta_time);
-                }
+        // Blurred vision (status effect)
+        if self.state == State::Playing {
+            self.framebuffer.apply_blur(self.status.blur_radius());
+        }
 
-                // Keyboard rotation
-                if rl.is_key_down(KeyboardKey::KEY_LEFT) {
-                    self.player.rotate(-self.player.rot_speed * delta_time);
-                }
-                if rl.is_key_down(KeyboardKey::KEY_RIGHT) {
-                    self.player.rotate(self.player.rot_speed * delta_time);
-                }
+        // Anxiety vignette (gameplay only)
+        if self.state == State::Playing && self.effects.anxiety_intensity > 0.0 {
+            self.framebuffer.apply_vignette_effect(
+                self.effects.anxiety_intensity,
+                self.framebuffer.width,
+                self.framebuffer.height
+            );
+        }
 
-                // Update sprites
-                for sprite in &mut self.sprites {
-                    sprite.update(delta_time);
-                }
+        // Low-health red edge vignette, persistent while hurt
+        if self.state == State::Playing {
+            self.framebuffer.apply_damage_vignette(self.damage_vignette_strength());
+        }
 
-                // Update pills (glow animation)
-                for pill in &mut self.pills {
-                    pill.update(delta_time);
-                }
+        // Lights flickering out (chat scare)
+        let brightness = self.effects.flicker_brightness();
+        if self.state == State::Playing && brightness < 1.0 {
+            self.framebuffer.apply_brightness(brightness);
+        }
 
-                // Check for pill collection
-                for pill in &mut self.pills {
-                    if !pill.collected && pill.can_collect(self.player.pos.x, self.player.pos.y, 0.6) {
-                        pill.collected = true;
-                        
-                        // Apply pill effect
-                        match pill.pill_type {
-                            PillType::Red => {
-                                // Red pill: -15 HP and trigger anxiety
-                                self.player.take_damage(15);
-                                self.effects.trigger_anxiety_effect();
-                                
-                                // Create floating text
-                                self.floating_texts.push(FloatingText::new(
-                                    "-15 HP".to_string(),
-                                    pill.pos.x,
-                                    pill.pos.y,
-                                    Color::RED,
-                                ));
-                            }
-                            PillType::Blue => {
-                                // Blue pill: +10 HP but -20 seconds on timer
-                                self.player.heal(10);
-
-                                // Reduce timer by 20 seconds
-                                self.game_timer -= 20.0;
-                                self.game_timer = self.game_timer.max(0.0); // Don't go below 0
-
-                                // Create floating text
-                                self.floating_texts.push(FloatingText::new(
-                                    "+10 HP".to_string(),
-                                    pill.pos.x,
-                                    pill.pos.y,
-                                    Color::SKYBLUE,
-                                ));
-
-                                // Add second floating text for timer penalty
-                                self.floating_texts.push(FloatingText::new(
-                                    "-20s".to_string(),
-                                    pill.pos.x,
-                                    pill.pos.y + 0.3,
-                                    Color::ORANGE,
-                                ));
-                            }
-                        }
+        // Light pouring through the open exit (a real escape or the demo's)
+        if let Some(escape) = &self.escape {
+            self.framebuffer.apply_bloom(escape.bloom());
+        }
+
+        // The game-over sequence for how the run ended
+        if let (State::GameOver, Some(scene)) = (self.state, &self.death) {
+            match scene.cause {
+                DeathCause::Timer => self.framebuffer.apply_bands_off(death::LIGHT_BANDS, scene.bands_off()),
+                DeathCause::Caught => {
+                    let size = scene.lunge() * self.framebuffer.height as f32;
+                    if let (true, Some(tex)) = (size > 0.0, self.textures.get(self.texture_ids.enemy)) {
+                        let width = size * tex.width as f32 / tex.height as f32;
+                        let (center_x, center_y) = (self.framebuffer.width as f32 / 2.0, self.framebuffer.height as f32 / 2.0);
+                        self.framebuffer.draw_sprite(tex, (center_x - width / 2.0, center_y - size / 2.0, width, size), false, 1.0, 0.0);
                     }
+                    self.framebuffer.apply_film_grain(scene.static_level(), self.frame_counter);
                 }
+                DeathCause::Health => {
+                    let (roll, drop, brightness) = scene.collapse();
+                    self.framebuffer.apply_tilt(roll, drop);
+                    self.framebuffer.apply_brightness(brightness);
+                }
+            }
+        }
 
-                // Update floating texts
-                self.floating_texts.retain_mut(|text| {
-                    text.update(delta_time);
-                    text.lifetime > 0.0
-                });
+        // Peeking through a door crack hides everything but a narrow slit
+        if self.state == State::Playing && self.peek_origin.is_some() {
+            self.framebuffer.apply_peek_mask(PEEK_SLIT);
+        }
 
-                // Update effects
-                self.effects.update(delta_time);
+        // Film grain / VHS static, degrading with the player's mental state
+        self.frame_counter = self.frame_counter.wrapping_add(1);
+        if self.config.film_grain {
+            self.framebuffer.apply_film_grain(self.grain_intensity(), self.frame_counter);
+        }
 
-                // Check if player reached goal
-                if self.maze.is_goal(self.player.pos.x, self.player.pos.y, 1.0) {
-                    self.state = State::Victory;
-                }
+        // Color grade always runs last
+        self.framebuffer.apply_color_grade(&self.color_grade);
+    }
 
-                // Escape to menu
-                if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
-                    self.state = State::Menu;
-                }
-            }
-            State::Victory => {
-                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
-                    // Reset game and return to menu
-                    self.player = Player::new(self.maze.start_pos.0, self.maze.start_pos.1);
-                    self.state = State::Menu;
-                }
-            }
-            State::GameOver => {
-                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
-                    // Reset game and return to menu
-                    self.player = Player::new(self.maze.start_pos.0, self.maze.start_pos.1);
-                    self.game_timer = 180.0;
-                    self.state = State::Menu;
-                }
-            }
+    /// Damage vignette strength from missing health, pulsing like a heartbeat when critical
+    fn damage_vignette_strength(&self) -> f32 {
+        let health = self.player().health.max(0) as f32 / self.player().max_health.max(1) as f32;
+        let missing = 1.0 - health.min(1.0);
+        if missing <= 0.0 {
+            return 0.0;
+        }
+
+        let mut strength = missing * DAMAGE_VIGNETTE_MAX;
+        if health < DAMAGE_VIGNETTE_PULSE_BELOW {
+            let pulse = (self.frame_counter as f32 * 0.15).sin() * 0.5 + 0.5;
+            strength += pulse * 0.25;
         }
+        strength
     }
 
-    pub fn render(&mut self, d: &mut RaylibDrawHandle) {
-        match self.state {
-            State::Menu => {
-                self.ui.render_menu(d, d.get_screen_width(), d.get_screen_height());
-            }
-            State::Playing => {
-                self.render_3d_view();
-                
-                // Apply anxiety vignette effect if active
-                if self.effects.anxiety_intensity > 0.0 {
-                    self.framebuffer.apply_vignette_effect(
-                        self.effects.anxiety_intensity,
-                        self.framebuffer.width,
-                        self.framebuffer.height
-                    );
-                }
-                
-                self.framebuffer.render(d, 1);
-                
-                // Render screen shake overlay (subtle red tint during anxiety)
-                if self.effects.anxiety_intensity > 0.0 {
-                    let shake_alpha = (self.effects.anxiety_intensity * 30.0) as u8;
-                    d.draw_rectangle(
-                        0, 0,
-                        d.get_screen_width(),
-                        d.get_screen_height(),
-                        Color::new(80, 0, 0, shake_alpha),
-                    );
-                }
-                
-                self.minimap.render(d, &self.maze, &self.player);
-                self.ui.render_hud(d, &self.player, d.get_fps());
-                // Render timer overlay
-                self.ui.render_timer(d, self.game_timer);
-                // Render floating texts
-                self.render_floating_texts(d);
-            }
-            State::Victory => {
-                self.render_3d_view();
-                self.framebuffer.render(d, 1);
-                self.ui.render_victory(d, d.get_screen_width(), d.get_screen_height());
-            }
-            State::GameOver => {
-                self.render_3d_view();
-                self.framebuffer.render(d, 1);
-                self.ui.render_game_over(d, d.get_screen_width(), d.get_screen_height());
+    /// Grain intensity from anxiety and idle time, limited by the accessibility cap
+    fn grain_intensity(&self) -> f32 {
+        let mut intensity = GRAIN_BASE;
+        if self.state == State::Playing {
+            intensity += self.effects.anxiety_intensity * GRAIN_ANXIETY;
+            if let Some(threshold) = self.idle_penalty().threshold {
+                intensity += (self.idle_timer / threshold).min(1.0) * GRAIN_IDLE;
             }
+            intensity += self.glitch.level() * GRAIN_GLITCH;
+        }
+        intensity.min(self.config.grain_cap)
+    }
+
+    /// Change a film grain setting and persist it
+    fn change_grain_settings(&mut self, change: impl FnOnce(&mut Config)) {
+        change(&mut self.config);
+        info!(
+            "Film grain {} (cap {:.2})",
+            if self.config.film_grain { "on" } else { "off" },
+            self.config.grain_cap
+        );
+
+        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Switch the color grading theme
+    fn set_theme(&mut self, theme: Theme) {
+        self.config.theme = theme;
+        self.color_grade = theme.grade();
+        info!("Theme set to {}", theme.display_name());
+
+        if let Err(e) = self.config.save(&self.profile.file(config::CONFIG_PATH)) {
+            warn!("{}", e);
         }
     }
 
+    /// Render the 3D view and post-processing into the framebuffer without presenting it
+    pub fn render_offscreen(&mut self) {
+        self.render_3d_view();
+        self.post_process();
+    }
+
     fn render_3d_view(&mut self) {
         // Get all textures we'll need
-        let wall_texture = self.textures.get_texture("wall").unwrap();
-        let wall_exit_texture = self.textures.get_texture("wall_exit").unwrap();
-        let floor_texture = self.textures.get_texture("floor").unwrap();
-        let ceiling_texture = self.textures.get_texture("ceiling").unwrap();
+        let wall_texture = self.textures.get(self.texture_ids.wall).unwrap();
+        let wall_exit_texture = self.textures.get(self.texture_ids.wall_exit).unwrap();
+        let floor_texture = self.textures.get(self.texture_ids.floor).unwrap();
+        let ceiling_texture = self.textures.get(self.texture_ids.ceiling).unwrap();
+        let door_texture = self.textures.get(self.texture_ids.door).unwrap_or(wall_texture);
 
-        // Cast rays
-        let ray_hits = self.raycaster.cast_rays(&self.player, &self.maze);
+        // Cast rays (each wall column's depth goes into the depth buffer for the sprites)
+        let ray_hits = self.raycaster.cast_rays(self.player(), &self.maze, &self.doors);
+        self.framebuffer.clear_depth();
         let num_rays = ray_hits.len();
         let screen_width = self.framebuffer.width;
 
+        // Lights as they shine right now (only the emergency beacons in a blackout)
+        let lights = self.blackout.scene_lights(&self.maze.lights);
+        let ambient = self.blackout.ambient();
+        self.framebuffer.ambient = ambient;
+
+        // Water tiles reflect the walls, and the Poolrooms' tiled floor is always a little wet
+        let floor_reflection = if self.config.theme == Theme::Poolrooms { POOLROOMS_REFLECTION } else { 0.0 };
+        let reflective = floor_reflection > 0.0 || self.maze.map.iter().flatten().any(|&tile| tile == '~');
+        let mut wetness = Vec::with_capacity(self.framebuffer.height);
+        let high_contrast = self.config.high_contrast;
+
+        // Looking up or down moves the horizon the walls are centered on
+        let horizon = self.framebuffer.height as f32 * (0.5 + self.player().pitch + self.view_bob.horizon());
+        self.framebuffer.horizon = horizon;
+
         // Render each vertical slice with scaling
         for (ray_index, hit) in ray_hits.iter().enumerate() {
             let screen_height = self.framebuffer.height as f32;
-            let wall_height = screen_height / hit.distance.max(0.1);
+            let wall_height = screen_height / guard::depth(hit.distance, self.raycaster.max_depth, "wall column");
 
-            let draw_start = ((screen_height / 2.0) - (wall_height / 2.0)) as usize;
-            let draw_end = ((screen_height / 2.0) + (wall_height / 2.0)) as usize;
+            let draw_start = (horizon - (wall_height / 2.0)) as usize;
+            let draw_end = (horizon + (wall_height / 2.0)) as usize;
 
             // Calculate shading once per ray
             let orientation_shade = self.effects.calculate_shading(hit.hit_vertical);
             let distance_shade = self.effects.calculate_distance_shading(hit.distance, self.raycaster.max_depth);
-            let total_shade = orientation_shade * distance_shade;
+            // High contrast keeps the exit at full brightness however far it is
+            let exit_lit = high_contrast && hit.wall_type == 'E';
+            let total_shade = if exit_lit { 1.0 } else { orientation_shade * distance_shade };
 
             // Select wall texture based on wall type
-            let current_wall_texture = if hit.wall_type == 'E' {
-                wall_exit_texture
-            } else {
-                wall_texture
+            let current_wall_texture = match hit.wall_type {
+                'E' => wall_exit_texture,
+                'D' => door_texture,
+                _ => wall_texture,
+            };
+
+            // Sample texture with variable size support (a door being forced rattles sideways,
+            // a door sliding open or shut moves its panel, the exit slides aside at the end of the run)
+            let shake = match &self.barricade {
+                Some(barricade) if barricade.door == (hit.map_x, hit.map_y) => barricade.shake(),
+                _ => 0.0,
+            };
+            let slide = match &self.escape {
+                Some(escape) if escape.door == (hit.map_x, hit.map_y) => escape.door_open(),
+                _ => 0.0,
             };
+            let doorway = hit.wall_x.rem_euclid(1.0) < slide;
+            let shake = shake - slide - self.doors.open_amount(hit.map_x, hit.map_y);
+            let tex_x = ((hit.wall_x + shake).rem_euclid(1.0) * current_wall_texture.width as f32) as usize;
 
-            // Sample texture with variable size support
-            let tex_x = (hit.wall_x * current_wall_texture.width as f32) as usize;
+            // Colored lights tint the wall where the ray hit it
+            let wall_tint = light::tint_at(&lights, hit.hit_x, hit.hit_y, ambient);
 
             // Calculate screen x range for this ray (scale rays to screen width)
             let x_start = (ray_index * screen_width) / num_rays;
             let x_end = ((ray_index + 1) * screen_width) / num_rays;
 
+            // The flashlight brightens walls near the middle of the view, the closer the more
+            let beam = self.effects.flashlight_beam((x_start + x_end) / 2, screen_width, hit.distance);
+            let wall_tint = if beam > 0.0 {
+                let tint = wall_tint.unwrap_or([1.0; 3]);
+                Some([0, 1, 2].map(|channel| tint[channel] + beam * FLASHLIGHT_COLOR[channel]))
+            } else {
+                wall_tint
+            };
+
             // Draw this ray across multiple screen columns
             for x in x_start..x_end {
+                self.framebuffer.set_depth(x, hit.distance);
+
                 // Draw textured ceiling
                 if draw_start > 0 {
                     self.framebuffer.draw_textured_ceiling_span(
@@ -377,8 +3772,8 @@ impl<'a> GameState<'a> {
                         &ceiling_texture.pixels,
                         ceiling_texture.width,
                         ceiling_texture.height,
-                        self.player.pos.x,
-                        self.player.pos.y,
+                        self.player().pos.x,
+                        self.player().pos.y,
                     );
 
                     // Fill remaining ceiling pixels with solid texture color
@@ -390,23 +3785,38 @@ impl<'a> GameState<'a> {
                             &ceiling_texture.pixels,
                             ceiling_texture.width,
                             ceiling_texture.height,
-                            self.player.pos.x + y as f32 * 0.1,
-                            self.player.pos.y + y as f32 * 0.1,
+                            self.player().pos.x + y as f32 * 0.1,
+                            self.player().pos.y + y as f32 * 0.1,
                         );
                     }
+                    if ambient < 1.0 {
+                        for y in 0..draw_start.min(self.framebuffer.height) {
+                            self.framebuffer.tint_pixel(x, y, [ambient; 3]);
+                        }
+                    }
                 }
 
-                // Draw wall with texture (variable size support)
-                self.framebuffer.draw_textured_line(
-                    x,
-                    draw_start,
-                    draw_end,
-                    &current_wall_texture.pixels,
-                    current_wall_texture.width,
-                    current_wall_texture.height,
-                    tex_x,
-                    total_shade,
-                );
+                // Draw wall with texture (variable size support), or the light behind an open exit
+                if doorway {
+                    self.framebuffer.draw_vertical_line(x, draw_start, draw_end, Color::WHITE);
+                } else {
+                    self.framebuffer.draw_textured_line(
+                        x,
+                        draw_start,
+                        draw_end,
+                        &current_wall_texture.pixels,
+                        current_wall_texture.width,
+                        current_wall_texture.height,
+                        tex_x,
+                        total_shade,
+                    );
+                    self.grime.draw_column(&mut self.framebuffer, x, hit, horizon - wall_height / 2.0, wall_height, total_shade);
+                }
+                if let (Some(tint), false) = (wall_tint, doorway) {
+                    for y in draw_start..draw_end.min(self.framebuffer.height) {
+                        self.framebuffer.tint_pixel(x, y, tint);
+                    }
+                }
 
                 // Draw textured floor
                 if draw_end < self.framebuffer.height {
@@ -418,77 +3828,175 @@ impl<'a> GameState<'a> {
                             &floor_texture.pixels,
                             floor_texture.width,
                             floor_texture.height,
-                            self.player.pos.x,
-                            self.player.pos.y,
-                            self.player.angle,
+                            self.player().pos.x,
+                            self.player().pos.y,
+                            self.player().angle,
                             hit.distance,
                             self.raycaster.max_depth,
                         );
                     }
+
+                    // Colored lights tint the floor around them, wet floor mirrors the wall above
+                    if !lights.is_empty() || ambient < 1.0 || reflective {
+                        let (player_x, player_y, angle) = (self.player().pos.x, self.player().pos.y, self.player().angle);
+                        wetness.clear();
+                        for y in draw_end..self.framebuffer.height {
+                            let (floor_x, floor_y) = self.framebuffer.floor_position(x, y, player_x, player_y, angle);
+                            if let Some(tint) = light::tint_at(&lights, floor_x, floor_y, ambient) {
+                                self.framebuffer.tint_pixel(x, y, tint);
+                            }
+                            wetness.push(if self.maze.is_water(floor_x, floor_y) { WATER_REFLECTION } else { floor_reflection });
+                        }
+                        if reflective {
+                            self.framebuffer.reflect_wall(x, draw_start, draw_end, &wetness, self.run_time);
+                        }
+                    }
+                }
+
+                // High contrast: outline the foot of the wall, make the exit glow
+                if high_contrast {
+                    for y in draw_end..(draw_end + contrast::EDGE_WIDTH).min(self.framebuffer.height) {
+                        self.framebuffer.set_pixel(x, y, contrast::EDGE_COLOR);
+                    }
+                    if exit_lit {
+                        let reach = (wall_height * contrast::EXIT_GLOW_REACH) as usize;
+                        let span = (draw_start, draw_end + contrast::EDGE_WIDTH);
+                        self.framebuffer.glow_column(x, span, reach, contrast::EXIT_GLOW_COLOR, contrast::EXIT_GLOW_STRENGTH);
+                    }
+                }
+
+                // See-through tiles in front of the wall (glass, smoke), blended back to front
+                for layer in hit.layers.iter().rev() {
+                    let layer_height = screen_height / guard::depth(layer.distance, self.raycaster.max_depth, "layer column");
+                    let (color, alpha) = layer.kind.shade(layer.wall_x);
+                    let shade = self.effects.calculate_distance_shading(layer.distance, self.raycaster.max_depth) * ambient;
+                    let color = Color::new(
+                        (color.r as f32 * shade) as u8,
+                        (color.g as f32 * shade) as u8,
+                        (color.b as f32 * shade) as u8,
+                        255,
+                    );
+                    let top = (horizon - layer_height / 2.0).max(0.0) as usize;
+                    let bottom = ((horizon + layer_height / 2.0).max(0.0) as usize).min(self.framebuffer.height);
+                    for y in top..bottom {
+                        self.framebuffer.blend_pixel(x, y, color, alpha);
+                    }
                 }
             }
         }
         
+        // Decals go under everything standing in the room
+        self.render_decals_to_framebuffer();
+
         // Render pills into the framebuffer
-        self.render_pills_to_framebuffer(&ray_hits);
-    }
+        self.render_pills_to_framebuffer();
+        self.render_props_to_framebuffer();
+        self.render_containers_to_framebuffer();
+        self.render_radio_to_framebuffer();
+        self.render_keys_to_framebuffer();
+        self.render_batteries_to_framebuffer();
+        self.render_clocks_to_framebuffer();
+        self.render_items_to_framebuffer();
+        self.render_orbs_to_framebuffer();
+        self.render_players_to_framebuffer();
+        self.render_enemies_to_framebuffer();
+        self.render_glimpse_to_framebuffer();
 
-    // Add these methods INSIDE the impl<'a> GameState<'a> { } block, BEFORE the final closing brace
+        // Heat haze warps whatever is behind it
+        self.render_haze_to_framebuffer();
+    }
 
-    fn render_pills_to_framebuffer(&mut self, ray_hits: &[crate::caster::RayHit]) {
+    /// Project a world position to (screen x, view depth) for billboard sprites.
+    /// None if the point is behind the player
+    fn project_billboard(&self, x: f32, y: f32) -> Option<(f32, f32)> {
         let screen_width = self.framebuffer.width as f32;
-        let screen_height = self.framebuffer.height as f32;
 
-        // Get pill textures
-        let red_pill_texture = self.textures.get_texture("red_pill");
-        let blue_pill_texture = self.textures.get_texture("blue_pill");
+        // Position relative to player
+        let dx = x - self.player().pos.x;
+        let dy = y - self.player().pos.y;
 
-        for pill in &self.pills {
-            if pill.collected {
-                continue;
-            }
+        // Rotate to player's view space (correct rotation for view transformation)
+        let cos_angle = self.player().angle.cos();
+        let sin_angle = self.player().angle.sin();
+        let transformed_x = dy * cos_angle - dx * sin_angle;
+        let transformed_y = dx * cos_angle + dy * sin_angle;
 
-            // Calculate pill position relative to player
-            let dx = pill.pos.x - self.player.pos.x;
-            let dy = pill.pos.y - self.player.pos.y;
-            let distance = (dx * dx + dy * dy).sqrt();
+        // Skip if behind player
+        if !guard::in_front(transformed_y, "billboard depth") {
+            return None;
+        }
 
-            // Rotate to player's view space (correct rotation for view transformation)
-            let cos_angle = self.player.angle.cos();
-            let sin_angle = self.player.angle.sin();
-            let transformed_x = dy * cos_angle - dx * sin_angle;
-            let transformed_y = dx * cos_angle + dy * sin_angle;
+        // Project to screen space
+        let fov = self.raycaster.fov;
+        let screen_x = (screen_width / 2.0) * (1.0 + transformed_x / (transformed_y * (fov / 2.0).tan()));
 
-            // Skip if behind player
-            if transformed_y <= 0.1 {
+        // Walls in front are sorted out per column against the depth buffer when drawing
+        Some((screen_x, transformed_y))
+    }
+
+    /// Distort the screen region over each haze tile in view, sized like a billboard of its radius
+    fn render_haze_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        for haze in &self.maze.hazes {
+            let Some((screen_x, depth)) = self.project_billboard(haze.x, haze.y) else {
+                continue;
+            };
+            if self.framebuffer.occluded(screen_x as i32, depth - FLAT_DEPTH_SLACK) {
                 continue;
             }
+            let tile_height = screen_height / depth;
+            let (width, height) = (tile_height * haze.radius * 2.0, tile_height * 1.2);
+            let rect = (screen_x - width / 2.0, self.framebuffer.horizon - height / 2.0, width, height);
+            self.framebuffer.apply_distortion(rect, haze.amplitude(height, self.run_time), haze.drift(self.run_time));
+        }
+    }
 
-            // Project to screen space
-            let fov = std::f32::consts::PI / 3.0;
-            let screen_x = (screen_width / 2.0) * (1.0 + transformed_x / (transformed_y * (fov / 2.0).tan()));
+    /// Floor decals lie flat where the floor meets the view at their depth, wall decals
+    /// stand upright at eye level
+    fn render_decals_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        for decal in &self.decals.list {
+            let Some((screen_x, depth)) = self.project_billboard(decal.x, decal.y) else {
+                continue;
+            };
+            if self.framebuffer.occluded(screen_x as i32, depth - FLAT_DEPTH_SLACK) {
+                continue;
+            }
+            let wall_height = screen_height / depth;
+            let width = wall_height * decal.kind.size();
+            let (center_y, height) = match decal.surface {
+                // Seen at a low angle, so squashed more the further away it is
+                Surface::Floor | Surface::Water => (self.framebuffer.horizon + wall_height / 2.0, width * (0.6 / depth).min(0.6)),
+                Surface::Wall => (self.framebuffer.horizon, width),
+            };
+            let kind = decal.kind;
+            self.framebuffer.blend_shape(
+                (screen_x - width / 2.0, center_y - height / 2.0, width, height),
+                kind.color(),
+                decal.alpha() * 0.85,
+                |u, v| kind.covers(u, v),
+            );
+        }
+    }
 
-            // Calculate which ray column this pill is in
-            let ray_index = ((screen_x / screen_width) * ray_hits.len() as f32) as usize;
+    fn render_pills_to_framebuffer(&mut self) {
+        let screen_width = self.framebuffer.width as f32;
+        let screen_height = self.framebuffer.height as f32;
 
-            // Improved occlusion check - check multiple rays around the pill
-            let mut is_occluded = false;
-            let check_radius = 2; // Check 2 rays on each side
-            for offset in -(check_radius as i32)..=(check_radius as i32) {
-                let check_index = (ray_index as i32 + offset).max(0).min(ray_hits.len() as i32 - 1) as usize;
-                if check_index < ray_hits.len() {
-                    // If there's a wall closer than the pill, it's occluded
-                    if ray_hits[check_index].distance < distance - 0.3 {
-                        is_occluded = true;
-                        break;
-                    }
-                }
-            }
+        // Get pill textures
+        let red_pill_texture = self.textures.get(self.texture_ids.red_pill);
+        let blue_pill_texture = self.textures.get(self.texture_ids.blue_pill);
 
-            if is_occluded {
+        for pill in &self.pills {
+            if pill.collected {
                 continue;
             }
 
+            // Project through the shared billboard pipeline
+            let Some((screen_x, transformed_y)) = self.project_billboard(pill.pos.x, pill.pos.y) else {
+                continue;
+            };
+
             let sprite_size = (screen_height / transformed_y) * 0.15; // Smaller size
 
             // Skip if off screen
@@ -497,14 +4005,22 @@ impl<'a> GameState<'a> {
             }
 
             // Position pill on the floor (lower on screen)
-            let screen_y = screen_height * 0.65;
+            let screen_y = screen_height * 0.15 + self.framebuffer.horizon;
             
-            // Select texture based on pill type
-            let texture = match pill.pill_type {
-                crate::pill::PillType::Red => red_pill_texture,
-                crate::pill::PillType::Blue => blue_pill_texture,
+            // Select texture based on pill type. The other colors have no texture of their
+            // own and recolor the blue one
+            let (texture, tint) = match pill.pill_type {
+                PillType::Red => (red_pill_texture, None),
+                PillType::Blue => (blue_pill_texture, None),
+                _ => (blue_pill_texture, Some(pill.get_color())),
             };
             
+            // High contrast: a wide halo so pills stand out from across the room
+            if self.config.high_contrast {
+                let radius = sprite_size * contrast::PILL_GLOW_RADIUS;
+                self.framebuffer.glow((screen_x, screen_y), radius, pill.get_glow_color(), contrast::PILL_GLOW_STRENGTH, transformed_y);
+            }
+
             if let Some(tex) = texture {
                 // Draw textured sprite - use fixed aspect ratio based on texture
                 let aspect_ratio = tex.width as f32 / tex.height as f32;
@@ -573,16 +4089,379 @@ impl<'a> GameState<'a> {
                             continue;
                         }
 
+                        if let Some(tint) = tint {
+                            let shade = glitched_color.r.max(glitched_color.g).max(glitched_color.b) as f32 / 255.0;
+                            glitched_color = Color::new(
+                                (tint.r as f32 * shade) as u8,
+                                (tint.g as f32 * shade) as u8,
+                                (tint.b as f32 * shade) as u8,
+                                glitched_color.a,
+                            );
+                        }
+
                         // Color distortion effect
                         if glitch_intensity > 0.7 {
                             glitched_color.r = glitched_color.r.saturating_add((glitch_intensity * 30.0) as u8);
                             glitched_color.g = glitched_color.g.saturating_sub((glitch_intensity * 20.0) as u8);
                         }
 
-                        // Draw to framebuffer with horizontal glitch offset
+                        // Draw to framebuffer with horizontal glitch offset, behind any wall in that column
                         let final_x = (x as i32 + row_glitch).max(0).min(self.framebuffer.width as i32 - 1) as usize;
-                        self.framebuffer.set_pixel(final_x, y, glitched_color);
+                        if !self.framebuffer.occluded(final_x as i32, transformed_y) {
+                            self.framebuffer.set_pixel(final_x, y, glitched_color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_props_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        let Some(tex) = self.textures.get(self.texture_ids.prop) else {
+            return;
+        };
+
+        // Far to near so closer props draw on top
+        let mut visible: Vec<(usize, f32, f32)> = self
+            .props
+            .iter()
+            .enumerate()
+            .filter_map(|(i, prop)| {
+                self.project_billboard(prop.pos.x, prop.pos.y)
+                    .map(|(screen_x, depth)| (i, screen_x, depth))
+            })
+            .collect();
+        visible.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        for (index, screen_x, depth) in visible {
+            // Resting on the floor like the enemies
+            let wall_height = screen_height / depth;
+            let sprite_height = wall_height * PROP_SPRITE_HEIGHT;
+            let sprite_width = sprite_height * tex.width as f32 / tex.height as f32;
+            let sprite_top = self.framebuffer.horizon + wall_height / 2.0 - sprite_height;
+            self.framebuffer.draw_sprite(
+                tex,
+                (screen_x - sprite_width / 2.0, sprite_top, sprite_width, sprite_height),
+                self.props[index].is_flashing(),
+                1.0,
+                depth,
+            );
+        }
+    }
+
+    fn render_containers_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+
+        // Far to near so closer containers draw on top
+        let mut visible: Vec<(usize, f32, f32)> = self
+            .containers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, container)| {
+                self.project_billboard(container.pos.x, container.pos.y)
+                    .map(|(screen_x, depth)| (i, screen_x, depth))
+            })
+            .collect();
+        visible.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        for (index, screen_x, depth) in visible {
+            let container = &self.containers[index];
+            let (texture_id, height) = match container.kind {
+                ContainerKind::Desk => (self.texture_ids.desk, DESK_SPRITE_HEIGHT),
+                ContainerKind::Cabinet => (self.texture_ids.cabinet, CABINET_SPRITE_HEIGHT),
+            };
+            let Some(tex) = self.textures.get(texture_id) else {
+                continue;
+            };
+
+            // Standing on the floor, searched containers are drawn darker
+            let wall_height = screen_height / depth;
+            let sprite_height = wall_height * height;
+            let sprite_width = sprite_height * tex.width as f32 / tex.height as f32;
+            let sprite_top = self.framebuffer.horizon + wall_height / 2.0 - sprite_height;
+            let brightness = if container.searched { 0.55 } else { 1.0 };
+            self.framebuffer.draw_sprite(
+                tex,
+                (screen_x - sprite_width / 2.0, sprite_top, sprite_width, sprite_height),
+                false,
+                brightness,
+                depth,
+            );
+        }
+    }
+
+    fn render_radio_to_framebuffer(&mut self) {
+        if !self.radio.placed {
+            return;
+        }
+        let screen_height = self.framebuffer.height as f32;
+        let Some(tex) = self.textures.get(self.texture_ids.radio) else {
+            return;
+        };
+        let Some((screen_x, depth)) = self.project_billboard(self.radio.pos.x, self.radio.pos.y) else {
+            return;
+        };
+
+        // Lying on the floor, dimmed once the battery is dead
+        let wall_height = screen_height / depth;
+        let sprite_height = wall_height * RADIO_SPRITE_HEIGHT;
+        let sprite_width = sprite_height * tex.width as f32 / tex.height as f32;
+        let sprite_top = self.framebuffer.horizon + wall_height / 2.0 - sprite_height;
+        let brightness = if self.radio.is_playing() { 1.0 } else { 0.55 };
+        self.framebuffer.draw_sprite(
+            tex,
+            (screen_x - sprite_width / 2.0, sprite_top, sprite_width, sprite_height),
+            false,
+            brightness,
+            depth,
+        );
+    }
+
+    /// Keys floating above the floor, turning (the sprite narrows and widens) with a glow
+    fn render_keys_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        let keys: Vec<(f32, f32)> = self.keys.remaining().collect();
+        for (x, y) in keys {
+            let Some((screen_x, depth)) = self.project_billboard(x, y) else {
+                continue;
+            };
+            if self.framebuffer.occluded(screen_x as i32, depth) {
+                continue;
+            }
+            let wall_height = screen_height / depth;
+            let height = wall_height * KEY_SPRITE_HEIGHT;
+            let width = height * 2.0 * (self.keys.spin * KEY_SPIN_RATE).cos().abs().max(0.15);
+            let center_y = self.framebuffer.horizon + wall_height * (0.2 + 0.03 * (self.keys.spin * 2.0).sin());
+            self.framebuffer.glow((screen_x, center_y), height * 1.5, keys::COLOR, 0.35, depth);
+            self.framebuffer.blend_shape(
+                (screen_x - width / 2.0, center_y - height / 2.0, width, height),
+                keys::COLOR,
+                1.0,
+                keys::covers,
+            );
+        }
+    }
+
+    /// Battery pickups standing on the floor, glowing faintly
+    fn render_batteries_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        let batteries = self.flashlight.batteries().to_vec();
+        for (x, y) in batteries {
+            let Some((screen_x, depth)) = self.project_billboard(x, y) else {
+                continue;
+            };
+            if self.framebuffer.occluded(screen_x as i32, depth) {
+                continue;
+            }
+            let wall_height = screen_height / depth;
+            let height = wall_height * BATTERY_SPRITE_HEIGHT;
+            let center_y = self.framebuffer.horizon + wall_height * 0.5 - height / 2.0;
+            self.framebuffer.glow((screen_x, center_y), height * 1.2, flashlight::COLOR, 0.25, depth);
+            self.framebuffer.blend_shape(
+                (screen_x - height / 4.0, center_y - height / 2.0, height / 2.0, height),
+                flashlight::COLOR,
+                1.0,
+                flashlight::covers,
+            );
+        }
+    }
+
+    /// Clock pickups standing on the floor, turning slowly
+    fn render_clocks_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        let clocks = self.clocks.remaining().to_vec();
+        for (x, y) in clocks {
+            let Some((screen_x, depth)) = self.project_billboard(x, y) else {
+                continue;
+            };
+            if self.framebuffer.occluded(screen_x as i32, depth) {
+                continue;
+            }
+            let wall_height = screen_height / depth;
+            let height = wall_height * CLOCK_SPRITE_HEIGHT;
+            let width = height * (self.clocks.time * CLOCK_TURN_RATE).cos().abs().max(0.15);
+            let center_y = self.framebuffer.horizon + wall_height * 0.5 - height / 2.0;
+            self.framebuffer.glow((screen_x, center_y), height * 1.2, clock::COLOR, 0.3, depth);
+            self.framebuffer.blend_shape((screen_x - width / 2.0, center_y - height / 2.0, width, height), clock::COLOR, 1.0, clock::covers);
+        }
+    }
+
+    /// Inventory items lying on the floor
+    fn render_items_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        let items = self.items.clone();
+        for (x, y, item) in items {
+            let Some((screen_x, depth)) = self.project_billboard(x, y) else {
+                continue;
+            };
+            if self.framebuffer.occluded(screen_x as i32, depth) {
+                continue;
+            }
+            let wall_height = screen_height / depth;
+            let height = wall_height * ITEM_SPRITE_HEIGHT;
+            let center_y = self.framebuffer.horizon + wall_height * 0.5 - height / 2.0;
+            self.framebuffer.glow((screen_x, center_y), height * 1.2, item.color(), 0.2, depth);
+            self.framebuffer.blend_shape((screen_x - height, center_y - height / 2.0, height * 2.0, height), item.color(), 1.0, |u, v| {
+                inventory::covers(item, u, v)
+            });
+        }
+    }
+
+    /// Orbs of a running challenge, bobbing in the air
+    fn render_orbs_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        for (index, (x, y)) in self.challenges.orbs().into_iter().enumerate() {
+            let Some((screen_x, depth)) = self.project_billboard(x, y) else {
+                continue;
+            };
+            if self.framebuffer.occluded(screen_x as i32, depth) {
+                continue;
+            }
+            let wall_height = screen_height / depth;
+            let size = wall_height * ORB_SPRITE_HEIGHT;
+            let bob = (self.challenges.time * 3.0 + index as f32).sin() * 0.05;
+            let center_y = self.framebuffer.horizon + wall_height * (0.1 + bob);
+            self.framebuffer.glow((screen_x, center_y), size * 1.5, challenge::COLOR, 0.35, depth);
+            self.framebuffer.blend_shape(
+                (screen_x - size / 2.0, center_y - size / 2.0, size, size),
+                challenge::COLOR,
+                0.9,
+                challenge::covers,
+            );
+        }
+    }
+
+    /// The chat-summoned entity, a dim silhouette that is gone before it can be reached
+    fn render_glimpse_to_framebuffer(&mut self) {
+        let Some((x, y, _)) = self.glimpse else {
+            return;
+        };
+        let screen_height = self.framebuffer.height as f32;
+        let Some(tex) = self.textures.get(self.texture_ids.enemy) else {
+            return;
+        };
+        let Some((screen_x, depth)) = self.project_billboard(x, y) else {
+            return;
+        };
+
+        let wall_height = screen_height / depth;
+        let sprite_height = wall_height * ENEMY_SPRITE_HEIGHT;
+        let sprite_width = sprite_height * tex.width as f32 / tex.height as f32;
+        let sprite_top = self.framebuffer.horizon + wall_height / 2.0 - sprite_height;
+        self.framebuffer.draw_sprite(
+            tex,
+            (screen_x - sprite_width / 2.0, sprite_top, sprite_width, sprite_height),
+            false,
+            0.35,
+            depth,
+        );
+    }
+
+    /// Other player entities (the companion, the versus hunter) as standing billboards
+    fn render_players_to_framebuffer(&mut self) {
+        let screen_height = self.framebuffer.height as f32;
+        let hunter_index = self.versus.as_ref().map(|versus| versus.hunter_index);
+
+        // Far to near so closer players draw on top
+        let mut visible: Vec<(usize, f32, f32)> = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.local_index)
+            .filter_map(|(i, other)| {
+                self.project_billboard(other.pos.x, other.pos.y)
+                    .map(|(screen_x, depth)| (i, screen_x, depth))
+            })
+            .collect();
+        visible.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        for (index, screen_x, depth) in visible {
+            // The hunter looks like the other entities
+            let texture_id = if Some(index) == hunter_index { self.texture_ids.enemy } else { self.texture_ids.companion };
+            let Some(tex) = self.textures.get(texture_id) else {
+                continue;
+            };
+            let wall_height = screen_height / depth;
+            let sprite_height = wall_height * COMPANION_SPRITE_HEIGHT;
+            let sprite_width = sprite_height * tex.width as f32 / tex.height as f32;
+            let sprite_top = self.framebuffer.horizon + wall_height / 2.0 - sprite_height;
+            self.framebuffer.draw_sprite(
+                tex,
+                (screen_x - sprite_width / 2.0, sprite_top, sprite_width, sprite_height),
+                false,
+                1.0,
+                depth,
+            );
+        }
+    }
+
+    fn render_enemies_to_framebuffer(&mut self) {
+        let screen_width = self.framebuffer.width as f32;
+        let screen_height = self.framebuffer.height as f32;
+        let Some(tex) = self.textures.get(self.texture_ids.enemy) else {
+            return;
+        };
+
+        // Far to near so closer enemies draw on top
+        let mut visible: Vec<(usize, f32, f32)> = self
+            .enemies
+            .iter()
+            .enumerate()
+            .filter_map(|(i, enemy)| {
+                self.project_billboard(enemy.pos.x, enemy.pos.y)
+                    .map(|(screen_x, depth)| (i, screen_x, depth))
+            })
+            .collect();
+        visible.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        for (index, screen_x, depth) in visible {
+            let enemy = &self.enemies[index];
+
+            // Standing on the floor: feet at the bottom of a wall slice at this depth
+            // Death frames squash the sprite towards the floor
+            let wall_height = screen_height / depth;
+            let full_height = wall_height * ENEMY_SPRITE_HEIGHT;
+            let scale = enemy.sprite_scale();
+            let sprite_height = full_height * scale;
+            let frame_width = tex.width as f32 / enemy::SHEET.columns as f32;
+            let sprite_width = full_height * frame_width / tex.height as f32 * (1.0 + (1.0 - scale) * 0.6);
+            let sprite_left = screen_x - sprite_width / 2.0;
+            let sprite_top = self.framebuffer.horizon + wall_height / 2.0 - sprite_height;
+
+            if sprite_left + sprite_width < 0.0 || sprite_left > screen_width {
+                continue;
+            }
+
+            // Hit flash turns the whole silhouette white. The sprite faces right, so it is
+            // mirrored while the enemy looks towards the left of the screen
+            let rect = (sprite_left, sprite_top, sprite_width, sprite_height);
+            let frame = enemy.animation.frame((enemy.facing - self.player().angle).sin() < 0.0);
+            self.framebuffer.draw_sprite_frame(tex, frame, rect, enemy.is_flashing(), enemy.brightness(), depth);
+
+            // World-space health bar above the head, fading after a while without damage
+            let alpha = enemy.health_bar_alpha();
+            if alpha <= 0.0 {
+                continue;
+            }
+            let bar_width = (sprite_height * 0.5).max(8.0);
+            let bar_height = (sprite_height * 0.04).max(2.0);
+            let bar_left = screen_x - bar_width / 2.0;
+            let bar_top = sprite_top - bar_height * 2.5;
+            let filled = bar_width * enemy.health as f32 / enemy.max_health.max(1) as f32;
+
+            for by in 0..(bar_height as usize) {
+                let y = bar_top as i32 + by as i32;
+                if y < 0 {
+                    continue;
+                }
+                for bx in 0..(bar_width as usize) {
+                    let x = bar_left as i32 + bx as i32;
+                    if x < 0 {
+                        continue;
                     }
+                    let color = if (bx as f32) < filled { Color::new(200, 30, 30, 255) } else { Color::new(40, 10, 10, 255) };
+                    self.framebuffer.blend_pixel(x as usize, y as usize, color, alpha * 0.85);
                 }
             }
         }
@@ -594,26 +4473,26 @@ impl<'a> GameState<'a> {
         
         for text in &self.floating_texts {
             // Calculate text position relative to player
-            let dx = text.pos.x - self.player.pos.x;
-            let dy = text.pos.y - self.player.pos.y;
+            let dx = text.pos.x - self.player().pos.x;
+            let dy = text.pos.y - self.player().pos.y;
             
             // Rotate to player's view space
-            let cos_angle = self.player.angle.cos();
-            let sin_angle = self.player.angle.sin();
+            let cos_angle = self.player().angle.cos();
+            let sin_angle = self.player().angle.sin();
             let transformed_x = dx * cos_angle + dy * sin_angle;
             let transformed_y = -dx * sin_angle + dy * cos_angle;
             
             // Skip if behind player
-            if transformed_y <= 0.1 {
+            if !guard::in_front(transformed_y, "floating text depth") {
                 continue;
             }
             
             // Project to screen space
-            let fov = std::f32::consts::PI / 3.0;
+            let fov = self.raycaster.fov;
             let screen_x = (screen_width / 2.0) * (1.0 + transformed_x / (transformed_y * (fov / 2.0).tan()));
             
             // Apply floating offset
-            let screen_y = (screen_height / 2.0) - (text.z * 20.0);
+            let screen_y = screen_height * (0.5 + self.player().pitch + self.view_bob.horizon()) - (text.z * 20.0);
             
             // Calculate alpha based on lifetime
             let alpha = ((text.lifetime / 1.0) * 255.0).min(255.0) as u8;
@@ -624,5 +4503,29 @@ impl<'a> GameState<'a> {
             d.draw_text(&text.text, screen_x as i32 - 30, screen_y as i32, 24, color);
         }
     }
+}
 
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playing_systems_run_in_order() {
+        let schedule = GameState::playing_schedule();
+        assert_eq!(schedule.order(Stage::Input), ["look", "actions", "bot"]);
+        assert_eq!(
+            schedule.order(Stage::Simulation),
+            ["clock", "idle", "companion", "versus", "motion", "hints", "ambience", "level_events", "footprints", "radio", "enemies", "flashlight", "status"]
+        );
+        assert_eq!(schedule.order(Stage::Collision), ["positions", "explored", "barricade", "doors", "pickups", "challenges"]);
+        assert_eq!(schedule.order(Stage::Post), ["upkeep", "feedback", "exit", "menu_key"]);
+
+        // Across stages: the clock runs out before the exit is checked, movement lands before
+        // anything is picked up, and bad positions are caught before doors and pickups see them
+        let all: Vec<&str> = Stage::ALL.into_iter().flat_map(|stage| schedule.order(stage)).collect();
+        let position = |name: &str| all.iter().position(|&system| system == name).unwrap();
+        assert!(position("clock") < position("exit"));
+        assert!(position("motion") < position("pickups"));
+        assert!(position("positions") < position("doors"));
+    }
+}
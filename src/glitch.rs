@@ -0,0 +1,108 @@
+// Reality-glitch zones for Backrooms Doom
+// Regions of the level where reality stops holding together, authored in the
+// maze file one rectangle of tiles per line after the grid:
+//
+//   glitch 20,10 24,13
+//
+// (two opposite corners, x,y counted from 0 at the top-left corner). While the
+// player is inside one the glitch level ramps up, and back down once they leave:
+// the minimap shows more and more corrupted random tiles and loses the player's
+// dot, the compass needle spins, and the VHS static post-effect gets stronger,
+// so the way through has to be found by landmarks alone.
+
+const RAMP_UP: f32 = 3.0;         // Seconds to full glitch inside a zone
+const RAMP_DOWN: f32 = 2.0;       // Seconds to recover after leaving
+const SCRAMBLE_RATE: f32 = 8.0;   // Times per second the corrupted tiles change
+const SPIN_SPEED: f32 = 9.0;      // Radians per second of the compass at full glitch
+const HIDE_PLAYER_ABOVE: f32 = 0.5; // Glitch level where the minimap loses the player's dot
+const CORRUPT_TILES: [char; 5] = ['#', '.', 'E', '~', 'D'];
+
+/// Rectangle of tiles, inclusive
+#[derive(Clone, Copy)]
+pub struct GlitchZone {
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+}
+
+impl GlitchZone {
+    /// Parse the rest of a "glitch x,y x,y" line (without the keyword)
+    pub fn parse(definition: &str) -> Result<Self, String> {
+        let corners: Vec<&str> = definition.split_whitespace().collect();
+        let invalid = || format!("bad glitch zone '{}', expected two corners x,y x,y", definition.trim());
+        let [first, second] = corners[..] else {
+            return Err(invalid());
+        };
+        let corner = |token: &str| -> Option<(usize, usize)> {
+            let (x, y) = token.split_once(',')?;
+            Some((x.parse().ok()?, y.parse().ok()?))
+        };
+        let (a, b) = (corner(first).ok_or_else(invalid)?, corner(second).ok_or_else(invalid)?);
+        Ok(Self { x0: a.0.min(b.0), y0: a.1.min(b.1), x1: a.0.max(b.0), y1: a.1.max(b.1) })
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x0..=self.x1).contains(&x) && (self.y0..=self.y1).contains(&y)
+    }
+}
+
+/// How glitched the player's senses are right now
+pub struct Glitch {
+    level: f32, // 0.0 - 1.0
+    time: f32,
+    spin: f32,  // Compass needle offset (radians)
+}
+
+impl Glitch {
+    pub fn new() -> Self {
+        Self { level: 0.0, time: 0.0, spin: 0.0 }
+    }
+
+    /// Ramp the glitch up while the player's tile (x, y) is in a zone, down otherwise
+    pub fn update(&mut self, zones: &[GlitchZone], (x, y): (usize, usize), delta_time: f32) {
+        self.time += delta_time;
+        if zones.iter().any(|zone| zone.contains(x, y)) {
+            self.level = (self.level + delta_time / RAMP_UP).min(1.0);
+        } else {
+            self.level = (self.level - delta_time / RAMP_DOWN).max(0.0);
+        }
+        // Wobbling spin that gets faster the worse it is
+        self.spin += SPIN_SPEED * self.level * (1.0 + (self.time * 1.3).sin()) * delta_time;
+        if self.level <= 0.0 {
+            self.spin = 0.0;
+        }
+    }
+
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Where the compass points instead of `heading`
+    pub fn compass_heading(&self, heading: f32) -> f32 {
+        heading + self.spin
+    }
+
+    /// Whether the minimap has lost track of the player's position
+    pub fn hides_player(&self) -> bool {
+        self.level > HIDE_PLAYER_ABOVE
+    }
+
+    /// Random tile the minimap shows at (x, y) instead of the real one, if that tile is
+    /// corrupted right now. More tiles are corrupted the higher the glitch level
+    pub fn scramble(&self, x: usize, y: usize) -> Option<char> {
+        if self.level <= 0.0 {
+            return None;
+        }
+        let tick = (self.time * SCRAMBLE_RATE) as u32;
+        let hash = hash(x as u32, y as u32, tick);
+        let roll = (hash & 0xffff) as f32 / 65535.0;
+        (roll < self.level * 0.8).then(|| CORRUPT_TILES[(hash >> 16) as usize % CORRUPT_TILES.len()])
+    }
+}
+
+fn hash(x: u32, y: u32, tick: u32) -> u32 {
+    let mut h = x.wrapping_mul(374_761_393) ^ y.wrapping_mul(668_265_263) ^ tick.wrapping_mul(2_246_822_519);
+    h = (h ^ (h >> 13)).wrapping_mul(1_274_126_177);
+    h ^ (h >> 16)
+}
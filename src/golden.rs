@@ -0,0 +1,200 @@
+// Golden image rendering tests for Backrooms Doom
+// Renders a few fixed scenes (built-in maze, fixed position and angle, procedural
+// textures, no film grain) into the framebuffer and compares them against the
+// images stored in golden/, so raycaster and shading changes can't silently
+// change what the game looks like. Runs headless, no window is opened. The scenes
+// keep the later view features at their neutral settings: the view is level (no
+// pitch, no view bob) and the test maze has no lights, texture grade or see-through
+// tiles, so those features change the images only if they change the plain view.
+//
+//   cargo run --release -- --golden          compare against golden/*.ppm
+//   cargo run --release -- --golden-update   re-record them after an intended change
+
+use crate::audio::AudioManager;
+use crate::caster::RayCaster;
+use crate::clock::Clocks;
+use crate::config::Config;
+use crate::doors::Doors;
+use crate::flashlight::Flashlight;
+use crate::framebuffer::Framebuffer;
+use crate::game::{GameState, State};
+use crate::keys::Keys;
+use crate::maze::Maze;
+use crate::player::Vector2;
+use crate::profile::Profile;
+use crate::textures::TextureManager;
+use log::{error, info, warn};
+use raylib::prelude::*;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fs;
+
+const GOLDEN_DIR: &str = "golden";
+const FAILURE_DIR: &str = "logs/golden";
+const WIDTH: usize = 160;
+const HEIGHT: usize = 120;
+const NUM_RAYS: usize = 80;
+const TEXTURE_SIZE: usize = 64;
+
+// A pixel counts as different when a channel is off by more than this, and a scene
+// fails when more than this fraction of its pixels differ (absorbs float rounding
+// differences between platforms)
+const CHANNEL_TOLERANCE: u8 = 2;
+const MAX_DIFFERENT_PIXELS: f32 = 0.001;
+
+// Fixed test level: a corridor, a closed door, a pillar block and the exit
+const GOLDEN_MAZE: &str = "\
+##########
+#S.......#
+#.##D###.#
+#.#....#.#
+#.#....#.E
+#........#
+##########
+";
+
+/// (name, x, y, angle)
+const SCENES: [(&str, f32, f32, f32); 5] = [
+    ("corridor", 1.5, 1.5, 0.0),
+    ("corner", 1.5, 5.5, -PI / 4.0),
+    ("door", 4.5, 1.5, PI / 2.0),
+    ("exit", 8.5, 5.5, -1.2),
+    ("wall_closeup", 1.3, 3.5, PI),
+];
+
+/// Check for "--golden" / "--golden-update" and run the scenes.
+/// Returns None when neither flag was given, otherwise whether every scene matched
+pub fn run_from_args(args: &[String]) -> Option<bool> {
+    let update = args.iter().any(|arg| arg == "--golden-update");
+    if !update && !args.iter().any(|arg| arg == "--golden") {
+        return None;
+    }
+
+    let mut game = match setup() {
+        Ok(game) => game,
+        Err(e) => {
+            error!("Golden image test could not start: {}", e);
+            return Some(false);
+        }
+    };
+
+    if update {
+        if let Err(e) = fs::create_dir_all(GOLDEN_DIR) {
+            error!("Failed to create {}: {}", GOLDEN_DIR, e);
+            return Some(false);
+        }
+    }
+
+    let mut passed = true;
+    for (name, x, y, angle) in SCENES {
+        let player = &mut game.players[game.local_index];
+        player.pos = Vector2::new(x, y);
+        player.angle = angle;
+        player.pitch = 0.0;
+        game.render_offscreen();
+
+        let pixels = game.framebuffer.pixels();
+        let path = format!("{}/{}.ppm", GOLDEN_DIR, name);
+        if update {
+            match write_ppm(&path, pixels) {
+                Ok(()) => info!("Recorded {} (hash {:016x})", path, hash(pixels)),
+                Err(e) => {
+                    error!("{}", e);
+                    passed = false;
+                }
+            }
+            continue;
+        }
+
+        match compare(&path, pixels) {
+            Ok(()) => info!("Golden scene '{}' matches (hash {:016x})", name, hash(pixels)),
+            Err(e) => {
+                error!("Golden scene '{}' differs: {}", name, e);
+                let actual = format!("{}/{}.ppm", FAILURE_DIR, name);
+                match fs::create_dir_all(FAILURE_DIR).map_err(|e| e.to_string()).and_then(|_| write_ppm(&actual, pixels)) {
+                    Ok(()) => info!("Rendered image saved to {}", actual),
+                    Err(e) => warn!("{}", e),
+                }
+                passed = false;
+            }
+        }
+    }
+
+    if passed && !update {
+        info!("All {} golden scenes match", SCENES.len());
+    }
+    Some(passed)
+}
+
+/// Game state with the fixed maze, procedural textures and nothing but walls to draw
+fn setup() -> Result<GameState<'static>, String> {
+    let textures = TextureManager::from_loaded(TEXTURE_SIZE, HashMap::new(), HashMap::new());
+    let config = Config { film_grain: false, ..Config::default() };
+
+    // Nothing is saved during the tests, the profile folder is never created
+    let mut game = GameState::new(WIDTH, HEIGHT, textures, AudioManager::new(None), config, Profile::new("golden"))?;
+    game.maze = Maze::parse(GOLDEN_MAZE, 1.0)?;
+    game.doors = Doors::new(&game.maze, 0.0);
+    game.keys = Keys::new(&game.maze);
+    game.clocks = Clocks::new(&game.maze);
+    game.flashlight = Flashlight::new(&game.maze);
+    game.pills.clear();
+    game.enemies.clear();
+    game.props.clear();
+    game.containers.clear();
+    game.sprites.clear();
+    game.framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    game.raycaster = RayCaster::new(PI / 3.0, NUM_RAYS, 20.0);
+    game.state = State::Playing;
+    Ok(game)
+}
+
+/// Compare pixels with a golden image
+fn compare(path: &str, pixels: &[Color]) -> Result<(), String> {
+    let golden = read_ppm(path)?;
+    if golden.len() != pixels.len() {
+        return Err(format!("{} has {} pixels, rendered {}", path, golden.len(), pixels.len()));
+    }
+
+    let close = |a: u8, b: u8| a.abs_diff(b) <= CHANNEL_TOLERANCE;
+    let different = golden
+        .iter()
+        .zip(pixels)
+        .filter(|(g, p)| !(close(g.r, p.r) && close(g.g, p.g) && close(g.b, p.b)))
+        .count();
+    let fraction = different as f32 / pixels.len() as f32;
+    if fraction > MAX_DIFFERENT_PIXELS {
+        return Err(format!("{} of {} pixels changed ({:.2}%)", different, pixels.len(), fraction * 100.0));
+    }
+    Ok(())
+}
+
+/// FNV-1a over the RGB values, logged so runs can be compared at a glance
+fn hash(pixels: &[Color]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for pixel in pixels {
+        for byte in [pixel.r, pixel.g, pixel.b] {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// Binary PPM (P6): tiny, uncompressed and viewable in most image viewers
+fn write_ppm(path: &str, pixels: &[Color]) -> Result<(), String> {
+    let mut data = format!("P6\n{} {}\n255\n", WIDTH, HEIGHT).into_bytes();
+    for pixel in pixels {
+        data.extend_from_slice(&[pixel.r, pixel.g, pixel.b]);
+    }
+    fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+fn read_ppm(path: &str) -> Result<Vec<Color>, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {} (record it with --golden-update)", path, e))?;
+    let header = format!("P6\n{} {}\n255\n", WIDTH, HEIGHT);
+    let body = data
+        .strip_prefix(header.as_bytes())
+        .ok_or_else(|| format!("{} is not a {}x{} P6 image", path, WIDTH, HEIGHT))?;
+    Ok(body.chunks_exact(3).map(|rgb| Color::new(rgb[0], rgb[1], rgb[2], 255)).collect())
+}
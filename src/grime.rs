@@ -0,0 +1,178 @@
+// Grime for Backrooms Doom
+// Marks that build up on the walls where things keep happening: scuffs where the
+// player's swings land, handprints on doors that get opened and closed a lot,
+// scratches where enemies forced a door. Each face of a wall cell keeps a short
+// list of stains (only faces that have any are stored) and a stain hit again
+// darkens instead of doubling up, so a busy door ends up visibly worn. They are
+// blended into the wall columns as they are drawn, shaped like decal.rs marks.
+
+use crate::caster::RayHit;
+use crate::framebuffer::Framebuffer;
+use raylib::prelude::*;
+use std::collections::HashMap;
+
+const MAX_STAINS_PER_FACE: usize = 6;
+const MERGE_DISTANCE: f32 = 0.15; // Across the face, closer than this builds on the same stain
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GrimeKind {
+    Scuff,     // A swing that hit the wall
+    Handprint, // A door opened or closed by hand
+    Scratch,   // An enemy shoving a door
+}
+
+impl GrimeKind {
+    fn color(self) -> Color {
+        match self {
+            GrimeKind::Scuff => Color::new(45, 38, 30, 255),
+            GrimeKind::Handprint => Color::new(70, 55, 35, 255),
+            GrimeKind::Scratch => Color::new(30, 24, 20, 255),
+        }
+    }
+
+    /// Half the stain's size, as a fraction of the wall face
+    fn size(self) -> f32 {
+        match self {
+            GrimeKind::Scuff => 0.08,
+            GrimeKind::Handprint => 0.05,
+            GrimeKind::Scratch => 0.12,
+        }
+    }
+
+    /// Darkening added each time it happens again (opacity reaches 1.0 at most)
+    fn build(self) -> f32 {
+        match self {
+            GrimeKind::Scuff => 0.35,
+            GrimeKind::Handprint => 0.12,
+            GrimeKind::Scratch => 0.25,
+        }
+    }
+
+    /// Where the mark sits, from the top of the wall (0.0) to the floor (1.0)
+    pub fn height(self) -> f32 {
+        match self {
+            GrimeKind::Scuff => 0.5,
+            GrimeKind::Handprint => 0.58,
+            GrimeKind::Scratch => 0.45,
+        }
+    }
+
+    /// Whether the point (u, v), each -1.0 - 1.0 across the stain, is part of the mark
+    fn covers(self, u: f32, v: f32) -> bool {
+        match self {
+            // A ragged blotch
+            GrimeKind::Scuff => u * u + v * v <= 1.0 - 0.35 * ((u * 7.0).sin() * (v * 5.0).cos()).abs(),
+            // A palm with four fingers above it
+            GrimeKind::Handprint => {
+                let palm = (u / 0.6).powi(2) + ((v - 0.4) / 0.55).powi(2) <= 1.0;
+                let fingers = v < 0.0 && u.abs() < 0.6 && ((u + 0.6) / 0.3).fract() < 0.55;
+                palm || fingers
+            }
+            // Three claw lines raking down and across
+            GrimeKind::Scratch => u.abs() < 0.8 && ((u + v * 0.4) * 2.5).rem_euclid(1.0) < 0.18,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Face {
+    North,
+    South,
+    East,
+    West,
+}
+
+struct Stain {
+    kind: GrimeKind,
+    u: f32,      // Across the face, 0.0 - 1.0
+    v: f32,      // Down the face, 0.0 - 1.0
+    amount: f32, // Opacity, 0.0 - 1.0
+}
+
+pub struct Grime {
+    tile: f32,
+    faces: HashMap<(usize, usize, Face), Vec<Stain>>,
+}
+
+impl Grime {
+    pub fn new(tile: f32) -> Self {
+        Self { tile, faces: HashMap::new() }
+    }
+
+    /// The face of `cell` turned towards `point`, and how far across it `point` is
+    fn face_toward(&self, (cell_x, cell_y): (usize, usize), (x, y): (f32, f32)) -> (Face, f32) {
+        let (local_x, local_y) = (x / self.tile - cell_x as f32, y / self.tile - cell_y as f32);
+        let (dx, dy) = (local_x - 0.5, local_y - 0.5);
+        let face = if dx.abs() > dy.abs() {
+            if dx < 0.0 { Face::West } else { Face::East }
+        } else if dy < 0.0 {
+            Face::North
+        } else {
+            Face::South
+        };
+        let across = match face {
+            Face::West | Face::East => local_y,
+            Face::North | Face::South => local_x,
+        };
+        (face, across.clamp(0.0, 1.0))
+    }
+
+    /// Leave a mark on the wall `cell` at `point`, on the face turned towards `from`
+    pub fn add(&mut self, kind: GrimeKind, cell: (usize, usize), point: (f32, f32), from: (f32, f32)) {
+        let (face, _) = self.face_toward(cell, from);
+        let (_, u) = self.face_toward(cell, point);
+        let v = kind.height();
+        let stains = self.faces.entry((cell.0, cell.1, face)).or_default();
+
+        if let Some(stain) = stains
+            .iter_mut()
+            .find(|stain| stain.kind == kind && (stain.u - u).abs() < MERGE_DISTANCE)
+        {
+            stain.amount = (stain.amount + kind.build()).min(1.0);
+            return;
+        }
+        // Make room by dropping the faintest stain on this face
+        if stains.len() >= MAX_STAINS_PER_FACE {
+            if let Some(faintest) = (0..stains.len()).min_by(|&a, &b| stains[a].amount.total_cmp(&stains[b].amount)) {
+                stains.remove(faintest);
+            }
+        }
+        stains.push(Stain { kind, u, v, amount: kind.build() });
+    }
+
+    /// Blend the stains on the face `hit` landed on into screen column `x`, where the wall
+    /// spans `height` pixels from `top` and is drawn at `shade` brightness
+    pub fn draw_column(&self, framebuffer: &mut Framebuffer, x: usize, hit: &RayHit, top: f32, height: f32, shade: f32) {
+        if self.faces.is_empty() {
+            return;
+        }
+        let cell = (hit.map_x, hit.map_y);
+        let (face, u) = self.face_toward(cell, (hit.hit_x, hit.hit_y));
+        let Some(stains) = self.faces.get(&(cell.0, cell.1, face)) else {
+            return;
+        };
+
+        for stain in stains {
+            let size = stain.kind.size();
+            let across = (u - stain.u) / size;
+            if across.abs() > 1.0 {
+                continue;
+            }
+            let color = stain.kind.color();
+            let color = Color::new(
+                (color.r as f32 * shade) as u8,
+                (color.g as f32 * shade) as u8,
+                (color.b as f32 * shade) as u8,
+                255,
+            );
+            let first = (top + (stain.v - size) * height).max(0.0) as usize;
+            let last = ((top + (stain.v + size) * height).max(0.0) as usize).min(framebuffer.height);
+            for y in first..last {
+                let down = ((y as f32 - top) / height - stain.v) / size;
+                if stain.kind.covers(across, down) {
+                    framebuffer.blend_pixel(x, y, color, stain.amount);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Numeric guards for Backrooms Doom
+// The raycaster, the sprite projection and the screen effects divide by distances
+// that can get arbitrarily close to zero (standing right against a wall, a sprite
+// on top of the player), and a single NaN that gets into a position spreads to
+// everything computed from it. The clamping lives here instead of as scattered
+// .max(0.1) calls, so every path agrees on the limits.
+//
+// Bad values are repaired and logged once. In strict mode (--strict-math, always
+// on in debug builds) they panic instead, naming what was being computed, so the
+// crash log points at where the NaN came from rather than where it ended up.
+
+use log::warn;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const MIN_DEPTH: f32 = 0.1;     // Closest distance anything is drawn at, in tiles
+pub const MIN_LENGTH: f32 = 0.001;  // Shortest vector that still gets normalized
+
+static STRICT: AtomicBool = AtomicBool::new(cfg!(debug_assertions));
+static REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Turn strict mode on when started with --strict-math
+pub fn init_from_args(args: &[String]) {
+    if args.iter().any(|arg| arg == "--strict-math") {
+        STRICT.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Panic in strict mode, otherwise warn the first time only (it would repeat every frame)
+fn report(context: &str, what: String) {
+    if STRICT.load(Ordering::Relaxed) {
+        panic!("{}: {}", context, what);
+    }
+    if !REPORTED.swap(true, Ordering::Relaxed) {
+        warn!("{}: {} (repaired, further bad values are not logged)", context, what);
+    }
+}
+
+/// A distance safe to divide by: at least MIN_DEPTH, `far` when it isn't finite
+pub fn depth(value: f32, far: f32, context: &str) -> f32 {
+    if !value.is_finite() {
+        report(context, format!("distance is {}", value));
+        return far;
+    }
+    value.max(MIN_DEPTH)
+}
+
+/// Whether a view-space depth is in front of the camera and far enough to project.
+/// False for NaN, which a plain `depth <= MIN_DEPTH` check would let through
+pub fn in_front(depth: f32, context: &str) -> bool {
+    if depth.is_nan() {
+        report(context, "depth is NaN".to_string());
+    }
+    depth > MIN_DEPTH
+}
+
+/// `value`, or `fallback` when it is NaN or infinite. Denormals are flushed to zero,
+/// so values decaying towards zero don't crawl through the slow float path forever
+pub fn finite_or(value: f32, fallback: f32) -> f32 {
+    if !value.is_finite() {
+        fallback
+    } else if value.is_subnormal() {
+        0.0
+    } else {
+        value
+    }
+}
+
+/// (x, y) scaled to length 1, or (0, 0) when it is too short to have a direction
+pub fn normalize(x: f32, y: f32) -> (f32, f32) {
+    let length = (x * x + y * y).sqrt();
+    if length.is_nan() || length < MIN_LENGTH {
+        return (0.0, 0.0);
+    }
+    (x / length, y / length)
+}
+
+/// Whether a world position is usable. Reports it otherwise, the caller puts the entity
+/// somewhere sane
+pub fn position_ok(context: &str, index: usize, x: f32, y: f32) -> bool {
+    if x.is_finite() && y.is_finite() {
+        return true;
+    }
+    report(context, format!("position of #{} is ({}, {})", index, x, y));
+    false
+}
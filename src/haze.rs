@@ -0,0 +1,104 @@
+// Heat haze for Backrooms Doom
+// Localized screen distortion over marked tiles, authored in the maze file one
+// line per tile after the grid:
+//
+//   haze 3,9 vent
+//   haze 21,13 tear 2
+//
+// The tile is x,y counted from 0 at the top-left corner, then the kind, then an
+// optional radius in tiles. `vent` is a gentle, slow shimmer like warm air over a
+// vent; `tear` is a strong, fast warp where reality is coming apart. The screen
+// region the tile covers gets its pixels pushed around by a noise field that
+// drifts over time (Framebuffer::apply_distortion), pulsing in strength.
+
+const DEFAULT_RADIUS: f32 = 1.0; // Tiles
+const MAX_RADIUS: f32 = 6.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HazeKind {
+    Vent,
+    Tear,
+}
+
+impl HazeKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "vent" => Some(HazeKind::Vent),
+            "tear" => Some(HazeKind::Tear),
+            _ => None,
+        }
+    }
+
+    /// Largest pixel offset, as a fraction of the region's height
+    fn strength(self) -> f32 {
+        match self {
+            HazeKind::Vent => 0.015,
+            HazeKind::Tear => 0.05,
+        }
+    }
+
+    /// How fast the noise drifts
+    fn speed(self) -> f32 {
+        match self {
+            HazeKind::Vent => 1.2,
+            HazeKind::Tear => 4.0,
+        }
+    }
+
+    /// Strength pulse: (period in seconds, how much of the strength it takes away)
+    fn pulse(self) -> (f32, f32) {
+        match self {
+            HazeKind::Vent => (3.0, 0.3),
+            HazeKind::Tear => (0.9, 0.6),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Haze {
+    pub x: f32, // World position (tile center)
+    pub y: f32,
+    pub kind: HazeKind,
+    pub radius: f32, // Tiles
+}
+
+impl Haze {
+    /// Parse the rest of a "haze ..." line (without the keyword) into its tile coordinates,
+    /// kind and radius in tiles
+    pub fn parse(definition: &str) -> Result<(usize, usize, HazeKind, f32), String> {
+        let mut parts = definition.split_whitespace();
+        let position = parts.next().ok_or("a haze needs a tile and a kind")?;
+        let invalid_position = || format!("bad haze position '{}', expected x,y", position);
+        let (x, y) = position.split_once(',').ok_or_else(invalid_position)?;
+        let x = x.parse::<usize>().map_err(|_| invalid_position())?;
+        let y = y.parse::<usize>().map_err(|_| invalid_position())?;
+
+        let kind = parts.next().ok_or("a haze needs a kind")?;
+        let kind = HazeKind::parse(kind).ok_or_else(|| format!("bad haze kind '{}', expected vent or tear", kind))?;
+
+        let radius = match parts.next() {
+            Some(radius) => radius
+                .parse::<f32>()
+                .ok()
+                .filter(|radius| *radius > 0.0 && *radius <= MAX_RADIUS)
+                .ok_or_else(|| format!("bad haze radius '{}', expected 0-{} tiles", radius, MAX_RADIUS))?,
+            None => DEFAULT_RADIUS,
+        };
+        if let Some(extra) = parts.next() {
+            return Err(format!("unexpected '{}' after the haze radius", extra));
+        }
+        Ok((x, y, kind, radius))
+    }
+
+    /// Pixel offset amplitude for a region `height` pixels tall at `time` seconds
+    pub fn amplitude(&self, height: f32, time: f32) -> f32 {
+        let (period, depth) = self.kind.pulse();
+        let pulse = 1.0 - depth * (0.5 + 0.5 * (time * std::f32::consts::TAU / period).sin());
+        height * self.kind.strength() * pulse
+    }
+
+    /// Noise field time for the distortion at `time` seconds
+    pub fn drift(&self, time: f32) -> f32 {
+        time * self.kind.speed()
+    }
+}
@@ -0,0 +1,155 @@
+// Health for Backrooms Doom
+// Every change to the local player's health goes through here: pills, poison, the
+// idle penalty, enemy strikes, doors bursting in and regeneration. Modifiers scale the
+// damage taken or regenerate health for a while, the result is clamped to
+// 0..=max_health, a hit leaves a short window where further hits don't land, and
+// each change is reported on the event bus (Damaged, Healed, LowHealth, Died) so
+// the feedback for it lives in GameState::handle_events.
+
+use crate::events::{EventQueue, GameEvent};
+use crate::player::Player;
+
+pub const LOW_HEALTH: f32 = 0.3;      // Fraction of max health under which LowHealth fires
+const INVULNERABLE_TIME: f32 = 0.5;   // Seconds after a hit before the next one lands
+
+/// What changed the player's health
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Source {
+    Pill,
+    Medkit,
+    Poison,    // The poison status effect (see status.rs)
+    Idle,      // Idle penalty
+    Enemy,     // Strike on contact
+    DoorBurst, // Standing behind a door an enemy broke through
+    Regen,
+}
+
+impl Source {
+    /// Hits from the world respect invulnerability frames; pills and penalties always count
+    fn is_hit(self) -> bool {
+        matches!(self, Source::Enemy | Source::DoorBurst)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Modifier {
+    DamageTaken(f32), // Multiplier on all damage
+    Regen(f32),       // Health per second
+}
+
+struct ActiveModifier {
+    modifier: Modifier,
+    time_left: Option<f32>, // None lasts until cleared
+}
+
+pub struct Health {
+    modifiers: Vec<ActiveModifier>,
+    invulnerable: f32, // Seconds left
+    regen: f32,        // Fraction of a health point regenerated so far
+    low: bool,         // Below LOW_HEALTH since the last LowHealth event
+}
+
+impl Health {
+    pub fn new() -> Self {
+        Self { modifiers: Vec::new(), invulnerable: 0.0, regen: 0.0, low: false }
+    }
+
+    /// Apply `modifier` for `duration` seconds (None = until cleared)
+    pub fn add_modifier(&mut self, modifier: Modifier, duration: Option<f32>) {
+        self.modifiers.push(ActiveModifier { modifier, time_left: duration });
+    }
+
+    pub fn clear_modifiers(&mut self) {
+        self.modifiers.clear();
+    }
+
+    /// Keep hits from landing for at least `seconds` (the invulnerable status effect)
+    pub fn shield(&mut self, seconds: f32) {
+        self.invulnerable = self.invulnerable.max(seconds);
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable > 0.0
+    }
+
+    /// Hurt the player. Returns the health actually lost (0 for a hit during invulnerability)
+    pub fn damage(&mut self, player: &mut Player, amount: i32, source: Source, events: &mut EventQueue) -> i32 {
+        if amount <= 0 || !player.is_alive() || (source.is_hit() && self.is_invulnerable()) {
+            return 0;
+        }
+        let scale: f32 = self
+            .modifiers
+            .iter()
+            .filter_map(|active| match active.modifier {
+                Modifier::DamageTaken(scale) => Some(scale),
+                Modifier::Regen(_) => None,
+            })
+            .product();
+        let before = player.health;
+        player.take_damage((amount as f32 * scale).round() as i32);
+        let lost = before - player.health;
+        if lost == 0 {
+            return 0;
+        }
+        if source.is_hit() {
+            self.invulnerable = INVULNERABLE_TIME;
+        }
+
+        events.push(GameEvent::Damaged { amount: lost, source });
+        if !player.is_alive() {
+            events.push(GameEvent::Died { source });
+        } else if !self.low && self.is_low(player) {
+            self.low = true;
+            events.push(GameEvent::LowHealth);
+        }
+        lost
+    }
+
+    /// Heal the player. Returns the health actually gained
+    pub fn heal(&mut self, player: &mut Player, amount: i32, source: Source, events: &mut EventQueue) -> i32 {
+        if amount <= 0 || !player.is_alive() {
+            return 0;
+        }
+        let before = player.health;
+        player.heal(amount);
+        let gained = player.health - before;
+        if gained > 0 {
+            events.push(GameEvent::Healed { amount: gained, source });
+        }
+        if !self.is_low(player) {
+            self.low = false;
+        }
+        gained
+    }
+
+    /// Count down invulnerability and timed modifiers, and regenerate
+    pub fn update(&mut self, player: &mut Player, delta_time: f32, events: &mut EventQueue) {
+        self.invulnerable = (self.invulnerable - delta_time).max(0.0);
+
+        let per_second: f32 = self
+            .modifiers
+            .iter()
+            .filter_map(|active| match active.modifier {
+                Modifier::Regen(per_second) => Some(per_second),
+                Modifier::DamageTaken(_) => None,
+            })
+            .sum();
+        self.regen += per_second * delta_time;
+        if self.regen >= 1.0 {
+            let points = self.regen.floor();
+            self.regen -= points;
+            self.heal(player, points as i32, Source::Regen, events);
+        }
+
+        for active in &mut self.modifiers {
+            if let Some(time_left) = &mut active.time_left {
+                *time_left -= delta_time;
+            }
+        }
+        self.modifiers.retain(|active| active.time_left.is_none_or(|time_left| time_left > 0.0));
+    }
+
+    fn is_low(&self, player: &Player) -> bool {
+        (player.health as f32) < player.max_health as f32 * LOW_HEALTH
+    }
+}
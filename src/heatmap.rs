@@ -0,0 +1,110 @@
+// AI heatmap debug view for Backrooms Doom
+// Developer overlay that draws the whole maze full-screen with a per-tile heat
+// value on top, rebuilt a few times per second while it is shown:
+//   scent  - the player's wet footprint trail, as fresh as enemies can follow it
+//   threat - how close each tile is to a living enemy, in walking steps
+//   routes - how many enemy routes pass through each tile (crowd.rs)
+// Toggled with `heatmap <off|scent|threat|routes>` in the console.
+
+use crate::crowd::Crowd;
+use crate::decal::{DecalKind, Decals};
+use crate::enemy::Enemy;
+use crate::maze::Maze;
+use crate::pathfinding;
+use crate::tracks;
+
+const REFRESH_TIME: f32 = 0.25; // Seconds between rebuilds
+const THREAT_RANGE: f32 = 8.0;  // Steps from an enemy where the threat reaches zero
+const ROUTES_FULL: f32 = 3.0;   // Routes through a tile for full heat
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HeatmapMode {
+    Off,
+    Scent,
+    Threat,
+    Routes,
+}
+
+impl HeatmapMode {
+    const ALL: [HeatmapMode; 4] = [HeatmapMode::Off, HeatmapMode::Scent, HeatmapMode::Threat, HeatmapMode::Routes];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HeatmapMode::Off => "off",
+            HeatmapMode::Scent => "scent",
+            HeatmapMode::Threat => "threat",
+            HeatmapMode::Routes => "routes",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == value)
+    }
+}
+
+pub struct Heatmap {
+    pub mode: HeatmapMode,
+    cells: Vec<f32>, // 0.0 - 1.0 per tile, indexed by y * width + x
+    width: usize,
+    refresh_timer: f32,
+}
+
+impl Heatmap {
+    pub fn new() -> Self {
+        Self { mode: HeatmapMode::Off, cells: Vec::new(), width: 0, refresh_timer: 0.0 }
+    }
+
+    pub fn set_mode(&mut self, mode: HeatmapMode) {
+        self.mode = mode;
+        self.refresh_timer = 0.0; // Rebuild right away
+    }
+
+    pub fn update(&mut self, maze: &Maze, decals: &Decals, enemies: &[Enemy], crowd: &Crowd, delta_time: f32) {
+        if self.mode == HeatmapMode::Off {
+            return;
+        }
+        self.refresh_timer -= delta_time;
+        if self.refresh_timer > 0.0 {
+            return;
+        }
+        self.refresh_timer = REFRESH_TIME;
+
+        self.width = maze.width;
+        self.cells = vec![0.0; maze.width * maze.height];
+        let tile = maze.tile_size;
+        match self.mode {
+            HeatmapMode::Off => {}
+            HeatmapMode::Scent => {
+                for print in decals.list.iter().filter(|decal| decal.kind == DecalKind::WetFootprint) {
+                    let (x, y) = ((print.x / tile) as usize, (print.y / tile) as usize);
+                    if let Some(cell) = self.cells.get_mut(y * maze.width + x) {
+                        *cell = cell.max(1.0 - print.age / tracks::SCENT_TIME);
+                    }
+                }
+            }
+            HeatmapMode::Threat => {
+                for enemy in enemies.iter().filter(|enemy| enemy.is_alive()) {
+                    let steps = pathfinding::distances(maze, (enemy.pos.x, enemy.pos.y));
+                    for (cell, steps) in self.cells.iter_mut().zip(steps) {
+                        if let Some(steps) = steps {
+                            *cell = cell.max(1.0 - steps as f32 / THREAT_RANGE);
+                        }
+                    }
+                }
+            }
+            HeatmapMode::Routes => {
+                for (cell, routes) in self.cells.iter_mut().zip(&crowd.occupancy) {
+                    *cell = (*routes as f32 / ROUTES_FULL).min(1.0);
+                }
+            }
+        }
+    }
+
+    /// Heat of the tile at grid position (x, y)
+    pub fn value(&self, x: usize, y: usize) -> f32 {
+        if x >= self.width {
+            return 0.0;
+        }
+        self.cells.get(y * self.width + x).copied().unwrap_or(0.0).max(0.0)
+    }
+}
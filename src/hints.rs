@@ -0,0 +1,178 @@
+// Contextual hints for Backrooms Doom
+// Watches for signs the player is struggling (walking into walls over and over,
+// circling the same few cells, wandering right past the exit) and shows a short,
+// gentle hint. How often hints may appear is a setting; hardcore mode turns
+// them off completely.
+
+use std::collections::{HashMap, VecDeque};
+
+const HINT_TIME: f32 = 6.0; // Seconds a hint stays on screen
+
+// Walking into walls: this many bumps inside the window
+const BUMP_MIN_TIME: f32 = 0.25; // Pressing into a wall this long counts as one bump
+const BUMP_COUNT: usize = 5;
+const BUMP_WINDOW: f32 = 30.0;
+
+// Circling: entering the same cell this many times inside the window
+const CIRCLE_VISITS: usize = 4;
+const CIRCLE_WINDOW: f32 = 60.0;
+
+// Ignoring the exit: time spent this close to it without leaving through it
+const EXIT_NEAR: f32 = 4.0; // Tiles
+const EXIT_LINGER: f32 = 10.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HintFrequency {
+    Off,
+    Rare,
+    Normal,
+    Often,
+}
+
+impl HintFrequency {
+    const ALL: [HintFrequency; 4] = [HintFrequency::Off, HintFrequency::Rare, HintFrequency::Normal, HintFrequency::Often];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HintFrequency::Off => "off",
+            HintFrequency::Rare => "rare",
+            HintFrequency::Normal => "normal",
+            HintFrequency::Often => "often",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|frequency| frequency.name() == value)
+    }
+
+    /// Minimum seconds between two hints
+    fn cooldown(self) -> Option<f32> {
+        match self {
+            HintFrequency::Off => None,
+            HintFrequency::Rare => Some(120.0),
+            HintFrequency::Normal => Some(60.0),
+            HintFrequency::Often => Some(25.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Struggle {
+    WallBumping,
+    Circling,
+    MissingExit,
+}
+
+impl Struggle {
+    fn hint(self) -> &'static str {
+        match self {
+            Struggle::WallBumping => "Feel your way along the walls - every hall leads somewhere.",
+            Struggle::Circling => "These halls look familiar... try a turn you haven't taken.",
+            Struggle::MissingExit => "The hum gets louder near the exit.",
+        }
+    }
+}
+
+/// What the hint tracker needs to know about the player this frame
+pub struct HintInput {
+    pub cell: (i32, i32),
+    pub pushing_into_wall: bool, // Holding a movement key but barely moving
+    pub exit_distance: f32,      // Tiles to the exit
+}
+
+pub struct Hints {
+    time: f32,
+    cooldown: f32,
+    bump_timer: f32,                        // How long the current push into a wall has lasted
+    bumps: VecDeque<f32>,                   // Times of recent bumps
+    last_cell: (i32, i32),
+    visits: HashMap<(i32, i32), VecDeque<f32>>, // Recent entry times per cell
+    near_exit: f32,
+    shown: Vec<Struggle>,                   // Each hint is given once per run
+    pub current: Option<(&'static str, f32)>, // Hint on screen and time left
+}
+
+impl Hints {
+    pub fn new() -> Self {
+        Self {
+            time: 0.0,
+            cooldown: 0.0,
+            bump_timer: 0.0,
+            bumps: VecDeque::new(),
+            last_cell: (-1, -1),
+            visits: HashMap::new(),
+            near_exit: 0.0,
+            shown: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Watch the player and queue a hint when they seem stuck
+    pub fn update(&mut self, input: &HintInput, frequency: HintFrequency, delta_time: f32) {
+        self.time += delta_time;
+        self.cooldown -= delta_time;
+        if let Some((_, time_left)) = &mut self.current {
+            *time_left -= delta_time;
+            if *time_left <= 0.0 {
+                self.current = None;
+            }
+        }
+
+        let struggle = self.detect(input, delta_time);
+        let Some(cooldown) = frequency.cooldown() else {
+            return;
+        };
+        if let Some(struggle) = struggle {
+            if self.cooldown <= 0.0 && self.current.is_none() && !self.shown.contains(&struggle) {
+                self.current = Some((struggle.hint(), HINT_TIME));
+                self.shown.push(struggle);
+                self.cooldown = cooldown;
+            }
+        }
+    }
+
+    fn detect(&mut self, input: &HintInput, delta_time: f32) -> Option<Struggle> {
+        let now = self.time;
+
+        // A push into a wall counts once it has lasted a moment
+        if input.pushing_into_wall {
+            self.bump_timer += delta_time;
+            if self.bump_timer >= BUMP_MIN_TIME && self.bump_timer - delta_time < BUMP_MIN_TIME {
+                self.bumps.push_back(now);
+            }
+        } else {
+            self.bump_timer = 0.0;
+        }
+        while self.bumps.front().is_some_and(|time| now - time > BUMP_WINDOW) {
+            self.bumps.pop_front();
+        }
+
+        // Count entries into each cell over the last minute
+        let mut circling = false;
+        if input.cell != self.last_cell {
+            self.last_cell = input.cell;
+            let entries = self.visits.entry(input.cell).or_default();
+            entries.push_back(now);
+            while entries.front().is_some_and(|time| now - time > CIRCLE_WINDOW) {
+                entries.pop_front();
+            }
+            circling = entries.len() >= CIRCLE_VISITS;
+        }
+
+        if input.exit_distance <= EXIT_NEAR {
+            self.near_exit += delta_time;
+        } else {
+            self.near_exit = 0.0;
+        }
+
+        if self.near_exit >= EXIT_LINGER {
+            Some(Struggle::MissingExit)
+        } else if circling {
+            Some(Struggle::Circling)
+        } else if self.bumps.len() >= BUMP_COUNT {
+            Some(Struggle::WallBumping)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// HUD layout for Backrooms Doom
+// Places HUD elements by screen anchor instead of fixed pixel positions, with a
+// scale derived from the window height (the 480 line base resolution) times the
+// `hud_scale` setting, so the HUD keeps its proportions at any resolution.
+
+use raylib::prelude::*;
+
+const BASE_HEIGHT: f32 = 480.0;
+const MARGIN: f32 = 10.0; // Distance from the screen edge at scale 1.0
+pub const MIN_SCALE: f32 = 0.5;
+pub const MAX_SCALE: f32 = 2.0;
+
+#[derive(Clone, Copy)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+}
+
+pub struct HudLayout {
+    pub scale: f32,
+    screen: (i32, i32),
+}
+
+impl HudLayout {
+    pub fn new(screen_width: i32, screen_height: i32, user_scale: f32) -> Self {
+        let scale = (screen_height as f32 / BASE_HEIGHT * user_scale).max(MIN_SCALE);
+        Self { scale, screen: (screen_width, screen_height) }
+    }
+
+    /// Pixels for a length given at scale 1.0
+    pub fn px(&self, length: f32) -> i32 {
+        (length * self.scale).round() as i32
+    }
+
+    /// Top-left corner of a box of (width, height) pixels placed at an anchor,
+    /// `offset` (at scale 1.0) pushes it inwards from the edge
+    pub fn place(&self, anchor: Anchor, (width, height): (i32, i32), offset: (f32, f32)) -> (i32, i32) {
+        let margin = self.px(MARGIN);
+        let (dx, dy) = (self.px(offset.0), self.px(offset.1));
+        let (screen_width, screen_height) = self.screen;
+        match anchor {
+            Anchor::TopLeft => (margin + dx, margin + dy),
+            Anchor::TopRight => (screen_width - width - margin - dx, margin + dy),
+            Anchor::BottomLeft => (margin + dx, screen_height - height - margin - dy),
+            Anchor::BottomCenter => ((screen_width - width) / 2 + dx, screen_height - height - margin - dy),
+        }
+    }
+}
+
+/// One entry in the status cluster: an effect with its remaining duration, a cooldown,
+/// or a gauge, drawn as a ring filled to `fraction`
+pub struct StatusItem {
+    pub label: String,
+    pub fraction: f32, // 0.0 - 1.0 of the ring filled
+    pub color: Color,
+    pub icon: Option<&'static str>, // Drawn inside the ring
+}
+
+impl StatusItem {
+    pub fn new(label: impl Into<String>, fraction: f32, color: Color) -> Self {
+        Self { label: label.into(), fraction: fraction.clamp(0.0, 1.0), color, icon: None }
+    }
+
+    pub fn with_icon(mut self, icon: &'static str) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+}
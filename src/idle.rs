@@ -0,0 +1,70 @@
+// Idle penalty for Backrooms Doom
+// Standing still too long hurts: after `threshold` seconds without moving the
+// player takes `damage` and the anxiety effect (shake, vignette, heartbeat) runs
+// at `anxiety` strength. That pressure is the point for most players, but not
+// for a streamer who has to step away or a player who can't tolerate it, so all
+// three are settings and each can be turned off. Hardcore runs ignore them and
+// always play by the defaults.
+
+pub const MAX_THRESHOLD: f32 = 120.0; // Seconds
+pub const MAX_DAMAGE: i32 = 100;
+
+#[derive(Clone, Copy)]
+pub struct IdlePenalty {
+    pub threshold: Option<f32>, // Seconds standing still before the penalty, None = never
+    pub damage: i32,            // Health lost per penalty
+    pub anxiety: f32,           // Strength of the anxiety effect (0.0 off - 1.0 full)
+}
+
+impl Default for IdlePenalty {
+    fn default() -> Self {
+        Self { threshold: Some(5.0), damage: 10, anxiety: 1.0 }
+    }
+}
+
+impl IdlePenalty {
+    /// Range of a setting by its settings.toml name (besides "off"), None for an unknown name
+    pub fn range(name: &str) -> Option<(f32, f32)> {
+        match name {
+            "idle_time" => Some((1.0, MAX_THRESHOLD)),
+            "idle_damage" => Some((0.0, MAX_DAMAGE as f32)),
+            "anxiety_strength" => Some((0.0, 1.0)),
+            _ => None,
+        }
+    }
+
+    /// Change a setting by its settings.toml name to a number or "off", clamped to a sane
+    /// range. False for an unknown name or a value that doesn't parse
+    pub fn set(&mut self, name: &str, value: &str) -> bool {
+        let off = value == "off";
+        let amount = || value.parse::<f32>().ok();
+        match (name, off) {
+            ("idle_time", true) => self.threshold = None,
+            ("idle_time", false) => match amount() {
+                Some(time) => self.threshold = Some(time.clamp(1.0, MAX_THRESHOLD)),
+                None => return false,
+            },
+            ("idle_damage", true) => self.damage = 0,
+            ("idle_damage", false) => match amount() {
+                Some(damage) => self.damage = (damage as i32).clamp(0, MAX_DAMAGE),
+                None => return false,
+            },
+            ("anxiety_strength", true) => self.anxiety = 0.0,
+            ("anxiety_strength", false) => match amount() {
+                Some(strength) => self.anxiety = strength.clamp(0.0, 1.0),
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// The rules a run plays by: hardcore always uses the defaults
+    pub fn for_run(self, hardcore: bool) -> Self {
+        if hardcore {
+            Self::default()
+        } else {
+            self
+        }
+    }
+}
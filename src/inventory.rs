@@ -0,0 +1,129 @@
+// Inventory for Backrooms Doom
+// The player carries consumables in a hotbar of SLOTS slots: medkits, spare flashlight
+// batteries, chalk sticks and, with `store_pills` on, pills. Items of a kind stack in one
+// slot up to their stack size; a full hotbar leaves new items lying where they are. The
+// number keys pick a slot and the use key uses one item from it:
+// - Medkit: MEDKIT_HEAL health back
+// - Battery: charges the flashlight (see flashlight.rs)
+// - Chalk: a fresh stick, the chalk marks back to full (see chalk.rs)
+// - Pill: taken as if it had just been walked over
+// Items that would do nothing (a medkit at full health, a battery into a full flashlight)
+// aren't used up. Medkits and chalk come from loot tables, batteries from the level too.
+
+use crate::pill::PillType;
+use raylib::prelude::Color;
+
+pub const SLOTS: usize = 5;
+pub const MEDKIT_HEAL: i32 = 35;
+pub const COLLECT_RADIUS: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Item {
+    Medkit,
+    Battery,
+    Chalk,
+    Pill(PillType),
+}
+
+impl Item {
+    /// Item name in the loot tables: medkit, battery, chalk or <color>_pill
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "medkit" => Some(Item::Medkit),
+            "battery" => Some(Item::Battery),
+            "chalk" => Some(Item::Chalk),
+            _ => value.strip_suffix("_pill").and_then(PillType::from_name).map(Item::Pill),
+        }
+    }
+
+    /// Name on the hotbar and in floating text
+    pub fn label(self) -> String {
+        match self {
+            Item::Medkit => "Medkit".to_string(),
+            Item::Battery => "Battery".to_string(),
+            Item::Chalk => "Chalk".to_string(),
+            Item::Pill(pill_type) => format!("{} pill", pill_type.label()),
+        }
+    }
+
+    /// Most of the item one slot holds
+    pub fn stack_size(self) -> u32 {
+        match self {
+            Item::Medkit => 3,
+            Item::Battery => 4,
+            Item::Chalk => 2,
+            Item::Pill(_) => 5,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Item::Medkit => Color::new(240, 240, 240, 255),
+            Item::Battery => crate::flashlight::COLOR,
+            Item::Chalk => Color::new(250, 250, 235, 255),
+            Item::Pill(pill_type) => pill_type.color(),
+        }
+    }
+}
+
+/// Whether the point (u, v), each -1.0 - 1.0 across the sprite, is part of the item lying
+/// on the floor (pills have sprites of their own)
+pub fn covers(item: Item, u: f32, v: f32) -> bool {
+    match item {
+        // A box with a cross cut out of it
+        Item::Medkit => u.abs() <= 0.9 && v.abs() <= 0.7 && !((u.abs() < 0.15 && v.abs() < 0.5) || (v.abs() < 0.15 && u.abs() < 0.5)),
+        Item::Battery => crate::flashlight::covers(u, v),
+        // A stick lying across
+        Item::Chalk => (u - v).abs() < 0.25 && u.abs() < 0.8,
+        Item::Pill(_) => u * u + v * v < 0.5,
+    }
+}
+
+#[derive(Clone)]
+pub struct Inventory {
+    slots: [Option<(Item, u32)>; SLOTS], // Item and how many
+    pub selected: usize,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self { slots: [None; SLOTS], selected: 0 }
+    }
+
+    /// Put an item on the slot already holding its kind (while it has room), otherwise the
+    /// first empty one. Returns false when there is no room
+    pub fn add(&mut self, item: Item) -> bool {
+        let stack = self.slots.iter().position(|slot| matches!(slot, Some((held, count)) if *held == item && *count < item.stack_size()));
+        match stack.or_else(|| self.slots.iter().position(|slot| slot.is_none())) {
+            Some(index) => {
+                let count = self.slots[index].map_or(0, |(_, count)| count);
+                self.slots[index] = Some((item, count + 1));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pick a slot, 0 - SLOTS - 1
+    pub fn select(&mut self, index: usize) {
+        if index < SLOTS {
+            self.selected = index;
+        }
+    }
+
+    /// The item in the selected slot
+    pub fn selected_item(&self) -> Option<Item> {
+        self.slots[self.selected].map(|(item, _)| item)
+    }
+
+    /// Use up one item from the selected slot
+    pub fn take_selected(&mut self) -> Option<Item> {
+        let (item, count) = self.slots[self.selected]?;
+        self.slots[self.selected] = (count > 1).then_some((item, count - 1));
+        Some(item)
+    }
+
+    pub fn slots(&self) -> &[Option<(Item, u32)>] {
+        &self.slots
+    }
+}
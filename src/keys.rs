@@ -0,0 +1,83 @@
+// Keys for Backrooms Doom
+// Levels can lock the exit: every key ('K' in the maze file) has to be picked up
+// before the exit door lets the player out. Touching the exit without them rattles
+// it with a "locked" message; the rattle has a cooldown so standing against the
+// door doesn't repeat it every frame. Keys are drawn as spinning brass billboards.
+
+use crate::maze::Maze;
+use raylib::prelude::*;
+
+pub const COLLECT_RADIUS: f32 = 0.5;
+const RATTLE_COOLDOWN: f32 = 1.5; // Seconds between "locked" rattles
+
+pub const COLOR: Color = Color::new(230, 190, 70, 255);
+
+struct Key {
+    pos: (f32, f32),
+    collected: bool,
+}
+
+pub struct Keys {
+    keys: Vec<Key>,
+    pub spin: f32,      // Seconds of animation, shared by every key
+    rattle_timer: f32, // Until the exit can rattle again
+}
+
+impl Keys {
+    pub fn new(maze: &Maze) -> Self {
+        let keys = maze.key_positions.iter().map(|&pos| Key { pos, collected: false }).collect();
+        Self { keys, spin: 0.0, rattle_timer: 0.0 }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.spin += delta_time;
+        self.rattle_timer = (self.rattle_timer - delta_time).max(0.0);
+    }
+
+    pub fn total(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn collected(&self) -> usize {
+        self.keys.iter().filter(|key| key.collected).count()
+    }
+
+    /// Whether the exit is open: no keys in the level, or all of them picked up
+    pub fn all_collected(&self) -> bool {
+        self.keys.iter().all(|key| key.collected)
+    }
+
+    /// Keys still lying in the level
+    pub fn remaining(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        self.keys.iter().filter(|key| !key.collected).map(|key| key.pos)
+    }
+
+    /// Pick up a key within reach of (x, y). Returns where it was
+    pub fn collect_near(&mut self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let key = self
+            .keys
+            .iter_mut()
+            .find(|key| !key.collected && ((key.pos.0 - x).powi(2) + (key.pos.1 - y).powi(2)).sqrt() < COLLECT_RADIUS)?;
+        key.collected = true;
+        Some(key.pos)
+    }
+
+    /// The player is touching the locked exit: whether it rattles now (not during the cooldown)
+    pub fn rattle(&mut self) -> bool {
+        if self.rattle_timer > 0.0 {
+            return false;
+        }
+        self.rattle_timer = RATTLE_COOLDOWN;
+        true
+    }
+}
+
+/// Whether the point (u, v), each -1.0 - 1.0 across the sprite, is part of the key:
+/// a ring on the left, the shaft and two teeth hanging off its end
+pub fn covers(u: f32, v: f32) -> bool {
+    let ring = (u + 0.55).powi(2) + v * v;
+    let bow = (0.04..=0.16).contains(&ring);
+    let shaft = (-0.2..=0.9).contains(&u) && v.abs() < 0.1;
+    let teeth = (0.1..=0.4).contains(&v) && ((0.5..=0.62).contains(&u) || (0.75..=0.9).contains(&u));
+    bow || shaft || teeth
+}
@@ -0,0 +1,101 @@
+// Leaderboard for Backrooms Doom
+// Finished runs (time, score, the seed they were played on and whether it was a
+// hardcore run) are kept in a local top list. Builds with the
+// `online-leaderboard` feature can also submit them to a server, see online.rs
+
+use log::warn;
+use std::fs;
+use std::path::Path;
+
+pub const LEADERBOARD_PATH: &str = "leaderboard.txt";
+pub const MAX_ENTRIES: usize = 10;
+
+#[derive(Clone, Copy)]
+pub struct RunRecord {
+    pub seed: u64,
+    pub time: f32, // Seconds from start to exit
+    pub score: i32,
+    pub hardcore: bool, // Finished in hardcore mode
+}
+
+impl RunRecord {
+    /// Score from the time left on the clock and the health the player escaped with
+    pub fn new(seed: u64, time: f32, time_left: f32, health: i32, hardcore: bool) -> Self {
+        Self {
+            seed,
+            time,
+            score: (time_left * 10.0) as i32 + health,
+            hardcore,
+        }
+    }
+
+    /// "<seed> <time> <score>" with " hardcore" after it for hardcore runs, the format used
+    /// on disk and over the wire
+    pub fn to_line(self) -> String {
+        let line = format!("{} {:.2} {}", self.seed, self.time, self.score);
+        if self.hardcore {
+            line + " hardcore"
+        } else {
+            line
+        }
+    }
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let seed = parts.next()?.parse().ok()?;
+        let time = parts.next()?.parse().ok()?;
+        let score = parts.next()?.parse().ok()?;
+        let hardcore = parts.next() == Some("hardcore");
+        Some(Self { seed, time, score, hardcore })
+    }
+}
+
+/// Parse one record per line, skipping anything malformed
+pub fn parse_records(content: &str) -> Vec<RunRecord> {
+    content.lines().filter_map(RunRecord::parse).collect()
+}
+
+/// Best runs on this machine, fastest first
+pub struct Leaderboard {
+    pub entries: Vec<RunRecord>,
+}
+
+impl Leaderboard {
+    pub fn load(path: &str) -> Self {
+        let entries = if Path::new(path).exists() {
+            match fs::read_to_string(path) {
+                Ok(content) => parse_records(&content),
+                Err(e) => {
+                    warn!("Failed to read {}: {}", path, e);
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        let mut leaderboard = Self { entries };
+        leaderboard.sort();
+        leaderboard
+    }
+
+    /// Add a finished run. Returns its rank (1-based) if it made the list
+    pub fn add(&mut self, record: RunRecord) -> Option<usize> {
+        self.entries.push(record);
+        self.sort();
+        self.entries
+            .iter()
+            .position(|entry| entry.seed == record.seed && entry.time == record.time)
+            .map(|index| index + 1)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let content: String = self.entries.iter().map(|entry| entry.to_line() + "\n").collect();
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path, e))
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by(|a, b| a.time.total_cmp(&b.time));
+        self.entries.truncate(MAX_ENTRIES);
+    }
+}
@@ -0,0 +1,104 @@
+// Colored lights for Backrooms Doom
+// Lights are authored in the maze file, one line per light after the grid:
+//
+//   light 19,16 exit
+//   light 6,5 #ff2020 3.5
+//
+// The tile is x,y counted from 0 at the top-left corner (the light sits at its
+// center, wall tiles are fine for signs), then a color: #rrggbb or one of the
+// named ones (exit = sickly green, emergency = red, fluorescent = warm white),
+// then an optional radius in tiles. Walls and floor within the radius are
+// tinted towards the light's color while rendering, strongest at the center.
+// Light goes through walls: there are no shadows. Lights named `emergency` stay
+// on during blackouts (see blackout.rs).
+
+use raylib::prelude::Color;
+
+const DEFAULT_RADIUS: f32 = 4.0; // Tiles
+const MAX_RADIUS: f32 = 16.0;
+const STRENGTH: f32 = 0.7; // How far a surface at the light's center is pulled to its color
+const BOOST: f32 = 1.5;    // Lit surfaces get brighter in the light's color, not only recolored
+
+#[derive(Clone, Copy)]
+pub struct Light {
+    pub x: f32, // World position (tile center)
+    pub y: f32,
+    pub color: Color,
+    pub radius: f32,    // Tiles
+    pub intensity: f32, // 0.0 - 1.0, animated by blackouts
+    pub emergency: bool, // Stays on during blackouts
+}
+
+impl Light {
+    /// Parse the rest of a "light ..." line (without the keyword) into its tile coordinates
+    /// and the light, placed at the origin with its radius in tiles
+    pub fn parse(definition: &str) -> Result<(usize, usize, Light), String> {
+        let mut parts = definition.split_whitespace();
+        let position = parts.next().ok_or("a light needs a tile and a color")?;
+        let invalid_position = || format!("bad light position '{}', expected x,y", position);
+        let (x, y) = position.split_once(',').ok_or_else(invalid_position)?;
+        let x = x.parse::<usize>().map_err(|_| invalid_position())?;
+        let y = y.parse::<usize>().map_err(|_| invalid_position())?;
+
+        let name = parts.next().ok_or("a light needs a color")?;
+        let color = parse_color(name).ok_or_else(|| {
+            format!("bad light color '{}', expected #rrggbb, exit, emergency or fluorescent", name)
+        })?;
+
+        let radius = match parts.next() {
+            Some(radius) => radius
+                .parse::<f32>()
+                .ok()
+                .filter(|radius| *radius > 0.0 && *radius <= MAX_RADIUS)
+                .ok_or_else(|| format!("bad light radius '{}', expected 0-{} tiles", radius, MAX_RADIUS))?,
+            None => DEFAULT_RADIUS,
+        };
+        if let Some(extra) = parts.next() {
+            return Err(format!("unexpected '{}' after the light radius", extra));
+        }
+        let light = Light { x: 0.0, y: 0.0, color, radius, intensity: 1.0, emergency: name == "emergency" };
+        Ok((x, y, light))
+    }
+}
+
+/// Named light colors, or #rrggbb
+fn parse_color(value: &str) -> Option<Color> {
+    match value {
+        "exit" => Some(Color::new(120, 255, 110, 255)),
+        "emergency" => Some(Color::new(255, 40, 30, 255)),
+        "fluorescent" => Some(Color::new(255, 245, 200, 255)),
+        _ => {
+            let hex = value.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii())?;
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+            Some(Color::new(channel(0)?, channel(2)?, channel(4)?, 255))
+        }
+    }
+}
+
+/// Per-channel color multiplier at world position (x, y): the ambient brightness (1.0 = normal,
+/// less during blackouts) pulled towards the color of every light in range. None when that
+/// leaves the color unchanged
+pub fn tint_at(lights: &[Light], x: f32, y: f32, ambient: f32) -> Option<[f32; 3]> {
+    let mut tint = [ambient; 3];
+    let mut lit = ambient < 1.0;
+    for light in lights {
+        let distance = ((light.x - x).powi(2) + (light.y - y).powi(2)).sqrt();
+        if distance >= light.radius || light.intensity <= 0.0 {
+            continue;
+        }
+        let falloff = 1.0 - distance / light.radius;
+        let strength = falloff * falloff * STRENGTH * light.intensity;
+        let channels = [light.color.r, light.color.g, light.color.b];
+        for (tint, channel) in tint.iter_mut().zip(channels) {
+            *tint += (channel as f32 / 255.0 * BOOST - *tint) * strength;
+        }
+        lit = true;
+    }
+    lit.then_some(tint)
+}
+
+/// Apply a tint from `tint_at` to a rendered color
+pub fn apply(color: Color, tint: [f32; 3]) -> Color {
+    let channel = |value: u8, tint: f32| (value as f32 * tint).min(255.0) as u8;
+    Color::new(channel(color.r, tint[0]), channel(color.g, tint[1]), channel(color.b, tint[2]), color.a)
+}
@@ -0,0 +1,115 @@
+// Logger for Backrooms Doom
+// Implements the `log` facade, writing to the console and logs/backrooms.log
+// and keeping the most recent lines in memory for the in-game console
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "logs/backrooms.log";
+const RECENT_CAPACITY: usize = 200;
+
+struct GameLogger {
+    start: Instant,
+    file: Mutex<Option<File>>,
+    recent: Mutex<VecDeque<String>>,
+}
+
+static LOGGER: OnceLock<GameLogger> = OnceLock::new();
+
+impl Log for GameLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // Strip the crate prefix from the module path ("doom_proyect::audio" -> "audio")
+        let target = record.target().rsplit("::").next().unwrap_or(record.target());
+        let line = format!(
+            "[{:>8.3}s {:<5} {}] {}",
+            self.start.elapsed().as_secs_f32(),
+            record.level(),
+            target,
+            record.args()
+        );
+
+        // Console output (errors and warnings go to stderr)
+        if record.level() <= Level::Warn {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(ref mut f) = *file {
+                let _ = writeln!(f, "{}", line);
+            }
+        }
+
+        if let Ok(mut recent) = self.recent.lock() {
+            if recent.len() >= RECENT_CAPACITY {
+                recent.pop_front();
+            }
+            recent.push_back(line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(ref mut f) = *file {
+                let _ = f.flush();
+            }
+        }
+    }
+}
+
+/// Install the logger. `verbose` enables debug output from startup
+pub fn init(verbose: bool) {
+    let file = fs::create_dir_all(LOG_DIR)
+        .and_then(|_| OpenOptions::new().create(true).append(true).open(LOG_FILE))
+        .map_err(|e| eprintln!("Could not open {}: {}", LOG_FILE, e))
+        .ok();
+
+    let logger = LOGGER.get_or_init(|| GameLogger {
+        start: Instant::now(),
+        file: Mutex::new(file),
+        recent: Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY)),
+    });
+
+    if log::set_logger(logger).is_ok() {
+        set_level(if verbose { LevelFilter::Debug } else { LevelFilter::Info });
+    }
+}
+
+/// Change the log verbosity at runtime
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+/// Parse a level name as typed in the console ("off", "error", "warn", "info", "debug", "trace")
+pub fn parse_level(name: &str) -> Option<LevelFilter> {
+    name.parse().ok()
+}
+
+/// Get up to `count` of the most recent log lines (oldest first)
+pub fn recent_lines(count: usize) -> Vec<String> {
+    match LOGGER.get().and_then(|l| l.recent.lock().ok()) {
+        Some(recent) => recent.iter().skip(recent.len().saturating_sub(count)).cloned().collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Forget the lines shown in the console (the log file is untouched)
+pub fn clear_recent() {
+    if let Some(mut recent) = LOGGER.get().and_then(|l| l.recent.lock().ok()) {
+        recent.clear();
+    }
+}
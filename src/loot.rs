@@ -0,0 +1,146 @@
+// Loot tables for Backrooms Doom
+// Item drops for enemies, breakable props and containers are defined in
+// assets/loot.txt so the item economy can be tuned without recompiling. Pills drop as
+// pills; medkits, batteries and chalk lie on the floor until picked up into the inventory
+
+use crate::inventory::Item;
+use log::warn;
+use rand::Rng;
+use std::collections::HashMap;
+use std::fs;
+
+pub const LOOT_PATH: &str = "assets/loot.txt";
+
+/// One weighted outcome. item = None means "nothing drops"
+pub struct LootEntry {
+    pub item: Option<Item>,
+    pub weight: u32,
+    pub min: u32,
+    pub max: u32,
+}
+
+pub struct LootTable {
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    /// Pick one weighted entry and roll its quantity
+    pub fn roll(&self) -> Vec<Item> {
+        let total: u32 = self.entries.iter().map(|e| e.weight).sum();
+        if total == 0 {
+            return Vec::new();
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut roll = rng.gen_range(0..total);
+        for entry in &self.entries {
+            if roll < entry.weight {
+                let Some(item) = entry.item else {
+                    return Vec::new();
+                };
+                let count = rng.gen_range(entry.min..=entry.max);
+                return vec![item; count as usize];
+            }
+            roll -= entry.weight;
+        }
+        Vec::new()
+    }
+}
+
+pub struct LootTables {
+    tables: HashMap<String, LootTable>,
+}
+
+impl LootTables {
+    /// Parse "[table]" sections of "<item> <weight> [min-max]" lines
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut tables: HashMap<String, LootTable> = HashMap::new();
+        let mut current: Option<String> = None;
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                tables.entry(name.to_string()).or_insert(LootTable { entries: Vec::new() });
+                current = Some(name.to_string());
+                continue;
+            }
+
+            let Some(table) = current.as_ref().and_then(|name| tables.get_mut(name)) else {
+                return Err(format!("line {}: entry outside of a [table] section", line_number + 1));
+            };
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 2 || parts.len() > 3 {
+                return Err(format!("line {}: expected '<item> <weight> [min-max]', got '{}'", line_number + 1, line));
+            }
+
+            let item = match parts[0] {
+                "nothing" => None,
+                name => Some(
+                    Item::parse(name).ok_or_else(|| format!("line {}: unknown item '{}'", line_number + 1, name))?,
+                ),
+            };
+            let weight = parts[1]
+                .parse()
+                .map_err(|_| format!("line {}: invalid weight '{}'", line_number + 1, parts[1]))?;
+            let (min, max) = match parts.get(2) {
+                Some(range) => parse_range(range).ok_or_else(|| format!("line {}: invalid quantity '{}'", line_number + 1, range))?,
+                None => (1, 1),
+            };
+
+            table.entries.push(LootEntry { item, weight, min, max });
+        }
+
+        Ok(Self { tables })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Self::parse(&content).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Tables used when assets/loot.txt is missing or invalid
+    pub fn builtin() -> Self {
+        Self::parse(
+            "[enemy]\nnothing 50\nblue_pill 35\nred_pill 15\n\
+             [prop]\nnothing 60\nblue_pill 25\nred_pill 15\nbattery 10\n\
+             [desk]\nnothing 55\nblue_pill 30\nred_pill 15\nchalk 15\nmedkit 10\nbattery 10\n\
+             [cabinet]\nnothing 30\nblue_pill 45 1-2\nred_pill 25\nmedkit 20\nbattery 15\n",
+        )
+        .expect("built-in loot tables are valid")
+    }
+
+    pub fn load_or_builtin(path: &str) -> Self {
+        Self::load(path).unwrap_or_else(|e| {
+            warn!("{} - using built-in loot tables", e);
+            Self::builtin()
+        })
+    }
+
+    /// Roll a table by name (unknown tables drop nothing)
+    pub fn roll(&self, name: &str) -> Vec<Item> {
+        match self.tables.get(name) {
+            Some(table) => table.roll(),
+            None => {
+                warn!("Unknown loot table '{}'", name);
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// "2" or "1-3"
+fn parse_range(value: &str) -> Option<(u32, u32)> {
+    let (min, max) = match value.split_once('-') {
+        Some((min, max)) => (min.parse().ok()?, max.parse().ok()?),
+        None => {
+            let count = value.parse().ok()?;
+            (count, count)
+        }
+    };
+    (min <= max).then_some((min, max))
+}
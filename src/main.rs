@@ -1,166 +1,545 @@
+mod alert;
+mod assets;
+mod attract;
 mod audio;
+mod autodetect;
+mod barricade;
+mod benchmark;
+mod blackout;
+mod bot;
+mod budget;
 mod camera;
+mod campaign;
 mod caster;
+mod chalk;
+mod challenge;
+mod clock;
+mod color_grade;
+mod companion;
+mod config;
+mod container;
+mod contrast;
+mod console;
+mod controls;
+mod crash;
+mod credits;
+mod crosshair;
+mod crowd;
+mod death;
+mod decal;
+mod display;
+mod doors;
+mod doppler;
 mod effects;
 mod enemy;
+mod escape;
+mod escalation;
+mod events;
+mod exit_hum;
+mod flashlight;
+mod flythrough;
+mod footsteps;
+mod frame_pacing;
 mod framebuffer;
+mod gait;
 mod game;
+mod glitch;
+mod grime;
+mod golden;
+mod guard;
+mod haze;
+mod health;
+mod heatmap;
+mod hints;
+mod hud;
+mod idle;
+mod inventory;
+mod keys;
+mod leaderboard;
+mod light;
+mod logger;
+mod loot;
 mod maze;
+mod maze_fuzz;
 mod minimap;
+mod narration;
+mod noise;
+mod options;
+#[cfg(feature = "online-leaderboard")]
+mod online;
+mod packs;
+mod patrol;
+mod pathfinding;
 mod pill;
 mod player;
+mod profile;
+mod prop;
+mod radio;
+mod resources;
+mod reverb;
+mod rumble;
+mod save;
+mod schedule;
+mod search;
+mod splash;
 mod sprite;
+mod stealth;
+mod status;
+mod stress;
+mod tally;
+mod texture_grade;
 mod textures;
+mod timeline;
+mod tips;
+mod toast;
+mod tracks;
+mod translucent;
+mod tween;
+mod twitch;
 mod ui;
+mod versus;
+mod whisper;
 
+use assets::{AssetLoader, AssetManifest};
 use audio::AudioManager;
+use autodetect::Autodetect;
+use budget::Pool;
+use config::Config;
+use profile::{Profile, ProfilePicker};
+use frame_pacing::FramePacer;
 use game::{GameState, State};
+use narration::Narrator;
+use log::{error, info, warn};
 use raylib::prelude::*;
+use std::panic::{self, AssertUnwindSafe};
+use textures::TextureManager;
+use ui::UI;
 
 const SCREEN_WIDTH: usize = 640;
 const SCREEN_HEIGHT: usize = 480;
-const TARGET_FPS: u32 = 60;
 
 fn main() {
+    // Console + logs/backrooms.log output, --verbose enables debug messages
+    let verbose = std::env::args().any(|arg| arg == "--verbose" || arg == "-v");
+    logger::init(verbose);
+
+    // Headless developer tests (input stress test, golden images, maze fuzzing), no window needed
+    let args: Vec<String> = std::env::args().collect();
+    guard::init_from_args(&args);
+    let test_result = stress::run_from_args(&args)
+        .or_else(|| bot::run_from_args(&args))
+        .or_else(|| golden::run_from_args(&args))
+        .or_else(|| maze_fuzz::run_from_args(&args));
+    if let Some(passed) = test_result {
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    // Record panic details for the crash log
+    crash::install_panic_hook();
+
+    // The window opens with the last profile's settings, the one picked below takes over from there
+    let config = match Profile::last_used() {
+        Some(profile) => Config::load_or_default(&profile.file(config::CONFIG_PATH)),
+        None => Config::default(),
+    };
+
     // Initialize raylib
-    let (mut rl, thread) = raylib::init()
+    let mut builder = raylib::init();
+    builder
         .size(SCREEN_WIDTH as i32, SCREEN_HEIGHT as i32)
-        .title("Backrooms Doom - Raycaster")
-        .build();
-
-    // Initialize audio device
-    let audio = match RaylibAudio::init_audio_device() {
-        Ok(a) => a,
-        Err(e) => {
-            eprintln!("Failed to initialize audio: {:?}", e);
-            eprintln!("Continuing without audio...");
-            return;
-        }
+        .title("Backrooms Doom - Raycaster");
+    if config.vsync {
+        builder.vsync();
+    }
+    let (mut rl, thread) = builder.build();
+
+    // Initialize audio device (optional, the audio manager keeps trying to open one)
+    let audio = audio::open_device();
+    match audio {
+        Some(_) => info!("Audio device initialized"),
+        None => warn!("No audio device, continuing without audio"),
+    }
+
+    // Pick or create the player's profile, its settings replace the startup ones
+    // (the startup ones decide whether the profile screen is read aloud)
+    let ui = UI::new(24);
+    let narrator = Narrator::from_setting(&config.narration);
+    let Some(profile) = choose_profile(&mut rl, &thread, &ui, narrator) else {
+        return; // Window closed on the profile screen
     };
-    audio.set_master_volume(1.0);
-    println!("✓ Audio device initialized");
+    profile.remember();
+    info!("Playing as profile '{}'", profile.name);
+    let config_path = profile.file(config::CONFIG_PATH);
+    let first_run = !std::path::Path::new(&config_path).exists();
+    let (config, config_problems) = Config::load_checked(&config_path);
 
-    // Set target FPS
-    rl.set_target_fps(TARGET_FPS);
+    // Apply VSync and FPS cap from the settings
+    FramePacer::apply(&mut rl, &config);
+    let mut pacer = FramePacer::new();
 
     // Hide cursor for immersive experience
     rl.hide_cursor();
 
-    // Initialize game state with audio
-    let mut game = match GameState::new(SCREEN_WIDTH, SCREEN_HEIGHT, &audio) {
-        Ok(g) => g,
-        Err(e) => {
-            eprintln!("Failed to initialize game: {}", e);
-            return;
+    // Initialize game state with audio (show the error screen until it loads or the player quits)
+    let mut game = loop {
+        let (textures, audio_manager) = match preload_assets(&mut rl, &thread, &ui, audio, &config.resource_pack) {
+            Some(assets) => assets,
+            None => return, // Window closed while loading
+        };
+
+        match GameState::new(SCREEN_WIDTH, SCREEN_HEIGHT, textures, audio_manager, config.clone(), profile.clone()) {
+            Ok(g) => break g,
+            Err(e) => {
+                error!("Failed to initialize game: {}", e);
+                if !wait_for_retry(&mut rl, &thread, &ui, &e) {
+                    return;
+                }
+            }
         }
     };
 
+    // Switch to the saved display mode / monitor, or pick one for this machine on first run
+    if first_run {
+        detect_defaults(&mut rl, &thread, &ui, &mut game);
+    } else {
+        game.apply_display(&mut rl);
+    }
+
+    // Settings that weren't accepted are reported before anything else (they use their defaults)
+    if !config_problems.is_empty() {
+        game.show_settings_problems(&config_path, &config_problems);
+    }
+
     let mut last_state = game.state;
 
-    // Play menu music on startup
-    game.audio.play_menu_music();
+    // Play menu music on startup (after the intro splash when it's shown)
+    if game.state == State::Menu {
+        game.audio.play_menu_music();
+    }
 
     // Main game loop
     while !rl.window_should_close() {
-        let delta_time = rl.get_frame_time();
-
-        // Handle state transitions for audio
-        if game.state != last_state {
-            match game.state {
-                State::Playing => {
-                    // Start ambient music when gameplay begins
-                    game.audio.play_background_music();
-                }
-                State::Victory => {
-                    // Stop all music and play victory sound
-                    game.audio.stop_music();
-                    game.audio.play_victory();
-                }
-                State::GameOver => {
-                    // Stop all music when game over
-                    game.audio.stop_music();
-                    // Could add a game over sound here if you have one
-                }
-                State::Menu => {
-                    // Stop gameplay music and play menu music
-                    game.audio.stop_music();
-                    game.audio.play_menu_music();
-                }
-            }
-            last_state = game.state;
+        // Catch panics so we can autosave and leave a crash log before exiting
+        let frame = panic::catch_unwind(AssertUnwindSafe(|| {
+            run_frame(&mut rl, &thread, &mut game, &mut last_state);
+        }));
+
+        if frame.is_err() {
+            handle_crash(&game);
+            break;
         }
 
-        // Update music stream
-        game.audio.update_music();
+        pacer.wait(&game.config);
 
-        // Update music volume based on distance to goal (dynamic volume)
-        if game.state == State::Playing {
-            let dx = game.player.pos.x - game.maze.goal_pos.0;
-            let dy = game.player.pos.y - game.maze.goal_pos.1;
-            let distance_to_goal = (dx * dx + dy * dy).sqrt();
-            game.audio.update_ambient_volume(distance_to_goal);
+        // Throttle to ~10 FPS while in the background
+        if !rl.is_window_focused() {
+            rl.wait_time(0.1);
         }
+    }
+}
 
-        // Play footstep sounds only when moving
-        if game.state == State::Playing {
-            let is_moving = rl.is_key_down(KeyboardKey::KEY_W)
-                || rl.is_key_down(KeyboardKey::KEY_S)
-                || rl.is_key_down(KeyboardKey::KEY_A)
-                || rl.is_key_down(KeyboardKey::KEY_D);
-
-            if is_moving {
-                game.audio.play_footstep(delta_time);
-            } else {
-                // Reset timer and stop sound when not moving
-                game.audio.reset_footstep_timer();
-                game.audio.stop_footstep();
+/// Update and render a single frame
+fn run_frame(rl: &mut RaylibHandle, thread: &RaylibThread, game: &mut GameState, last_state: &mut State) {
+    let delta_time = guard::finite_or(rl.get_frame_time(), 0.0).max(0.0);
+
+    // Re-layout after display mode, monitor or window size changes
+    let screen_size = (rl.get_screen_width(), rl.get_screen_height());
+    if screen_size != game.screen_size {
+        game.relayout(screen_size.0, screen_size.1);
+    }
+
+    // Handle state transitions for audio
+    if game.state != *last_state {
+        match game.state {
+            State::Playing => {
+                // Start ambient music when gameplay begins
+                game.audio.play_background_music();
+            }
+            State::Victory => {
+                // Stop all music and play victory sound
+                game.audio.stop_music();
+                game.audio.play_victory();
+            }
+            State::GameOver => {
+                // Stop all music when game over
+                game.audio.stop_music();
+                // Could add a game over sound here if you have one
+            }
+            State::Menu => {
+                // Stop gameplay music and play menu music
+                game.audio.stop_music();
+                game.audio.play_menu_music();
+            }
+            State::Splash => {
+                // Silent apart from the logo sting
+            }
+            State::LevelComplete => {
+                // The ambient music keeps playing over the level summary
+            }
+            State::Demo => {
+                // The demo plays to the level's ambient music
+                game.audio.stop_music();
+                game.audio.play_background_music();
+            }
+            State::Escaping => {
+                // The music cuts out as the exit opens, the victory sting follows
+                game.audio.stop_music();
+            }
+            State::Leaderboard | State::Credits | State::Options => {
+                // Menu music keeps playing (after the victory sting the credits roll in silence)
+            }
+            State::Error | State::Benchmark => {
+                // Silence music while the error or benchmark is shown
+                game.audio.stop_music();
             }
         }
+        *last_state = game.state;
+    }
+
+    // Update music stream, intensity follows the enemy alert level (see alert.rs)
+    game.audio.watch_device(delta_time);
+    game.audio.update_music();
+    let (alert, blackout) = if game.state == State::Playing { (game.alerts.intensity(), game.blackout.level()) } else { (0.0, 0.0) };
+    game.audio.update_music_intensity(alert, blackout, delta_time);
 
-        // Store previous anxiety intensity to detect triggers
-        let prev_anxiety = game.effects.anxiety_intensity;
+    // Hot/cold exit hum (see exit_hum.rs)
+    let hum_playback = if game.state == State::Playing && !game.is_focus_paused() && game.exit_hum_enabled() {
+        game.exit_hum.playback()
+    } else {
+        None
+    };
+    game.audio.update_hum(hum_playback);
+    game.audio.update_whisper(game.whisper_playback());
+    game.audio.set_reverb(game.reverb());
+    game.audio.update_echoes(delta_time);
 
-        // Update game state
-        game.update(&rl, delta_time);
+    // Store previous anxiety intensity to detect triggers
+    let prev_anxiety = game.effects.anxiety_intensity;
 
-        // Check if anxiety effect was just triggered (idle penalty)
-        if game.state == State::Playing && game.effects.anxiety_intensity > prev_anxiety && prev_anxiety == 0.0 {
-            // Play heartbeat sound when anxiety effect triggers
-            game.audio.play_heartbeat();
+    // Update game state
+    game.update(rl, delta_time);
+    game.narrate();
+
+    // Footsteps land with the view bob (see gait.rs), and stop when the player does
+    if game.state == State::Playing && !game.is_focus_paused() {
+        if game.gait.stepped() {
+            game.audio.play_footstep();
+            game.haptics.footstep(game.gait.strength());
+        } else if !game.gait.walking() {
+            game.audio.stop_footstep();
         }
+    }
 
-        // Render
-        let mut d = rl.begin_drawing(&thread);
-        d.clear_background(Color::BLACK);
-
-        game.render(&mut d);
-
-        // Debug info (optional - can be toggled with F3)
-        if d.is_key_down(KeyboardKey::KEY_F3) {
-            d.draw_text(
-                &format!(
-                    "Player Pos: ({:.2}, {:.2})",
-                    game.player.pos.x, game.player.pos.y
-                ),
-                10,
-                SCREEN_HEIGHT as i32 - 60,
-                16,
-                Color::YELLOW,
-            );
-            d.draw_text(
-                &format!("Player Angle: {:.2}°", game.player.angle.to_degrees()),
-                10,
-                SCREEN_HEIGHT as i32 - 40,
-                16,
-                Color::YELLOW,
-            );
-            d.draw_text(
-                &format!("Delta Time: {:.4}s", delta_time),
-                10,
-                SCREEN_HEIGHT as i32 - 20,
-                16,
-                Color::YELLOW,
-            );
+    // Enemy footsteps, the loudest one this frame (see footsteps.rs)
+    if let Some(step) = game.enemy_step.take() {
+        game.audio.play_enemy_step(step.volume, step.pan, step.pitch);
+    }
+
+    // Check if anxiety effect was just triggered (idle penalty)
+    if game.state == State::Playing && game.effects.anxiety_intensity > prev_anxiety && prev_anxiety == 0.0 {
+        // Play heartbeat sound when anxiety effect triggers
+        game.audio.play_heartbeat();
+    }
+
+    // Controller rumble for the heartbeat and nearby entity footsteps (damage and door
+    // slams are queued with their sounds in GameState::handle_events)
+    if *last_state == State::Playing {
+        let closest = game.closest_walking_enemy();
+        game.haptics.update_heartbeat(game.effects.anxiety_intensity, delta_time);
+        game.haptics.update_footsteps(closest, delta_time);
+    }
+    let rumble_active = game.config.rumble && game.state == State::Playing && !game.is_focus_paused();
+    game.haptics.update(rl, delta_time, rumble_active);
+
+    // Keep the placed radio looping, panned to where it is relative to the player
+    let radio_playback = game.radio_playback();
+    game.audio.update_radio(radio_playback);
+
+    // Render
+    game.framebuffer.prepare(rl, thread);
+    let mut d = rl.begin_drawing(thread);
+    d.clear_background(Color::BLACK);
+
+    game.render(&mut d);
+
+    // Debug info (optional - can be toggled with F3)
+    if d.is_key_down(KeyboardKey::KEY_F3) {
+        let screen_height = d.get_screen_height();
+        let caps: Vec<String> = Pool::ALL
+            .iter()
+            .map(|&pool| format!("{} {}", pool.name(), game.budget.cap(pool)))
+            .collect();
+        if let Some(bot) = &game.bot {
+            let goal = bot.goal.map_or("nothing reachable".to_string(), |goal| format!("{:?}", goal));
+            d.draw_text(&format!("Bot: heading for {}", goal), 10, screen_height - 140, 16, Color::YELLOW);
         }
+        d.draw_text(
+            &format!("Entity Budget: {:.0}% ({})", game.budget.scale() * 100.0, caps.join(", ")),
+            10,
+            screen_height - 120,
+            16,
+            Color::YELLOW,
+        );
+        d.draw_text(
+            &format!(
+                "VSync: {}  FPS Cap: {}  Pacing: {}",
+                if game.config.vsync { "on" } else { "off" },
+                game.config.fps_cap.name(),
+                game.config.frame_pacing.name()
+            ),
+            10,
+            screen_height - 100,
+            16,
+            Color::YELLOW,
+        );
+        d.draw_text(
+            &format!(
+                "Texture Memory: {} KB / {} KB ({} textures)",
+                game.textures.memory_bytes() / 1024,
+                textures::TEXTURE_BUDGET_BYTES / 1024,
+                game.textures.textures.loaded_count()
+            ),
+            10,
+            screen_height - 80,
+            16,
+            Color::YELLOW,
+        );
+        d.draw_text(
+            &format!(
+                "Player Pos: ({:.2}, {:.2})",
+                game.player().pos.x, game.player().pos.y
+            ),
+            10,
+            screen_height - 60,
+            16,
+            Color::YELLOW,
+        );
+        d.draw_text(
+            &format!("Player Angle: {:.2}°", game.player().angle.to_degrees()),
+            10,
+            screen_height - 40,
+            16,
+            Color::YELLOW,
+        );
+        d.draw_text(
+            &format!("Delta Time: {:.4}s", delta_time),
+            10,
+            screen_height - 20,
+            16,
+            Color::YELLOW,
+        );
+    }
+}
+
+/// Load every asset in the manifest while showing the loading screen.
+/// Returns None if the window was closed before loading finished
+fn preload_assets(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    ui: &UI,
+    audio: Option<&'static RaylibAudio>,
+    pack: &str,
+) -> Option<(TextureManager, AudioManager<'static>)> {
+    let manifest = packs::apply(pack, AssetManifest::load_or_builtin(assets::MANIFEST_PATH, "default"));
+    let mut loader = AssetLoader::start(&manifest);
+    let mut audio_manager = AudioManager::new(audio);
+
+    while !loader.is_done() {
+        if rl.window_should_close() {
+            return None;
+        }
+
+        loader.poll(&mut audio_manager);
+
+        let mut d = rl.begin_drawing(thread);
+        let screen_width = d.get_screen_width();
+        let screen_height = d.get_screen_height();
+        ui.render_loading(&mut d, screen_width, screen_height, loader.progress(), &loader.current_item);
+    }
+
+    for name in audio_manager.sounds.missing() {
+        warn!("Sound '{}' is not loaded and will be silent", name);
+    }
+
+    // Very small textures for maximum performance when falling back to procedural ones
+    let textures = TextureManager::from_loaded(64, loader.textures, loader.texture_errors);
+    Some((textures, audio_manager))
+}
+
+/// First run of a profile: pick the display mode from the monitor and the quality preset
+/// from a short render benchmark, then save them (see autodetect.rs)
+fn detect_defaults(rl: &mut RaylibHandle, thread: &RaylibThread, ui: &UI, game: &mut GameState) {
+    let monitor = get_current_monitor();
+    let monitor_size = (get_monitor_width(monitor), get_monitor_height(monitor));
+    game.config.display_mode = autodetect::display_mode_for(monitor_size, game.base_resolution);
+    game.apply_display(rl);
+    game.relayout(rl.get_screen_width(), rl.get_screen_height());
+    info!("First run: monitor {}x{}, display mode {}", monitor_size.0, monitor_size.1, game.config.display_mode.name());
+
+    let mut detect = Autodetect::new(&game.maze);
+    while let Some(preset) = detect.current_preset() {
+        if rl.window_should_close() {
+            return;
+        }
+
+        let render_time = game.time_render(preset, detect.camera());
+        detect.record(render_time, rl.get_frame_time());
+
+        let mut d = rl.begin_drawing(thread);
+        let screen_width = d.get_screen_width();
+        let screen_height = d.get_screen_height();
+        ui.render_loading(&mut d, screen_width, screen_height, detect.progress(), "Tuning graphics for this machine");
+    }
+    game.finish_detection(detect.recommended());
+}
+
+/// Show the startup error screen. Returns true if the player asked to retry,
+/// false if the window was closed
+fn wait_for_retry(rl: &mut RaylibHandle, thread: &RaylibThread, ui: &UI, message: &str) -> bool {
+    while !rl.window_should_close() {
+        if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            return true;
+        }
+
+        let mut d = rl.begin_drawing(thread);
+        let screen_width = d.get_screen_width();
+        let screen_height = d.get_screen_height();
+        ui.render_error(&mut d, screen_width, screen_height, message, "ENTER - Retry    ESC - Quit");
+    }
+    false
+}
+
+/// Show the profile screen until a profile is picked or created.
+/// Returns None if the window was closed first
+fn choose_profile(rl: &mut RaylibHandle, thread: &RaylibThread, ui: &UI, mut narrator: Narrator) -> Option<Profile> {
+    let mut picker = ProfilePicker::new();
+    while !rl.window_should_close() {
+        if let Some(profile) = picker.update(rl) {
+            return Some(profile);
+        }
+        narrator.update("Choose a profile. UP and DOWN to select, ENTER to play", Some(&picker.focus_label()));
+
+        let mut d = rl.begin_drawing(thread);
+        let screen_width = d.get_screen_width();
+        let screen_height = d.get_screen_height();
+        ui.render_profiles(&mut d, screen_width, screen_height, &picker);
+    }
+    None
+}
+
+/// Save an autosave and a crash log after a panic
+fn handle_crash(game: &GameState) {
+    let report = crash::take_last_panic().unwrap_or_else(|| "unknown panic".to_string());
+
+    let path = game.profile.file(save::AUTOSAVE_PATH);
+    match game.autosave(&path) {
+        Ok(()) => info!("Autosave written to {}", path),
+        Err(e) => error!("Failed to write autosave: {}", e),
+    }
+
+    match crash::write_crash_log(&report, &game.crash_context()) {
+        Ok(path) => info!("Crash log written to {}", path.display()),
+        Err(e) => error!("Failed to write crash log: {}", e),
     }
+    log::logger().flush();
 }
@@ -1,5 +1,37 @@
+use crate::blackout::BlackoutEvent;
+use crate::challenge::{ChallengeRoom, Trial};
+use crate::escalation::{Escalate, EscalationStep};
+use crate::glitch::GlitchZone;
+use crate::haze::Haze;
+use crate::light::Light;
+use crate::patrol::{Patrol, Waypoint};
+use crate::texture_grade::TextureGrade;
+use crate::translucent::Translucent;
+use log::warn;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::VecDeque;
 use std::fs;
 
+// Limits that keep a broken or hostile maze file from eating memory
+const MAX_FILE_BYTES: usize = 1024 * 1024;
+const MAX_DIMENSION: usize = 512; // Tiles per row / rows
+
+// Every tile a maze file may contain (' ' is legacy floor, same as '.')
+const KNOWN_TILES: &str = "#. SEDpebdcKBT/~G%";
+
+// Keywords of the lines after the grid that aren't part of it
+const DIRECTIVES: [&str; 8] = ["patrol", "light", "blackout", "haze", "glitch", "escalate", "grade", "challenge"];
+
+// Generated mazes (see Maze::generate)
+const MIN_GENERATED: usize = 7;   // Smallest side, in tiles
+const LOOP_CHANCE: f64 = 0.12;    // Share of inner walls knocked out so corridors loop
+const TILES_PER_PILL: usize = 25; // Floor tiles per pill
+const TILES_PER_ENEMY: usize = 60;
+const PILL_MIN_STEPS: usize = 4;   // Closest a pill is placed to the start
+const ENEMY_MIN_STEPS: usize = 10; // Closest an enemy is placed to the start
+
 pub struct Maze {
     pub map: Vec<Vec<char>>,
     pub width: usize,
@@ -8,24 +40,54 @@ pub struct Maze {
     pub start_pos: (f32, f32),
     pub goal_pos: (f32, f32),
     pub pill_positions: Vec<(f32, f32)>, // Positions where 'p' was found
+    pub enemy_positions: Vec<(f32, f32)>, // Positions where 'e' was found
+    pub prop_positions: Vec<(f32, f32)>,  // Positions where 'b' (breakable box) was found
+    pub desk_positions: Vec<(f32, f32)>,  // Positions where 'd' was found
+    pub cabinet_positions: Vec<(f32, f32)>, // Positions where 'c' was found
+    pub key_positions: Vec<(f32, f32)>,     // Positions where 'K' was found (the exit needs them all)
+    pub battery_positions: Vec<(f32, f32)>, // Positions where 'B' (flashlight battery) was found
+    pub clock_positions: Vec<(f32, f32)>,   // Positions where 'T' (clock, time back on the timer) was found
+    pub patrols: Vec<Patrol>, // "patrol" lines after the grid, each starting at an 'e'
+    pub lights: Vec<Light>,   // "light" lines after the grid
+    pub blackouts: Vec<BlackoutEvent>, // "blackout" lines after the grid
+    pub hazes: Vec<Haze>,              // "haze" lines after the grid
+    pub glitch_zones: Vec<GlitchZone>, // "glitch" lines after the grid
+    pub escalation: Vec<EscalationStep>, // "escalate" lines after the grid
+    pub texture_grade: Option<TextureGrade>, // "grade" line after the grid
+    pub challenges: Vec<ChallengeRoom>,      // "challenge" lines after the grid
+    pub skipped: Vec<String>,                // Directive lines left out as invalid, and why
 }
 
 impl Maze {
     /// Load maze from file (e.g., "maze.txt")
     pub fn load_from_file(path: &str, tile_size: f32) -> Result<Self, String> {
-        let content = fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read maze file: {}", e))?;
-
-        let mut map: Vec<Vec<char>> = content
-            .lines()
-            .filter(|line| !line.is_empty())
-            .map(|line| line.chars().collect())
-            .collect();
+        let bytes = fs::read(path).map_err(|e| format!("Failed to read maze file {}: {}", path, e))?;
+        let maze = Self::from_bytes(&bytes, tile_size).map_err(|e| format!("Invalid maze file {}: {}", path, e))?;
+        for problem in &maze.skipped {
+            warn!("{}: {}, line skipped", path, problem);
+        }
+        Ok(maze)
+    }
 
-        if map.is_empty() {
-            return Err("Maze file is empty".to_string());
+    /// Build a maze from the raw contents of a maze file, rejecting a malformed grid. Invalid
+    /// directive lines are left out and listed in `skipped` instead
+    pub fn from_bytes(bytes: &[u8], tile_size: f32) -> Result<Self, String> {
+        if bytes.len() > MAX_FILE_BYTES {
+            return Err(format!("file is {} KB, the limit is {} KB", bytes.len() / 1024, MAX_FILE_BYTES / 1024));
         }
+        let content = std::str::from_utf8(bytes).map_err(|e| {
+            // The text before the bad byte is valid, so its lines give the position
+            let valid = std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default();
+            let line = valid.matches('\n').count() + 1;
+            let column = valid.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+            format!("line {}, column {}: not valid UTF-8 text", line, column)
+        })?;
+        Self::parse(content, tile_size)
+    }
 
+    /// Build a maze from the text of a maze file
+    pub fn parse(content: &str, tile_size: f32) -> Result<Self, String> {
+        let mut map = Self::parse_rows(content)?;
         let height = map.len();
         let width = map[0].len();
 
@@ -33,6 +95,13 @@ impl Maze {
         let mut start_pos = (1.5 * tile_size, 1.5 * tile_size);
         let mut goal_pos = (1.5 * tile_size, 1.5 * tile_size);
         let mut pill_positions = Vec::new();
+        let mut enemy_positions = Vec::new();
+        let mut prop_positions = Vec::new();
+        let mut desk_positions = Vec::new();
+        let mut cabinet_positions = Vec::new();
+        let mut key_positions = Vec::new();
+        let mut battery_positions = Vec::new();
+        let mut clock_positions = Vec::new();
 
         for (y, row) in map.iter_mut().enumerate() {
             for (x, tile) in row.iter_mut().enumerate() {
@@ -45,11 +114,60 @@ impl Maze {
                     pill_positions.push(((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size));
                     // Replace 'p' with '.' so it's walkable
                     *tile = '.';
+                } else if *tile == 'e' {
+                    // Enemy spawn location, walkable like 'p'
+                    enemy_positions.push(((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size));
+                    *tile = '.';
+                } else if *tile == 'b' {
+                    // Breakable box, walkable like 'p'
+                    prop_positions.push(((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size));
+                    *tile = '.';
+                } else if *tile == 'd' || *tile == 'c' {
+                    // Searchable desk / filing cabinet, walkable like 'p'
+                    let center = ((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size);
+                    if *tile == 'd' {
+                        desk_positions.push(center);
+                    } else {
+                        cabinet_positions.push(center);
+                    }
+                    *tile = '.';
+                } else if *tile == 'K' {
+                    // Key to the exit, walkable like 'p'
+                    key_positions.push(((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size));
+                    *tile = '.';
+                } else if *tile == 'B' {
+                    // Flashlight battery, walkable like 'p'
+                    battery_positions.push(((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size));
+                    *tile = '.';
+                } else if *tile == 'T' {
+                    // Clock pickup, walkable like 'p'
+                    clock_positions.push(((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size));
+                    *tile = '.';
                 }
             }
         }
 
+        // A bad directive line only loses that line, the level still loads
+        let mut skipped = Vec::new();
+        let patrols = Self::parse_patrols(content, &map, tile_size, &mut skipped);
+        let lights = Self::parse_lights(content, width, height, tile_size, &mut skipped);
+        let blackouts = Self::parse_blackouts(content, &mut skipped);
+        let hazes = Self::parse_hazes(content, width, height, tile_size, &mut skipped);
+        let glitch_zones = Self::parse_glitch_zones(content, width, height, &mut skipped);
+        let escalation = Self::parse_escalation(content, &map, tile_size, &mut skipped);
+        let texture_grade = Self::parse_texture_grade(content, &mut skipped);
+        let challenges = Self::parse_challenges(content, &map, &mut skipped);
+
         Ok(Maze {
+            challenges,
+            skipped,
+            texture_grade,
+            glitch_zones,
+            escalation,
+            patrols,
+            lights,
+            blackouts,
+            hazes,
             map,
             width,
             height,
@@ -57,9 +175,442 @@ impl Maze {
             start_pos,
             goal_pos,
             pill_positions,
+            enemy_positions,
+            prop_positions,
+            desk_positions,
+            cabinet_positions,
+            key_positions,
+            battery_positions,
+            clock_positions,
         })
     }
 
+    /// Generate a maze of about width x height tiles (rounded down to odd sides, at least
+    /// 7) from `seed`, the same seed always giving the same maze. Corridors are carved with
+    /// a recursive backtracker and then opened into loops here and there, the start goes
+    /// in the top-left corner, the exit in the outer wall as far from it as the corridors
+    /// go, and pills and enemies on floor tiles picked by the seed
+    pub fn generate(width: usize, height: usize, seed: u64, tile_size: f32) -> Self {
+        let odd = |side: usize| (side.clamp(MIN_GENERATED, MAX_DIMENSION) - 1) / 2 * 2 + 1;
+        let (width, height) = (odd(width), odd(height));
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut grid = vec![vec!['#'; width]; height];
+
+        // Carve between the odd cells, backtracking out of dead ends
+        let mut stack = vec![(1, 1)];
+        grid[1][1] = '.';
+        while let Some(&(x, y)) = stack.last() {
+            let unvisited: Vec<(usize, usize)> = [(2, 0), (-2, 0), (0, 2), (0, -2)]
+                .iter()
+                .map(|&(dx, dy)| (x as i32 + dx, y as i32 + dy))
+                .filter(|&(nx, ny)| nx > 0 && ny > 0 && nx < width as i32 - 1 && ny < height as i32 - 1)
+                .map(|(nx, ny)| (nx as usize, ny as usize))
+                .filter(|&(nx, ny)| grid[ny][nx] == '#')
+                .collect();
+            match unvisited.choose(&mut rng) {
+                Some(&(nx, ny)) => {
+                    grid[(y + ny) / 2][(x + nx) / 2] = '.';
+                    grid[ny][nx] = '.';
+                    stack.push((nx, ny));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+
+        // The Backrooms aren't a perfect maze: open some walls between two corridors
+        for (y, row) in grid.iter_mut().enumerate().take(height - 1).skip(1) {
+            for (x, tile) in row.iter_mut().enumerate().take(width - 1).skip(1) {
+                let between_x = x % 2 == 0 && y % 2 == 1;
+                let between_y = x % 2 == 1 && y % 2 == 0;
+                if *tile == '#' && (between_x || between_y) && rng.gen_bool(LOOP_CHANCE) {
+                    *tile = '.';
+                }
+            }
+        }
+
+        // Steps from the start to every floor tile
+        let mut steps = vec![vec![None; width]; height];
+        let mut queue = VecDeque::from([(1, 1)]);
+        steps[1][1] = Some(0);
+        while let Some((x, y)) = queue.pop_front() {
+            let step = steps[y][x].unwrap_or(0);
+            for (nx, ny) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                if grid[ny][nx] == '.' && steps[ny][nx].is_none() {
+                    steps[ny][nx] = Some(step + 1);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        // Exit in the outer wall next to the farthest floor tile along it
+        let mut exit = (0, 1);
+        let mut farthest = 0;
+        for (y, row) in steps.iter().enumerate().take(height - 1).skip(1) {
+            for (x, &step) in row.iter().enumerate().take(width - 1).skip(1) {
+                let Some(step) = step else {
+                    continue;
+                };
+                let wall = if x == width - 2 {
+                    (x + 1, y)
+                } else if y == height - 2 {
+                    (x, y + 1)
+                } else if x == 1 {
+                    (0, y)
+                } else if y == 1 {
+                    (x, 0)
+                } else {
+                    continue;
+                };
+                if step > farthest {
+                    farthest = step;
+                    exit = wall;
+                }
+            }
+        }
+        grid[exit.1][exit.0] = 'E';
+        grid[1][1] = 'S';
+
+        // Pills and enemies on floor tiles far enough from the start
+        let mut floor: Vec<(usize, usize, usize)> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid[y][x] == '.')
+            .filter_map(|(x, y)| Some((x, y, steps[y][x]?)))
+            .collect();
+        floor.shuffle(&mut rng);
+        let pills = floor.len() / TILES_PER_PILL;
+        let enemies = floor.len() / TILES_PER_ENEMY;
+        for &(x, y, _) in floor.iter().filter(|&&(_, _, step)| step >= PILL_MIN_STEPS).take(pills) {
+            grid[y][x] = 'p';
+        }
+        let enemy_tiles: Vec<(usize, usize)> = floor
+            .iter()
+            .filter(|&&(x, y, step)| step >= ENEMY_MIN_STEPS && grid[y][x] == '.')
+            .map(|&(x, y, _)| (x, y))
+            .take(enemies)
+            .collect();
+        for (x, y) in enemy_tiles {
+            grid[y][x] = 'e';
+        }
+
+        let content: String = grid.iter().map(|row| row.iter().collect::<String>() + "\n").collect();
+        Self::parse(&content, tile_size).expect("generated mazes are always valid")
+    }
+
+    /// Read the "patrol x,y x,y:pause ..." lines (see patrol.rs). Every waypoint has to be a
+    /// walkable tile and the first one an enemy spawn
+    fn parse_patrols(content: &str, map: &[Vec<char>], tile_size: f32, skipped: &mut Vec<String>) -> Vec<Patrol> {
+        let mut patrols = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let Some(waypoints) = line.trim().strip_prefix("patrol") else {
+                continue;
+            };
+            let line_number = index + 1;
+            let points = match Patrol::parse(waypoints) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    skipped.push(format!("line {}: {}", line_number, e));
+                    continue;
+                }
+            };
+
+            let blocked = points.iter().find(|&&(x, y, _)| matches!(map.get(y).and_then(|row| row.get(x)), Some('#' | 'E' | 'D' | 'G') | None));
+            if let Some(&(x, y, _)) = blocked {
+                skipped.push(format!("line {}: waypoint {},{} is not a walkable tile", line_number, x, y));
+                continue;
+            }
+            let (x, y, _) = points[0];
+            // Spawn tiles were turned into floor, so look at the original row
+            if !Self::is_enemy_spawn(content, x, y) {
+                skipped.push(format!("line {}: a patrol has to start on an enemy 'e', {},{} isn't one", line_number, x, y));
+                continue;
+            }
+
+            let waypoints = points
+                .into_iter()
+                .map(|(x, y, pause)| Waypoint {
+                    x: (x as f32 + 0.5) * tile_size,
+                    y: (y as f32 + 0.5) * tile_size,
+                    pause,
+                })
+                .collect();
+            patrols.push(Patrol { waypoints });
+        }
+        patrols
+    }
+
+    /// Read the "light x,y color [radius]" lines (see light.rs). Any tile inside the maze will do
+    fn parse_lights(content: &str, width: usize, height: usize, tile_size: f32, skipped: &mut Vec<String>) -> Vec<Light> {
+        let mut lights = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let Some(definition) = line.trim().strip_prefix("light") else {
+                continue;
+            };
+            let line_number = index + 1;
+            let (x, y, light) = match Light::parse(definition) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    skipped.push(format!("line {}: {}", line_number, e));
+                    continue;
+                }
+            };
+            if x >= width || y >= height {
+                skipped.push(format!("line {}: light {},{} is outside the maze", line_number, x, y));
+                continue;
+            }
+            lights.push(Light {
+                x: (x as f32 + 0.5) * tile_size,
+                y: (y as f32 + 0.5) * tile_size,
+                radius: light.radius * tile_size,
+                ..light
+            });
+        }
+        lights
+    }
+
+    /// Read the "haze x,y kind [radius]" lines (see haze.rs)
+    fn parse_hazes(content: &str, width: usize, height: usize, tile_size: f32, skipped: &mut Vec<String>) -> Vec<Haze> {
+        let mut hazes = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let Some(definition) = line.trim().strip_prefix("haze") else {
+                continue;
+            };
+            let line_number = index + 1;
+            let (x, y, kind, radius) = match Haze::parse(definition) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    skipped.push(format!("line {}: {}", line_number, e));
+                    continue;
+                }
+            };
+            if x >= width || y >= height {
+                skipped.push(format!("line {}: haze {},{} is outside the maze", line_number, x, y));
+                continue;
+            }
+            hazes.push(Haze {
+                x: (x as f32 + 0.5) * tile_size,
+                y: (y as f32 + 0.5) * tile_size,
+                kind,
+                radius: radius * tile_size,
+            });
+        }
+        hazes
+    }
+
+    /// Read the "glitch x,y x,y" lines (see glitch.rs)
+    fn parse_glitch_zones(content: &str, width: usize, height: usize, skipped: &mut Vec<String>) -> Vec<GlitchZone> {
+        let mut zones = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let Some(definition) = line.trim().strip_prefix("glitch") else {
+                continue;
+            };
+            let line_number = index + 1;
+            let zone = match GlitchZone::parse(definition) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    skipped.push(format!("line {}: {}", line_number, e));
+                    continue;
+                }
+            };
+            if zone.x1 >= width || zone.y1 >= height {
+                skipped.push(format!("line {}: glitch zone reaches outside the maze", line_number));
+                continue;
+            }
+            zones.push(zone);
+        }
+        zones
+    }
+
+    /// Read the "escalate <seconds left> <action> <value>" lines (see escalation.rs). Entities
+    /// have to spawn on a walkable tile
+    fn parse_escalation(content: &str, map: &[Vec<char>], tile_size: f32, skipped: &mut Vec<String>) -> Vec<EscalationStep> {
+        let mut steps = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let Some(definition) = line.trim().strip_prefix("escalate") else {
+                continue;
+            };
+            let line_number = index + 1;
+            let mut step = match EscalationStep::parse(definition) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    skipped.push(format!("line {}: {}", line_number, e));
+                    continue;
+                }
+            };
+            if let Escalate::Spawn(x, y) = step.action {
+                let (x, y) = (x as usize, y as usize);
+                if matches!(map.get(y).and_then(|row| row.get(x)), Some('#' | 'E' | 'D' | 'G') | None) {
+                    skipped.push(format!("line {}: spawn {},{} is not a walkable tile", line_number, x, y));
+                    continue;
+                }
+                step.action = Escalate::Spawn((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size);
+            }
+            steps.push(step);
+        }
+        steps
+    }
+
+    /// Read the "grade <name|#rrggbb> [brightness]" line (see texture_grade.rs), at most one
+    fn parse_texture_grade(content: &str, skipped: &mut Vec<String>) -> Option<TextureGrade> {
+        let mut grade = None;
+        for (index, line) in content.lines().enumerate() {
+            let Some(definition) = line.trim().strip_prefix("grade") else {
+                continue;
+            };
+            let line_number = index + 1;
+            if grade.is_some() {
+                skipped.push(format!("line {}: a level can only have one grade, keeping the first", line_number));
+                continue;
+            }
+            match TextureGrade::parse(definition) {
+                Ok(parsed) => grade = Some(parsed),
+                Err(e) => skipped.push(format!("line {}: {}", line_number, e)),
+            }
+        }
+        grade
+    }
+
+    /// Read the "challenge x,y x,y <trial> <reward>" lines (see challenge.rs). The room has to
+    /// lie inside the maze, with enough floor for its orbs and one tile to walk in on
+    fn parse_challenges(content: &str, map: &[Vec<char>], skipped: &mut Vec<String>) -> Vec<ChallengeRoom> {
+        let mut rooms = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let Some(definition) = line.trim().strip_prefix("challenge") else {
+                continue;
+            };
+            let line_number = index + 1;
+            let room = match ChallengeRoom::parse(definition) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    skipped.push(format!("line {}: {}", line_number, e));
+                    continue;
+                }
+            };
+            if room.y1 >= map.len() || room.x1 >= map[0].len() {
+                skipped.push(format!("line {}: challenge room reaches outside the maze", line_number));
+                continue;
+            }
+            let floor = (room.y0..=room.y1)
+                .flat_map(|y| (room.x0..=room.x1).map(move |x| (x, y)))
+                .filter(|&(x, y)| !matches!(map[y][x], '#' | 'E' | 'D' | 'G'))
+                .count();
+            let needed = match room.trial {
+                Trial::Orbs { count, .. } => count + 1,
+                Trial::Blackout { .. } => 1,
+            };
+            if floor < needed {
+                skipped.push(format!("line {}: challenge room has {} floor tiles, it needs {}", line_number, floor, needed));
+                continue;
+            }
+            rooms.push(room);
+        }
+        rooms
+    }
+
+    /// Read the "blackout <start> <duration>" lines (see blackout.rs)
+    fn parse_blackouts(content: &str, skipped: &mut Vec<String>) -> Vec<BlackoutEvent> {
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| Some((index, line.trim().strip_prefix("blackout")?)))
+            .filter_map(|(index, definition)| {
+                BlackoutEvent::parse(definition)
+                    .map_err(|e| skipped.push(format!("line {}: {}", index + 1, e)))
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Whether a line of the file is a directive ("patrol ...", "light ...", "blackout ...",
+    /// "haze ...", "glitch ...", "escalate ...", "grade ...", "challenge ...") rather than a row
+    fn is_directive(line: &str) -> bool {
+        DIRECTIVES.iter().any(|keyword| line.trim().starts_with(keyword))
+    }
+
+    /// Whether the tile at (x, y) of the grid in `content` is an enemy spawn 'e'
+    fn is_enemy_spawn(content: &str, x: usize, y: usize) -> bool {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !Self::is_directive(line))
+            .nth(y)
+            .and_then(|row| row.chars().nth(x))
+            == Some('e')
+    }
+
+    /// Split the file into rows of tiles and check its shape: a non-empty rectangle within the
+    /// size limit, only known tiles, exactly one start and one exit, closed off by an outer wall.
+    /// Errors name the line and column (1-based, counting blank lines) of the problem
+    fn parse_rows(content: &str) -> Result<Vec<Vec<char>>, String> {
+        let mut rows: Vec<(usize, Vec<char>)> = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            if line.trim().is_empty() || Self::is_directive(line) {
+                continue;
+            }
+            let width = line.chars().count();
+            if width > MAX_DIMENSION {
+                return Err(format!("line {} is {} tiles wide, the limit is {}", line_number, width, MAX_DIMENSION));
+            }
+            if rows.len() == MAX_DIMENSION {
+                return Err(format!("more than {} rows", MAX_DIMENSION));
+            }
+            rows.push((line_number, line.chars().collect()));
+        }
+
+        let Some((_, first)) = rows.first() else {
+            return Err("maze is empty".to_string());
+        };
+        let width = first.len();
+        let height = rows.len();
+        if width < 3 || height < 3 {
+            return Err(format!("maze is {}x{}, it needs at least 3x3 tiles", width, height));
+        }
+
+        let (mut start, mut exit) = (None, None);
+        for (row_index, (line_number, row)) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(format!(
+                    "line {} is {} tiles wide, expected {} like the first row",
+                    line_number,
+                    row.len(),
+                    width
+                ));
+            }
+            for (column, &tile) in row.iter().enumerate() {
+                let at = || format!("line {}, column {}", line_number, column + 1);
+                if !KNOWN_TILES.contains(tile) {
+                    return Err(format!("{}: unknown tile '{}'", at(), tile.escape_debug()));
+                }
+                let found = match tile {
+                    'S' => Some(("start", &mut start)),
+                    'E' => Some(("exit", &mut exit)),
+                    _ => None,
+                };
+                if let Some((name, found)) = found {
+                    if let Some(first) = found {
+                        return Err(format!("{}: a second {} '{}', the first is at {}", at(), name, tile, first));
+                    }
+                    *found = Some(at());
+                }
+                // Anything walkable on the edge would let the player walk off the map
+                let on_edge = row_index == 0 || row_index == height - 1 || column == 0 || column == width - 1;
+                if on_edge && tile != '#' && tile != 'E' {
+                    return Err(format!("{}: the outer edge must be wall ('#') or exit ('E'), found '{}'", at(), tile));
+                }
+            }
+        }
+
+        if start.is_none() {
+            return Err("no start 'S'".to_string());
+        }
+        if exit.is_none() {
+            return Err("no exit 'E'".to_string());
+        }
+
+        Ok(rows.into_iter().map(|(_, row)| row).collect())
+    }
+
     /// Get tile at grid position (returns None if out of bounds)
     #[inline]
     pub fn get_tile(&self, x: usize, y: usize) -> Option<char> {
@@ -67,20 +618,55 @@ impl Maze {
     }
 
     /// Check if position is a wall (optimized for raycasting)
-    /// Recognizes both normal walls '#' and exit doors 'E'
+    /// Recognizes normal walls '#', exit doors 'E', closed doors 'D' and glass 'G'.
+    /// Anything outside the map counts as solid
     #[inline]
     pub fn is_wall(&self, x: usize, y: usize) -> bool {
-        matches!(self.get_tile(x, y), Some('#') | Some('E'))
+        matches!(self.get_tile(x, y), Some('#') | Some('E') | Some('D') | Some('G') | None)
+    }
+
+    /// Whether a ray stops at the tile: a wall that can't be seen through
+    #[inline]
+    pub fn is_opaque(&self, x: usize, y: usize) -> bool {
+        self.is_wall(x, y) && self.translucency(x, y).is_none()
+    }
+
+    /// See-through surface at grid position, if any (rays pass it, see caster.rs)
+    #[inline]
+    pub fn translucency(&self, x: usize, y: usize) -> Option<Translucent> {
+        self.get_tile(x, y).and_then(Translucent::from_tile)
+    }
+
+    /// Whether the maze has any see-through tiles (rays only collect layers then)
+    pub fn has_translucent(&self) -> bool {
+        self.map.iter().flatten().any(|&tile| Translucent::from_tile(tile).is_some())
+    }
+
+    /// Door at grid position: Some(true) if open, Some(false) if closed, None if not a door
+    pub fn door_at(&self, x: usize, y: usize) -> Option<bool> {
+        match self.get_tile(x, y) {
+            Some('D') => Some(false),
+            Some('/') => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Open or close the door at grid position (does nothing if there is no door)
+    pub fn set_door(&mut self, x: usize, y: usize, open: bool) {
+        if self.door_at(x, y).is_some() {
+            self.map[y][x] = if open { '/' } else { 'D' };
+        }
     }
 
     /// Get the type of wall at position
-    /// Returns the character representing the wall type ('# for normal, 'E' for exit)
+    /// Returns the character representing the wall type ('# for normal, 'E' for exit, 'D' for a closed door)
     /// Returns ' ' for non-wall tiles
     #[inline]
     pub fn get_wall_type(&self, x: usize, y: usize) -> char {
         match self.get_tile(x, y) {
             Some('#') => '#',
             Some('E') => 'E',
+            Some('D') => 'D',
             _ => ' ',
         }
     }
@@ -93,6 +679,14 @@ impl Maze {
         !self.is_wall(grid_x, grid_y)
     }
 
+    /// Check if world position is on a water tile ('~', walkable floor under a puddle)
+    #[inline]
+    pub fn is_water(&self, world_x: f32, world_y: f32) -> bool {
+        let grid_x = (world_x / self.tile_size) as usize;
+        let grid_y = (world_y / self.tile_size) as usize;
+        self.get_tile(grid_x, grid_y) == Some('~')
+    }
+
     /// Check if player reached the goal
     #[inline]
     pub fn is_goal(&self, world_x: f32, world_y: f32, threshold: f32) -> bool {
@@ -100,4 +694,43 @@ impl Maze {
         let dy = world_y - self.goal_pos.1;
         (dx * dx + dy * dy).sqrt() < threshold
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(content: &str) -> String {
+        match Maze::from_bytes(content.as_bytes(), 1.0) {
+            Ok(_) => panic!("maze was accepted:\n{}", content),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn malformed_grids_name_the_problem_and_where_it_is() {
+        assert_eq!(error(""), "maze is empty");
+        assert_eq!(error("#####\n#S.E#\n\n####\n"), "line 4 is 4 tiles wide, expected 5 like the first row");
+        assert_eq!(error("#####\n#S?.#\n#..E#\n#####\n"), "line 2, column 3: unknown tile '?'");
+        assert_eq!(
+            error("#####\n.S..#\n#..E#\n#####\n"),
+            "line 2, column 1: the outer edge must be wall ('#') or exit ('E'), found '.'"
+        );
+        assert_eq!(
+            error("#####\n#S..#\n#.S.#\n#..E#\n#####\n"),
+            "line 3, column 3: a second start 'S', the first is at line 2, column 2"
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_names_the_line_and_column() {
+        let bytes = b"#####\n#S\xff.#\n#..E#\n#####\n";
+        assert_eq!(Maze::from_bytes(bytes, 1.0).err().unwrap(), "line 2, column 3: not valid UTF-8 text");
+    }
+
+    #[test]
+    fn a_valid_grid_loads() {
+        let maze = Maze::from_bytes(b"#####\n#S..#\n#..E#\n#####\n", 1.0).unwrap();
+        assert_eq!((maze.width, maze.height), (5, 4));
+        assert_eq!(maze.start_pos, (1.5, 1.5));
+    }
+}
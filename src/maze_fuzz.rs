@@ -0,0 +1,216 @@
+// Maze parser fuzzer for Backrooms Doom
+// Feeds Maze::from_bytes thousands of malformed variants of a real maze file
+// (invalid UTF-8, huge lines, zero rows, unknown tiles, ragged rows, truncation)
+// and checks that it never panics: every input must either be rejected with an
+// error or produce a maze that is safe to use. Inputs that break this are saved
+// to logs/ for debugging. Afterwards it generates mazes of random sizes from
+// random seeds (Maze::generate), which must pass the same checks, reach the
+// exit from the start, and come out identical when generated again.
+//
+//   cargo run --release -- --fuzz-maze [iterations] [--seed N]
+
+use crate::maze::Maze;
+use crate::pathfinding;
+use log::{error, info};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+
+const MAZE_PATH: &str = "maze.txt";
+const DEFAULT_ITERATIONS: usize = 10_000;
+const MAX_MUTATIONS: usize = 4;
+const GENERATED_SHARE: usize = 20; // One generated maze per this many parser inputs
+
+// Used when maze.txt is missing, so the fuzzer always has a valid starting point
+const FALLBACK_MAZE: &str = "\
+#######
+#S..p.#
+#.#D#.#
+#e..b.E
+#######
+";
+
+/// Parse "--fuzz-maze [iterations] [--seed N]" and run the fuzzer.
+/// Returns None when the flag wasn't given, otherwise whether every input was handled safely
+pub fn run_from_args(args: &[String]) -> Option<bool> {
+    let index = args.iter().position(|arg| arg == "--fuzz-maze")?;
+    let iterations = args
+        .get(index + 1)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_ITERATIONS);
+    let seed = args
+        .iter()
+        .position(|arg| arg == "--seed")
+        .and_then(|index| args.get(index + 1))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(rand::random);
+
+    let base = fs::read(MAZE_PATH).unwrap_or_else(|_| FALLBACK_MAZE.as_bytes().to_vec());
+    Some(run(&base, iterations, seed))
+}
+
+fn run(base: &[u8], iterations: usize, seed: u64) -> bool {
+    info!("Fuzzing the maze parser (seed {}): {} inputs", seed, iterations);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (mut accepted, mut rejected) = (0, 0);
+
+    for iteration in 0..iterations {
+        let mut input = base.to_vec();
+        for _ in 0..rng.gen_range(1..=MAX_MUTATIONS) {
+            mutate(&mut input, &mut rng);
+        }
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| match Maze::from_bytes(&input, 1.0) {
+            Ok(maze) => check(&maze).map(|_| true),
+            Err(_) => Ok(false),
+        }));
+
+        let failure = match result {
+            Ok(Ok(true)) => {
+                accepted += 1;
+                continue;
+            }
+            Ok(Ok(false)) => {
+                rejected += 1;
+                continue;
+            }
+            Ok(Err(problem)) => problem,
+            Err(_) => "parser panicked".to_string(),
+        };
+
+        error!("Fuzz input {} failed: {}", iteration, failure);
+        let path = format!("logs/fuzz_maze_{}_{}.txt", seed, iteration);
+        match fs::create_dir_all("logs").and_then(|_| fs::write(&path, &input)) {
+            Ok(()) => info!("Input saved to {}", path),
+            Err(e) => error!("Failed to write {}: {}", path, e),
+        }
+        return false;
+    }
+
+    info!("Maze fuzzing passed: {} accepted, {} rejected with an error", accepted, rejected);
+    check_generated(&mut rng, iterations / GENERATED_SHARE)
+}
+
+/// Generate `count` mazes of random sizes from random seeds and check each
+fn check_generated(rng: &mut StdRng, count: usize) -> bool {
+    for _ in 0..count {
+        let (width, height, seed) = (rng.gen_range(0..80), rng.gen_range(0..80), rng.gen());
+        let maze = Maze::generate(width, height, seed, 1.0);
+        let problem = check(&maze).err().or_else(|| {
+            if !exit_reachable(&maze) {
+                Some("the exit can't be reached from the start".to_string())
+            } else if Maze::generate(width, height, seed, 1.0).map != maze.map {
+                Some("the same seed gave a different maze".to_string())
+            } else {
+                None
+            }
+        });
+        if let Some(problem) = problem {
+            error!("Generated maze {}x{} (seed {}) failed: {}", width, height, seed, problem);
+            return false;
+        }
+    }
+    info!("Generated mazes passed: {} sizes and seeds", count);
+    true
+}
+
+/// Whether a floor tile next to the exit can be walked to from the start
+fn exit_reachable(maze: &Maze) -> bool {
+    let steps = pathfinding::distances(maze, maze.start_pos);
+    let (x, y) = (maze.goal_pos.0 as usize, maze.goal_pos.1 as usize);
+    [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)]
+        .iter()
+        .any(|&(nx, ny)| nx < maze.width && ny < maze.height && steps[ny * maze.width + nx].is_some())
+}
+
+/// Apply one random kind of damage to the file
+fn mutate(input: &mut Vec<u8>, rng: &mut StdRng) {
+    let position = |rng: &mut StdRng, input: &Vec<u8>| rng.gen_range(0..=input.len());
+    match rng.gen_range(0..9) {
+        // Random byte, often outside ASCII (invalid UTF-8)
+        0 => {
+            if !input.is_empty() {
+                let index = rng.gen_range(0..input.len());
+                input[index] = rng.gen();
+            }
+        }
+        // Swap a tile for another printable character
+        1 => {
+            if !input.is_empty() {
+                let index = rng.gen_range(0..input.len());
+                input[index] = rng.gen_range(b' '..=b'~');
+            }
+        }
+        // Swap a tile for a valid one in the wrong place (extra starts/exits, doors on the edge)
+        2 => {
+            if !input.is_empty() {
+                let index = rng.gen_range(0..input.len());
+                input[index] = b"#. SEDpebdc/"[rng.gen_range(0..12)];
+            }
+        }
+        // Insert a run of random bytes
+        3 => {
+            let index = position(rng, input);
+            let bytes: Vec<u8> = (0..rng.gen_range(1..16)).map(|_| rng.gen()).collect();
+            input.splice(index..index, bytes);
+        }
+        // Delete a range (ragged rows, missing walls)
+        4 => {
+            let start = position(rng, input);
+            let end = (start + rng.gen_range(1..64)).min(input.len());
+            input.drain(start..end);
+        }
+        // Truncate, sometimes down to nothing
+        5 => {
+            let length = position(rng, input);
+            input.truncate(length);
+        }
+        // A huge line
+        6 => {
+            let index = position(rng, input);
+            let line = vec![b'#'; rng.gen_range(500..200_000)];
+            input.splice(index..index, line);
+        }
+        // Only blank lines / CRLF line endings
+        7 => {
+            if rng.gen_bool(0.5) {
+                *input = b"\n\n\r\n   \n".to_vec();
+            } else {
+                *input = input.iter().flat_map(|&b| if b == b'\n' { vec![b'\r', b'\n'] } else { vec![b] }).collect();
+            }
+        }
+        // Many extra rows
+        _ => {
+            let row: Vec<u8> = input.split(|&b| b == b'\n').next().unwrap_or_default().to_vec();
+            for _ in 0..rng.gen_range(1..700) {
+                input.extend_from_slice(&row);
+                input.push(b'\n');
+            }
+        }
+    }
+}
+
+/// A maze the parser accepted must be safe to play on
+fn check(maze: &Maze) -> Result<(), String> {
+    if maze.height != maze.map.len() || maze.map.iter().any(|row| row.len() != maze.width) {
+        return Err(format!("map is not {}x{}", maze.width, maze.height));
+    }
+    let (x, y) = maze.start_pos;
+    if (x as usize) >= maze.width || (y as usize) >= maze.height || !maze.is_walkable(x, y) {
+        return Err(format!("start ({}, {}) is outside the map or inside a wall", x, y));
+    }
+
+    // Nothing walkable may lie outside the map, in any direction
+    let (w, h) = (maze.width as f32, maze.height as f32);
+    for (x, y) in [(-0.5, y), (w + 0.5, y), (x, -0.5), (x, h + 0.5), (-1e9, 1e9), (f32::NAN, f32::NAN)] {
+        if maze.is_walkable(x, y) && (x < 0.0 || y < 0.0 || x >= w || y >= h) {
+            return Err(format!("({}, {}) outside the map is walkable", x, y));
+        }
+    }
+
+    // Route searches must cope with whatever layout got through
+    pathfinding::distances(maze, maze.start_pos);
+    pathfinding::find_path(maze, maze.start_pos, maze.goal_pos);
+    Ok(())
+}
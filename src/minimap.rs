@@ -1,5 +1,10 @@
 use raylib::prelude::*;
+use crate::decal::Decal;
+use crate::enemy::Enemy;
+use crate::glitch::Glitch;
+use crate::heatmap::Heatmap;
 use crate::maze::Maze;
+use crate::noise::Noise;
 use crate::player::Player;
 
 pub struct Minimap {
@@ -18,7 +23,8 @@ impl Minimap {
     }
 
     /// Render the minimap showing the ENTIRE map at all times
-    pub fn render(&self, d: &mut RaylibDrawHandle, maze: &Maze, player: &Player) {
+    /// Inside a glitch zone corrupted tiles show instead of the real ones (see glitch.rs)
+    pub fn render(&self, d: &mut RaylibDrawHandle, maze: &Maze, players: &[Player], local_index: usize, glitch: &Glitch) {
         let (x_offset, y_offset) = self.position;
 
         // Draw semi-transparent background
@@ -56,7 +62,7 @@ impl Minimap {
         // Draw ALL map tiles
         for map_y in 0..maze.height {
             for map_x in 0..maze.width {
-                let tile = maze.get_tile(map_x, map_y);
+                let tile = glitch.scramble(map_x, map_y).or(maze.get_tile(map_x, map_y));
 
                 let screen_x = map_x_offset + (map_x as f32 * maze.tile_size * map_scale) as i32;
                 let screen_y = map_y_offset + (map_y as f32 * maze.tile_size * map_scale) as i32;
@@ -66,6 +72,9 @@ impl Minimap {
                     Some('#') => Color::new(60, 60, 60, 255),     // Wall - dark gray
                     Some('E') => Color::new(255, 0, 0, 255),      // Exit door - red
                     Some('S') => Color::new(100, 200, 100, 255),  // Start - green
+                    Some('D') => Color::new(140, 95, 50, 255),    // Closed door - brown
+                    Some('/') => Color::new(200, 160, 110, 255),  // Open door - light brown
+                    Some('~') => Color::new(90, 130, 190, 255),   // Water - blue
                     _ => Color::new(180, 180, 140, 255),          // Floor - light
                 };
 
@@ -79,7 +88,23 @@ impl Minimap {
             }
         }
 
-        // Draw player as a BLUE DOT that moves on the map
+        // Other players as smaller green dots
+        for (i, other) in players.iter().enumerate() {
+            if i != local_index {
+                d.draw_circle(
+                    map_x_offset + (other.pos.x * map_scale) as i32,
+                    map_y_offset + (other.pos.y * map_scale) as i32,
+                    3.5,
+                    Color::new(80, 220, 120, 255),
+                );
+            }
+        }
+
+        // Draw player as a BLUE DOT that moves on the map (lost in a bad glitch)
+        if glitch.hides_player() {
+            return;
+        }
+        let player = &players[local_index];
         let player_screen_x = map_x_offset + (player.pos.x * map_scale) as i32;
         let player_screen_y = map_y_offset + (player.pos.y * map_scale) as i32;
         let player_dot_size = 5.0;
@@ -92,4 +117,225 @@ impl Minimap {
             Color::new(0, 150, 255, 255), // Bright blue
         );
     }
+
+    /// Small compass left of the minimap, the needle pointing where the player faces.
+    /// `heading` is the view angle (0 = east, clockwise)
+    pub fn render_compass(&self, d: &mut RaylibDrawHandle, heading: f32) {
+        let radius = 14.0;
+        let center = Vector2::new(self.position.0 as f32 - radius - 8.0, self.position.1 as f32 + radius + 2.0);
+        d.draw_circle_v(center, radius, Color::new(0, 0, 0, 180));
+        d.draw_circle_lines(center.x as i32, center.y as i32, radius, Color::WHITE);
+        d.draw_text("N", center.x as i32 - 3, center.y as i32 - radius as i32 + 1, 8, Color::new(255, 90, 60, 255));
+        let tip = Vector2::new(center.x + heading.cos() * (radius - 3.0), center.y + heading.sin() * (radius - 3.0));
+        d.draw_line_v(center, tip, Color::new(255, 220, 120, 255));
+        d.draw_circle_v(tip, 2.0, Color::new(255, 220, 120, 255));
+    }
+
+    /// Draw noises the player made as fading rings (their hearing range)
+    pub fn render_noises(&self, d: &mut RaylibDrawHandle, maze: &Maze, noises: &[Noise]) {
+        let (x_offset, y_offset) = self.position;
+        let max_dimension = (maze.width as f32).max(maze.height as f32) * maze.tile_size;
+        let map_scale = (self.size as f32 - 4.0) / max_dimension;
+        let map_x_offset = x_offset + ((self.size as f32 - maze.width as f32 * maze.tile_size * map_scale) / 2.0) as i32;
+        let map_y_offset = y_offset + ((self.size as f32 - maze.height as f32 * maze.tile_size * map_scale) / 2.0) as i32;
+
+        for noise in noises {
+            let alpha = (noise.strength() * 220.0) as u8;
+            d.draw_circle_lines(
+                map_x_offset + (noise.x * map_scale) as i32,
+                map_y_offset + (noise.y * map_scale) as i32,
+                noise.radius * map_scale,
+                Color::new(255, 200, 60, alpha),
+            );
+        }
+    }
+
+    /// Outline rooms (tile corners, inclusive) on the map, like the challenge rooms still to play
+    pub fn render_rooms(&self, d: &mut RaylibDrawHandle, maze: &Maze, rooms: &[(usize, usize, usize, usize)], color: Color) {
+        let (x_offset, y_offset) = self.position;
+        let max_dimension = (maze.width as f32).max(maze.height as f32) * maze.tile_size;
+        let map_scale = (self.size as f32 - 4.0) / max_dimension;
+        let map_x_offset = x_offset + ((self.size as f32 - maze.width as f32 * maze.tile_size * map_scale) / 2.0) as i32;
+        let map_y_offset = y_offset + ((self.size as f32 - maze.height as f32 * maze.tile_size * map_scale) / 2.0) as i32;
+        let tile = maze.tile_size * map_scale;
+
+        for &(x0, y0, x1, y1) in rooms {
+            d.draw_rectangle_lines(
+                map_x_offset + (x0 as f32 * tile) as i32,
+                map_y_offset + (y0 as f32 * tile) as i32,
+                ((x1 - x0 + 1) as f32 * tile) as i32,
+                ((y1 - y0 + 1) as f32 * tile) as i32,
+                color,
+            );
+        }
+    }
+
+    /// Dots for things the map doesn't normally show, at world positions (the reveal effect)
+    pub fn render_marks(&self, d: &mut RaylibDrawHandle, maze: &Maze, marks: &[(f32, f32, Color)]) {
+        let (x_offset, y_offset) = self.position;
+        let max_dimension = (maze.width as f32).max(maze.height as f32) * maze.tile_size;
+        let map_scale = (self.size as f32 - 4.0) / max_dimension;
+        let map_x_offset = x_offset + ((self.size as f32 - maze.width as f32 * maze.tile_size * map_scale) / 2.0) as i32;
+        let map_y_offset = y_offset + ((self.size as f32 - maze.height as f32 * maze.tile_size * map_scale) / 2.0) as i32;
+
+        for &(x, y, color) in marks {
+            let (screen_x, screen_y) = (map_x_offset + (x * map_scale) as i32, map_y_offset + (y * map_scale) as i32);
+            d.draw_circle(screen_x, screen_y, 3.0, color);
+            d.draw_circle_lines(screen_x, screen_y, 3.0, Color::BLACK);
+        }
+    }
+
+    /// Draw decals that show on the map (chalk marks) as small crosses in their color
+    pub fn render_decals(&self, d: &mut RaylibDrawHandle, maze: &Maze, decals: &[Decal]) {
+        let (x_offset, y_offset) = self.position;
+        let max_dimension = (maze.width as f32).max(maze.height as f32) * maze.tile_size;
+        let map_scale = (self.size as f32 - 4.0) / max_dimension;
+        let map_x_offset = x_offset + ((self.size as f32 - maze.width as f32 * maze.tile_size * map_scale) / 2.0) as i32;
+        let map_y_offset = y_offset + ((self.size as f32 - maze.height as f32 * maze.tile_size * map_scale) / 2.0) as i32;
+
+        for decal in decals.iter().filter(|decal| decal.kind.on_minimap()) {
+            let x = map_x_offset + (decal.x * map_scale) as i32;
+            let y = map_y_offset + (decal.y * map_scale) as i32;
+            let color = decal.kind.color();
+            let color = Color::new(color.r, color.g, color.b, (decal.alpha() * 255.0) as u8);
+            d.draw_line(x - 2, y - 2, x + 3, y + 3, color);
+            d.draw_line(x - 2, y + 2, x + 3, y - 3, color);
+        }
+    }
+
+    /// Developer view: the whole maze filling the screen with the heatmap over the floor,
+    /// enemies (lines to where they are heading) and the player on top
+    pub fn render_heatmap(
+        &self,
+        d: &mut RaylibDrawHandle,
+        maze: &Maze,
+        heatmap: &Heatmap,
+        enemies: &[Enemy],
+        player: &Player,
+        (screen_width, screen_height): (i32, i32),
+    ) {
+        d.draw_rectangle(0, 0, screen_width, screen_height, Color::new(0, 0, 0, 220));
+        let tile_pixels = ((screen_width - 40) as f32 / maze.width as f32)
+            .min((screen_height - 60) as f32 / maze.height as f32)
+            .floor()
+            .max(1.0);
+        let x_offset = (screen_width - (tile_pixels * maze.width as f32) as i32) / 2;
+        let y_offset = (screen_height - (tile_pixels * maze.height as f32) as i32) / 2;
+        let scale = tile_pixels / maze.tile_size;
+        let to_screen = |x: f32, y: f32| (x_offset + (x * scale) as i32, y_offset + (y * scale) as i32);
+
+        for map_y in 0..maze.height {
+            for map_x in 0..maze.width {
+                let color = if maze.is_wall(map_x, map_y) {
+                    Color::new(50, 50, 50, 255)
+                } else {
+                    // Cold blue through to hot red
+                    let heat = heatmap.value(map_x, map_y);
+                    let base = Color::new(40, 40, 70, 255);
+                    if heat <= 0.0 {
+                        base
+                    } else {
+                        Color::new((60.0 + 195.0 * heat) as u8, (160.0 * (1.0 - heat)) as u8, (200.0 * (1.0 - heat)) as u8, 255)
+                    }
+                };
+                d.draw_rectangle(
+                    x_offset + map_x as i32 * tile_pixels as i32,
+                    y_offset + map_y as i32 * tile_pixels as i32,
+                    tile_pixels as i32 - 1,
+                    tile_pixels as i32 - 1,
+                    color,
+                );
+            }
+        }
+
+        for enemy in enemies.iter().filter(|enemy| enemy.is_alive()) {
+            let (x, y) = to_screen(enemy.pos.x, enemy.pos.y);
+            if let Some(target) = enemy.investigate {
+                let (target_x, target_y) = to_screen(target.x, target.y);
+                d.draw_line(x, y, target_x, target_y, Color::ORANGE);
+            }
+            d.draw_circle(x, y, tile_pixels * 0.3, Color::new(230, 40, 40, 255));
+        }
+
+        let (x, y) = to_screen(player.pos.x, player.pos.y);
+        d.draw_circle(x, y, tile_pixels * 0.3, Color::new(0, 150, 255, 255));
+        d.draw_line(x, y, x + (player.angle.cos() * tile_pixels) as i32, y + (player.angle.sin() * tile_pixels) as i32, Color::WHITE);
+
+        let title = format!("HEATMAP: {}  (heatmap off to close)", heatmap.mode.name());
+        d.draw_text(&title, 20, 10, 20, Color::YELLOW);
+    }
+
+    /// Top-down view for the versus hunter: the area around it in a square panel at (x, y),
+    /// the escaper only when sensed, and recent noises as rings
+    pub fn render_hunter_view(
+        &self,
+        d: &mut RaylibDrawHandle,
+        maze: &Maze,
+        hunter: &Player,
+        escaper: Option<(f32, f32)>,
+        noises: &[Noise],
+        (x, y, size): (i32, i32, i32),
+    ) {
+        const VIEW_RADIUS: f32 = 6.0; // Tiles shown around the hunter in each direction
+
+        d.draw_rectangle(x, y, size, size, Color::new(10, 0, 0, 220));
+        let scale = size as f32 / (VIEW_RADIUS * 2.0 * maze.tile_size);
+        let to_screen = |wx: f32, wy: f32| {
+            (
+                x + size / 2 + ((wx - hunter.pos.x) * scale) as i32,
+                y + size / 2 + ((wy - hunter.pos.y) * scale) as i32,
+            )
+        };
+
+        let tile_x = (hunter.pos.x / maze.tile_size) as i32;
+        let tile_y = (hunter.pos.y / maze.tile_size) as i32;
+        let reach = VIEW_RADIUS as i32 + 1;
+        let tile_pixels = (maze.tile_size * scale).ceil() as i32;
+        for map_y in (tile_y - reach).max(0)..(tile_y + reach).min(maze.height as i32) {
+            for map_x in (tile_x - reach).max(0)..(tile_x + reach).min(maze.width as i32) {
+                let color = match maze.get_tile(map_x as usize, map_y as usize) {
+                    Some('#') => Color::new(70, 20, 20, 255),
+                    Some('E') => Color::new(255, 60, 60, 255),
+                    Some('D') => Color::new(120, 70, 40, 255),
+                    _ => continue,
+                };
+                let (screen_x, screen_y) = to_screen(map_x as f32 * maze.tile_size, map_y as f32 * maze.tile_size);
+                if screen_x + tile_pixels < x || screen_y + tile_pixels < y || screen_x > x + size || screen_y > y + size {
+                    continue;
+                }
+                // Clip tiles to the panel
+                let left = screen_x.max(x);
+                let top = screen_y.max(y);
+                let right = (screen_x + tile_pixels).min(x + size);
+                let bottom = (screen_y + tile_pixels).min(y + size);
+                d.draw_rectangle(left, top, right - left, bottom - top, color);
+            }
+        }
+
+        for noise in noises {
+            let (noise_x, noise_y) = to_screen(noise.x, noise.y);
+            if noise_x < x || noise_y < y || noise_x > x + size || noise_y > y + size {
+                continue;
+            }
+            let alpha = (noise.strength() * 220.0) as u8;
+            d.draw_circle_lines(noise_x, noise_y, (noise.radius * scale).min(size as f32 / 2.0), Color::new(255, 200, 60, alpha));
+        }
+
+        if let Some((escaper_x, escaper_y)) = escaper {
+            let (screen_x, screen_y) = to_screen(escaper_x, escaper_y);
+            d.draw_circle(screen_x, screen_y, 5.0, Color::new(0, 150, 255, 255));
+        }
+
+        // Hunter in the center with its facing direction
+        let center = (x + size / 2, y + size / 2);
+        d.draw_circle(center.0, center.1, 6.0, Color::new(230, 40, 40, 255));
+        d.draw_line(
+            center.0,
+            center.1,
+            center.0 + (hunter.angle.cos() * 14.0) as i32,
+            center.1 + (hunter.angle.sin() * 14.0) as i32,
+            Color::WHITE,
+        );
+        d.draw_rectangle_lines(x, y, size, size, Color::new(230, 40, 40, 255));
+    }
 }
@@ -0,0 +1,127 @@
+// Menu narration for Backrooms Doom
+// For players who can't read the screen, menus can be read aloud: every frame
+// the game reports which screen is showing and which item has focus, and the
+// narrator speaks whatever changed, the screen with its keys when it opens, then
+// each newly focused item or changed value. Speaking goes through a pluggable
+// voice: the platform's text-to-speech command (say on macOS, spd-say on Linux,
+// System.Speech on Windows), any command set in settings.toml (the text is
+// passed as its last argument), or a callback (`narration = "log"` writes the
+// lines to the log, handy without a speech engine). A new line cuts off the
+// one still being spoken so fast menu navigation doesn't queue up.
+
+use log::{info, warn};
+use std::process::{Child, Command, Stdio};
+
+/// Where narrated lines go
+pub enum Voice {
+    Off,
+    Command(Vec<String>),           // Program and arguments, the text is added last
+    Callback(Box<dyn FnMut(&str)>), // Called with each line
+}
+
+pub struct Narrator {
+    voice: Voice,
+    screen: String,          // Screen last announced
+    item: Option<String>,    // Focused item last announced
+    speaking: Option<Child>, // Speech command still running
+}
+
+impl Narrator {
+    pub fn new(voice: Voice) -> Self {
+        Self { voice, screen: String::new(), item: None, speaking: None }
+    }
+
+    /// Narrator for the `narration` setting: "off", "auto" (the platform's speech
+    /// command), "log", or a command line to run
+    pub fn from_setting(setting: &str) -> Self {
+        let voice = match setting {
+            "off" | "" => Voice::Off,
+            "auto" => platform_voice(),
+            "log" => Voice::Callback(Box::new(|line| info!("Narration: {}", line))),
+            command => Voice::Command(command.split_whitespace().map(str::to_string).collect()),
+        };
+        Self::new(voice)
+    }
+
+    /// Report the screen showing and its focused item (None when nothing has focus).
+    /// Speaks the screen when it changes, otherwise the item when it changes
+    pub fn update(&mut self, screen: &str, item: Option<&str>) {
+        if matches!(self.voice, Voice::Off) {
+            return;
+        }
+        if screen != self.screen {
+            self.screen = screen.to_string();
+            self.item = item.map(str::to_string);
+            let line = match item {
+                Some(item) => format!("{}. {}", screen, item),
+                None => screen.to_string(),
+            };
+            self.speak(&line);
+        } else if item != self.item.as_deref() {
+            self.item = item.map(str::to_string);
+            if let Some(item) = item {
+                self.speak(item);
+            }
+        }
+    }
+
+    fn speak(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        match &mut self.voice {
+            Voice::Off => {}
+            Voice::Callback(callback) => callback(line),
+            Voice::Command(command) => {
+                // Cut off the previous line
+                if let Some(mut child) = self.speaking.take() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                }
+                let Some((program, args)) = command.split_first() else {
+                    return;
+                };
+                let spawned = Command::new(program)
+                    .args(args)
+                    .arg(line)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn();
+                match spawned {
+                    Ok(child) => self.speaking = Some(child),
+                    Err(e) => {
+                        warn!("Narration off: failed to run '{}': {}", program, e);
+                        self.voice = Voice::Off;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Narrator {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.speaking.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// The speech command that ships with the operating system
+fn platform_voice() -> Voice {
+    let command: &[&str] = if cfg!(target_os = "macos") {
+        &["say"]
+    } else if cfg!(target_os = "windows") {
+        &[
+            "powershell",
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak($args[0])",
+        ]
+    } else {
+        &["spd-say", "--wait"]
+    };
+    Voice::Command(command.iter().map(|arg| arg.to_string()).collect())
+}
@@ -0,0 +1,31 @@
+// Noise for Backrooms Doom
+// Sounds enemies can hear (searching, failed rummaging, the radio lure).
+// Each noise is a circle that lingers for a moment and shows on the minimap
+
+const NOISE_LIFETIME: f32 = 1.2; // Seconds a noise stays audible
+
+pub struct Noise {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32, // Hearing range in tiles
+    pub age: f32,
+}
+
+impl Noise {
+    pub fn new(x: f32, y: f32, radius: f32) -> Self {
+        Self { x, y, radius, age: 0.0 }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.age += delta_time;
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.age >= NOISE_LIFETIME
+    }
+
+    /// 1.0 when fresh, fading to 0.0
+    pub fn strength(&self) -> f32 {
+        (1.0 - self.age / NOISE_LIFETIME).max(0.0)
+    }
+}
@@ -0,0 +1,188 @@
+// Online leaderboard client for Backrooms Doom (feature "online-leaderboard")
+// Strictly opt-in: nothing is sent unless `online_leaderboard = true` and a
+// `leaderboard_url` are set in settings.toml. Runs are queued on disk first and
+// the queue is flushed in the background, so offline runs are submitted later. One
+// flush runs at a time; a flush asked for meanwhile waits for it to finish, and only
+// the records a flush sent are taken off the queue once the server accepts them.
+//
+// Protocol (plain HTTP, one "<seed> <time> <score>" record per line):
+//   POST <url>/submit  body: queued records       -> any 2xx clears the queue
+//   GET  <url>/top     response: the global top list
+
+use crate::leaderboard::{self, RunRecord};
+use log::{info, warn};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+pub const QUEUE_PATH: &str = "leaderboard_queue.txt";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+enum Message {
+    Submitted { body: String, count: usize }, // Queued records the server accepted
+    SubmitFailed(String),
+    Top(Vec<RunRecord>),
+    Failed(String),
+}
+
+pub struct OnlineLeaderboard {
+    url: String,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+    pub global: Vec<RunRecord>, // Last fetched top list
+    pub status: String,         // Shown on the leaderboard screen
+    flushing: bool,             // A flush is waiting for the server
+    flush_again: bool,          // Another flush was asked for meanwhile
+}
+
+impl OnlineLeaderboard {
+    pub fn new(url: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            sender,
+            receiver,
+            global: Vec::new(),
+            status: "Not synced yet".to_string(),
+            flushing: false,
+            flush_again: false,
+        }
+    }
+
+    /// Queue a finished run and try to send everything queued so far
+    pub fn submit(&mut self, record: RunRecord) {
+        let queued = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(QUEUE_PATH)
+            .and_then(|mut file| writeln!(file, "{}", record.to_line()));
+        if let Err(e) = queued {
+            warn!("Failed to queue run in {}: {}", QUEUE_PATH, e);
+        }
+        self.flush_queue();
+    }
+
+    /// Send queued runs in the background (called on submit and when the leaderboard opens).
+    /// While a flush is under way this one waits for it, so no run is sent twice
+    pub fn flush_queue(&mut self) {
+        if self.flushing {
+            self.flush_again = true;
+            return;
+        }
+        let Ok(body) = fs::read_to_string(QUEUE_PATH) else {
+            return;
+        };
+        let count = leaderboard::parse_records(&body).len();
+        if count == 0 {
+            return;
+        }
+
+        let url = format!("{}/submit", self.url);
+        let sender = self.sender.clone();
+        self.flushing = true;
+        thread::spawn(move || {
+            let message = match request("POST", &url, &body) {
+                Ok(_) => Message::Submitted { body, count },
+                Err(e) => Message::SubmitFailed(format!("Offline, {} run(s) queued ({})", count, e)),
+            };
+            let _ = sender.send(message);
+        });
+    }
+
+    /// Fetch the global top list in the background
+    pub fn fetch_top(&mut self) {
+        let url = format!("{}/top", self.url);
+        let sender = self.sender.clone();
+        self.status = "Fetching global top list...".to_string();
+        thread::spawn(move || {
+            let message = match request("GET", &url, "") {
+                Ok(body) => Message::Top(leaderboard::parse_records(&body)),
+                Err(e) => Message::Failed(format!("Could not reach the leaderboard ({})", e)),
+            };
+            let _ = sender.send(message);
+        });
+    }
+
+    /// Apply results from background requests (call every frame)
+    pub fn poll(&mut self) {
+        while let Ok(message) = self.receiver.try_recv() {
+            match message {
+                Message::Submitted { body, count } => {
+                    // Only clear what was sent; runs queued meanwhile stay for the next flush
+                    let remaining = fs::read_to_string(QUEUE_PATH).unwrap_or_default();
+                    let rest = remaining.strip_prefix(body.as_str()).unwrap_or(&remaining);
+                    if let Err(e) = fs::write(QUEUE_PATH, rest) {
+                        warn!("Failed to update {}: {}", QUEUE_PATH, e);
+                    }
+                    info!("Submitted {} run(s) to the online leaderboard", count);
+                    self.status = format!("Submitted {} run(s)", count);
+                    self.flushing = false;
+                    if std::mem::take(&mut self.flush_again) {
+                        self.flush_queue();
+                    }
+                }
+                Message::SubmitFailed(reason) => {
+                    // Still offline: the runs stay queued for the next submit or visit
+                    warn!("{}", reason);
+                    self.status = reason;
+                    self.flushing = false;
+                    self.flush_again = false;
+                }
+                Message::Top(mut records) => {
+                    records.truncate(leaderboard::MAX_ENTRIES);
+                    self.status = format!("Global top {}", records.len());
+                    self.global = records;
+                }
+                Message::Failed(reason) => {
+                    warn!("{}", reason);
+                    self.status = reason;
+                }
+            }
+        }
+    }
+}
+
+/// Minimal blocking HTTP/1.1 request. Returns the body of a 2xx response
+fn request(method: &str, url: &str, body: &str) -> Result<String, String> {
+    let rest = url.strip_prefix("http://").ok_or("only http:// URLs are supported")?;
+    let (host, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let address = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+
+    let socket = address
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("could not resolve {}", host))?;
+    let mut stream = TcpStream::connect_timeout(&socket, TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(TIMEOUT)).map_err(|e| e.to_string())?;
+
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        path,
+        host,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(|e| e.to_string())?;
+    let (head, body) = response.split_once("\r\n\r\n").ok_or("malformed response")?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or("malformed status line")?;
+    if !(200..300).contains(&status) {
+        return Err(format!("server answered {}", status));
+    }
+    Ok(body.to_string())
+}
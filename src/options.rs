@@ -0,0 +1,173 @@
+// Options screen for Backrooms Doom
+// Opened from the main menu (O) for the settings players reach for most: mouse
+// sensitivity and invert Y, music and sound effect volume, field of view, the crosshair,
+// high contrast (see contrast.rs), whether menus are read aloud (narration.rs),
+// the resource pack (packs.rs), which reloads textures and audio in place, the
+// control preset and keyboard layout (controls.rs), and the display mode and monitor
+// (display.rs).
+// UP/DOWN picks a setting and LEFT/RIGHT changes it; every change applies right
+// away and is written to settings.toml, like the console commands, so it
+// survives a restart. Everything else stays in settings.toml and the console.
+
+use crate::config::Config;
+use crate::controls::Layout;
+use crate::packs;
+use raylib::prelude::*;
+
+pub const MIN_SENSITIVITY: f32 = 0.1; // Multiplier on the base mouse turn rate
+pub const MAX_SENSITIVITY: f32 = 5.0;
+pub const MIN_FOV: f32 = 50.0; // Degrees
+pub const MAX_FOV: f32 = 120.0;
+const SENSITIVITY_STEP: f32 = 0.1;
+const VOLUME_STEP: f32 = 0.05;
+const FOV_STEP: f32 = 5.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Setting {
+    Sensitivity,
+    InvertY,
+    MusicVolume,
+    SfxVolume,
+    Fov,
+    Crosshair,
+    HighContrast,
+    Narration,
+    ResourcePack,
+    Controls,
+    KeyboardLayout,
+    DisplayMode,
+    Monitor,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 13] = [
+        Setting::Sensitivity,
+        Setting::InvertY,
+        Setting::MusicVolume,
+        Setting::SfxVolume,
+        Setting::Fov,
+        Setting::Crosshair,
+        Setting::HighContrast,
+        Setting::Narration,
+        Setting::ResourcePack,
+        Setting::Controls,
+        Setting::KeyboardLayout,
+        Setting::DisplayMode,
+        Setting::Monitor,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Setting::Sensitivity => "Mouse sensitivity",
+            Setting::InvertY => "Invert mouse Y",
+            Setting::MusicVolume => "Music volume",
+            Setting::SfxVolume => "Sound effects volume",
+            Setting::Fov => "Field of view",
+            Setting::Crosshair => "Crosshair",
+            Setting::HighContrast => "High contrast",
+            Setting::Narration => "Narration",
+            Setting::ResourcePack => "Resource pack",
+            Setting::Controls => "Controls",
+            Setting::KeyboardLayout => "Keyboard layout",
+            Setting::DisplayMode => "Display mode",
+            Setting::Monitor => "Monitor",
+        }
+    }
+
+    /// Current value as shown on the screen
+    pub fn value(self, config: &Config) -> String {
+        match self {
+            Setting::Sensitivity => format!("{:.1}x", config.mouse_sensitivity),
+            Setting::InvertY => if config.invert_y { "on" } else { "off" }.to_string(),
+            Setting::MusicVolume => format!("{:.0}%", config.music_volume * 100.0),
+            Setting::SfxVolume => format!("{:.0}%", config.sfx_volume * 100.0),
+            Setting::Fov => format!("{:.0} deg", config.fov),
+            Setting::Crosshair => config.crosshair.style.name().to_string(),
+            Setting::HighContrast => if config.high_contrast { "on" } else { "off" }.to_string(),
+            Setting::Narration => match config.narration.as_str() {
+                "off" => "off",
+                "auto" => "on",
+                "log" => "log",
+                _ => "custom",
+            }
+            .to_string(),
+            Setting::ResourcePack => config.resource_pack.clone(),
+            Setting::Controls => config.controls.name().to_string(),
+            Setting::KeyboardLayout => config.keyboard_layout.map_or("auto", |layout| layout.display_name()).to_string(),
+            Setting::DisplayMode => config.display_mode.name().to_string(),
+            Setting::Monitor => config.monitor.to_string(),
+        }
+    }
+
+    /// Step the setting one notch up (1) or down (-1)
+    fn adjust(self, config: &mut Config, step: i32) {
+        let step_by = |value: f32, size: f32, min: f32, max: f32| {
+            // Round to the step so repeated presses don't drift
+            ((value / size).round() * size + size * step as f32).clamp(min, max)
+        };
+        match self {
+            Setting::Sensitivity => {
+                config.mouse_sensitivity = step_by(config.mouse_sensitivity, SENSITIVITY_STEP, MIN_SENSITIVITY, MAX_SENSITIVITY)
+            }
+            Setting::MusicVolume => config.music_volume = step_by(config.music_volume, VOLUME_STEP, 0.0, 1.0),
+            Setting::SfxVolume => config.sfx_volume = step_by(config.sfx_volume, VOLUME_STEP, 0.0, 1.0),
+            Setting::Fov => config.fov = step_by(config.fov, FOV_STEP, MIN_FOV, MAX_FOV),
+            Setting::Crosshair => config.crosshair.style = config.crosshair.style.cycle(step),
+            Setting::InvertY => config.invert_y = !config.invert_y,
+            Setting::HighContrast => config.high_contrast = !config.high_contrast,
+            // Either way toggles, a custom command is set in settings.toml or the console
+            Setting::Narration => {
+                config.narration = if config.narration == "off" { "auto" } else { "off" }.to_string();
+            }
+            Setting::ResourcePack => {
+                let available = packs::available();
+                let current = available.iter().position(|pack| *pack == config.resource_pack).unwrap_or(0) as i32;
+                let next = (current + step).rem_euclid(available.len() as i32) as usize;
+                config.resource_pack = available[next].clone();
+            }
+            Setting::Controls => config.controls = config.controls.cycle(step),
+            Setting::KeyboardLayout => config.keyboard_layout = Layout::cycle(config.keyboard_layout, step),
+            Setting::DisplayMode => config.display_mode = config.display_mode.cycle(step),
+            // Through the connected monitors, wrapping around
+            Setting::Monitor => config.monitor = (config.monitor + step).rem_euclid(get_monitor_count().max(1)),
+        }
+    }
+}
+
+pub struct Options {
+    pub selected: usize, // Index into Setting::ALL
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self { selected: 0 }
+    }
+
+    /// Handle input, changing `config` in place. Returns the setting that changed, if any
+    pub fn update(&mut self, rl: &RaylibHandle, config: &mut Config) -> Option<Setting> {
+        let count = Setting::ALL.len();
+        if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+            self.selected = (self.selected + count - 1) % count;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+            self.selected = (self.selected + 1) % count;
+        }
+
+        let step = if rl.is_key_pressed(KeyboardKey::KEY_LEFT) || rl.is_key_pressed_repeat(KeyboardKey::KEY_LEFT) {
+            -1
+        } else if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) || rl.is_key_pressed_repeat(KeyboardKey::KEY_RIGHT) {
+            1
+        } else {
+            return None;
+        };
+        let setting = Setting::ALL[self.selected];
+        setting.adjust(config, step);
+        Some(setting)
+    }
+
+    /// Focused setting and its value, as read aloud
+    pub fn focus_label(&self, config: &Config) -> String {
+        let setting = Setting::ALL[self.selected];
+        format!("{}: {}", setting.label(), setting.value(config))
+    }
+}
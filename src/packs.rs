@@ -0,0 +1,63 @@
+// Resource packs for Backrooms Doom
+// A pack is a folder under assets/packs/ that mirrors the layout of assets/:
+// assets/packs/vhs/textures/wall.png replaces assets/textures/wall.png while the
+// "vhs" pack is active. Anything a pack doesn't have falls through to the base
+// file, so a pack can be a single texture. The manifest keeps naming the base
+// paths; they are resolved through the active pack when loading, and the
+// managers reload in place so the handles the renderer holds stay valid.
+
+use crate::assets::AssetManifest;
+use std::fs;
+use std::path::Path;
+
+pub const PACKS_DIR: &str = "assets/packs";
+pub const DEFAULT_PACK: &str = "default"; // No overlay, the base assets only
+const BASE_DIR: &str = "assets/";
+
+/// Installed packs, "default" first and the rest sorted by name
+pub fn available() -> Vec<String> {
+    let mut packs: Vec<String> = fs::read_dir(PACKS_DIR)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+                .filter(|name| name != DEFAULT_PACK)
+                .collect()
+        })
+        .unwrap_or_default();
+    packs.sort();
+    packs.insert(0, DEFAULT_PACK.to_string());
+    packs
+}
+
+/// Whether `name` is the default or an installed pack. Only folder names directly under
+/// PACKS_DIR count, so a name like "../.." can't point the loaders outside it
+pub fn exists(name: &str) -> bool {
+    available().iter().any(|pack| pack == name)
+}
+
+/// Where a base asset path is read from with `pack` active: the pack's copy if it
+/// has one, the base file otherwise
+pub fn resolve(pack: &str, path: &str) -> String {
+    if pack == DEFAULT_PACK {
+        return path.to_string();
+    }
+    let Some(relative) = path.strip_prefix(BASE_DIR) else {
+        return path.to_string();
+    };
+    let overlay = Path::new(PACKS_DIR).join(pack).join(relative);
+    if overlay.is_file() {
+        overlay.to_string_lossy().into_owned()
+    } else {
+        path.to_string()
+    }
+}
+
+/// The manifest with every path resolved through `pack`
+pub fn apply(pack: &str, mut manifest: AssetManifest) -> AssetManifest {
+    for entry in &mut manifest.entries {
+        entry.path = resolve(pack, &entry.path);
+    }
+    manifest
+}
@@ -0,0 +1,112 @@
+// Pathfinding for Backrooms Doom
+// Routes over the maze grid for AI-controlled entities (A*, optionally weighted
+// per tile), and breadth-first step counts from a tile to everywhere reachable.
+// Callers cache a route together with the cell it leads to (see cell) and only
+// plan again once their target moves to another cell
+
+use crate::maze::Maze;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Tile containing the world position (x, y)
+pub fn cell(maze: &Maze, (x, y): (f32, f32)) -> (usize, usize) {
+    ((x / maze.tile_size) as usize, (y / maze.tile_size) as usize)
+}
+
+/// Shortest walkable route from one world position to another as a list of tile
+/// centers (excluding the start tile). None if the goal can't be reached
+pub fn find_path(maze: &Maze, from: (f32, f32), to: (f32, f32)) -> Option<Vec<(f32, f32)>> {
+    find_path_weighted(maze, from, to, |_| 0)
+}
+
+/// Like find_path, but entering a tile costs one step plus `extra_cost(index)` (index =
+/// y * width + x), so routes bend around expensive tiles when a detour is cheap enough
+pub fn find_path_weighted(
+    maze: &Maze,
+    from: (f32, f32),
+    to: (f32, f32),
+    extra_cost: impl Fn(usize) -> u32,
+) -> Option<Vec<(f32, f32)>> {
+    let tile = maze.tile_size;
+    let start = cell(maze, from);
+    let goal = cell(maze, to);
+    let in_bounds = |(x, y): (usize, usize)| x < maze.width && y < maze.height;
+    if !in_bounds(start) || !in_bounds(goal) || maze.is_wall(goal.0, goal.1) {
+        return None;
+    }
+
+    // A*: cheapest known cost and predecessor per tile, explored in order of cost so
+    // far plus the Manhattan distance left (never more than the real cost, every
+    // step costs at least one)
+    let index = |(x, y): (usize, usize)| y * maze.width + x;
+    let estimate = |(x, y): (usize, usize)| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u32;
+    let mut cost: Vec<u32> = vec![u32::MAX; maze.width * maze.height];
+    let mut came_from: Vec<Option<(usize, usize)>> = vec![None; maze.width * maze.height];
+    let mut open = BinaryHeap::new();
+    cost[index(start)] = 0;
+    open.push(Reverse((estimate(start), 0, start)));
+
+    while let Some(Reverse((_, current_cost, current))) = open.pop() {
+        if current == goal {
+            break;
+        }
+        if current_cost > cost[index(current)] {
+            continue; // Stale entry
+        }
+
+        let (x, y) = current;
+        for next in [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)] {
+            if !in_bounds(next) || maze.is_wall(next.0, next.1) {
+                continue;
+            }
+            let next_cost = current_cost + 1 + extra_cost(index(next));
+            if next_cost < cost[index(next)] {
+                cost[index(next)] = next_cost;
+                came_from[index(next)] = Some(current);
+                open.push(Reverse((next_cost + estimate(next), next_cost, next)));
+            }
+        }
+    }
+
+    if start != goal && came_from[index(goal)].is_none() {
+        return None;
+    }
+
+    // Walk back from the goal to rebuild the route
+    let mut path = Vec::new();
+    let mut current = goal;
+    while current != start {
+        path.push(((current.0 as f32 + 0.5) * tile, (current.1 as f32 + 0.5) * tile));
+        current = came_from[index(current)]?;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Steps from (x, y) to every tile reachable from it, indexed by y * width + x
+pub fn distances(maze: &Maze, from: (f32, f32)) -> Vec<Option<usize>> {
+    let tile = maze.tile_size;
+    let start = ((from.0 / tile) as usize, (from.1 / tile) as usize);
+    let mut steps: Vec<Option<usize>> = vec![None; maze.width * maze.height];
+    if start.0 >= maze.width || start.1 >= maze.height {
+        return steps;
+    }
+
+    let mut queue = VecDeque::new();
+    steps[start.1 * maze.width + start.0] = Some(0);
+    queue.push_back(start);
+    while let Some((x, y)) = queue.pop_front() {
+        let current = steps[y * maze.width + x].unwrap_or(0);
+        for (nx, ny) in [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)] {
+            if nx >= maze.width || ny >= maze.height || maze.is_wall(nx, ny) {
+                continue;
+            }
+            let index = ny * maze.width + nx;
+            if steps[index].is_none() {
+                steps[index] = Some(current + 1);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    steps
+}
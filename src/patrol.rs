@@ -0,0 +1,114 @@
+// Enemy patrols for Backrooms Doom
+// Patrol loops are authored in the maze file, one line per patrol after the grid:
+//
+//   patrol 14,3 14,5:2 20,5 20,3:3
+//
+// Each waypoint is a tile as x,y counted from 0 at the top-left corner, with an
+// optional pause in seconds after ':' spent looking around. The first waypoint
+// must be an enemy spawn ('e'); that enemy walks the loop (back to the first
+// waypoint after the last) whenever it has nothing else to investigate, and
+// after a chase picks the loop up again at the waypoint nearest to it.
+
+const LOOK_ANGLE: f32 = 1.6; // Radians swept to each side while looking around
+const LOOK_SPEED: f32 = 1.8; // Sweeps per second (radians of the sine)
+const SAME_POINT: f32 = 0.01;
+
+#[derive(Clone, Copy)]
+pub struct Waypoint {
+    pub x: f32, // World position (tile center)
+    pub y: f32,
+    pub pause: f32, // Seconds spent looking around on arrival
+}
+
+/// Patrol loop as authored in the level
+#[derive(Clone)]
+pub struct Patrol {
+    pub waypoints: Vec<Waypoint>,
+}
+
+impl Patrol {
+    /// Parse the waypoints of a "patrol ..." line (without the keyword) into tile coordinates
+    pub fn parse(waypoints: &str) -> Result<Vec<(usize, usize, f32)>, String> {
+        let mut points = Vec::new();
+        for token in waypoints.split_whitespace() {
+            let invalid = || format!("bad waypoint '{}', expected x,y or x,y:seconds", token);
+            let (position, pause) = match token.split_once(':') {
+                Some((position, pause)) => (position, pause.parse::<f32>().map_err(|_| invalid())?),
+                None => (token, 0.0),
+            };
+            let (x, y) = position.split_once(',').ok_or_else(invalid)?;
+            let x = x.parse::<usize>().map_err(|_| invalid())?;
+            let y = y.parse::<usize>().map_err(|_| invalid())?;
+            if !pause.is_finite() || pause < 0.0 {
+                return Err(invalid());
+            }
+            points.push((x, y, pause));
+        }
+        if points.len() < 2 {
+            return Err("a patrol needs at least two waypoints".to_string());
+        }
+        Ok(points)
+    }
+}
+
+/// An enemy's progress along its patrol
+pub struct PatrolState {
+    waypoints: Vec<Waypoint>,
+    next: usize,               // Waypoint being walked to (or waited at)
+    pause_left: Option<f32>,   // Waiting at `next`
+    look_time: f32,
+    arrival_facing: f32,
+    pub interrupted: bool,     // Left the loop to investigate or chase
+}
+
+impl PatrolState {
+    pub fn new(patrol: &Patrol) -> Self {
+        Self {
+            waypoints: patrol.waypoints.clone(),
+            next: 0,
+            pause_left: None,
+            look_time: 0.0,
+            arrival_facing: 0.0,
+            interrupted: false,
+        }
+    }
+
+    /// Whether (x, y) is the waypoint the patrol is heading for
+    pub fn is_heading_to(&self, x: f32, y: f32) -> bool {
+        let waypoint = self.waypoints[self.next];
+        (waypoint.x - x).abs() < SAME_POINT && (waypoint.y - y).abs() < SAME_POINT
+    }
+
+    /// Called while the enemy stands idle at (x, y). Waits out the pause at the waypoint it
+    /// reached, sweeping `facing` from side to side, then returns the next waypoint to walk to
+    pub fn idle(&mut self, (x, y): (f32, f32), facing: &mut f32, delta_time: f32) -> Option<(f32, f32)> {
+        if self.interrupted {
+            // Rejoin the loop where it is closest
+            self.interrupted = false;
+            self.pause_left = None;
+            let distance = |w: &Waypoint| (w.x - x).powi(2) + (w.y - y).powi(2);
+            self.next = (0..self.waypoints.len())
+                .min_by(|&a, &b| distance(&self.waypoints[a]).total_cmp(&distance(&self.waypoints[b])))
+                .unwrap_or(0);
+            let waypoint = self.waypoints[self.next];
+            return Some((waypoint.x, waypoint.y));
+        }
+
+        let pause_left = self.pause_left.get_or_insert_with(|| {
+            self.look_time = 0.0;
+            self.arrival_facing = *facing;
+            self.waypoints[self.next].pause
+        });
+        *pause_left -= delta_time;
+        if *pause_left > 0.0 {
+            self.look_time += delta_time;
+            *facing = self.arrival_facing + LOOK_ANGLE * (self.look_time * LOOK_SPEED).sin();
+            return None;
+        }
+
+        self.pause_left = None;
+        self.next = (self.next + 1) % self.waypoints.len();
+        let waypoint = self.waypoints[self.next];
+        Some((waypoint.x, waypoint.y))
+    }
+}
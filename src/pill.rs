@@ -1,10 +1,212 @@
+// Pills for Backrooms Doom
+// What each color of pill does and how often it is placed comes from assets/pills.toml
+// (see PillTable), so pills can be tuned without recompiling. The built-in table:
+// - Red: poison (15 HP over 3 seconds), blurred vision
+// - Blue: +10 HP, -20 seconds on the timer
+// - Green: a speed boost
+// - Yellow: the minimap shows pills, keys, clocks and enemies for 10 seconds
+// - Purple: does what one of the other colors does, picked when it is taken
+
 use crate::player::Vector2;
+use crate::status::Effect;
+use log::warn;
+use rand::Rng;
 use raylib::prelude::*;
+use std::fs;
+
+pub const PILLS_PATH: &str = "assets/pills.toml";
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum PillType {
-    Red,   // Bad: -15 HP, anxiety effect
-    Blue,  // Good: +10 HP, Bad: -20 seconds on timer
+    Red,
+    Blue,
+    Green,
+    Yellow,
+    Purple,
+}
+
+impl PillType {
+    pub const ALL: [PillType; 5] = [PillType::Red, PillType::Blue, PillType::Green, PillType::Yellow, PillType::Purple];
+
+    /// Section name in pills.toml
+    pub fn name(self) -> &'static str {
+        match self {
+            PillType::Red => "red",
+            PillType::Blue => "blue",
+            PillType::Green => "green",
+            PillType::Yellow => "yellow",
+            PillType::Purple => "purple",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|pill_type| pill_type.name() == name)
+    }
+
+    /// Color name for the HUD and floating text
+    pub fn label(self) -> &'static str {
+        match self {
+            PillType::Red => "Red",
+            PillType::Blue => "Blue",
+            PillType::Green => "Green",
+            PillType::Yellow => "Yellow",
+            PillType::Purple => "Purple",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            PillType::Red => Color::new(255, 50, 50, 255),
+            PillType::Blue => Color::new(50, 150, 255, 255),
+            PillType::Green => Color::new(80, 230, 100, 255),
+            PillType::Yellow => Color::new(245, 220, 60, 255),
+            PillType::Purple => Color::new(175, 80, 235, 255),
+        }
+    }
+}
+
+/// What taking a pill does: health and time right away, then status effects (see status.rs)
+#[derive(Clone, Default)]
+pub struct Dose {
+    pub heal: i32,
+    pub time: f32,                       // Seconds added to the timer (negative takes time off)
+    pub effects: Vec<(Effect, f32, f32)>, // (effect, strength, seconds)
+}
+
+/// One color's section of pills.toml
+#[derive(Clone, Default)]
+struct Variety {
+    weight: f32,  // Relative chance of a pill spot getting this color
+    dose: Dose,
+    random: bool, // Takes the dose of another color, picked at random
+}
+
+pub struct PillTable {
+    varieties: Vec<Variety>, // In PillType::ALL order
+}
+
+impl PillTable {
+    /// Parse "[color]" sections of "key = value" lines (weight, heal, time, effects, random).
+    /// Colors without a section are never placed
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut varieties = vec![Variety::default(); PillType::ALL.len()];
+        let mut current: Option<usize> = None;
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {}", line_number + 1, message);
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let pill_type = PillType::from_name(name).ok_or_else(|| error(format!("unknown pill '{}'", name)))?;
+                current = Some(pill_type as usize);
+                continue;
+            }
+            let Some(variety) = current.map(|index| &mut varieties[index]) else {
+                return Err(error("entry outside of a [pill] section".to_string()));
+            };
+            let Some((key, value)) = line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) else {
+                return Err(error(format!("expected 'key = value', got '{}'", line)));
+            };
+            let invalid = || error(format!("invalid {} '{}'", key, value));
+            match key {
+                "weight" => variety.weight = value.parse().ok().filter(|weight: &f32| weight.is_finite() && *weight >= 0.0).ok_or_else(invalid)?,
+                "heal" => variety.dose.heal = value.parse().ok().filter(|heal: &i32| *heal >= 0).ok_or_else(invalid)?,
+                "time" => variety.dose.time = value.parse().ok().filter(|time: &f32| time.is_finite()).ok_or_else(invalid)?,
+                "random" => variety.random = value.parse().map_err(|_| invalid())?,
+                "effects" => {
+                    let list = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')).ok_or_else(invalid)?;
+                    variety.dose.effects = list
+                        .split(',')
+                        .map(|entry| entry.trim().trim_matches('"'))
+                        .filter(|entry| !entry.is_empty())
+                        .map(|entry| parse_effect(entry).ok_or_else(|| error(format!("invalid effect '{}', expected \"<effect> <strength> <seconds>\"", entry))))
+                        .collect::<Result<_, _>>()?;
+                }
+                _ => return Err(error(format!("unknown key '{}'", key))),
+            }
+        }
+
+        // roll() draws from 0 up to the total, which has to be a real, positive number
+        let total: f32 = varieties.iter().map(|variety| variety.weight).sum();
+        if !total.is_finite() || total <= 0.0 {
+            return Err(format!("pill weights add up to {}, expected a finite total above 0", total));
+        }
+        Ok(Self { varieties })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Self::parse(&content).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Pills used when assets/pills.toml is missing or invalid
+    pub fn builtin() -> Self {
+        Self::parse(
+            "[red]\nweight = 4\neffects = [\"poison 5 3\", \"blur 0.6 4\"]\n\
+             [blue]\nweight = 4\nheal = 10\ntime = -20\n\
+             [green]\nweight = 2\neffects = [\"speed 0.3 8\"]\n\
+             [yellow]\nweight = 1\neffects = [\"reveal 1 10\"]\n\
+             [purple]\nweight = 1\nrandom = true\n",
+        )
+        .expect("built-in pill table is valid")
+    }
+
+    pub fn load_or_builtin(path: &str) -> Self {
+        Self::load(path).unwrap_or_else(|e| {
+            warn!("{} - using built-in pills", e);
+            Self::builtin()
+        })
+    }
+
+    /// Pick a color for a pill spot by weight (None when every weight is 0)
+    pub fn roll(&self, rng: &mut impl Rng) -> Option<PillType> {
+        let total: f32 = self.varieties.iter().map(|variety| variety.weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0.0..total);
+        for (pill_type, variety) in PillType::ALL.into_iter().zip(&self.varieties) {
+            if roll < variety.weight {
+                return Some(pill_type);
+            }
+            roll -= variety.weight;
+        }
+        // Rounding left the roll at the very end: the last color placed
+        PillType::ALL.into_iter().zip(&self.varieties).rev().find(|(_, variety)| variety.weight > 0.0).map(|(pill_type, _)| pill_type)
+    }
+
+    /// What taking a pill of `pill_type` does. A random pill takes on another color, picked
+    /// evenly from the placed colors that aren't random themselves; that color is returned with it
+    pub fn take(&self, pill_type: PillType, rng: &mut impl Rng) -> (PillType, &Dose) {
+        let variety = &self.varieties[pill_type as usize];
+        if !variety.random {
+            return (pill_type, &variety.dose);
+        }
+        let choices: Vec<PillType> = PillType::ALL
+            .into_iter()
+            .filter(|&other| {
+                let other = &self.varieties[other as usize];
+                !other.random && other.weight > 0.0
+            })
+            .collect();
+        if choices.is_empty() {
+            return (pill_type, &variety.dose);
+        }
+        let picked = choices[rng.gen_range(0..choices.len())];
+        (picked, &self.varieties[picked as usize].dose)
+    }
+}
+
+/// "poison 5 3" = (effect, strength, seconds)
+fn parse_effect(entry: &str) -> Option<(Effect, f32, f32)> {
+    let [name, strength, seconds] = entry.split_whitespace().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let number = |value: &str| value.parse::<f32>().ok().filter(|number| number.is_finite() && *number > 0.0);
+    Some((Effect::from_name(name)?, number(strength)?, number(seconds)?))
 }
 
 pub struct Pill {
@@ -44,31 +246,21 @@ impl Pill {
         distance < collect_radius
     }
 
-    /// Get glow color based on pill type
+    /// Get glow color based on pill type (the base color, pulsing)
     pub fn get_glow_color(&self) -> Color {
         let pulse = (self.glow_timer.sin() * 0.3 + 0.7) as f32;
-        match self.pill_type {
-            PillType::Red => Color::new(
-                (255.0 * pulse) as u8,
-                (50.0 * pulse) as u8,
-                (50.0 * pulse) as u8,
-                200
-            ),
-            PillType::Blue => Color::new(
-                (50.0 * pulse) as u8,
-                (150.0 * pulse) as u8,
-                (255.0 * pulse) as u8,
-                200
-            ),
-        }
+        let color = self.get_color();
+        Color::new(
+            (color.r as f32 * pulse) as u8,
+            (color.g as f32 * pulse) as u8,
+            (color.b as f32 * pulse) as u8,
+            200
+        )
     }
 
     /// Get base color for the pill
     pub fn get_color(&self) -> Color {
-        match self.pill_type {
-            PillType::Red => Color::new(255, 50, 50, 255),
-            PillType::Blue => Color::new(50, 150, 255, 255),
-        }
+        self.pill_type.color()
     }
 }
 
@@ -1,5 +1,18 @@
+use crate::guard;
+use crate::inventory::Inventory;
 use crate::maze::Maze;
 
+// Melee attack tuning
+pub const ATTACK_RANGE: f32 = 1.2;    // Reach in tiles
+pub const ATTACK_ARC: f32 = 0.5;      // Max angle (radians) off-center that still connects
+pub const ATTACK_DAMAGE: i32 = 25;
+const ATTACK_COOLDOWN: f32 = 0.45;    // Seconds between swings
+
+const CROUCH_SPEED: f32 = 0.5;        // Movement speed multiplier while crouching
+const STAMINA_REGEN: f32 = 0.15;      // Stamina recovered per second when not exerting
+const MAX_MOVE_SPEED: f32 = 10.0;     // Limits for the physics settings
+const MAX_MOVE_FORCE: f32 = 200.0;
+
 #[derive(Clone, Copy)]
 pub struct Vector2 {
     pub x: f32,
@@ -12,14 +25,81 @@ impl Vector2 {
     }
 }
 
+/// How the player accelerates and slows down (stored in settings.toml). The Backrooms carpet
+/// has a little give to it, and puddles are slick
+#[derive(Clone, Copy)]
+pub struct Physics {
+    pub acceleration: f32,   // Tiles per second, per second, towards the input direction
+    pub friction: f32,       // Tiles per second, per second, of slowdown without input
+    pub max_speed: f32,      // Tiles per second
+    pub water_friction: f32, // Grip multiplier on water tiles (lower = slides further)
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Self {
+            acceleration: 24.0,
+            friction: 18.0,
+            max_speed: 3.0,
+            water_friction: 0.35,
+        }
+    }
+}
+
+impl Physics {
+    /// Sane range of a setting by its settings.toml name, None for an unknown name
+    pub fn range(name: &str) -> Option<(f32, f32)> {
+        match name {
+            "move_acceleration" | "move_friction" => Some((1.0, MAX_MOVE_FORCE)),
+            "max_speed" => Some((0.5, MAX_MOVE_SPEED)),
+            "water_friction" => Some((0.05, 2.0)),
+            _ => None,
+        }
+    }
+
+    /// Change a setting by its settings.toml name, clamped to its range. False for an
+    /// unknown name
+    pub fn set(&mut self, name: &str, value: f32) -> bool {
+        let Some((min, max)) = Self::range(name) else {
+            return false;
+        };
+        let value = value.clamp(min, max);
+        match name {
+            "move_acceleration" => self.acceleration = value,
+            "move_friction" => self.friction = value,
+            "max_speed" => self.max_speed = value,
+            _ => self.water_friction = value,
+        }
+        true
+    }
+
+    /// Multiplier on acceleration and friction for the surface at (x, y)
+    fn grip(&self, maze: &Maze, x: f32, y: f32) -> f32 {
+        if maze.is_water(x, y) {
+            self.water_friction
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Player {
     pub pos: Vector2,
     pub angle: f32,
     pub health: i32,
     pub max_health: i32,
-    pub move_speed: f32,
+    pub physics: Physics,
+    pub velocity: Vector2, // Tiles per second
+    input: (f32, f32),     // Directions pushed this frame, not normalized
     pub rot_speed: f32,
     pub collision_radius: f32,
+    pub attack_cooldown: f32, // Time until the next swing is ready
+    pub crouching: bool,
+    pub stamina: f32, // 0.0 - 1.0, spent bracing doors
+    pub pitch: f32,   // Looking up (+) or down (-): horizon shift in view heights (see camera.rs)
+    pub speed_multiplier: f32, // From status effects (see status.rs)
+    pub inventory: Inventory,  // Consumables on the hotbar (see inventory.rs)
 }
 
 impl Player {
@@ -29,54 +109,118 @@ impl Player {
             angle: 0.0,
             health: 100,
             max_health: 100,
-            move_speed: 3.0,
+            physics: Physics::default(),
+            velocity: Vector2::new(0.0, 0.0),
+            input: (0.0, 0.0),
             rot_speed: 2.5,
             collision_radius: 0.3,
+            attack_cooldown: 0.0,
+            crouching: false,
+            stamina: 1.0,
+            pitch: 0.0,
+            speed_multiplier: 1.0,
+            inventory: Inventory::new(),
         }
     }
 
-    /// Move forward in the direction the player is facing
-    pub fn move_forward(&mut self, maze: &Maze, delta_time: f32) {
-        let new_x = self.pos.x + self.angle.cos() * self.move_speed * delta_time;
-        let new_y = self.pos.y + self.angle.sin() * self.move_speed * delta_time;
+    /// Current movement speed (slower while crouching, scaled by status effects)
+    pub fn speed(&self) -> f32 {
+        let speed = if self.crouching { self.physics.max_speed * CROUCH_SPEED } else { self.physics.max_speed };
+        speed * self.speed_multiplier
+    }
 
-        if self.check_collision(maze, new_x, new_y) {
-            self.pos.x = new_x;
-            self.pos.y = new_y;
-        }
+    /// Push towards the direction the player is facing this frame (see update_movement)
+    pub fn move_forward(&mut self) {
+        self.push(self.angle);
     }
 
-    /// Move backward (opposite of facing direction)
-    pub fn move_backward(&mut self, maze: &Maze, delta_time: f32) {
-        let new_x = self.pos.x - self.angle.cos() * self.move_speed * delta_time;
-        let new_y = self.pos.y - self.angle.sin() * self.move_speed * delta_time;
+    /// Push backward (opposite of facing direction)
+    pub fn move_backward(&mut self) {
+        self.push(self.angle + std::f32::consts::PI);
+    }
 
-        if self.check_collision(maze, new_x, new_y) {
-            self.pos.x = new_x;
-            self.pos.y = new_y;
-        }
+    /// Push left (perpendicular to facing direction)
+    pub fn move_left(&mut self) {
+        self.push(self.angle - std::f32::consts::PI / 2.0);
     }
 
-    /// Strafe left (perpendicular to facing direction)
-    pub fn move_left(&mut self, maze: &Maze, delta_time: f32) {
-        let new_x = self.pos.x + (self.angle - std::f32::consts::PI / 2.0).cos() * self.move_speed * delta_time;
-        let new_y = self.pos.y + (self.angle - std::f32::consts::PI / 2.0).sin() * self.move_speed * delta_time;
+    /// Push right
+    pub fn move_right(&mut self) {
+        self.push(self.angle + std::f32::consts::PI / 2.0);
+    }
 
+    fn push(&mut self, angle: f32) {
+        self.input.0 += angle.cos();
+        self.input.1 += angle.sin();
+    }
+
+    /// Accelerate towards this frame's input at up to the current speed, or slow down with
+    /// friction without any, both scaled by the grip of the floor underneath. Then move,
+    /// sliding along walls: velocity into a wall is lost
+    pub fn update_movement(&mut self, maze: &Maze, delta_time: f32) {
+        let (input_x, input_y) = guard::normalize(self.input.0, self.input.1);
+        self.input = (0.0, 0.0);
+        let grip = self.physics.grip(maze, self.pos.x, self.pos.y);
+
+        if input_x != 0.0 || input_y != 0.0 {
+            let (dx, dy) = (input_x * self.speed() - self.velocity.x, input_y * self.speed() - self.velocity.y);
+            let change = (self.physics.acceleration * grip * delta_time).min((dx * dx + dy * dy).sqrt());
+            let (dir_x, dir_y) = guard::normalize(dx, dy);
+            self.velocity.x += dir_x * change;
+            self.velocity.y += dir_y * change;
+        } else {
+            let speed = (self.velocity.x * self.velocity.x + self.velocity.y * self.velocity.y).sqrt();
+            let slowed = (speed - self.physics.friction * grip * delta_time).max(0.0);
+            let (dir_x, dir_y) = guard::normalize(self.velocity.x, self.velocity.y);
+            self.velocity = Vector2::new(dir_x * slowed, dir_y * slowed);
+        }
+
+        let new_x = self.pos.x + self.velocity.x * delta_time;
+        let new_y = self.pos.y + self.velocity.y * delta_time;
         if self.check_collision(maze, new_x, new_y) {
             self.pos.x = new_x;
             self.pos.y = new_y;
+        } else if self.check_collision(maze, new_x, self.pos.y) {
+            self.pos.x = new_x;
+            self.velocity.y = 0.0;
+        } else if self.check_collision(maze, self.pos.x, new_y) {
+            self.pos.y = new_y;
+            self.velocity.x = 0.0;
+        } else {
+            self.velocity = Vector2::new(0.0, 0.0);
         }
     }
 
-    /// Strafe right
-    pub fn move_right(&mut self, maze: &Maze, delta_time: f32) {
-        let new_x = self.pos.x + (self.angle + std::f32::consts::PI / 2.0).cos() * self.move_speed * delta_time;
-        let new_y = self.pos.y + (self.angle + std::f32::consts::PI / 2.0).sin() * self.move_speed * delta_time;
+    /// Drop all momentum (movement locked, e.g. while searching a container)
+    pub fn stop(&mut self) {
+        self.input = (0.0, 0.0);
+        self.velocity = Vector2::new(0.0, 0.0);
+    }
+
+    /// Turn towards (x, y) and walk there at a fraction of the normal speed, sliding along
+    /// walls. Used for bot-controlled players. Returns the distance left afterwards
+    pub fn walk_towards(&mut self, maze: &Maze, x: f32, y: f32, speed_factor: f32, delta_time: f32) -> f32 {
+        let dx = x - self.pos.x;
+        let dy = y - self.pos.y;
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance <= f32::EPSILON {
+            return 0.0;
+        }
 
+        self.angle = dy.atan2(dx);
+        let step = (self.speed() * speed_factor * delta_time).min(distance);
+        let new_x = self.pos.x + dx / distance * step;
+        let new_y = self.pos.y + dy / distance * step;
         if self.check_collision(maze, new_x, new_y) {
             self.pos.x = new_x;
             self.pos.y = new_y;
+        } else if self.check_collision(maze, new_x, self.pos.y) {
+            self.pos.x = new_x;
+        } else if self.check_collision(maze, self.pos.x, new_y) {
+            self.pos.y = new_y;
         }
+
+        ((x - self.pos.x).powi(2) + (y - self.pos.y).powi(2)).sqrt()
     }
 
     /// Rotate player view
@@ -111,6 +255,54 @@ impl Player {
         true
     }
 
+    /// Tick down the attack cooldown
+    pub fn update_attack(&mut self, delta_time: f32) {
+        self.attack_cooldown = (self.attack_cooldown - delta_time).max(0.0);
+    }
+
+    /// How far the swing cooldown has recovered, 1.0 = ready
+    pub fn attack_ready(&self) -> f32 {
+        1.0 - self.attack_cooldown / ATTACK_COOLDOWN
+    }
+
+    /// Start a swing if the cooldown allows it
+    pub fn try_attack(&mut self) -> bool {
+        if self.attack_cooldown > 0.0 {
+            return false;
+        }
+        self.attack_cooldown = ATTACK_COOLDOWN;
+        true
+    }
+
+    /// Point where a swing straight ahead hits a wall, if one is within reach
+    pub fn attack_wall_hit(&self, maze: &Maze) -> Option<(f32, f32)> {
+        let step = 0.05;
+        let mut distance = 0.0;
+        while distance <= ATTACK_RANGE {
+            let x = self.pos.x + self.angle.cos() * distance;
+            let y = self.pos.y + self.angle.sin() * distance;
+            if !maze.is_walkable(x, y) {
+                return Some((x, y));
+            }
+            distance += step;
+        }
+        None
+    }
+
+    /// Spend stamina if there is any left. Returns false when exhausted
+    pub fn use_stamina(&mut self, amount: f32) -> bool {
+        if self.stamina <= 0.0 {
+            return false;
+        }
+        self.stamina = (self.stamina - amount).max(0.0);
+        true
+    }
+
+    /// Recover stamina over time
+    pub fn regen_stamina(&mut self, delta_time: f32) {
+        self.stamina = (self.stamina + STAMINA_REGEN * delta_time).min(1.0);
+    }
+
     /// Take damage
     pub fn take_damage(&mut self, amount: i32) {
         self.health = (self.health - amount).max(0);
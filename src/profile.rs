@@ -0,0 +1,185 @@
+// Player profiles for Backrooms Doom
+// Everything the game keeps between launches (settings.toml, the leaderboard and
+// the crash autosave) lives in a folder per profile, profiles/<name>/, so several
+// people can share one machine without overwriting each other's settings and
+// times. The startup screen lists the profiles with the last one used selected;
+// on first launch it goes straight to entering a name. The first profile created
+// adopts any settings, leaderboard and autosave left in the game folder by older
+// versions, so nobody loses their runs when updating.
+//
+// The online submission queue (leaderboard_queue.txt) stays shared: it is only a
+// list of runs waiting to be sent.
+
+use crate::config;
+use crate::leaderboard;
+use crate::save;
+use log::{info, warn};
+use raylib::prelude::*;
+use std::fs;
+use std::path::Path;
+
+pub const PROFILES_DIR: &str = "profiles";
+const LAST_USED_PATH: &str = "profiles/last.txt";
+pub const MAX_NAME_LENGTH: usize = 16;
+
+#[derive(Clone)]
+pub struct Profile {
+    pub name: String,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string() }
+    }
+
+    /// Path of one of this profile's files, e.g. file("settings.toml")
+    pub fn file(&self, name: &str) -> String {
+        format!("{}/{}/{}", PROFILES_DIR, self.name, name)
+    }
+
+    /// The profile used last time, if it still exists
+    pub fn last_used() -> Option<Self> {
+        let name = fs::read_to_string(LAST_USED_PATH).ok()?;
+        let name = name.trim();
+        list().iter().any(|profile| profile == name).then(|| Self::new(name))
+    }
+
+    /// Remember this profile for the next launch
+    pub fn remember(&self) {
+        if let Err(e) = fs::write(LAST_USED_PATH, format!("{}\n", self.name)) {
+            warn!("Could not write {}: {}", LAST_USED_PATH, e);
+        }
+    }
+
+    /// Create a new profile folder. The first profile adopts pre-profile files
+    pub fn create(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        validate_name(name)?;
+        let existing = list();
+        if existing.iter().any(|profile| profile.eq_ignore_ascii_case(name)) {
+            return Err(format!("There is already a profile called '{}'", name));
+        }
+
+        let profile = Self::new(name);
+        let folder = format!("{}/{}", PROFILES_DIR, name);
+        fs::create_dir_all(&folder).map_err(|e| format!("Could not create {}: {}", folder, e))?;
+        info!("Created profile '{}'", name);
+
+        if existing.is_empty() {
+            for file in [config::CONFIG_PATH, leaderboard::LEADERBOARD_PATH, save::AUTOSAVE_PATH] {
+                if Path::new(file).exists() {
+                    match fs::rename(file, profile.file(file)) {
+                        Ok(()) => info!("Moved {} into profile '{}'", file, name),
+                        Err(e) => warn!("Could not move {} into profile '{}': {}", file, name, e),
+                    }
+                }
+            }
+        }
+        Ok(profile)
+    }
+}
+
+/// Names of all profiles, sorted
+pub fn list() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(PROFILES_DIR) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| validate_name(name).is_ok())
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names
+}
+
+/// Characters allowed in a profile name (it becomes a folder name)
+pub fn name_char_allowed(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_'
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Enter a name".to_string());
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(format!("Names can be at most {} characters", MAX_NAME_LENGTH));
+    }
+    if !name.chars().all(name_char_allowed) || name != name.trim() {
+        return Err("Use letters, digits, spaces, - and _".to_string());
+    }
+    Ok(())
+}
+
+/// Startup screen: pick a profile from the list or type a name for a new one
+pub struct ProfilePicker {
+    pub profiles: Vec<String>,
+    pub selected: usize,        // Index into profiles, profiles.len() = "New profile"
+    pub naming: Option<String>, // Name being typed for a new profile
+    pub error: Option<String>,
+}
+
+impl ProfilePicker {
+    pub fn new() -> Self {
+        let profiles = list();
+        let last = Profile::last_used().map(|profile| profile.name);
+        let selected = last.and_then(|last| profiles.iter().position(|name| *name == last)).unwrap_or(0);
+        let naming = profiles.is_empty().then(String::new);
+        Self { profiles, selected, naming, error: None }
+    }
+
+    /// Handle input. Returns the chosen profile once one is picked or created
+    pub fn update(&mut self, rl: &mut RaylibHandle) -> Option<Profile> {
+        let Some(name) = &mut self.naming else {
+            let count = self.profiles.len() + 1; // The profiles plus "New profile"
+            if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+                self.selected = (self.selected + count - 1) % count;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+                self.selected = (self.selected + 1) % count;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                // Drop anything typed on the list so it doesn't end up in the name
+                while rl.get_char_pressed().is_some() {}
+                match self.profiles.get(self.selected) {
+                    Some(name) => return Some(Profile::new(name)),
+                    None => self.naming = Some(String::new()),
+                }
+            }
+            return None;
+        };
+
+        while let Some(c) = rl.get_char_pressed() {
+            if name_char_allowed(c) && name.len() < MAX_NAME_LENGTH {
+                name.push(c);
+                self.error = None;
+            }
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) || rl.is_key_pressed_repeat(KeyboardKey::KEY_BACKSPACE) {
+            // Backspace on an empty name goes back to the list, if there is one
+            if name.pop().is_none() && !self.profiles.is_empty() && rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+                self.naming = None;
+            }
+            self.error = None;
+            return None;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            match Profile::create(name) {
+                Ok(profile) => return Some(profile),
+                Err(e) => self.error = Some(e),
+            }
+        }
+        None
+    }
+
+    /// Focused entry, as read aloud: a profile, "New profile", or the name being typed
+    pub fn focus_label(&self) -> String {
+        match (&self.naming, &self.error) {
+            (Some(_), Some(error)) => error.clone(),
+            (Some(name), None) if name.is_empty() => "Type a name for the new profile, then press ENTER".to_string(),
+            (Some(name), None) => name.clone(),
+            (None, _) => self.profiles.get(self.selected).map_or("New profile".to_string(), |name| name.clone()),
+        }
+    }
+}
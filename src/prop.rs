@@ -0,0 +1,45 @@
+// Breakable props for Backrooms Doom
+// Cardboard boxes placed with 'b' in the maze. Melee attacks break them and
+// broken props roll the "prop" loot table
+
+use crate::player::Vector2;
+
+const HIT_FLASH_DURATION: f32 = 0.1; // Seconds the sprite stays white after a hit
+
+pub struct Prop {
+    pub pos: Vector2,
+    pub health: i32,
+    pub hit_flash_timer: f32,
+}
+
+impl Prop {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            pos: Vector2::new(x, y),
+            health: 40,
+            hit_flash_timer: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.hit_flash_timer = (self.hit_flash_timer - delta_time).max(0.0);
+    }
+
+    /// Apply damage. Returns true if this hit broke the prop
+    pub fn take_damage(&mut self, amount: i32) -> bool {
+        if self.is_broken() {
+            return false;
+        }
+        self.health = (self.health - amount).max(0);
+        self.hit_flash_timer = HIT_FLASH_DURATION;
+        self.is_broken()
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.health <= 0
+    }
+
+    pub fn is_flashing(&self) -> bool {
+        self.hit_flash_timer > 0.0
+    }
+}
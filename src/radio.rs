@@ -0,0 +1,86 @@
+// Radio lure for Backrooms Doom
+// A battery-powered radio the player can set down or toss. While it plays it
+// keeps making noise so enemies wander over to it instead of the player. Its loop
+// is Doppler shifted (doppler.rs) as the player runs past it
+
+use crate::doppler::Doppler;
+use crate::maze::Maze;
+use crate::player::Vector2;
+
+const BATTERY_LIFE: f32 = 30.0;   // Seconds of playback on a full battery
+const NOISE_INTERVAL: f32 = 1.5;  // Seconds between noise pulses
+pub const NOISE_RADIUS: f32 = 7.0; // Hearing range of each pulse in tiles
+pub const PICKUP_RANGE: f32 = 1.0;
+const THROW_DISTANCE: f32 = 1.5;  // How far ahead the radio lands when tossed
+pub const HEARING_RANGE: f32 = 10.0; // Distance where the loop becomes inaudible
+
+pub struct Radio {
+    pub pos: Vector2,
+    pub battery: f32,      // Seconds of playback left
+    pub placed: bool,      // On the floor (playing) rather than carried
+    pub noise_timer: f32,  // Time until the next noise pulse
+    pub doppler: Doppler,  // Pitch shift of the loop heard by the player
+}
+
+impl Radio {
+    pub fn new() -> Self {
+        Self {
+            pos: Vector2::new(0.0, 0.0),
+            battery: BATTERY_LIFE,
+            placed: false,
+            noise_timer: 0.0,
+            doppler: Doppler::new(),
+        }
+    }
+
+    /// Toss the radio ahead of (x, y), landing at the last walkable point along the way
+    pub fn place(&mut self, maze: &Maze, x: f32, y: f32, angle: f32) {
+        let mut landing = (x, y);
+        let mut distance = 0.1;
+        while distance <= THROW_DISTANCE {
+            let point = (x + angle.cos() * distance, y + angle.sin() * distance);
+            if !maze.is_walkable(point.0, point.1) {
+                break;
+            }
+            landing = point;
+            distance += 0.1;
+        }
+
+        self.pos = Vector2::new(landing.0, landing.1);
+        self.placed = true;
+        self.noise_timer = 0.0; // Make noise right away
+        self.doppler.reset();
+    }
+
+    pub fn pick_up(&mut self) {
+        self.placed = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.placed && self.battery > 0.0
+    }
+
+    /// Drain the battery. Returns true when a noise pulse is due
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        if !self.is_playing() {
+            return false;
+        }
+
+        self.battery = (self.battery - delta_time).max(0.0);
+        self.noise_timer -= delta_time;
+        if self.noise_timer <= 0.0 {
+            self.noise_timer = NOISE_INTERVAL;
+            return true;
+        }
+        false
+    }
+
+    /// Battery left as 0.0 - 1.0
+    pub fn charge(&self) -> f32 {
+        self.battery / BATTERY_LIFE
+    }
+
+    pub fn in_reach(&self, x: f32, y: f32) -> bool {
+        self.placed && ((self.pos.x - x).powi(2) + (self.pos.y - y).powi(2)).sqrt() <= PICKUP_RANGE
+    }
+}
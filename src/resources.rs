@@ -0,0 +1,128 @@
+// Resource handles for Backrooms Doom
+// Managers hand out small typed handles (TextureId, SoundId) so names are
+// resolved once at load time and hot paths index a Vec instead of a HashMap
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Marker for texture handles
+pub enum TextureKind {}
+/// Marker for sound handles
+pub enum SoundKind {}
+
+pub type TextureId = Handle<TextureKind>;
+pub type SoundId = Handle<SoundKind>;
+
+/// Index into a `Registry`. The kind parameter keeps texture and sound handles apart
+pub struct Handle<K> {
+    index: usize,
+    _kind: PhantomData<K>,
+}
+
+impl<K> Clone for Handle<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for Handle<K> {}
+
+impl<K> PartialEq for Handle<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+/// Named storage where every name keeps the same handle for the whole session,
+/// even while its resource is unloaded
+pub struct Registry<K, T> {
+    slots: Vec<Option<T>>,
+    names: Vec<String>,
+    handles: HashMap<String, Handle<K>>,
+}
+
+impl<K, T> Registry<K, T> {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            names: Vec::new(),
+            handles: HashMap::new(),
+        }
+    }
+
+    /// Get the handle for a name, reserving an empty slot if it has never been seen
+    pub fn handle(&mut self, name: &str) -> Handle<K> {
+        if let Some(handle) = self.handles.get(name) {
+            return *handle;
+        }
+
+        let handle = Handle {
+            index: self.slots.len(),
+            _kind: PhantomData,
+        };
+        self.slots.push(None);
+        self.names.push(name.to_string());
+        self.handles.insert(name.to_string(), handle);
+        handle
+    }
+
+    /// Store a resource under a name and return its handle
+    pub fn insert(&mut self, name: &str, value: T) -> Handle<K> {
+        let handle = self.handle(name);
+        self.slots[handle.index] = Some(value);
+        handle
+    }
+
+    /// Unload a resource, its handle stays valid but resolves to nothing
+    pub fn remove(&mut self, name: &str) -> Option<T> {
+        let handle = *self.handles.get(name)?;
+        self.slots[handle.index].take()
+    }
+
+    #[inline]
+    pub fn get(&self, handle: Handle<K>) -> Option<&T> {
+        self.slots.get(handle.index)?.as_ref()
+    }
+
+    /// Unload everything, every handle stays valid but resolves to nothing
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+    }
+
+    /// Look up a loaded resource by name (for load-time code, not per frame)
+    pub fn get_by_name(&self, name: &str) -> Option<&T> {
+        self.get(*self.handles.get(name)?)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get_by_name(name).is_some()
+    }
+
+    /// Number of loaded resources
+    pub fn loaded_count(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// All loaded resources
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().flatten()
+    }
+
+    /// All loaded resources with their names, for changing them in place at load time
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut T)> {
+        self.names
+            .iter()
+            .zip(self.slots.iter_mut())
+            .filter_map(|(name, slot)| Some((name.as_str(), slot.as_mut()?)))
+    }
+
+    /// Names that have a handle but nothing loaded behind it
+    pub fn missing(&self) -> Vec<&str> {
+        self.slots
+            .iter()
+            .zip(&self.names)
+            .filter(|(slot, _)| slot.is_none())
+            .map(|(_, name)| name.as_str())
+            .collect()
+    }
+}
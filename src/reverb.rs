@@ -0,0 +1,93 @@
+// Room acoustics for Backrooms Doom
+// A narrow corridor should sound close and dry, an empty hall cavernous. The
+// openness of the space around the player is the share of tiles within
+// REACH steps (walking, so rooms behind a wall don't count) that are open,
+// worked out once per tile and cached for the level. Openness above a tight
+// corridor's maps to a wet level (0.0 - 1.0) that the audio manager uses to
+// layer delayed echoes of footsteps and impacts under the dry sound: bigger
+// spaces echo later and louder (see AudioManager::play_echo).
+
+use crate::maze::Maze;
+use crate::pathfinding;
+use std::collections::VecDeque;
+
+const REACH: usize = 5;        // Steps counted around the player
+const TIGHT: f32 = 0.25;       // Openness of a plain corridor and below: no echo
+const SMOOTHING: f32 = 2.0;    // How fast the wet level follows the player (per second)
+const MIN_DELAY: f32 = 0.08;   // Seconds until the first echo in a small room...
+const MAX_DELAY: f32 = 0.3;    // ...and in a huge hall
+const ECHO_VOLUME: f32 = 0.45; // First echo at full wet level, relative to the sound
+const DECAY: f32 = 0.45;       // Each further echo relative to the one before
+pub const TAPS: usize = 2;     // Echoes per sound
+
+pub struct Acoustics {
+    openness: Vec<Option<f32>>, // Per tile, indexed by y * width + x, worked out on first visit
+    pub wet: f32,               // Smoothed wet level where the player is
+}
+
+impl Acoustics {
+    pub fn new() -> Self {
+        Self { openness: Vec::new(), wet: 0.0 }
+    }
+
+    /// Follow the player to (x, y)
+    pub fn update(&mut self, maze: &Maze, x: f32, y: f32, delta_time: f32) {
+        let target = ((self.openness(maze, x, y) - TIGHT) / (1.0 - TIGHT)).clamp(0.0, 1.0);
+        self.wet += (target - self.wet) * (SMOOTHING * delta_time).min(1.0);
+    }
+
+    /// Share of the tiles within REACH steps of (x, y) that can be walked to (0.0 - 1.0)
+    fn openness(&mut self, maze: &Maze, x: f32, y: f32) -> f32 {
+        if self.openness.len() != maze.width * maze.height {
+            self.openness = vec![None; maze.width * maze.height];
+        }
+        let (cell_x, cell_y) = pathfinding::cell(maze, (x, y));
+        if cell_x >= maze.width || cell_y >= maze.height {
+            return 0.0;
+        }
+        let index = cell_y * maze.width + cell_x;
+        if let Some(openness) = self.openness[index] {
+            return openness;
+        }
+
+        // Flood fill out to REACH steps, against every tile that close as the crow flies
+        let mut seen = vec![false; maze.width * maze.height];
+        let mut queue = VecDeque::new();
+        seen[index] = true;
+        queue.push_back((cell_x, cell_y, 0));
+        let mut open = 0;
+        while let Some((x, y, step)) = queue.pop_front() {
+            open += 1;
+            if step == REACH {
+                continue;
+            }
+            for (nx, ny) in [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)] {
+                if nx >= maze.width || ny >= maze.height || maze.is_wall(nx, ny) || seen[ny * maze.width + nx] {
+                    continue;
+                }
+                seen[ny * maze.width + nx] = true;
+                queue.push_back((nx, ny, step + 1));
+            }
+        }
+        let possible = 2 * REACH * (REACH + 1) + 1;
+        let openness = open as f32 / possible as f32;
+        self.openness[index] = Some(openness);
+        openness
+    }
+}
+
+/// (delay in seconds, volume relative to the dry sound) of each echo at wet level `wet`.
+/// None in a tight space
+pub fn taps(wet: f32) -> Option<[(f32, f32); TAPS]> {
+    if wet <= 0.0 {
+        return None;
+    }
+    let delay = MIN_DELAY + (MAX_DELAY - MIN_DELAY) * wet;
+    let mut taps = [(0.0, 0.0); TAPS];
+    let mut volume = ECHO_VOLUME * wet;
+    for (i, tap) in taps.iter_mut().enumerate() {
+        *tap = (delay * (i + 1) as f32, volume);
+        volume *= DECAY;
+    }
+    Some(taps)
+}
@@ -0,0 +1,161 @@
+// Controller rumble for Backrooms Doom
+// When a gamepad is connected, gameplay feedback that has a sound also shakes the
+// controller: taking damage, the heartbeat while anxiety is high, doors slamming,
+// the player's own footsteps and entities walking nearby. Each cue has an intensity curve (attack, hold,
+// release on the low/high frequency motors), overlapping cues take the strongest
+// value per motor, and the mix is sent to raylib every frame.
+
+use raylib::prelude::*;
+
+const GAMEPAD: i32 = 0;
+const REFRESH_TIME: f32 = 0.1; // Vibration length per update, so a dropped frame doesn't leave it running
+
+// Heartbeat: a lub-dub that speeds up with anxiety
+const HEARTBEAT_THRESHOLD: f32 = 0.4; // Anxiety above this is felt
+const HEARTBEAT_SLOW: f32 = 1.0;      // Seconds between beats at the threshold
+const HEARTBEAT_FAST: f32 = 0.5;      // ... and at full anxiety
+const HEARTBEAT_GAP: f32 = 0.18;      // Delay from "lub" to "dub"
+
+// Entity footsteps: light taps that get stronger as it gets closer
+const FOOTSTEP_RANGE: f32 = 6.0; // Tiles
+const FOOTSTEP_INTERVAL: f32 = 0.45;
+
+const FULL_DAMAGE: f32 = 20.0; // Hits this big or bigger rumble at full strength
+
+/// Envelope for one rumble cue, values 0.0 - 1.0 per motor
+#[derive(Clone, Copy)]
+pub struct RumbleCurve {
+    pub low: f32,     // Heavy motor: thuds and impacts
+    pub high: f32,    // Light motor: buzz and texture
+    pub attack: f32,  // Seconds to ramp up
+    pub hold: f32,    // Seconds at full strength
+    pub release: f32, // Seconds to fade out
+}
+
+impl RumbleCurve {
+    fn length(&self) -> f32 {
+        self.attack + self.hold + self.release
+    }
+
+    /// Envelope value at `time` seconds into the cue
+    fn envelope(&self, time: f32) -> f32 {
+        if time < 0.0 || time >= self.length() {
+            0.0
+        } else if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.hold {
+            1.0
+        } else {
+            1.0 - (time - self.attack - self.hold) / self.release
+        }
+    }
+}
+
+// Curves, named after the sound they accompany
+const DAMAGE: RumbleCurve = RumbleCurve { low: 0.9, high: 0.6, attack: 0.02, hold: 0.12, release: 0.25 };
+const HEARTBEAT_LUB: RumbleCurve = RumbleCurve { low: 0.55, high: 0.0, attack: 0.03, hold: 0.05, release: 0.1 };
+const HEARTBEAT_DUB: RumbleCurve = RumbleCurve { low: 0.35, high: 0.0, attack: 0.03, hold: 0.03, release: 0.1 };
+pub const DOOR_SLAM: RumbleCurve = RumbleCurve { low: 1.0, high: 0.3, attack: 0.0, hold: 0.08, release: 0.3 };
+const PLAYER_FOOTSTEP: RumbleCurve = RumbleCurve { low: 0.12, high: 0.0, attack: 0.01, hold: 0.02, release: 0.06 };
+const ENTITY_FOOTSTEP: RumbleCurve = RumbleCurve { low: 0.3, high: 0.1, attack: 0.02, hold: 0.02, release: 0.08 };
+
+struct Pulse {
+    curve: RumbleCurve,
+    scale: f32,
+    time: f32, // Negative while waiting to start
+}
+
+pub struct Haptics {
+    pulses: Vec<Pulse>,
+    heartbeat_timer: f32,
+    footstep_timer: f32,
+    rumbling: bool, // Motors were left on last frame
+}
+
+impl Haptics {
+    pub fn new() -> Self {
+        Self {
+            pulses: Vec::new(),
+            heartbeat_timer: 0.0,
+            footstep_timer: 0.0,
+            rumbling: false,
+        }
+    }
+
+    /// Start a cue, scale 0.0 - 1.0
+    pub fn play(&mut self, curve: RumbleCurve, scale: f32) {
+        self.play_after(0.0, curve, scale);
+    }
+
+    pub fn play_after(&mut self, delay: f32, curve: RumbleCurve, scale: f32) {
+        let scale = scale.clamp(0.0, 1.0);
+        if scale > 0.0 {
+            self.pulses.push(Pulse { curve, scale, time: -delay });
+        }
+    }
+
+    /// Jolt for health lost
+    pub fn damage(&mut self, amount: i32) {
+        self.play(DAMAGE, amount as f32 / FULL_DAMAGE);
+    }
+
+    /// Faint thud as the player's foot lands, strength 0.0 - 1.0
+    pub fn footstep(&mut self, strength: f32) {
+        self.play(PLAYER_FOOTSTEP, strength);
+    }
+
+    /// Beat in time with the heart while anxiety (0.0 - 1.0) is high
+    pub fn update_heartbeat(&mut self, anxiety: f32, delta_time: f32) {
+        if anxiety <= HEARTBEAT_THRESHOLD {
+            self.heartbeat_timer = 0.0;
+            return;
+        }
+        self.heartbeat_timer -= delta_time;
+        if self.heartbeat_timer <= 0.0 {
+            self.play(HEARTBEAT_LUB, anxiety);
+            self.play_after(HEARTBEAT_GAP, HEARTBEAT_DUB, anxiety);
+            let t = (anxiety - HEARTBEAT_THRESHOLD) / (1.0 - HEARTBEAT_THRESHOLD);
+            self.heartbeat_timer = HEARTBEAT_SLOW + (HEARTBEAT_FAST - HEARTBEAT_SLOW) * t;
+        }
+    }
+
+    /// Tap with the steps of the closest walking entity, if one is within FOOTSTEP_RANGE
+    pub fn update_footsteps(&mut self, closest: Option<f32>, delta_time: f32) {
+        let Some(distance) = closest.filter(|d| *d < FOOTSTEP_RANGE) else {
+            self.footstep_timer = 0.0;
+            return;
+        };
+        self.footstep_timer -= delta_time;
+        if self.footstep_timer <= 0.0 {
+            self.play(ENTITY_FOOTSTEP, 1.0 - distance / FOOTSTEP_RANGE);
+            self.footstep_timer = FOOTSTEP_INTERVAL;
+        }
+    }
+
+    /// Advance the cues and drive the motors. With `active` false (disabled in the settings,
+    /// not playing) cues are dropped and the controller is stopped
+    pub fn update(&mut self, rl: &mut RaylibHandle, delta_time: f32, active: bool) {
+        if !active || !rl.is_gamepad_available(GAMEPAD) {
+            self.pulses.clear();
+        }
+
+        for pulse in &mut self.pulses {
+            pulse.time += delta_time;
+        }
+        self.pulses.retain(|pulse| pulse.time < pulse.curve.length());
+
+        // Strongest cue wins on each motor
+        let (mut low, mut high) = (0.0f32, 0.0f32);
+        for pulse in &self.pulses {
+            let value = pulse.curve.envelope(pulse.time) * pulse.scale;
+            low = low.max(pulse.curve.low * value);
+            high = high.max(pulse.curve.high * value);
+        }
+
+        let rumbling = low > 0.0 || high > 0.0;
+        if (rumbling || self.rumbling) && rl.is_gamepad_available(GAMEPAD) {
+            rl.set_gamepad_vibration(GAMEPAD, low, high, REFRESH_TIME);
+        }
+        self.rumbling = rumbling;
+    }
+}
@@ -0,0 +1,34 @@
+// Save data for Backrooms Doom
+// Stores a snapshot of the current run as simple "key = value" lines.
+// Hardcore runs can't be saved: writing one is refused.
+
+use std::fs;
+
+pub const AUTOSAVE_PATH: &str = "autosave.sav";
+
+pub struct SaveData {
+    pub player_x: f32,
+    pub player_y: f32,
+    pub player_angle: f32,
+    pub health: i32,
+    pub game_timer: f32,
+    pub hardcore: bool, // Run started in hardcore mode
+}
+
+impl SaveData {
+    /// Serialize save data to "key = value" lines
+    pub fn serialize(&self) -> String {
+        format!(
+            "player_x = {}\nplayer_y = {}\nplayer_angle = {}\nhealth = {}\ngame_timer = {}\n",
+            self.player_x, self.player_y, self.player_angle, self.health, self.game_timer
+        )
+    }
+
+    /// Write save data to disk
+    pub fn write_to_file(&self, path: &str) -> Result<(), String> {
+        if self.hardcore {
+            return Err("Saving is disabled in hardcore runs".to_string());
+        }
+        fs::write(path, self.serialize()).map_err(|e| format!("Failed to write save file: {}", e))
+    }
+}
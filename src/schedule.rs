@@ -0,0 +1,90 @@
+// Update scheduling for Backrooms Doom
+// A frame of play is a list of named systems run in order. Each system belongs to a
+// stage, and the stages always run in the same order: Input (reading the player's
+// keys and mouse), Simulation (timers, AI, the world changing on its own), Collision
+// (who touches what: positions, doors, pickups) and Post (effects, events, the win
+// check). Within a stage systems run in the order they were added, so a new feature
+// registers into its stage and lands after everything already there, without having
+// to find the right line in one long update function. The order can be printed with
+// the console's `systems` command. Once a system ends the run (game over, reaching
+// the exit) the rest of the frame is skipped.
+
+use raylib::prelude::RaylibHandle;
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Stage {
+    Input,
+    Simulation,
+    Collision,
+    Post,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [Stage::Input, Stage::Simulation, Stage::Collision, Stage::Post];
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+pub type Run<T> = fn(&mut T, &mut RaylibHandle, f32);
+
+struct System<T> {
+    name: &'static str,
+    stage: Stage,
+    run: Run<T>,
+}
+
+pub struct Schedule<T> {
+    systems: Vec<System<T>>, // Sorted by stage, in the order added within one
+}
+
+impl<T> Schedule<T> {
+    pub fn new() -> Self {
+        Self { systems: Vec::new() }
+    }
+
+    /// Add a system at the end of its stage
+    pub fn add(mut self, stage: Stage, name: &'static str, run: Run<T>) -> Self {
+        let index = self.systems.iter().position(|system| system.stage > stage).unwrap_or(self.systems.len());
+        self.systems.insert(index, System { name, stage, run });
+        self
+    }
+
+    /// Run every system in order, as long as `running` holds after each one
+    pub fn run(&self, target: &mut T, rl: &mut RaylibHandle, delta_time: f32, running: impl Fn(&T) -> bool) {
+        for system in &self.systems {
+            (system.run)(target, rl, delta_time);
+            if !running(target) {
+                break;
+            }
+        }
+    }
+
+    /// Names of the systems in `stage`, in the order they run
+    pub fn order(&self, stage: Stage) -> Vec<&'static str> {
+        self.systems.iter().filter(|system| system.stage == stage).map(|system| system.name).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn systems_run_by_stage_then_in_the_order_added() {
+        let schedule = Schedule::<()>::new()
+            .add(Stage::Post, "exit", |_, _, _| {})
+            .add(Stage::Input, "look", |_, _, _| {})
+            .add(Stage::Simulation, "clock", |_, _, _| {})
+            .add(Stage::Input, "actions", |_, _, _| {})
+            .add(Stage::Post, "menu_key", |_, _, _| {});
+        assert_eq!(schedule.order(Stage::Input), ["look", "actions"]);
+        assert_eq!(schedule.order(Stage::Simulation), ["clock"]);
+        assert!(schedule.order(Stage::Collision).is_empty());
+        assert_eq!(schedule.order(Stage::Post), ["exit", "menu_key"]);
+    }
+}
@@ -0,0 +1,89 @@
+// Last-known-position search for Backrooms Doom
+// An enemy that loses sight of the player doesn't forget about them right away:
+// it walks to where it last saw them, and once there searches the surrounding
+// rooms, walking to random spots reachable within a few steps, until the search
+// time (search_time in settings.toml) runs out without another sighting. Then it
+// gives up and goes back to its patrol. Every new sighting starts over.
+
+use crate::maze::Maze;
+use crate::pathfinding;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+pub const DEFAULT_SEARCH_TIME: f32 = 15.0;
+pub const MAX_SEARCH_TIME: f32 = 120.0;
+const LOST_SIGHT: f32 = 0.5;  // Seconds out of sight before the enemy starts searching
+const SEARCH_STEPS: usize = 8; // How far from the last known position spots are picked, in steps
+const MIN_STEPS: usize = 2;    // Skip spots right next to it
+const SPOT_SPACING: f32 = 3.0; // Tiles between two spots of the same round
+const SPOTS: usize = 4;        // Spots per round, a new round is picked when they run out
+
+/// What an enemy remembers about the player after losing sight of them
+pub struct Search {
+    pub last_seen: (f32, f32),
+    since_seen: f32,
+    time_left: f32,
+    spots: Vec<(f32, f32)>,
+    started: bool, // Reached the last known position and searching around it
+}
+
+impl Search {
+    pub fn new(last_seen: (f32, f32), search_time: f32) -> Self {
+        Self { last_seen, since_seen: 0.0, time_left: search_time, spots: Vec::new(), started: false }
+    }
+
+    /// Called while the enemy has nothing to walk to. Returns the next spot to check, or None
+    /// while it stands still for a moment after losing sight
+    pub fn idle(&mut self, maze: &Maze, rng: &mut impl Rng, delta_time: f32) -> Option<(f32, f32)> {
+        self.since_seen += delta_time;
+        if self.since_seen < LOST_SIGHT {
+            return None;
+        }
+        self.started = true;
+        if self.spots.is_empty() {
+            self.spots = spots_around(maze, self.last_seen, rng);
+        }
+        self.spots.pop()
+    }
+
+    /// Count down the search time, only once the enemy has reached the last known position
+    pub fn update(&mut self, delta_time: f32) {
+        if self.started {
+            self.time_left -= delta_time;
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.time_left <= 0.0
+    }
+}
+
+/// A round of spots to check around (x, y): reachable tiles a few steps away, spread apart,
+/// in random order
+fn spots_around(maze: &Maze, (x, y): (f32, f32), rng: &mut impl Rng) -> Vec<(f32, f32)> {
+    let tile = maze.tile_size;
+    let mut candidates: Vec<(f32, f32)> = pathfinding::distances(maze, (x, y))
+        .into_iter()
+        .enumerate()
+        .filter(|(_, steps)| steps.is_some_and(|steps| (MIN_STEPS..=SEARCH_STEPS).contains(&steps)))
+        .map(|(index, _)| (((index % maze.width) as f32 + 0.5) * tile, ((index / maze.width) as f32 + 0.5) * tile))
+        .collect();
+    candidates.shuffle(rng);
+
+    let mut spots: Vec<(f32, f32)> = Vec::new();
+    for candidate in candidates {
+        let spaced = spots
+            .iter()
+            .all(|spot| ((spot.0 - candidate.0).powi(2) + (spot.1 - candidate.1).powi(2)).sqrt() >= SPOT_SPACING * tile);
+        if spaced {
+            spots.push(candidate);
+            if spots.len() == SPOTS {
+                break;
+            }
+        }
+    }
+    if spots.is_empty() {
+        spots.push((x, y)); // Dead end: just look around where the player was
+    }
+    spots
+}
@@ -0,0 +1,45 @@
+// Intro splash for Backrooms Doom
+// A few seconds of logo before the main menu: the logo fades in out of black,
+// holds, and fades out again, with the impact thud as its sting. The sequence is
+// a small keyframe timeline of (time, opacity) pairs (see timeline.rs), so the
+// timing can be retuned by editing TIMELINE alone. ENTER, SPACE or a click skips it.
+//
+// `intro_splash = false` in settings.toml goes straight to the menu, which saves
+// a few seconds on every launch while iterating.
+
+use crate::timeline;
+
+const TIMELINE: [(f32, f32); 4] = [
+    (0.0, 0.0), // Black
+    (1.0, 1.0), // Faded in
+    (2.4, 1.0), // Held
+    (3.2, 0.0), // Faded out
+];
+const STING_AT: f32 = 0.6; // Seconds in, as the logo is nearly visible
+
+pub struct Splash {
+    time: f32,
+}
+
+impl Splash {
+    pub fn new() -> Self {
+        Self { time: 0.0 }
+    }
+
+    /// Advance the timeline. Returns true on the frame the sting should play
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        let before = self.time;
+        self.time += delta_time;
+        before < STING_AT && self.time >= STING_AT
+    }
+
+    /// Whether the timeline has played through
+    pub fn finished(&self) -> bool {
+        self.time >= timeline::end(&TIMELINE)
+    }
+
+    /// Logo opacity (0.0 - 1.0) at the current time
+    pub fn opacity(&self) -> f32 {
+        timeline::sample(&TIMELINE, self.time)
+    }
+}
@@ -1,41 +1,165 @@
+// Sprites and sprite sheet animation for Backrooms Doom
+// A sprite sheet is a texture cut into equal frames side by side. Each animation state
+// (idle, walk, attack, die) plays a range of those frames at its own frame rate, looping
+// or holding the last frame. An Animator follows one entity through its states: the
+// entity's logic picks the state with `play`, and the renderer asks it for the frame.
+
 use crate::player::Vector2;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnimState {
+    Idle,
+    Walk,
+    Attack,
+    Die,
+}
+
+/// A run of sheet frames played for one state
+#[derive(Clone, Copy, Debug)]
+pub struct Clip {
+    pub first: usize,  // First frame on the sheet
+    pub frames: usize, // Frames in the run
+    pub fps: f32,
+    pub looping: bool, // Otherwise it holds the last frame
+}
+
+impl Clip {
+    pub const fn looping(first: usize, frames: usize, fps: f32) -> Self {
+        Self { first, frames, fps, looping: true }
+    }
+
+    pub const fn once(first: usize, frames: usize, fps: f32) -> Self {
+        Self { first, frames, fps, looping: false }
+    }
+
+    /// Seconds a non-looping clip takes to reach its last frame's end
+    pub fn duration(&self) -> f32 {
+        self.frames as f32 / self.fps
+    }
+}
+
+/// Frame layout of a texture and the clip each state plays
+pub struct SpriteSheet {
+    pub columns: usize, // Frames side by side across the texture
+    pub idle: Clip,
+    pub walk: Clip,
+    pub attack: Clip,
+    pub die: Clip,
+}
+
+impl SpriteSheet {
+    pub fn clip(&self, state: AnimState) -> &Clip {
+        match state {
+            AnimState::Idle => &self.idle,
+            AnimState::Walk => &self.walk,
+            AnimState::Attack => &self.attack,
+            AnimState::Die => &self.die,
+        }
+    }
+}
+
+/// Part of a texture to draw: frame `index` of `count` side by side, possibly flipped
+#[derive(Clone, Copy, Debug)]
+pub struct Frame {
+    pub index: usize,
+    pub count: usize,
+    pub mirrored: bool,
+}
+
+impl Frame {
+    pub const WHOLE: Frame = Frame { index: 0, count: 1, mirrored: false };
+}
+
+/// Animation state of one entity on its sprite sheet
+#[derive(Clone, Copy)]
+pub struct Animator {
+    sheet: &'static SpriteSheet,
+    state: AnimState,
+    time: f32, // Seconds in the current state
+}
+
+impl Animator {
+    pub fn new(sheet: &'static SpriteSheet) -> Self {
+        Self { sheet, state: AnimState::Idle, time: 0.0 }
+    }
+
+    /// Switch to `state`, starting its clip over. Already playing it, nothing changes
+    pub fn play(&mut self, state: AnimState) {
+        if state != self.state {
+            self.state = state;
+            self.time = 0.0;
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+
+    pub fn state(&self) -> AnimState {
+        self.state
+    }
+
+    /// Frame within the current clip, 0 - frames - 1
+    pub fn clip_frame(&self) -> usize {
+        let clip = self.sheet.clip(self.state);
+        let frame = (self.time * clip.fps) as usize;
+        if clip.looping {
+            frame % clip.frames.max(1)
+        } else {
+            frame.min(clip.frames.saturating_sub(1))
+        }
+    }
+
+    /// Share of a non-looping clip played, 0.0 - 1.0 (looping clips count as never done)
+    pub fn progress(&self) -> f32 {
+        let clip = self.sheet.clip(self.state);
+        if clip.looping {
+            return 0.0;
+        }
+        (self.time / clip.duration()).min(1.0)
+    }
+
+    /// A non-looping clip has played to the end
+    pub fn finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// The sheet frame to draw
+    pub fn frame(&self, mirrored: bool) -> Frame {
+        let index = self.sheet.clip(self.state).first + self.clip_frame();
+        Frame { index: index.min(self.sheet.columns - 1), count: self.sheet.columns, mirrored }
+    }
+}
+
+// Flickering lights: four brightness steps, one every 0.1 seconds whatever the state
+const LIGHT_FLICKER: Clip = Clip::looping(0, 4, 10.0);
+const LIGHT_SHEET: SpriteSheet = SpriteSheet { columns: 4, idle: LIGHT_FLICKER, walk: LIGHT_FLICKER, attack: LIGHT_FLICKER, die: LIGHT_FLICKER };
+
 pub struct Sprite {
     pub pos: Vector2,
     pub texture_index: usize,
-    pub animation_speed: f32,
-    pub animation_timer: f32,
-    pub num_frames: usize,
-    pub current_frame: usize,
+    pub animation: Animator,
     pub scale: f32,
 }
 
 impl Sprite {
-    pub fn new(x: f32, y: f32, texture_index: usize, num_frames: usize, animation_speed: f32) -> Self {
+    pub fn new(x: f32, y: f32, texture_index: usize, sheet: &'static SpriteSheet) -> Self {
         Self {
             pos: Vector2::new(x, y),
             texture_index,
-            animation_speed,
-            animation_timer: 0.0,
-            num_frames,
-            current_frame: 0,
+            animation: Animator::new(sheet),
             scale: 1.0,
         }
     }
 
     /// Update sprite animation
     pub fn update(&mut self, delta_time: f32) {
-        self.animation_timer += delta_time;
-
-        if self.animation_timer >= self.animation_speed {
-            self.animation_timer = 0.0;
-            self.current_frame = (self.current_frame + 1) % self.num_frames;
-        }
+        self.animation.update(delta_time);
     }
 
     /// Create a flickering light sprite (for Backrooms atmosphere)
     pub fn new_flickering_light(x: f32, y: f32) -> Self {
-        Self::new(x, y, 0, 4, 0.1) // Fast flicker
+        Self::new(x, y, 0, &LIGHT_SHEET) // Fast flicker
     }
 }
 
@@ -66,4 +190,4 @@ impl SpriteRenderer {
 
         angle_diff < fov / 2.0 + 0.5 // Add small margin
     }
-}
\ No newline at end of file
+}
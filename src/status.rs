@@ -0,0 +1,209 @@
+// Status effects for Backrooms Doom
+// Timed conditions on the local player from pills and hazards: a speed boost, being
+// slowed, blurred vision, poison eating health over time, invulnerability and the
+// minimap revealing pills, keys, clocks and enemies. Each effect has a strength and a
+// duration, and a stacking rule for taking it again while it is still running:
+// - Refresh: one at a time, keeping the stronger strength and the longer time left
+// - Extend: one at a time, the new duration added to what is left (up to MAX_TIME)
+// - Stack: up to n copies running side by side, the oldest replaced once full
+// The effects only keep the numbers; GameState reads them each frame (speed multiplier,
+// blur radius, poison damage through the health system, invulnerability frames, the
+// minimap marks) and the HUD status cluster shows one ring per effect with its icon and
+// time left.
+
+use raylib::prelude::Color;
+
+const MAX_TIME: f32 = 30.0;   // Longest an extended effect can run
+const MAX_SPEED: f32 = 0.6;   // Strongest speed boost (+60%)
+const MAX_SLOW: f32 = 0.6;    // Strongest slow (-60%)
+const MAX_BLUR: f32 = 4.0;    // Blur radius in pixels at full strength
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Effect {
+    SpeedBoost,   // Strength = extra speed, 0.25 = +25%
+    Slow,         // Strength = speed lost, 0.25 = -25%
+    Blur,         // Strength = 0.0 - 1.0 of MAX_BLUR
+    Poison,       // Strength = health per second
+    Invulnerable, // Hits from entities and doors don't land
+    Reveal,       // The minimap marks pills, keys, clocks and enemies (shown even in hardcore)
+}
+
+pub enum Stacking {
+    Refresh,
+    Extend,
+    Stack(usize),
+}
+
+impl Effect {
+    pub const ALL: [Effect; 6] = [Effect::SpeedBoost, Effect::Slow, Effect::Blur, Effect::Poison, Effect::Invulnerable, Effect::Reveal];
+
+    pub fn stacking(self) -> Stacking {
+        match self {
+            Effect::SpeedBoost | Effect::Slow | Effect::Blur | Effect::Reveal => Stacking::Refresh,
+            Effect::Invulnerable => Stacking::Extend,
+            Effect::Poison => Stacking::Stack(3),
+        }
+    }
+
+    /// Name in the console
+    pub fn name(self) -> &'static str {
+        match self {
+            Effect::SpeedBoost => "speed",
+            Effect::Slow => "slow",
+            Effect::Blur => "blur",
+            Effect::Poison => "poison",
+            Effect::Invulnerable => "invulnerable",
+            Effect::Reveal => "reveal",
+        }
+    }
+
+    /// Floating text when it starts, and the HUD label
+    pub fn label(self) -> &'static str {
+        match self {
+            Effect::SpeedBoost => "Speed boost",
+            Effect::Slow => "Slowed",
+            Effect::Blur => "Blurred",
+            Effect::Poison => "Poisoned",
+            Effect::Invulnerable => "Invulnerable",
+            Effect::Reveal => "Map revealed",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|effect| effect.name() == name)
+    }
+
+    /// Short glyph drawn inside the effect's HUD ring
+    pub fn icon(self) -> &'static str {
+        match self {
+            Effect::SpeedBoost => ">>",
+            Effect::Slow => "<<",
+            Effect::Blur => "~",
+            Effect::Poison => "x",
+            Effect::Invulnerable => "[]",
+            Effect::Reveal => "#",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Effect::SpeedBoost => Color::new(120, 255, 140, 255),
+            Effect::Slow => Color::new(150, 150, 220, 255),
+            Effect::Blur => Color::new(200, 170, 255, 255),
+            Effect::Poison => Color::new(150, 220, 40, 255),
+            Effect::Invulnerable => Color::new(255, 230, 120, 255),
+            Effect::Reveal => Color::new(245, 220, 60, 255),
+        }
+    }
+}
+
+struct Active {
+    effect: Effect,
+    strength: f32,
+    time_left: f32,
+    duration: f32, // Full length, for the HUD ring
+}
+
+pub struct StatusEffects {
+    active: Vec<Active>,
+    poison: f32, // Fraction of a health point of poison damage so far
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self { active: Vec::new(), poison: 0.0 }
+    }
+
+    /// Start `effect` at `strength` for `seconds`, following its stacking rule
+    pub fn apply(&mut self, effect: Effect, strength: f32, seconds: f32) {
+        if seconds <= 0.0 || strength <= 0.0 {
+            return;
+        }
+        let running = self.active.iter().position(|active| active.effect == effect);
+        match (effect.stacking(), running) {
+            (Stacking::Refresh, Some(index)) => {
+                let active = &mut self.active[index];
+                active.strength = active.strength.max(strength);
+                if seconds > active.time_left {
+                    active.time_left = seconds;
+                    active.duration = seconds;
+                }
+            }
+            (Stacking::Extend, Some(index)) => {
+                let active = &mut self.active[index];
+                active.strength = active.strength.max(strength);
+                active.time_left = (active.time_left + seconds).min(MAX_TIME);
+                active.duration = active.duration.max(active.time_left);
+            }
+            (Stacking::Stack(limit), Some(index)) if self.count(effect) >= limit => {
+                self.active.remove(index);
+                self.active.push(Active { effect, strength, time_left: seconds, duration: seconds });
+            }
+            _ => self.active.push(Active { effect, strength, time_left: seconds, duration: seconds }),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+        self.poison = 0.0;
+    }
+
+    /// Count the effects down. Returns the whole health points of poison damage due this frame
+    pub fn update(&mut self, delta_time: f32) -> i32 {
+        let per_second: f32 = self.active.iter().filter(|active| active.effect == Effect::Poison).map(|active| active.strength).sum();
+        self.poison += per_second * delta_time;
+        let damage = self.poison.floor();
+        self.poison -= damage;
+
+        for active in &mut self.active {
+            active.time_left -= delta_time;
+        }
+        self.active.retain(|active| active.time_left > 0.0);
+        if !self.has(Effect::Poison) {
+            self.poison = 0.0;
+        }
+        damage as i32
+    }
+
+    pub fn has(&self, effect: Effect) -> bool {
+        self.count(effect) > 0
+    }
+
+    fn count(&self, effect: Effect) -> usize {
+        self.active.iter().filter(|active| active.effect == effect).count()
+    }
+
+    /// Seconds left of `effect` (the longest copy)
+    pub fn time_left(&self, effect: Effect) -> f32 {
+        self.active.iter().filter(|active| active.effect == effect).map(|active| active.time_left).fold(0.0, f32::max)
+    }
+
+    fn strength(&self, effect: Effect) -> f32 {
+        self.active.iter().filter(|active| active.effect == effect).map(|active| active.strength).fold(0.0, f32::max)
+    }
+
+    /// Multiplier on the player's walking speed
+    pub fn speed_multiplier(&self) -> f32 {
+        (1.0 + self.strength(Effect::SpeedBoost).min(MAX_SPEED)) * (1.0 - self.strength(Effect::Slow).min(MAX_SLOW))
+    }
+
+    /// Blur radius in pixels for the post effect, easing off over the last second
+    pub fn blur_radius(&self) -> usize {
+        let fade = self.time_left(Effect::Blur).min(1.0);
+        (self.strength(Effect::Blur).min(1.0) * fade * MAX_BLUR).round() as usize
+    }
+
+    /// One entry per running effect for the HUD: (effect, copies, fraction of its time left)
+    pub fn hud(&self) -> Vec<(Effect, usize, f32)> {
+        Effect::ALL
+            .into_iter()
+            .filter(|&effect| self.has(effect))
+            .map(|effect| {
+                let longest = self.active.iter().filter(|active| active.effect == effect).fold((0.0, 1.0), |best, active| {
+                    if active.time_left > best.0 { (active.time_left, active.duration) } else { best }
+                });
+                (effect, self.count(effect), longest.0 / longest.1)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,85 @@
+// Stealth for Backrooms Doom
+// Scores how visible the player is from the light around them, crouching and
+// movement speed. Enemies use the score to decide how far they can spot the player
+
+use crate::maze::Maze;
+use crate::sprite::Sprite;
+
+const AMBIENT_LIGHT: f32 = 0.35;     // Light level away from any fixture
+const LIGHT_RADIUS: f32 = 5.0;       // Tiles a fluorescent light reaches
+const LIGHT_STRENGTH: f32 = 0.65;    // Light added right under a fixture
+const FLICKER: [f32; 4] = [1.0, 0.85, 1.0, 0.45]; // Brightness per flicker frame
+
+const CROUCH_FACTOR: f32 = 0.55;     // Crouching makes the player this much less visible
+const STILL_FACTOR: f32 = 0.6;       // Visibility when standing still (1.0 at full speed)
+const SMOOTHING: f32 = 4.0;          // How fast the meter follows the target (per second)
+
+// Enemy vision
+const VISION_RANGE: f32 = 9.0;       // Tiles an enemy sees a fully visible player
+const AWARENESS_GAIN: f32 = 1.5;     // Awareness per second at full visibility
+const AWARENESS_DECAY: f32 = 0.4;    // Awareness lost per second without sight
+
+/// Light level (0.0 - 1.0) at a point from the flickering light fixtures
+pub fn light_level(x: f32, y: f32, lights: &[Sprite]) -> f32 {
+    let mut level = AMBIENT_LIGHT;
+    for light in lights {
+        let dx = light.pos.x - x;
+        let dy = light.pos.y - y;
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance < LIGHT_RADIUS {
+            let falloff = 1.0 - distance / LIGHT_RADIUS;
+            level += falloff * falloff * LIGHT_STRENGTH * FLICKER[light.animation.frame(false).index % FLICKER.len()];
+        }
+    }
+    level.min(1.0)
+}
+
+/// Visibility target (0.0 - 1.0). speed is the fraction of full movement speed
+pub fn visibility(light: f32, crouching: bool, speed: f32) -> f32 {
+    let motion = STILL_FACTOR + (1.0 - STILL_FACTOR) * speed.clamp(0.0, 1.0);
+    let posture = if crouching { CROUCH_FACTOR } else { 1.0 };
+    (light * motion * posture).clamp(0.0, 1.0)
+}
+
+/// Move the displayed meter towards the target so it doesn't jitter with the flicker
+pub fn smooth(current: f32, target: f32, delta_time: f32) -> f32 {
+    current + (target - current) * (SMOOTHING * delta_time).min(1.0)
+}
+
+/// Whether an enemy at `from` can see the point `to` given the player's visibility
+pub fn can_see(maze: &Maze, from: (f32, f32), to: (f32, f32), visibility: f32) -> bool {
+    let dx = to.0 - from.0;
+    let dy = to.1 - from.1;
+    let distance = (dx * dx + dy * dy).sqrt();
+    if distance > VISION_RANGE * visibility {
+        return false;
+    }
+
+    line_of_sight(maze, from, to)
+}
+
+/// Whether nothing but open floor lies between two points
+pub fn line_of_sight(maze: &Maze, from: (f32, f32), to: (f32, f32)) -> bool {
+    let dx = to.0 - from.0;
+    let dy = to.1 - from.1;
+    let distance = (dx * dx + dy * dy).sqrt();
+
+    // March along the sight line looking for walls
+    let steps = (distance / 0.1).ceil() as usize;
+    for i in 1..steps {
+        let t = i as f32 / steps as f32;
+        if !maze.is_walkable(from.0 + dx * t, from.1 + dy * t) {
+            return false;
+        }
+    }
+    true
+}
+
+/// New awareness (0.0 - 1.0) after a frame of seeing / not seeing the player
+pub fn update_awareness(awareness: f32, sees: bool, visibility: f32, delta_time: f32) -> f32 {
+    if sees {
+        (awareness + AWARENESS_GAIN * visibility * delta_time).min(1.0)
+    } else {
+        (awareness - AWARENESS_DECAY * delta_time).max(0.0)
+    }
+}
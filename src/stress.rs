@@ -0,0 +1,331 @@
+// Input stress test for Backrooms Doom
+// Runs the raylib-free part of the simulation (maze collision, player movement,
+// enemies, companion pathfinding) headless for thousands of frames, fed with
+// randomized or recorded input, and checks invariants after every frame:
+// nobody ends up inside a wall, health and stamina stay in bounds and no
+// position or angle turns NaN. A failing run's input is written out so it can
+// be replayed exactly.
+//
+//   cargo run --release -- --stress [frames] [--seed N]
+//   cargo run --release -- --stress-replay logs/stress_<seed>.txt
+
+use crate::companion::Companion;
+use crate::enemy::Enemy;
+use crate::events::EventQueue;
+use crate::health::{Health, Source};
+use crate::maze::Maze;
+use crate::player::Player;
+use log::{error, info};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs;
+
+const MAZE_PATH: &str = "maze.txt";
+const DEFAULT_FRAMES: usize = 20_000;
+const MIN_HOLD_FRAMES: u32 = 5;   // Random keys are held for a while, like a real player
+const MAX_HOLD_FRAMES: u32 = 90;
+const LAG_SPIKE_CHANCE: f64 = 0.01; // Chance of a long frame, to catch tunneling through walls
+const MAX_DELTA_TIME: f32 = 0.25;
+const NOISE_CHANCE: f64 = 0.02;   // Chance per frame that enemies hear the player
+const PILL_CHANCE: f64 = 0.005;   // Chance per frame of a pill's health change
+
+/// Input for one simulated frame
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct InputFrame {
+    pub delta_time: f32,
+    pub forward: bool,
+    pub back: bool,
+    pub left: bool,
+    pub right: bool,
+    pub crouch: bool,
+    pub attack: bool,
+    pub turn: f32, // Radians this frame (mouse look)
+}
+
+impl InputFrame {
+    /// "<delta time> <keys> <turn>", keys from WASDCX or '-' for none. Floats are written
+    /// in their shortest form that reads back to the same value, so a replay is exact
+    pub fn to_line(self) -> String {
+        let keys: String = [
+            (self.forward, 'W'),
+            (self.left, 'A'),
+            (self.back, 'S'),
+            (self.right, 'D'),
+            (self.crouch, 'C'),
+            (self.attack, 'X'),
+        ]
+        .iter()
+        .filter(|(down, _)| *down)
+        .map(|(_, key)| *key)
+        .collect();
+        let keys = if keys.is_empty() { "-".to_string() } else { keys };
+        format!("{} {} {}", self.delta_time, keys, self.turn)
+    }
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let delta_time = parts.next()?.parse().ok()?;
+        let keys = parts.next()?;
+        let turn = parts.next()?.parse().ok()?;
+        Some(Self {
+            delta_time,
+            forward: keys.contains('W'),
+            left: keys.contains('A'),
+            back: keys.contains('S'),
+            right: keys.contains('D'),
+            crouch: keys.contains('C'),
+            attack: keys.contains('X'),
+            turn,
+        })
+    }
+}
+
+/// Random input that holds keys for several frames at a time
+struct RandomInput {
+    rng: StdRng,
+    held: InputFrame,
+    hold_frames: u32,
+}
+
+impl RandomInput {
+    fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            held: InputFrame::default(),
+            hold_frames: 0,
+        }
+    }
+
+    fn next_frame(&mut self) -> InputFrame {
+        if self.hold_frames == 0 {
+            let rng = &mut self.rng;
+            self.held = InputFrame {
+                delta_time: 0.0,
+                forward: rng.gen_bool(0.5),
+                back: rng.gen_bool(0.15),
+                left: rng.gen_bool(0.25),
+                right: rng.gen_bool(0.25),
+                crouch: rng.gen_bool(0.15),
+                attack: false,
+                turn: rng.gen_range(-0.08..0.08),
+            };
+            self.hold_frames = rng.gen_range(MIN_HOLD_FRAMES..=MAX_HOLD_FRAMES);
+        }
+        self.hold_frames -= 1;
+
+        let mut frame = self.held;
+        frame.attack = self.rng.gen_bool(0.05);
+        frame.delta_time = if self.rng.gen_bool(LAG_SPIKE_CHANCE) {
+            self.rng.gen_range(0.1..MAX_DELTA_TIME)
+        } else {
+            self.rng.gen_range(0.004..0.034)
+        };
+        frame
+    }
+}
+
+/// Everything the stress test simulates
+struct Simulation {
+    maze: Maze,
+    players: Vec<Player>, // Local player first, then the companion bot
+    companion: Companion,
+    enemies: Vec<Enemy>,
+    health: Health,     // The local player's, as in GameState
+    events: EventQueue, // Nothing reacts to them here
+    rng: StdRng, // World randomness (noises, pills), separate from the input
+}
+
+impl Simulation {
+    fn new(seed: u64) -> Result<Self, String> {
+        let maze = Maze::load_from_file(MAZE_PATH, 1.0)?;
+        let (x, y) = maze.start_pos;
+        let enemies = maze.enemy_positions.iter().map(|(x, y)| Enemy::new(*x, *y)).collect();
+        Ok(Self {
+            maze,
+            players: vec![Player::new(x, y), Player::new(x, y)],
+            companion: Companion::new(1),
+            enemies,
+            health: Health::new(),
+            events: EventQueue::new(),
+            rng: StdRng::seed_from_u64(seed ^ 0x5eed),
+        })
+    }
+
+    /// Advance one frame with the same player calls GameState makes for this input
+    fn step(&mut self, input: &InputFrame) {
+        let dt = input.delta_time;
+        let [player, bot] = &mut self.players[..] else {
+            return;
+        };
+
+        player.crouching = input.crouch;
+        player.rotate(input.turn);
+        if input.forward {
+            player.move_forward();
+        }
+        if input.back {
+            player.move_backward();
+        }
+        if input.left {
+            player.move_left();
+        }
+        if input.right {
+            player.move_right();
+        }
+        player.update_movement(&self.maze, dt);
+        player.update_attack(dt);
+        if input.attack && player.try_attack() {
+            player.attack_wall_hit(&self.maze);
+            player.use_stamina(0.04);
+        }
+        player.regen_stamina(dt);
+
+        // Pills randomly hurt or heal, like red and blue ones do
+        if self.rng.gen_bool(PILL_CHANCE) {
+            if self.rng.gen_bool(0.5) {
+                self.health.damage(player, 15, Source::Pill, &mut self.events);
+            } else {
+                self.health.heal(player, 10, Source::Pill, &mut self.events);
+            }
+        }
+        self.health.update(player, dt, &mut self.events);
+        self.events.clear();
+
+        self.companion.update(&self.maze, bot, player, dt);
+
+        // Enemies wander over to wherever the player made noise
+        let heard = self.rng.gen_bool(NOISE_CHANCE);
+        for enemy in &mut self.enemies {
+            if heard {
+                enemy.hear(player.pos.x, player.pos.y);
+            }
+            enemy.update(dt);
+            enemy.update_movement(&self.maze, (0.0, 0.0), dt);
+        }
+    }
+
+    /// First broken invariant, if any
+    fn check(&self) -> Option<String> {
+        for (i, player) in self.players.iter().enumerate() {
+            let (x, y) = (player.pos.x, player.pos.y);
+            if !x.is_finite() || !y.is_finite() || !player.angle.is_finite() {
+                return Some(format!("player {} has a non-finite position/angle ({}, {}, {})", i, x, y, player.angle));
+            }
+            if !self.maze.is_walkable(x, y) {
+                return Some(format!("player {} is inside a wall at ({:.3}, {:.3})", i, x, y));
+            }
+            if player.health < 0 || player.health > player.max_health {
+                return Some(format!("player {} health {} is outside 0..={}", i, player.health, player.max_health));
+            }
+            if !(0.0..=1.0).contains(&player.stamina) {
+                return Some(format!("player {} stamina {} is outside 0..=1", i, player.stamina));
+            }
+        }
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            let (x, y) = (enemy.pos.x, enemy.pos.y);
+            if !x.is_finite() || !y.is_finite() {
+                return Some(format!("enemy {} has a non-finite position ({}, {})", i, x, y));
+            }
+            if !self.maze.is_walkable(x, y) {
+                return Some(format!("enemy {} is inside a wall at ({:.3}, {:.3})", i, x, y));
+            }
+        }
+        None
+    }
+}
+
+/// Parse "--stress [frames] [--seed N]" / "--stress-replay <file>" and run the test.
+/// Returns None when no stress flag was given, otherwise whether every invariant held
+pub fn run_from_args(args: &[String]) -> Option<bool> {
+    let flag_value = |flag: &str| {
+        args.iter().position(|arg| arg == flag).and_then(|index| args.get(index + 1))
+    };
+
+    if let Some(path) = flag_value("--stress-replay") {
+        return Some(match load_recording(path) {
+            Ok((seed, inputs)) => run(inputs, seed, path),
+            Err(e) => {
+                error!("{}", e);
+                false
+            }
+        });
+    }
+
+    let index = args.iter().position(|arg| arg == "--stress")?;
+    let frames = args
+        .get(index + 1)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_FRAMES);
+    let seed = flag_value("--seed").and_then(|value| value.parse().ok()).unwrap_or_else(rand::random);
+
+    let mut input = RandomInput::new(seed);
+    let inputs = (0..frames).map(|_| input.next_frame()).collect();
+    Some(run(inputs, seed, &format!("seed {}", seed)))
+}
+
+/// Read a recording: a "# seed N" header (world randomness) then one input frame per line
+fn load_recording(path: &str) -> Result<(u64, Vec<InputFrame>), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let seed = content
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("# seed "))
+        .and_then(|seed| seed.trim().parse().ok())
+        .ok_or_else(|| format!("{}: missing '# seed' header", path))?;
+    Ok((seed, content.lines().skip(1).filter_map(InputFrame::parse).collect()))
+}
+
+/// Run the inputs frame by frame, stopping at the first broken invariant
+fn run(inputs: Vec<InputFrame>, seed: u64, label: &str) -> bool {
+    let mut simulation = match Simulation::new(seed) {
+        Ok(simulation) => simulation,
+        Err(e) => {
+            error!("Stress test could not start: {}", e);
+            return false;
+        }
+    };
+    info!("Stress test ({}): {} frames", label, inputs.len());
+
+    for (frame, input) in inputs.iter().enumerate() {
+        simulation.step(input);
+        if let Some(failure) = simulation.check() {
+            error!("Stress test failed at frame {}: {}", frame, failure);
+            save_recording(&inputs[..=frame], seed);
+            return false;
+        }
+    }
+
+    let time: f32 = inputs.iter().map(|input| input.delta_time).sum();
+    info!("Stress test passed: {} frames ({:.0}s of simulated play)", inputs.len(), time);
+    true
+}
+
+/// Write the input up to a failure so it can be replayed with --stress-replay
+fn save_recording(inputs: &[InputFrame], seed: u64) {
+    let path = format!("logs/stress_{}.txt", seed);
+    let mut content = format!("# seed {}\n", seed);
+    for input in inputs {
+        content.push_str(&input.to_line());
+        content.push('\n');
+    }
+    let saved = fs::create_dir_all("logs").and_then(|_| fs::write(&path, content));
+    match saved {
+        Ok(()) => info!("Failing input saved to {} (replay with --stress-replay {})", path, path),
+        Err(e) => error!("Failed to write {}: {}", path, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_frames_read_back_unchanged() {
+        let mut input = RandomInput::new(7);
+        let mut frames: Vec<InputFrame> = (0..500).map(|_| input.next_frame()).collect();
+        frames.push(InputFrame { delta_time: 0.1 + 0.2, turn: -f32::MIN_POSITIVE, ..InputFrame::default() });
+        for frame in frames {
+            assert_eq!(InputFrame::parse(&frame.to_line()), Some(frame));
+        }
+    }
+}
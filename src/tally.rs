@@ -0,0 +1,185 @@
+// Level tally for Backrooms Doom
+// The end-of-level screen adds the level up line by line: a time bonus for the
+// seconds left on the clock, exploration for the share of the floor walked, a
+// penalty for the health lost and a bonus for each challenge room won (the level's
+// secrets). Each line counts up from 0 in turn, ticking as it goes, higher the
+// further along it is, and once the total stands the medal it earned is stamped
+// under it. ENTER skips straight to the end.
+//
+// LevelLog gathers what the tally needs while the level is played.
+
+use crate::maze::Maze;
+use crate::tween::{Easing, Tween};
+use raylib::prelude::Color;
+
+const TIME_POINTS: f32 = 5.0;        // Per second left on the clock
+const EXPLORE_POINTS: f32 = 5.0;     // Per percent of the floor walked
+const DAMAGE_POINTS: i32 = 2;        // Lost per health point lost
+const SECRET_POINTS: i32 = 250;      // Per challenge room won
+const MEDALS: [(Medal, i32); 3] = [(Medal::Gold, 1200), (Medal::Silver, 800), (Medal::Bronze, 400)];
+
+const COUNT_TIME: f32 = 1.0;    // Seconds a line takes to count up
+const LINE_PAUSE: f32 = 0.35;   // Between one line finishing and the next starting
+const TICK_INTERVAL: f32 = 0.06; // Seconds between ticks while counting
+const STAMP_TIME: f32 = 0.25;   // The medal dropping onto the screen
+
+/// What happened in the level, as far as the tally cares
+pub struct LevelLog {
+    visited: Vec<bool>, // Per tile, row by row
+    width: usize,
+    pub damage_taken: i32,
+}
+
+impl LevelLog {
+    pub fn new(maze: &Maze) -> Self {
+        Self { visited: vec![false; maze.width * maze.height], width: maze.width, damage_taken: 0 }
+    }
+
+    /// Mark the tile under (x, y) as walked
+    pub fn visit(&mut self, maze: &Maze, x: f32, y: f32) {
+        let (tile_x, tile_y) = ((x / maze.tile_size) as usize, (y / maze.tile_size) as usize);
+        if let Some(visited) = self.visited.get_mut(tile_y * self.width + tile_x) {
+            *visited = true;
+        }
+    }
+
+    /// Share of the floor (doors included) walked so far, 0.0 - 1.0
+    pub fn explored(&self, maze: &Maze) -> f32 {
+        let floor: Vec<usize> = (0..maze.height)
+            .flat_map(|y| (0..maze.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| !maze.is_wall(x, y) || maze.door_at(x, y).is_some())
+            .map(|(x, y)| y * self.width + x)
+            .collect();
+        if floor.is_empty() {
+            return 0.0;
+        }
+        floor.iter().filter(|&&index| self.visited.get(index).copied().unwrap_or(false)).count() as f32 / floor.len() as f32
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Medal {
+    Gold,
+    Silver,
+    Bronze,
+}
+
+impl Medal {
+    pub fn name(self) -> &'static str {
+        match self {
+            Medal::Gold => "GOLD",
+            Medal::Silver => "SILVER",
+            Medal::Bronze => "BRONZE",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Medal::Gold => Color::new(255, 205, 60, 255),
+            Medal::Silver => Color::new(200, 205, 215, 255),
+            Medal::Bronze => Color::new(205, 127, 50, 255),
+        }
+    }
+}
+
+/// Something for the tally screen to play
+pub enum Tick {
+    Count(f32), // A line counted up a step, at this pitch
+    Medal,      // The medal landed (or the tally ended without one)
+}
+
+struct Line {
+    label: &'static str,
+    detail: String, // What the points were for, e.g. "96s left"
+    count: Tween,   // Points shown, 0 up to the line's points
+}
+
+pub struct Tally {
+    lines: Vec<Line>,
+    stamp: Tween, // Medal scale, 2.0 falling to 1.0
+    medal: Option<Medal>,
+    tick_timer: f32,
+    stamped: bool,
+}
+
+impl Tally {
+    /// Tally a level finished with `time_left` seconds, `explored` of the floor walked
+    /// (0.0 - 1.0), `damage` health lost and `secrets` challenge rooms won out of the total
+    pub fn new(time_left: f32, explored: f32, damage: i32, secrets: (usize, usize)) -> Self {
+        let mut entries = vec![
+            ("Time bonus", format!("{:.0}s left", time_left), (time_left * TIME_POINTS).round() as i32),
+            ("Exploration", format!("{:.0}% walked", explored * 100.0), (explored * 100.0 * EXPLORE_POINTS).round() as i32),
+            ("Damage", format!("{} HP lost", damage), -damage * DAMAGE_POINTS),
+        ];
+        if secrets.1 > 0 {
+            entries.push(("Secrets", format!("{} of {} found", secrets.0, secrets.1), secrets.0 as i32 * SECRET_POINTS));
+        }
+
+        let step = COUNT_TIME + LINE_PAUSE;
+        let lines: Vec<Line> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, (label, detail, points))| Line {
+                label,
+                detail,
+                count: Tween::between(0.0, points as f32, COUNT_TIME, Easing::EaseOut).with_delay(i as f32 * step),
+            })
+            .collect();
+        let total: i32 = lines.iter().map(|line| line.count.target() as i32).sum();
+        let medal = MEDALS.iter().find(|&&(_, points)| total >= points).map(|&(medal, _)| medal);
+        let stamp = Tween::between(2.0, 1.0, STAMP_TIME, Easing::EaseIn).with_delay(lines.len() as f32 * step);
+        Self { lines, stamp, medal, tick_timer: 0.0, stamped: false }
+    }
+
+    /// Count on. Returns what to play this frame
+    pub fn update(&mut self, delta_time: f32) -> Option<Tick> {
+        for line in &mut self.lines {
+            line.count.update(delta_time);
+        }
+        self.stamp.update(delta_time);
+
+        if self.stamp.finished() && !self.stamped {
+            self.stamped = true;
+            return Some(Tick::Medal);
+        }
+        // Lines worth nothing (no damage taken) have nothing to count, so they don't tick
+        let counting = self.lines.iter().find(|line| line.count.started() && !line.count.finished() && line.count.target() != 0.0)?;
+        self.tick_timer -= delta_time;
+        if self.tick_timer > 0.0 {
+            return None;
+        }
+        self.tick_timer = TICK_INTERVAL;
+        let progress = (counting.count.value() / counting.count.target()).abs();
+        Some(Tick::Count(1.0 + 0.6 * progress))
+    }
+
+    /// Jump to the end: every line counted and the medal stamped
+    pub fn skip(&mut self) {
+        for line in &mut self.lines {
+            line.count.snap(line.count.target());
+        }
+        self.stamp.snap(1.0);
+    }
+
+    pub fn finished(&self) -> bool {
+        self.stamp.finished()
+    }
+
+    /// Lines shown so far: (label, detail, points counted)
+    pub fn lines(&self) -> impl Iterator<Item = (&str, &str, i32)> {
+        self.lines
+            .iter()
+            .filter(|line| line.count.started())
+            .map(|line| (line.label, line.detail.as_str(), line.count.value().round() as i32))
+    }
+
+    /// Sum of the points counted so far
+    pub fn total(&self) -> i32 {
+        self.lines().map(|(_, _, points)| points).sum()
+    }
+
+    /// The medal and its scale once it drops in (None before, or if the total earned none)
+    pub fn medal(&self) -> Option<(Medal, f32)> {
+        self.medal.filter(|_| self.stamp.started()).map(|medal| (medal, self.stamp.value()))
+    }
+}
@@ -0,0 +1,75 @@
+// Per-level texture grading for Backrooms Doom
+// A level can give the whole texture set a tint and brightness, written on one
+// line after the grid of the maze file:
+//
+//   grade dusk
+//   grade #a0b0ff 0.6
+//
+// Either a named grade (dusk = warm and dim, night = cold and dark, overexposed =
+// blown-out fluorescents) or a #rrggbb tint with an optional brightness (1.0 =
+// unchanged). The grade is multiplied into the texture pixels once when the level
+// loads (TextureManager::set_grade), so the same assets read as a different time
+// of day without any per-pixel cost while rendering.
+
+use raylib::prelude::Color;
+
+const MIN_BRIGHTNESS: f32 = 0.1;
+const MAX_BRIGHTNESS: f32 = 2.0;
+
+#[derive(Clone, Copy)]
+pub struct TextureGrade {
+    pub tint: Color,
+    pub brightness: f32,
+}
+
+impl TextureGrade {
+    /// Parse the rest of a "grade ..." line (without the keyword)
+    pub fn parse(definition: &str) -> Result<Self, String> {
+        let mut parts = definition.split_whitespace();
+        let name = parts.next().ok_or("a grade needs a name or a #rrggbb tint")?;
+        let preset = match name {
+            "dusk" => Some(Self { tint: Color::new(255, 190, 140, 255), brightness: 0.8 }),
+            "night" => Some(Self { tint: Color::new(130, 145, 210, 255), brightness: 0.5 }),
+            "overexposed" => Some(Self { tint: Color::new(255, 252, 235, 255), brightness: 1.4 }),
+            _ => None,
+        };
+        if let Some(preset) = preset {
+            return match parts.next() {
+                Some(extra) => Err(format!("unexpected '{}' after the grade name", extra)),
+                None => Ok(preset),
+            };
+        }
+
+        let tint = parse_hex(name)
+            .ok_or_else(|| format!("bad grade '{}', expected dusk, night, overexposed or #rrggbb", name))?;
+        let brightness = match parts.next() {
+            Some(brightness) => brightness
+                .parse::<f32>()
+                .ok()
+                .filter(|brightness| (MIN_BRIGHTNESS..=MAX_BRIGHTNESS).contains(brightness))
+                .ok_or_else(|| format!("bad grade brightness '{}', expected {}-{}", brightness, MIN_BRIGHTNESS, MAX_BRIGHTNESS))?,
+            None => 1.0,
+        };
+        if let Some(extra) = parts.next() {
+            return Err(format!("unexpected '{}' after the grade brightness", extra));
+        }
+        Ok(Self { tint, brightness })
+    }
+
+    /// Grade every pixel of a texture in place
+    pub fn apply(&self, pixels: &mut [Color]) {
+        let scale = |tint: u8| tint as f32 / 255.0 * self.brightness;
+        let (r, g, b) = (scale(self.tint.r), scale(self.tint.g), scale(self.tint.b));
+        let channel = |value: u8, scale: f32| (value as f32 * scale).min(255.0) as u8;
+        for pixel in pixels {
+            *pixel = Color::new(channel(pixel.r, r), channel(pixel.g, g), channel(pixel.b, b), pixel.a);
+        }
+    }
+}
+
+/// "#rrggbb"
+fn parse_hex(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii())?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::new(channel(0)?, channel(2)?, channel(4)?, 255))
+}
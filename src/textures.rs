@@ -1,6 +1,13 @@
+use crate::assets::{AssetKind, AssetManifest};
+use crate::resources::{Registry, TextureId, TextureKind};
+use crate::texture_grade::TextureGrade;
+use log::{debug, info, warn};
 use raylib::prelude::*;
 use std::collections::HashMap;
 
+// Soft limit for decoded texture memory, exceeding it only logs a warning
+pub const TEXTURE_BUDGET_BYTES: usize = 32 * 1024 * 1024;
+
 /// Individual texture with its own dimensions
 #[derive(Clone)]
 pub struct Texture {
@@ -14,6 +21,39 @@ impl Texture {
         Self { pixels, width, height }
     }
 
+    /// Load and decode a texture from an image file (safe to call from a worker thread)
+    pub fn load(path: &str) -> Result<Self, String> {
+        let image = Image::load_image(path).map_err(|e| e.to_string())?;
+        Self::from_image(&image)
+    }
+
+    /// Convert raylib Image to our Texture format
+    pub fn from_image(image: &Image) -> Result<Self, String> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+
+        if width == 0 || height == 0 {
+            return Err("Invalid texture dimensions".to_string());
+        }
+
+        // Get pixel data from image
+        let mut pixels = Vec::with_capacity(width * height);
+
+        unsafe {
+            let data = image.data as *const Color;
+            for i in 0..(width * height) {
+                pixels.push(*data.offset(i as isize));
+            }
+        }
+
+        Ok(Self::new(pixels, width, height))
+    }
+
+    /// Size of the decoded pixel data in bytes
+    pub fn memory_bytes(&self) -> usize {
+        self.pixels.len() * std::mem::size_of::<Color>()
+    }
+
     /// Sample texture at normalized UV coordinates [0, 1]
     #[inline]
     pub fn sample(&self, u: f32, v: f32) -> Color {
@@ -32,90 +72,45 @@ impl Texture {
 }
 
 pub struct TextureManager {
-    pub textures: HashMap<String, Texture>,
+    pub textures: Registry<TextureKind, Texture>,
     pub texture_size: usize, // Keep for backwards compatibility
+    pub load_errors: HashMap<String, String>, // Texture name -> reason it failed to load
+    pub level_textures: Vec<String>, // Textures owned by the current level (freed on level change)
+    grade: Option<TextureGrade>,     // The current level's grade, baked into the pixels
+    ungraded: HashMap<String, Vec<Color>>, // Pixels as loaded, to regrade for another level
 }
 
 impl TextureManager {
-    pub fn new(texture_size: usize) -> Self {
+    /// Build the manager from preloaded textures, falling back to procedural
+    /// generation if too few of them loaded
+    pub fn from_loaded(
+        texture_size: usize,
+        textures: HashMap<String, Texture>,
+        load_errors: HashMap<String, String>,
+    ) -> Self {
+        let mut registry = Registry::new();
+        for (name, texture) in textures {
+            registry.insert(&name, texture);
+        }
+
         let mut manager = Self {
-            textures: HashMap::new(),
+            textures: registry,
             texture_size,
+            load_errors,
+            level_textures: Vec::new(),
+            grade: None,
+            ungraded: HashMap::new(),
         };
 
-        // Try to load PNG textures from assets/textures/
-        // If loading fails, fall back to procedural generation
-        if !manager.load_png_textures() {
-            println!("⚠ PNG loading failed or incomplete, using procedural textures");
+        // Use the PNGs only if at least the essential textures loaded
+        if manager.textures.loaded_count() < 2 {
+            warn!("PNG loading failed or incomplete, using procedural textures");
             manager.generate_backrooms_textures();
         }
 
         manager
     }
 
-    /// Load PNG textures from assets/textures/ directory
-    fn load_png_textures(&mut self) -> bool {
-        let texture_paths = vec![
-            ("wall", "assets/textures/wall.png"),
-            ("wall_exit", "assets/textures/wall_exit.png"),
-            ("floor", "assets/textures/floor2.png"),
-            ("ceiling", "assets/textures/ceiling.png"),
-            ("red_pill", "assets/textures/red_pill.png"),
-            ("blue_pill", "assets/textures/blue_pill.png"),
-        ];
-
-        let mut success_count = 0;
-
-        for (name, path) in texture_paths {
-            match Image::load_image(path) {
-                Ok(image) => {
-                    match self.convert_image_to_texture(&image, name) {
-                        Ok(_) => {
-                            println!("✓ Loaded texture: {} ({}x{})", name,
-                                self.textures.get(name).unwrap().width,
-                                self.textures.get(name).unwrap().height);
-                            success_count += 1;
-                        }
-                        Err(e) => {
-                            println!("⚠ Failed to convert {}: {}", path, e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("⚠ Failed to load {}: {:?}", path, e);
-                }
-            }
-        }
-
-        // Return true if at least the essential textures loaded
-        success_count >= 2
-    }
-
-    /// Convert raylib Image to our Texture format
-    fn convert_image_to_texture(&mut self, image: &Image, name: &str) -> Result<(), String> {
-        let width = image.width as usize;
-        let height = image.height as usize;
-
-        if width == 0 || height == 0 {
-            return Err("Invalid texture dimensions".to_string());
-        }
-
-        // Get pixel data from image
-        let mut pixels = Vec::with_capacity(width * height);
-
-        unsafe {
-            let data = image.data as *const Color;
-            for i in 0..(width * height) {
-                pixels.push(*data.offset(i as isize));
-            }
-        }
-
-        let texture = Texture::new(pixels, width, height);
-        self.textures.insert(name.to_string(), texture);
-
-        Ok(())
-    }
-
     /// Generate Backrooms-themed textures procedurally (fallback)
     fn generate_backrooms_textures(&mut self) {
         let size = self.texture_size;
@@ -137,7 +132,7 @@ impl TextureManager {
                 ));
             }
         }
-        self.textures.insert("wall".to_string(), Texture::new(yellow_wall, size, size));
+        self.textures.insert("wall", Texture::new(yellow_wall, size, size));
 
         // Blue door texture (goal/exit)
         let mut blue_door = Vec::with_capacity(size * size);
@@ -150,7 +145,7 @@ impl TextureManager {
                 blue_door.push(Color::new(30, 80, blue_val, 255));
             }
         }
-        self.textures.insert("wall_exit".to_string(), Texture::new(blue_door, size, size));
+        self.textures.insert("wall_exit", Texture::new(blue_door, size, size));
 
         // Ceiling texture (off-white with panels)
         let mut ceiling = Vec::with_capacity(size * size);
@@ -162,7 +157,7 @@ impl TextureManager {
                 ceiling.push(Color::new(gray, gray, gray - 10, 255));
             }
         }
-        self.textures.insert("ceiling".to_string(), Texture::new(ceiling, size, size));
+        self.textures.insert("ceiling", Texture::new(ceiling, size, size));
 
         // Floor texture (dull carpet)
         let mut floor = Vec::with_capacity(size * size);
@@ -180,11 +175,125 @@ impl TextureManager {
                 ));
             }
         }
-        self.textures.insert("floor".to_string(), Texture::new(floor, size, size));
+        self.textures.insert("floor", Texture::new(floor, size, size));
     }
 
-    /// Get texture data by name
-    pub fn get_texture(&self, name: &str) -> Option<&Texture> {
-        self.textures.get(name)
+    /// Load the textures a level references and free the ones the previous level
+    /// used that are no longer needed. Shared textures from [default] are kept
+    pub fn load_level(&mut self, manifest: &AssetManifest) {
+        let wanted: Vec<&str> = manifest
+            .entries
+            .iter()
+            .filter(|e| e.kind == AssetKind::Texture)
+            .map(|e| e.name.as_str())
+            .collect();
+
+        // Free textures of the previous level
+        for name in std::mem::take(&mut self.level_textures) {
+            if !wanted.contains(&name.as_str()) {
+                if let Some(texture) = self.textures.remove(&name) {
+                    info!("Unloaded texture: {} ({} KB)", name, texture.memory_bytes() / 1024);
+                }
+            }
+        }
+
+        // Load the new level's textures (reusing any that are still resident)
+        for entry in manifest.entries.iter().filter(|e| e.kind == AssetKind::Texture) {
+            if !self.textures.contains(&entry.name) {
+                match Texture::load(&entry.path) {
+                    Ok(texture) => {
+                        info!("Loaded level texture: {} ({}x{})", entry.name, texture.width, texture.height);
+                        self.load_errors.remove(&entry.name);
+                        self.textures.insert(&entry.name, texture);
+                    }
+                    Err(e) => {
+                        warn!("Failed to load {}: {}", entry.path, e);
+                        self.load_errors.insert(entry.name.clone(), format!("{}: {}", entry.path, e));
+                        continue;
+                    }
+                }
+            }
+            self.level_textures.push(entry.name.clone());
+        }
+        self.regrade();
+
+        let used = self.memory_bytes();
+        if used > TEXTURE_BUDGET_BYTES {
+            warn!(
+                "Texture memory {} KB exceeds budget of {} KB",
+                used / 1024,
+                TEXTURE_BUDGET_BYTES / 1024
+            );
+        }
+    }
+
+    /// Reload the resident textures listed in `manifest` from its paths (switching
+    /// resource packs). Handles stay valid; a texture that fails to load keeps its
+    /// current pixels, and ones that aren't resident are left to their level
+    pub fn reload(&mut self, manifest: &AssetManifest) {
+        for entry in manifest.entries.iter().filter(|e| e.kind == AssetKind::Texture) {
+            if !self.textures.contains(&entry.name) {
+                continue;
+            }
+            match Texture::load(&entry.path) {
+                Ok(texture) => {
+                    debug!("Reloaded texture: {} from {}", entry.name, entry.path);
+                    self.ungraded.remove(&entry.name); // Graded again from the new pixels
+                    self.load_errors.remove(&entry.name);
+                    self.textures.insert(&entry.name, texture);
+                }
+                Err(e) => warn!("Failed to reload {}: {}", entry.path, e),
+            }
+        }
+        self.regrade();
+    }
+
+    /// Total decoded texture memory in bytes (including the ungraded copies)
+    pub fn memory_bytes(&self) -> usize {
+        let ungraded: usize = self.ungraded.values().map(|pixels| pixels.len() * std::mem::size_of::<Color>()).sum();
+        self.textures.values().map(Texture::memory_bytes).sum::<usize>() + ungraded
+    }
+
+    /// Bake a level's grade into every loaded texture (see texture_grade.rs), undoing the
+    /// previous level's. None leaves the textures as loaded
+    pub fn set_grade(&mut self, grade: Option<TextureGrade>) {
+        self.grade = grade;
+        self.regrade();
+    }
+
+    /// Restore the pixels as loaded, then apply the current grade to everything resident
+    fn regrade(&mut self) {
+        let mut ungraded = std::mem::take(&mut self.ungraded);
+        for (name, texture) in self.textures.iter_mut() {
+            if let Some(pixels) = ungraded.remove(name) {
+                texture.pixels = pixels;
+            }
+            if let Some(grade) = self.grade {
+                self.ungraded.insert(name.to_string(), texture.pixels.clone());
+                grade.apply(&mut texture.pixels);
+            }
+        }
+    }
+
+    /// Get the handle for a texture that may not be loaded yet (e.g. level textures)
+    pub fn id(&mut self, name: &str) -> TextureId {
+        self.textures.handle(name)
+    }
+
+    /// Get the handle for a texture that must already be loaded, describing why it isn't
+    pub fn require(&mut self, name: &str) -> Result<TextureId, String> {
+        if !self.textures.contains(name) {
+            return Err(match self.load_errors.get(name) {
+                Some(reason) => format!("Bad texture '{}' ({})", name, reason),
+                None => format!("Missing texture '{}'", name),
+            });
+        }
+        Ok(self.textures.handle(name))
+    }
+
+    /// Get texture data by handle
+    #[inline]
+    pub fn get(&self, id: TextureId) -> Option<&Texture> {
+        self.textures.get(id)
     }
 }
@@ -0,0 +1,23 @@
+// Keyframe timelines for Backrooms Doom
+// Scripted sequences (the intro splash, the escape through the exit door, ...)
+// are a handful of values that change over a few seconds. Each value is a track
+// of (time, value) keyframes interpolated linearly, held at the first value
+// before the first keyframe and at the last one after it, so a sequence can be
+// retuned by editing its keyframe tables alone.
+
+/// Value of the `track` keyframes at `time` seconds
+pub fn sample(track: &[(f32, f32)], time: f32) -> f32 {
+    for pair in track.windows(2) {
+        let ((start, from), (end, to)) = (pair[0], pair[1]);
+        if time < end {
+            let t = ((time - start) / (end - start)).clamp(0.0, 1.0);
+            return from + (to - from) * t;
+        }
+    }
+    track.last().map_or(0.0, |&(_, value)| value)
+}
+
+/// Time of the last keyframe of `track`
+pub fn end(track: &[(f32, f32)]) -> f32 {
+    track.last().map_or(0.0, |&(time, _)| time)
+}
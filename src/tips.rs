@@ -0,0 +1,78 @@
+// Game-over tips for Backrooms Doom
+// The game-over screen rotates through a few tips picked for how the run went.
+// A handful of counters follow the run (pills taken, idle penalties, strikes,
+// ...) and the tips for what ended it come first, most telling first: dying to
+// red pills suggests leaving them alone, running out the clock after several
+// blue pills points at their time cost. General tips for the cause fill in
+// behind so there is always something to show.
+
+use crate::death::DeathCause;
+
+const TIP_TIME: f64 = 5.0; // Seconds each tip stays on the game-over screen
+
+/// What happened during the run, as far as the tips care
+#[derive(Default)]
+pub struct RunStats {
+    pub red_pills: u32,      // Each cost health
+    pub blue_pills: u32,     // Each cost time
+    pub idle_penalties: u32, // Health lost to standing still
+    pub strikes: u32,        // Hits taken from entities
+    pub door_bursts: u32,    // Hits taken from doors forced open next to the player
+    pub kills: u32,          // Entities put down
+    pub challenges_won: u32,    // Challenge rooms finished in time
+    pub challenges_failed: u32, // Challenge rooms run out of time or left early
+}
+
+/// Tips for a run that ended by `cause`, the most relevant first
+pub fn tips(cause: DeathCause, stats: &RunStats) -> Vec<&'static str> {
+    let mut tips = Vec::new();
+    match cause {
+        DeathCause::Timer => {
+            if stats.blue_pills > 0 {
+                tips.push("Blue pills heal, but each one takes 20 seconds off the clock");
+            }
+            if stats.idle_penalties > 0 {
+                tips.push("Standing still wastes time and drains health: keep moving");
+            }
+            if stats.challenges_failed > 0 {
+                tips.push("Challenge rooms can win back time: plan a route through the orbs before stepping in");
+            }
+            tips.push("The exit hums: it gets louder and higher as you close in");
+            tips.push("Chalk marks on the walls show which corridors you've already walked");
+        }
+        DeathCause::Caught => {
+            if stats.strikes > 0 {
+                tips.push("Every touch from an entity costs 10 HP: don't let them close in");
+            }
+            if stats.kills == 0 {
+                tips.push("Entities can be fought off: attack them before they reach you");
+            }
+            tips.push("Entities run once they've seen you: break their line of sight");
+            tips.push("Crouch in the dark to stay unseen");
+        }
+        DeathCause::Health => {
+            if stats.red_pills > 0 {
+                tips.push("Red pills cost 15 HP: leave them when you're hurt");
+            }
+            if stats.idle_penalties > 0 {
+                tips.push("Standing still for 5 seconds drains 10 HP");
+            }
+            if stats.door_bursts > 0 {
+                tips.push("Stay clear of a door an entity is forcing open");
+            }
+            if stats.strikes > 0 {
+                tips.push("Every touch from an entity costs 10 HP: don't let them close in");
+            }
+            tips.push("Blue pills restore health");
+        }
+    }
+    tips
+}
+
+/// The tip to show `time` seconds in, rotating every TIP_TIME
+pub fn rotate<'a>(tips: &[&'a str], time: f64) -> Option<&'a str> {
+    if tips.is_empty() {
+        return None;
+    }
+    Some(tips[(time / TIP_TIME) as usize % tips.len()])
+}
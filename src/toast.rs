@@ -0,0 +1,87 @@
+// Toasts for Backrooms Doom
+// Short messages ("Key acquired", "Power restored") that slide in under the timer,
+// stay a few seconds and slide back out. Up to three are on screen, stacked one per
+// slot; the rest wait in a queue. When one leaves, the ones below glide up into its
+// slot. Any system can raise one through GameEvent::Toast (see events.rs).
+
+use crate::tween::{Easing, Tween};
+use raylib::prelude::*;
+use std::collections::VecDeque;
+
+const MAX_SHOWN: usize = 3;
+const SHOW_TIME: f32 = 2.5;  // Seconds a toast stays in place
+const SLIDE_TIME: f32 = 0.3; // Seconds to slide in or out, or move up a slot
+const MAX_QUEUED: usize = 8; // Older waiting toasts are dropped past this
+
+struct Toast {
+    text: String,
+    color: Color,
+    slide: Tween, // 1.0 (off screen) - 0.0 (in place)
+    slot: Tween,  // Row in the stack, 0 at the top
+    time_left: f32,
+    leaving: bool,
+}
+
+pub struct Toasts {
+    shown: Vec<Toast>,
+    queued: VecDeque<(String, Color)>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self { shown: Vec::new(), queued: VecDeque::new() }
+    }
+
+    /// Queue a message, shown as soon as there is room
+    pub fn push(&mut self, text: impl Into<String>, color: Color) {
+        if self.queued.len() >= MAX_QUEUED {
+            self.queued.pop_front();
+        }
+        self.queued.push_back((text.into(), color));
+    }
+
+    pub fn clear(&mut self) {
+        self.shown.clear();
+        self.queued.clear();
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for toast in &mut self.shown {
+            toast.slide.update(delta_time);
+            toast.slot.update(delta_time);
+            if !toast.leaving {
+                toast.time_left -= delta_time;
+                if toast.time_left <= 0.0 {
+                    toast.leaving = true;
+                    toast.slide = Tween::between(toast.slide.value(), 1.0, SLIDE_TIME, Easing::EaseIn);
+                }
+            }
+        }
+        self.shown.retain(|toast| !toast.leaving || toast.slide.value() < 1.0);
+
+        // Close the gaps left behind, then fill the free slots from the queue
+        for (index, toast) in self.shown.iter_mut().enumerate() {
+            if toast.slot.target() != index as f32 {
+                toast.slot = Tween::between(toast.slot.value(), index as f32, SLIDE_TIME, Easing::EaseInOut);
+            }
+        }
+        while self.shown.len() < MAX_SHOWN {
+            let Some((text, color)) = self.queued.pop_front() else {
+                break;
+            };
+            self.shown.push(Toast {
+                text,
+                color,
+                slide: Tween::between(1.0, 0.0, SLIDE_TIME, Easing::EaseOut),
+                slot: Tween::new(self.shown.len() as f32),
+                time_left: SHOW_TIME,
+                leaving: false,
+            });
+        }
+    }
+
+    /// Toasts on screen: (text, color, slide 1.0 off screen - 0.0 in place, row in the stack)
+    pub fn visible(&self) -> impl Iterator<Item = (&str, Color, f32, f32)> {
+        self.shown.iter().map(|toast| (toast.text.as_str(), toast.color, toast.slide.value(), toast.slot.value()))
+    }
+}
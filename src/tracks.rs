@@ -0,0 +1,61 @@
+// Footprint trails for Backrooms Doom
+// Enemies leave footprints wherever they walk, so the player can follow a
+// trail or steer clear of it. Walking through water leaves the player with wet
+// feet for a while, and the wet footprints left meanwhile can be picked up by
+// idle enemies nearby, who follow them from print to print, always to one
+// fresher than the last, until the trail runs out. Footprints are decals (see decal.rs).
+
+use crate::decal::{DecalKind, Decals};
+
+const STRIDE: f32 = 0.55;       // Tiles walked between two footprints
+const FOOT_SPACING: f32 = 0.08; // Sideways offset of each foot from the walking line
+const TELEPORT: f32 = 1.0;      // Moving further than this in one step starts a new trail
+pub const WET_TIME: f32 = 8.0;  // Seconds of wet footprints after leaving water
+
+// Enemies tracking wet footprints
+const TRACK_RANGE: f32 = 4.0;     // How far away an enemy notices a wet print, in tiles
+pub const SCENT_TIME: f32 = 15.0; // Prints older than this can't be followed any more
+const TRACK_MIN_STEP: f32 = 0.5;  // Ignore prints this close, the enemy is standing on them
+
+/// Counts the distance walked and says where to put the next footprint
+pub struct Strider {
+    last: (f32, f32),
+    travelled: f32,
+    left_foot: bool,
+}
+
+impl Strider {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { last: (x, y), travelled: 0.0, left_foot: false }
+    }
+
+    /// Record a move to (x, y). Returns where the next footprint goes once a full stride is walked
+    pub fn step(&mut self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let (dx, dy) = (x - self.last.0, y - self.last.1);
+        let moved = (dx * dx + dy * dy).sqrt();
+        self.last = (x, y);
+        if moved > TELEPORT {
+            self.travelled = 0.0;
+            return None;
+        }
+
+        self.travelled += moved;
+        if self.travelled < STRIDE || moved <= 0.0 {
+            return None;
+        }
+        self.travelled = (self.travelled - STRIDE).min(STRIDE);
+
+        // Alternate feet on either side of the walking direction
+        self.left_foot = !self.left_foot;
+        let side = if self.left_foot { FOOT_SPACING } else { -FOOT_SPACING };
+        Some((x - dy / moved * side, y + dx / moved * side))
+    }
+}
+
+/// Where an idle enemy at (x, y) would go next to follow the player's wet footprints: the
+/// freshest print in range that is younger than `younger_than` seconds. Returns (x, y, age)
+pub fn follow_wet_prints(decals: &Decals, x: f32, y: f32, younger_than: f32) -> Option<(f32, f32, f32)> {
+    decals
+        .freshest(DecalKind::WetFootprint, (x, y), TRACK_MIN_STEP, TRACK_RANGE, younger_than.min(SCENT_TIME))
+        .map(|print| (print.x, print.y, print.age))
+}
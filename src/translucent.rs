@@ -0,0 +1,38 @@
+// See-through tiles for Backrooms Doom
+// Rays don't stop at these: the caster records each one it passes, nearest first,
+// as a layer on the way to the opaque wall behind (see caster.rs), and the column
+// renderer blends the layers over the wall from the back to the front.
+//
+//   G  glass window: solid to walk into, a faint tint with a frame around it
+//   %  smoke: walkable, every tile of it thickens the haze a little
+//
+// New kinds only need a tile, a color and how much of what is behind they hide.
+
+use raylib::prelude::*;
+
+const FRAME_WIDTH: f32 = 0.05; // Window frame, as a fraction of the pane
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Translucent {
+    Glass,
+    Smoke,
+}
+
+impl Translucent {
+    pub fn from_tile(tile: char) -> Option<Self> {
+        match tile {
+            'G' => Some(Translucent::Glass),
+            '%' => Some(Translucent::Smoke),
+            _ => None,
+        }
+    }
+
+    /// Color and opacity (0.0 - 1.0) at `u` across the surface (0.0 - 1.0)
+    pub fn shade(self, u: f32) -> (Color, f32) {
+        match self {
+            Translucent::Glass if !(FRAME_WIDTH..=1.0 - FRAME_WIDTH).contains(&u) => (Color::new(120, 115, 100, 255), 0.9),
+            Translucent::Glass => (Color::new(170, 210, 215, 255), 0.22),
+            Translucent::Smoke => (Color::new(150, 148, 140, 255), 0.18),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// UI tweening for Backrooms Doom
+// A tween moves a value to a target over a duration with an easing curve, after an
+// optional delay. Widgets keep a Tween per animated property, start a new one from
+// the current value when the thing they show changes and read the value when
+// drawing, instead of each one doing its own timing math. Looping effects (pulsing
+// prompts) use pulse() on the clock since they never settle.
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseIn,    // Starts slow, ends fast
+    EaseOut,   // Starts fast, settles gently
+    EaseInOut,
+}
+
+impl Easing {
+    /// Progress 0.0 - 1.0 along the curve for time 0.0 - 1.0
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+pub struct Tween {
+    from: f32,
+    to: f32,
+    duration: f32,
+    delay: f32,   // Seconds held at `from` before moving
+    elapsed: f32, // Since the tween started, delay included
+    easing: Easing,
+}
+
+impl Tween {
+    /// A tween resting at `value`
+    pub fn new(value: f32) -> Self {
+        Self { from: value, to: value, duration: 0.0, delay: 0.0, elapsed: 0.0, easing: Easing::Linear }
+    }
+
+    /// Move from `from` to `to` over `duration` seconds
+    pub fn between(from: f32, to: f32, duration: f32, easing: Easing) -> Self {
+        Self { from, to, duration, delay: 0.0, elapsed: 0.0, easing }
+    }
+
+    /// Wait `delay` seconds before starting to move
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Jump straight to `value`
+    pub fn snap(&mut self, value: f32) {
+        *self = Self::new(value);
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.elapsed += delta_time;
+    }
+
+    pub fn value(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return if self.elapsed >= self.delay { self.to } else { self.from };
+        }
+        let t = (self.elapsed - self.delay) / self.duration;
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+
+    /// Whether the delay is over and the value has started moving
+    pub fn started(&self) -> bool {
+        self.elapsed >= self.delay
+    }
+
+    /// Whether the value has reached its target
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.delay + self.duration
+    }
+
+    /// Where the value is heading
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    /// Seconds since the tween started (drives oscillations scaled by the value)
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+}
+
+/// Value swinging smoothly between `low` and `high`, `rate` times per second, at
+/// `time` seconds on the clock
+pub fn pulse(time: f64, rate: f32, low: f32, high: f32) -> f32 {
+    let t = ((time * rate as f64).fract() * 2.0) as f32;
+    let swing = if t < 1.0 { t } else { 2.0 - t };
+    low + (high - low) * Easing::EaseInOut.apply(swing)
+}
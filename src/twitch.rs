@@ -0,0 +1,192 @@
+// Twitch chat integration for Backrooms Doom
+// Optional: set `twitch_channel` in settings.toml and chat can vote on a scare
+// from a fixed safe list by typing !flicker, !glimpse or !pill. Votes open once
+// the cooldown runs out, each viewer counts once, and the winner is pushed into
+// the event queue like any other gameplay event.
+//
+// The connection is anonymous and read-only (no OAuth token, nothing is sent to
+// chat), using Twitch's "justinfan" guest login on the plain IRC port.
+
+use log::{info, warn};
+use rand::Rng;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+const IRC_ADDRESS: &str = "irc.chat.twitch.tv:6667";
+const VOTE_TIME: f32 = 20.0; // Seconds a vote stays open
+
+/// Events chat is allowed to trigger. Nothing on this list can hurt the player
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scare {
+    Flicker, // The lights flicker and cut out for a moment
+    Glimpse, // An entity shows up down the hall and vanishes
+    Pill,    // A pill drops next to the player
+}
+
+impl Scare {
+    pub const ALL: [Scare; 3] = [Scare::Flicker, Scare::Glimpse, Scare::Pill];
+
+    /// Chat command for this scare, without the leading '!'
+    pub fn command(self) -> &'static str {
+        match self {
+            Scare::Flicker => "flicker",
+            Scare::Glimpse => "glimpse",
+            Scare::Pill => "pill",
+        }
+    }
+
+    pub fn parse(message: &str) -> Option<Self> {
+        let command = message.trim().strip_prefix('!')?;
+        Self::ALL.into_iter().find(|scare| command.eq_ignore_ascii_case(scare.command()))
+    }
+}
+
+enum Message {
+    Connected,
+    Vote { user: String, scare: Scare },
+    Disconnected(String),
+}
+
+pub struct TwitchChat {
+    receiver: Receiver<Message>,
+    votes: HashMap<String, Scare>, // One vote per viewer, changing it replaces the old one
+    cooldown: f32,                 // Seconds until the next vote opens
+    vote_cooldown: f32,            // Cooldown applied after each vote
+    vote_timer: f32,               // Seconds left in the open vote
+    pub status: String,
+}
+
+impl TwitchChat {
+    /// Join the channel's chat in the background
+    pub fn connect(channel: &str, vote_cooldown: f32) -> Self {
+        let channel = channel.trim_start_matches('#').to_lowercase();
+        let (sender, receiver) = mpsc::channel();
+        let status = format!("Connecting to #{}...", channel);
+        thread::spawn(move || {
+            let reason = match listen(&channel, &sender) {
+                Ok(()) => "connection closed".to_string(),
+                Err(e) => e,
+            };
+            let _ = sender.send(Message::Disconnected(reason));
+        });
+
+        Self {
+            receiver,
+            votes: HashMap::new(),
+            cooldown: vote_cooldown,
+            vote_cooldown,
+            vote_timer: 0.0,
+            status,
+        }
+    }
+
+    pub fn voting(&self) -> bool {
+        self.vote_timer > 0.0
+    }
+
+    /// Seconds left in the open vote, or until the next one opens
+    pub fn time_left(&self) -> f32 {
+        if self.voting() { self.vote_timer } else { self.cooldown }
+    }
+
+    /// Current vote count for each scare, in list order
+    pub fn tally(&self) -> Vec<(Scare, usize)> {
+        Scare::ALL
+            .into_iter()
+            .map(|scare| (scare, self.votes.values().filter(|vote| **vote == scare).count()))
+            .collect()
+    }
+
+    /// Collect chat votes and run the vote clock. Returns the winning scare when a vote closes
+    pub fn update(&mut self, delta_time: f32) -> Option<Scare> {
+        while let Ok(message) = self.receiver.try_recv() {
+            match message {
+                Message::Connected => self.status = "Chat connected".to_string(),
+                Message::Vote { user, scare } => {
+                    // Votes outside the window are ignored
+                    if self.voting() {
+                        self.votes.insert(user, scare);
+                    }
+                }
+                Message::Disconnected(reason) => {
+                    warn!("Twitch chat disconnected: {}", reason);
+                    self.status = format!("Chat offline ({})", reason);
+                }
+            }
+        }
+
+        if !self.voting() {
+            self.cooldown -= delta_time;
+            if self.cooldown <= 0.0 {
+                self.votes.clear();
+                self.vote_timer = VOTE_TIME;
+            }
+            return None;
+        }
+
+        self.vote_timer -= delta_time;
+        if self.vote_timer > 0.0 {
+            return None;
+        }
+
+        // Nobody voted: keep the poll open for another round
+        let tally = self.tally();
+        let best = tally.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        if best == 0 {
+            self.vote_timer = VOTE_TIME;
+            return None;
+        }
+
+        // Ties are settled randomly
+        let tied: Vec<Scare> = tally.iter().filter(|(_, count)| *count == best).map(|(scare, _)| *scare).collect();
+        let winner = tied[rand::thread_rng().gen_range(0..tied.len())];
+        info!("Chat voted for !{} ({} vote(s))", winner.command(), best);
+        self.votes.clear();
+        self.cooldown = self.vote_cooldown;
+        Some(winner)
+    }
+}
+
+/// Read chat until the connection drops, forwarding votes
+fn listen(channel: &str, sender: &Sender<Message>) -> Result<(), String> {
+    let mut stream = TcpStream::connect(IRC_ADDRESS).map_err(|e| e.to_string())?;
+    let nick = format!("justinfan{}", rand::thread_rng().gen_range(10000..99999));
+    write!(stream, "NICK {}\r\nJOIN #{}\r\n", nick, channel).map_err(|e| e.to_string())?;
+
+    let reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+
+        // Keep-alive, or the server drops us
+        if let Some(server) = line.strip_prefix("PING") {
+            write!(stream, "PONG{}\r\n", server).map_err(|e| e.to_string())?;
+            continue;
+        }
+
+        // ":<user>!<user>@<user>.tmi.twitch.tv PRIVMSG #<channel> :<text>"
+        let mut parts = line.splitn(4, ' ');
+        let (Some(prefix), Some(command)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        match command {
+            "JOIN" => {
+                let _ = sender.send(Message::Connected);
+            }
+            "PRIVMSG" => {
+                let user = prefix.trim_start_matches(':').split('!').next().unwrap_or_default();
+                let text = parts.nth(1).unwrap_or_default().trim_start_matches(':');
+                if let Some(scare) = Scare::parse(text) {
+                    let vote = Message::Vote { user: user.to_string(), scare };
+                    if sender.send(vote).is_err() {
+                        return Ok(()); // Game closed the chat
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
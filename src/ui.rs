@@ -1,21 +1,118 @@
 use raylib::prelude::*;
+use crate::benchmark::Benchmark;
+use crate::config::Config;
+use crate::color_grade::ColorGrade;
+use crate::container::Search;
+use crate::controls::{Action, Controls};
+use crate::hud::{Anchor, HudLayout, StatusItem};
+use crate::inventory::Inventory;
+use crate::credits::{self, Credits};
+use crate::crosshair::{self, CrosshairConfig, CrosshairState, CrosshairStyle};
+use crate::death::DeathCause;
+use crate::leaderboard::RunRecord;
 use crate::player::Player;
+use crate::options::{Options, Setting};
+use crate::profile::{self, ProfilePicker};
+use crate::tally::Tally;
+use crate::textures::Texture;
+use crate::tips;
+use crate::toast::Toasts;
+use crate::tween::{self, Easing, Tween};
+use crate::twitch::TwitchChat;
+
+// Menu entries slide in from the left one after another when the menu opens
+const MENU_ENTRIES: usize = 9;
+const MENU_SLIDE_TIME: f32 = 0.35;
+const MENU_SLIDE_STAGGER: f32 = 0.05; // Seconds between one entry and the next
+// The health bar's damage chip holds where health was, then drains to it
+const CHIP_DELAY: f32 = 0.4;
+const CHIP_TIME: f32 = 0.5;
+// The timer shakes when time is taken away, and flashes (red for time lost, green and
+// swelling for time gained) while the change floats up beside it
+const TIMER_SHAKE_TIME: f32 = 0.5;
+const TIMER_SHAKE_PIXELS: f32 = 8.0;
+const TIMER_FLASH_TIME: f32 = 0.6;
+const TIMER_SWELL: f32 = 8.0;  // Extra font size at the start of a gain
+const TIMER_DELTA_TIME: f32 = 1.2;
+const TIMER_DELTA_RISE: f32 = 24.0; // Pixels the change floats up
 
 pub struct UI {
     pub font_size: i32,
+    menu_slide: Vec<Tween>, // Per menu entry, 1.0 (off screen) - 0.0 (in place)
+    health_chip: Tween,     // Health fraction the chip behind the bar shows
+    timer_shake: Tween,     // Shake strength, 0.0 - 1.0
+    timer_flash: Tween,     // Flash strength, 0.0 - 1.0
+    timer_delta: Tween,     // The floating change, 0.0 (appearing) - 1.0 (gone)
+    timer_change: f32,      // Seconds of the last change, negative for time lost
+    in_menu: bool,
+    pub toasts: Toasts,
 }
 
 impl UI {
     pub fn new(font_size: i32) -> Self {
-        Self { font_size }
+        Self {
+            font_size,
+            menu_slide: Vec::new(),
+            health_chip: Tween::new(1.0),
+            timer_shake: Tween::new(0.0),
+            timer_flash: Tween::new(0.0),
+            timer_delta: Tween::new(1.0),
+            timer_change: 0.0,
+            in_menu: false,
+            toasts: Toasts::new(),
+        }
     }
 
-    /// Render the main menu
-    pub fn render_menu(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32) {
-        // Background
-        d.clear_background(Color::BLACK);
+    /// Advance the animations and retarget them at what the screens show now: whether
+    /// the main menu is up and the local player's health (0.0 - 1.0)
+    pub fn update(&mut self, delta_time: f32, in_menu: bool, health: f32) {
+        if in_menu && !self.in_menu {
+            self.menu_slide = (0..MENU_ENTRIES)
+                .map(|i| Tween::between(1.0, 0.0, MENU_SLIDE_TIME, Easing::EaseOut).with_delay(i as f32 * MENU_SLIDE_STAGGER))
+                .collect();
+        }
+        self.in_menu = in_menu;
+
+        if health < self.health_chip.target() {
+            self.health_chip = Tween::between(self.health_chip.value(), health, CHIP_TIME, Easing::EaseIn).with_delay(CHIP_DELAY);
+        } else if health > self.health_chip.value() {
+            self.health_chip.snap(health);
+        }
+
+        for tween in self.menu_slide.iter_mut().chain([&mut self.health_chip, &mut self.timer_shake, &mut self.timer_flash, &mut self.timer_delta]) {
+            tween.update(delta_time);
+        }
+        self.toasts.update(delta_time);
+    }
+
+    /// Toasts stacked under the timer, sliding in from the right
+    pub fn render_toasts(&self, d: &mut RaylibDrawHandle, screen_width: i32) {
+        let (font_size, padding, top) = (18, 8, 84); // Below the timer and its warning
+        let row = font_size + padding * 2 + 6;
+        for (text, color, slide, slot) in self.toasts.visible() {
+            let width = d.measure_text(text, font_size) + padding * 2;
+            let x = screen_width / 2 - width / 2 + (slide * (screen_width / 2 + width / 2) as f32) as i32;
+            let y = top + (slot * row as f32) as i32;
+            let alpha = 1.0 - slide;
+            d.draw_rectangle(x, y, width, font_size + padding * 2, Color::new(0, 0, 0, (180.0 * alpha) as u8));
+            d.draw_rectangle(x, y, 3, font_size + padding * 2, Color::new(color.r, color.g, color.b, (255.0 * alpha) as u8));
+            d.draw_text(text, x + padding, y + padding, font_size, Color::new(color.r, color.g, color.b, (255.0 * alpha) as u8));
+        }
+    }
+
+    /// Flash the timer for `seconds` added to it (negative: taken away, which also shakes it)
+    pub fn change_timer(&mut self, seconds: f32) {
+        if seconds < 0.0 {
+            self.timer_shake = Tween::between(1.0, 0.0, TIMER_SHAKE_TIME, Easing::EaseOut);
+        }
+        self.timer_flash = Tween::between(1.0, 0.0, TIMER_FLASH_TIME, Easing::EaseOut);
+        self.timer_delta = Tween::between(0.0, 1.0, TIMER_DELTA_TIME, Easing::Linear);
+        self.timer_change = seconds;
+    }
 
-        // Title with Backrooms aesthetic
+    /// Render the main menu
+    pub fn render_menu(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, controls: &Controls, profile: &str) {
+        // Title with Backrooms aesthetic (over the flythrough, see flythrough.rs)
         let title = "ENTER THE BACKROOMS";
         let title_size = 40;
         let title_width = d.measure_text(title, title_size);
@@ -40,21 +137,29 @@ impl UI {
         );
 
         // Menu options
+        let move_hint = format!("{} - Move", controls.move_label());
         let options = vec![
             "PRESS ENTER TO START",
-            "WASD - Move",
+            move_hint.as_str(),
             "Mouse - Look Around",
+            "B - Benchmark",
+            "V - Versus (hotseat)",
+            "L - Leaderboard",
+            "C - Credits",
+            "O - Options",
             "ESC - Quit",
         ];
 
-        let start_y = screen_height / 2;
+        let start_y = screen_height / 3 + 30;
         for (i, option) in options.iter().enumerate() {
             let text_width = d.measure_text(option, self.font_size);
-            let y = start_y + (i as i32 * (self.font_size + 10));
+            let y = start_y + (i as i32 * (self.font_size + 6));
+            let slide = self.menu_slide.get(i).map_or(0.0, Tween::value);
+            let x = screen_width / 2 - text_width / 2 - (slide * (screen_width / 2 + text_width / 2) as f32) as i32;
 
             let color = if i == 0 {
                 // Pulsing effect for "Press Enter"
-                let pulse = ((d.get_time() * 3.0).sin() * 0.3 + 0.7) as f32;
+                let pulse = tween::pulse(d.get_time(), 0.5, 0.4, 1.0);
                 Color::new(
                     (255.0 * pulse) as u8,
                     (220.0 * pulse) as u8,
@@ -65,49 +170,62 @@ impl UI {
                 Color::new(200, 200, 200, 255)
             };
 
-            d.draw_text(
-                option,
-                screen_width / 2 - text_width / 2,
-                y,
-                self.font_size,
-                color,
-            );
+            d.draw_text(option, x, y, self.font_size, color);
+        }
+
+        // The keyboard types like a different layout than the one in the settings
+        if let Some(layout) = controls.suggestion() {
+            let text = format!("{} keyboard detected - type 'layout {}' in the console (`)", layout.display_name(), layout.name());
+            let text_width = d.measure_text(&text, 16);
+            d.draw_text(&text, screen_width / 2 - text_width / 2, screen_height - 18, 16, Color::new(255, 220, 0, 200));
         }
 
+        d.draw_text(&format!("Profile: {}", profile), 10, 10, 16, Color::new(200, 200, 200, 255));
+
         // Atmospheric flavor text
         let warning = "Find the blue door to escape...";
         let warning_width = d.measure_text(warning, 20);
         d.draw_text(
             warning,
             screen_width / 2 - warning_width / 2,
-            screen_height - 100,
+            screen_height - 40,
             20,
             Color::new(150, 150, 150, 200),
         );
     }
 
-    /// Render the HUD during gameplay
-    pub fn render_hud(&self, d: &mut RaylibDrawHandle, player: &Player, fps: u32) {
-        // Health bar in UPPER LEFT CORNER
-        let health_bar_width = 200;
-        let health_bar_height = 20;
+    /// Render the HUD during gameplay: health bar and FPS counter in the top-left corner
+    pub fn render_hud(&self, d: &mut RaylibDrawHandle, layout: &HudLayout, player: &Player, fps: u32) {
+        let health_bar_width = layout.px(200.0);
+        let health_bar_height = layout.px(20.0);
+        let font_size = layout.px(16.0);
         let health_percentage = player.health as f32 / player.max_health as f32;
-        let margin = 10;
-        let bar_x = margin; // Left side
+        let (bar_x, top) = layout.place(Anchor::TopLeft, (health_bar_width, health_bar_height), (0.0, 0.0));
+        let bar_y = top + layout.px(15.0);
 
         // "Health" label above the bar
         let health_label = "Health";
-        let label_width = d.measure_text(health_label, 16);
+        let label_width = d.measure_text(health_label, font_size);
         d.draw_text(
             health_label,
             bar_x + (health_bar_width - label_width) / 2,
-            margin - 5,
-            16,
+            top - layout.px(5.0),
+            font_size,
             Color::WHITE,
         );
 
         // Health bar background
-        d.draw_rectangle(bar_x, margin + 15, health_bar_width, health_bar_height, Color::new(50, 50, 50, 200));
+        d.draw_rectangle(bar_x, bar_y, health_bar_width, health_bar_height, Color::new(50, 50, 50, 200));
+
+        // Damage chip: what was just lost, draining down to the fill
+        let chip = self.health_chip.value().clamp(health_percentage, 1.0);
+        d.draw_rectangle(
+            bar_x,
+            bar_y,
+            (health_bar_width as f32 * chip) as i32,
+            health_bar_height,
+            Color::new(230, 230, 200, 220),
+        );
 
         // Health bar fill
         let health_color = if health_percentage > 0.5 {
@@ -120,7 +238,7 @@ impl UI {
 
         d.draw_rectangle(
             bar_x,
-            margin + 15,
+            bar_y,
             (health_bar_width as f32 * health_percentage) as i32,
             health_bar_height,
             health_color,
@@ -128,43 +246,69 @@ impl UI {
 
         // Health value text centered on bar
         let health_text = format!("{}/{}", player.health, player.max_health);
-        let health_text_width = d.measure_text(&health_text, 16);
+        let health_text_width = d.measure_text(&health_text, font_size);
         d.draw_text(
             &health_text,
             bar_x + (health_bar_width - health_text_width) / 2,
-            margin + 17,
-            16,
+            bar_y + layout.px(2.0),
+            font_size,
             Color::WHITE,
         );
 
-        // FPS counter in UPPER LEFT below health bar
+        // FPS counter below the health bar
         d.draw_text(
             &format!("FPS: {}", fps),
-            margin,
-            margin + 15 + health_bar_height + 10,
-            20,
+            bar_x,
+            bar_y + health_bar_height + layout.px(10.0),
+            layout.px(20.0),
             Color::WHITE,
         );
+    }
 
-        // Crosshair
-        let center_x = d.get_screen_width() / 2;
-        let center_y = d.get_screen_height() / 2;
-        let crosshair_size = 10;
+    /// Crosshair in the configured style, opened up by spread, with the hit marker on top
+    pub fn render_crosshair(&self, d: &mut RaylibDrawHandle, config: &CrosshairConfig, state: &CrosshairState) {
+        let center = Vector2::new((d.get_screen_width() / 2) as f32, (d.get_screen_height() / 2) as f32);
+        let spread = if config.dynamic { state.spread * config.size } else { 0.0 };
+        let gap = config.gap + spread;
+        let thickness = config.thickness.max(1.0);
 
-        d.draw_line(
-            center_x - crosshair_size,
-            center_y,
-            center_x + crosshair_size,
-            center_y,
-            Color::WHITE,
-        );
-        d.draw_line(
-            center_x,
-            center_y - crosshair_size,
-            center_x,
-            center_y + crosshair_size,
-            Color::WHITE,
-        );
+        let draw_cross = |d: &mut RaylibDrawHandle| {
+            for (dx, dy) in [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)] {
+                let start = Vector2::new(center.x + dx * gap, center.y + dy * gap);
+                let end = Vector2::new(center.x + dx * (gap + config.size), center.y + dy * (gap + config.size));
+                d.draw_line_ex(start, end, thickness, config.color);
+            }
+        };
+        let draw_dot = |d: &mut RaylibDrawHandle| {
+            d.draw_circle(center.x as i32, center.y as i32, thickness.max(1.5), config.color);
+        };
+
+        match config.style {
+            CrosshairStyle::Cross => draw_cross(d),
+            CrosshairStyle::Dot => draw_dot(d),
+            CrosshairStyle::Circle => {
+                let radius = gap + config.size;
+                d.draw_ring(center, radius - thickness, radius, 0.0, 360.0, 32, config.color);
+            }
+            CrosshairStyle::CrossDot => {
+                draw_cross(d);
+                draw_dot(d);
+            }
+            CrosshairStyle::Off => {}
+        }
+
+        // Hit marker: diagonal ticks around the center, red for a kill
+        if state.hit_marker > 0.0 {
+            let alpha = (255.0 * state.hit_marker / crosshair::HIT_MARKER_TIME) as u8;
+            let color = if state.kill { Color::new(255, 60, 40, alpha) } else { Color::new(255, 255, 255, alpha) };
+            let (inner, outer) = (gap + 4.0, gap + 11.0);
+            let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+            for (dx, dy) in [(diagonal, diagonal), (-diagonal, diagonal), (diagonal, -diagonal), (-diagonal, -diagonal)] {
+                let start = Vector2::new(center.x + dx * inner, center.y + dy * inner);
+                let end = Vector2::new(center.x + dx * outer, center.y + dy * outer);
+                d.draw_line_ex(start, end, 2.0, color);
+            }
+        }
     }
 
     /// Render the victory screen
@@ -197,7 +341,7 @@ impl UI {
         );
 
         // Instructions
-        let restart = "Press ENTER to return to menu";
+        let restart = "Press ENTER to continue";
         let restart_width = d.measure_text(restart, 20);
         d.draw_text(
             restart,
@@ -208,13 +352,283 @@ impl UI {
         );
     }
 
+    /// Render the end-of-level tally between two campaign levels: each line counting up, the
+    /// total and the medal stamped under it. `level` is (number, level count), `carried` the
+    /// health and time bonus taken into the next level (None after the last)
+    pub fn render_level_complete(
+        &self,
+        d: &mut RaylibDrawHandle,
+        screen_width: i32,
+        screen_height: i32,
+        level: (usize, usize),
+        tally: &Tally,
+        carried: Option<(i32, f32)>,
+    ) {
+        d.draw_rectangle(0, 0, screen_width, screen_height, Color::new(0, 0, 0, 200));
+
+        let title = format!("LEVEL {} OF {} CLEARED", level.0, level.1);
+        let title_width = d.measure_text(&title, 40);
+        d.draw_text(&title, screen_width / 2 - title_width / 2, screen_height / 6, 40, Color::new(230, 210, 120, 255));
+
+        let subtitle = if carried.is_some() { "The exit only leads deeper..." } else { "The last exit. The way out is open" };
+        let subtitle_width = d.measure_text(subtitle, 24);
+        d.draw_text(subtitle, screen_width / 2 - subtitle_width / 2, screen_height / 6 + 56, 24, Color::new(200, 200, 200, 255));
+
+        // Label on the left of the column, detail in the middle, points on the right
+        let (left, right) = (screen_width / 2 - 220, screen_width / 2 + 220);
+        let mut y = screen_height / 3 + 10;
+        for (label, detail, points) in tally.lines() {
+            let color = if points < 0 { Color::new(230, 90, 80, 255) } else { Color::new(220, 220, 220, 255) };
+            d.draw_text(label, left, y, 22, Color::new(200, 200, 200, 255));
+            d.draw_text(detail, left + 170, y + 3, 18, Color::new(150, 150, 150, 255));
+            let points = format!("{:+}", points);
+            d.draw_text(&points, right - d.measure_text(&points, 22), y, 22, color);
+            y += 34;
+        }
+        d.draw_line(left, y, right, y, Color::new(120, 120, 120, 255));
+        let total = format!("{}", tally.total());
+        d.draw_text("Total", left, y + 10, 26, Color::new(230, 210, 120, 255));
+        d.draw_text(&total, right - d.measure_text(&total, 26), y + 10, 26, Color::new(230, 210, 120, 255));
+
+        if let Some((medal, scale)) = tally.medal() {
+            let size = (30.0 * scale) as i32;
+            let text = format!("{} MEDAL", medal.name());
+            let text_width = d.measure_text(&text, size);
+            let (x, medal_y) = (screen_width / 2 - text_width / 2, y + 60);
+            d.draw_rectangle_lines(x - 14, medal_y - 8, text_width + 28, size + 16, medal.color());
+            d.draw_text(&text, x, medal_y, size, medal.color());
+        }
+
+        if tally.finished() {
+            let next = match carried {
+                Some((health, carried_time)) => format!("Health {}  |  Time bonus +{:.0}s  |  Press ENTER for the next level", health, carried_time),
+                None => "Press ENTER to continue".to_string(),
+            };
+            let next_width = d.measure_text(&next, 20);
+            d.draw_text(&next, screen_width / 2 - next_width / 2, screen_height - 70, 20, Color::new(150, 150, 150, 255));
+        }
+    }
+
+    /// Time, seed and leaderboard placement of the run that just finished
+    pub fn render_run_summary(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, time: f32, seed: u64, rank: Option<usize>) {
+        let placement = match rank {
+            Some(rank) => format!("#{} on the leaderboard", rank),
+            None => "Not in the top runs".to_string(),
+        };
+        let line = format!("Time {:.2}s  |  Seed {}  |  {}", time, seed, placement);
+        let text_width = d.measure_text(&line, 20);
+        d.draw_text(&line, screen_width / 2 - text_width / 2, screen_height / 2 + 40, 20, Color::new(230, 210, 120, 255));
+    }
+
+    /// Challenge rooms won and failed over the run, under the run summary (and the hardcore badge)
+    pub fn render_challenge_record(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, won: u32, failed: u32) {
+        let line = format!("Challenges: {} won, {} failed", won, failed);
+        let text_width = d.measure_text(&line, 18);
+        d.draw_text(&line, screen_width / 2 - text_width / 2, screen_height / 2 + 120, 18, Color::new(120, 220, 255, 255));
+    }
+
+    /// Badge under the run summary for escaping in hardcore mode
+    pub fn render_hardcore_badge(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32) {
+        let text = "HARDCORE - one life, no saves";
+        let text_width = d.measure_text(text, 22);
+        let (x, y) = (screen_width / 2 - text_width / 2, screen_height / 2 + 80);
+        d.draw_rectangle(x - 12, y - 6, text_width + 24, 34, Color::new(120, 20, 20, 230));
+        d.draw_rectangle_lines(x - 12, y - 6, text_width + 24, 34, Color::new(255, 200, 80, 255));
+        d.draw_text(text, x, y, 22, Color::new(255, 220, 120, 255));
+    }
+
+    /// Render the local top list and, when online submission is enabled, the global one
+    pub fn render_leaderboard(
+        &self,
+        d: &mut RaylibDrawHandle,
+        screen_width: i32,
+        screen_height: i32,
+        local: &[RunRecord],
+        global: Option<&[RunRecord]>,
+        status: &str,
+    ) {
+        d.draw_rectangle(0, 0, screen_width, screen_height, Color::new(10, 10, 5, 255));
+
+        let title = "LEADERBOARD";
+        let title_width = d.measure_text(title, 40);
+        d.draw_text(title, screen_width / 2 - title_width / 2, 40, 40, Color::new(255, 220, 100, 255));
+
+        // Local list on the left, global list on the right
+        let column_width = screen_width / 2;
+        Self::render_record_list(d, "This machine", local, 40, 120);
+        if let Some(records) = global {
+            Self::render_record_list(d, "Global", records, column_width + 20, 120);
+        }
+
+        let status_width = d.measure_text(status, 18);
+        d.draw_text(status, screen_width / 2 - status_width / 2, screen_height - 80, 18, Color::new(160, 160, 160, 255));
+
+        let hint = "ENTER / BACKSPACE - Back";
+        let hint_width = d.measure_text(hint, 18);
+        d.draw_text(hint, screen_width / 2 - hint_width / 2, screen_height - 45, 18, Color::new(200, 200, 200, 255));
+    }
+
+    /// Render the startup profile screen: the profile list, or the name being typed
+    pub fn render_profiles(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, picker: &ProfilePicker) {
+        d.clear_background(Color::new(10, 10, 5, 255));
+
+        let title = if picker.naming.is_some() { "WHO IS ENTERING?" } else { "CHOOSE PROFILE" };
+        let title_width = d.measure_text(title, 40);
+        d.draw_text(title, screen_width / 2 - title_width / 2, screen_height / 6, 40, Color::new(255, 220, 100, 255));
+
+        let hint = if let Some(name) = &picker.naming {
+            // Text box with a blinking cursor
+            let (box_width, box_height) = (320, 44);
+            let (x, y) = (screen_width / 2 - box_width / 2, screen_height / 2 - box_height / 2);
+            d.draw_rectangle(x, y, box_width, box_height, Color::new(30, 28, 15, 255));
+            d.draw_rectangle_lines(x, y, box_width, box_height, Color::new(255, 220, 0, 255));
+            let cursor = if (d.get_time() * 2.0) as i64 % 2 == 0 { "_" } else { "" };
+            d.draw_text(&format!("{}{}", name, cursor), x + 12, y + 10, 24, Color::new(230, 230, 230, 255));
+
+            let limit = format!("{}/{}", name.len(), profile::MAX_NAME_LENGTH);
+            d.draw_text(&limit, x + box_width - d.measure_text(&limit, 14) - 6, y + box_height + 6, 14, Color::new(140, 140, 140, 255));
+            if let Some(error) = &picker.error {
+                let error_width = d.measure_text(error, 18);
+                d.draw_text(error, screen_width / 2 - error_width / 2, y + box_height + 30, 18, Color::new(255, 90, 60, 255));
+            }
+            if picker.profiles.is_empty() {
+                "Type a name    ENTER - Create"
+            } else {
+                "Type a name    ENTER - Create    BACKSPACE - Back"
+            }
+        } else {
+            let start_y = screen_height / 3;
+            let entries = picker.profiles.iter().map(String::as_str).chain(std::iter::once("+ New profile"));
+            for (i, entry) in entries.enumerate() {
+                let selected = i == picker.selected;
+                let text = if selected { format!("> {} <", entry) } else { entry.to_string() };
+                let color = if selected { Color::new(255, 220, 0, 255) } else { Color::new(200, 200, 200, 255) };
+                let text_width = d.measure_text(&text, self.font_size);
+                d.draw_text(&text, screen_width / 2 - text_width / 2, start_y + i as i32 * (self.font_size + 10), self.font_size, color);
+            }
+            "UP/DOWN - Select    ENTER - Play"
+        };
+
+        let hint_width = d.measure_text(hint, 18);
+        d.draw_text(hint, screen_width / 2 - hint_width / 2, screen_height - 45, 18, Color::new(200, 200, 200, 255));
+    }
+
+    /// Render the attract mode banner over the demo
+    pub fn render_demo(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32) {
+        // Blinks like an arcade cabinet
+        if (d.get_time() * 1.5) as i64 % 2 == 0 {
+            let title = "DEMO";
+            let title_width = d.measure_text(title, 48);
+            d.draw_text(title, screen_width / 2 - title_width / 2 + 3, 33, 48, Color::new(0, 0, 0, 200));
+            d.draw_text(title, screen_width / 2 - title_width / 2, 30, 48, Color::new(255, 220, 0, 255));
+        }
+
+        let hint = "Press any key";
+        let hint_width = d.measure_text(hint, 20);
+        d.draw_text(hint, screen_width / 2 - hint_width / 2, screen_height - 40, 20, Color::new(200, 200, 200, 220));
+    }
+
+    /// Render the options screen: every setting with its value, the selected one highlighted
+    pub fn render_options(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, options: &Options, config: &Config) {
+        d.clear_background(Color::new(10, 10, 5, 255));
+
+        let title = "OPTIONS";
+        let title_width = d.measure_text(title, 40);
+        d.draw_text(title, screen_width / 2 - title_width / 2, screen_height / 6, 40, Color::new(255, 220, 100, 255));
+
+        // Rows close up on short windows so every setting fits above the hint
+        let start_y = screen_height / 3;
+        let row_height = ((screen_height - 60 - start_y) / Setting::ALL.len() as i32).min(self.font_size + 10);
+        for (i, setting) in Setting::ALL.iter().enumerate() {
+            let selected = i == options.selected;
+            let text = format!("{}: {}", setting.label(), setting.value(config));
+            let text = if selected { format!("< {} >", text) } else { text };
+            let color = if selected { Color::new(255, 220, 0, 255) } else { Color::new(200, 200, 200, 255) };
+            let text_width = d.measure_text(&text, self.font_size);
+            d.draw_text(&text, screen_width / 2 - text_width / 2, start_y + i as i32 * row_height, self.font_size, color);
+        }
+
+        let hint = "UP/DOWN - Select    LEFT/RIGHT - Change    ENTER - Back";
+        let hint_width = d.measure_text(hint, 18);
+        d.draw_text(hint, screen_width / 2 - hint_width / 2, screen_height - 45, 18, Color::new(200, 200, 200, 255));
+    }
+
+    /// Render the intro logo at `opacity` (0.0 - 1.0) over black
+    pub fn render_splash(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, opacity: f32) {
+        d.draw_rectangle(0, 0, screen_width, screen_height, Color::BLACK);
+        let alpha = (opacity.clamp(0.0, 1.0) * 255.0) as u8;
+
+        // Fluorescent-yellow panel with the title cut out of it
+        let title = "BACKROOMS DOOM";
+        let title_size = 44;
+        let title_width = d.measure_text(title, title_size);
+        let (panel_width, panel_height) = (title_width + 60, title_size + 40);
+        let (x, y) = (screen_width / 2 - panel_width / 2, screen_height / 2 - panel_height / 2);
+        d.draw_rectangle(x, y, panel_width, panel_height, Color::new(230, 205, 90, alpha));
+        d.draw_text(title, screen_width / 2 - title_width / 2, y + 20, title_size, Color::new(20, 18, 8, alpha));
+
+        let byline = "a nadissa1508 game";
+        let byline_width = d.measure_text(byline, 18);
+        d.draw_text(byline, screen_width / 2 - byline_width / 2, y + panel_height + 20, 18, Color::new(200, 200, 180, alpha));
+    }
+
+    /// Render the credits roll, scrolled up from the bottom of the screen
+    pub fn render_credits(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, credits: &Credits) {
+        d.draw_rectangle(0, 0, screen_width, screen_height, Color::new(10, 10, 5, 255));
+
+        let mut y = screen_height - credits.scroll as i32;
+        for section in &credits.sections {
+            // Only draw what is on screen
+            if y > -credits::TITLE_SIZE && y < screen_height {
+                let width = d.measure_text(&section.title, credits::TITLE_SIZE);
+                d.draw_text(&section.title, screen_width / 2 - width / 2, y, credits::TITLE_SIZE, Color::new(255, 220, 100, 255));
+            }
+            y += credits::TITLE_SIZE + credits::LINE_SPACING;
+            for line in &section.lines {
+                if y > -credits::LINE_SIZE && y < screen_height {
+                    let width = d.measure_text(line, credits::LINE_SIZE);
+                    d.draw_text(line, screen_width / 2 - width / 2, y, credits::LINE_SIZE, Color::new(200, 200, 180, 255));
+                }
+                y += credits::LINE_SIZE + credits::LINE_SPACING;
+            }
+            y += credits::SECTION_GAP;
+        }
+
+        let hint = "ENTER / BACKSPACE - Skip    SPACE (hold) - Faster";
+        let hint_width = d.measure_text(hint, 18);
+        d.draw_rectangle(0, screen_height - 50, screen_width, 50, Color::new(10, 10, 5, 255));
+        d.draw_text(hint, screen_width / 2 - hint_width / 2, screen_height - 35, 18, Color::new(140, 140, 140, 255));
+    }
+
+    fn render_record_list(d: &mut RaylibDrawHandle, heading: &str, records: &[RunRecord], x: i32, y: i32) {
+        d.draw_text(heading, x, y, 24, Color::new(230, 230, 230, 255));
+        if records.is_empty() {
+            d.draw_text("No runs yet", x, y + 40, 20, Color::new(140, 140, 140, 255));
+            return;
+        }
+        for (i, record) in records.iter().enumerate() {
+            let badge = if record.hardcore { "  HC" } else { "" };
+            let line = format!("{:>2}. {:>7.2}s  score {:<5} seed {}{}", i + 1, record.time, record.score, record.seed, badge);
+            d.draw_text(&line, x, y + 40 + i as i32 * 26, 18, Color::new(200, 200, 180, 255));
+        }
+    }
+
+    /// Winner banner drawn over the victory / game over screen after a versus round
+    pub fn render_versus_result(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, result: &str) {
+        let size = 32;
+        let text_width = d.measure_text(result, size);
+        let y = screen_height / 6;
+        d.draw_rectangle(0, y - 10, screen_width, size + 20, Color::new(0, 0, 0, 200));
+        d.draw_text(result, screen_width / 2 - text_width / 2, y, size, Color::new(255, 90, 60, 255));
+    }
+
     /// Render the game over screen
-    pub fn render_game_over(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32) {
+    pub fn render_game_over(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, cause: DeathCause, tips: &[&str]) {
         // Dark red overlay
         d.draw_rectangle(0, 0, screen_width, screen_height, Color::new(20, 0, 0, 220));
 
         // Game Over message with dramatic effect
-        let title = "TIME'S UP!";
+        let title = cause.title();
         let title_size = 60;
         let title_width = d.measure_text(title, title_size);
 
@@ -237,7 +651,7 @@ impl UI {
         );
 
         // Subtitle
-        let subtitle = "You've been lost in the Backrooms forever...";
+        let subtitle = cause.reason();
         let subtitle_width = d.measure_text(subtitle, 24);
         d.draw_text(
             subtitle,
@@ -247,10 +661,17 @@ impl UI {
             Color::new(200, 150, 150, 255),
         );
 
+        // Rotating tip picked from how the run went
+        if let Some(tip) = tips::rotate(tips, d.get_time()) {
+            let tip = format!("TIP: {}", tip);
+            let tip_width = d.measure_text(&tip, 18);
+            d.draw_text(&tip, screen_width / 2 - tip_width / 2, screen_height / 2 + 40, 18, Color::new(230, 210, 140, 255));
+        }
+
         // Instructions with pulsing effect
         let restart = "Press ENTER to try again";
         let restart_width = d.measure_text(restart, 20);
-        let pulse = ((d.get_time() * 2.0).sin() * 0.3 + 0.7) as f32;
+        let pulse = tween::pulse(d.get_time(), 0.3, 0.4, 1.0);
         d.draw_text(
             restart,
             screen_width / 2 - restart_width / 2,
@@ -265,6 +686,443 @@ impl UI {
         );
     }
 
+    /// Render small graded texture swatches in the bottom-left corner of the menu
+    pub fn render_theme_preview(&self, d: &mut RaylibDrawHandle, theme_name: &str, swatches: &[&Texture], grade: &ColorGrade) {
+        const SWATCH_SIZE: usize = 32;
+        let screen_height = d.get_screen_height();
+        let x = 10;
+        let y = screen_height - SWATCH_SIZE as i32 - 40;
+
+        d.draw_text(
+            &format!("T - Theme: {}", theme_name),
+            x,
+            y - 22,
+            16,
+            Color::new(200, 200, 200, 255),
+        );
+
+        for (i, texture) in swatches.iter().enumerate() {
+            let swatch_x = x + i as i32 * (SWATCH_SIZE as i32 + 6);
+            for py in 0..SWATCH_SIZE {
+                for px in 0..SWATCH_SIZE {
+                    let color = texture.sample(px as f32 / SWATCH_SIZE as f32, py as f32 / SWATCH_SIZE as f32);
+                    d.draw_pixel(swatch_x + px as i32, y + py as i32, grade.apply(color));
+                }
+            }
+            d.draw_rectangle_lines(swatch_x, y, SWATCH_SIZE as i32, SWATCH_SIZE as i32, Color::new(255, 220, 0, 255));
+        }
+    }
+
+    /// Render the benchmark progress overlay, or the results once every preset ran
+    pub fn render_benchmark(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, benchmark: &Benchmark) {
+        let yellow = Color::new(255, 220, 0, 255);
+        let gray = Color::new(200, 200, 200, 255);
+
+        if let Some(preset) = benchmark.current_preset() {
+            // Progress while measuring
+            d.draw_rectangle(0, 0, screen_width, 50, Color::new(0, 0, 0, 180));
+            d.draw_text(
+                &format!("BENCHMARK - {} ({} FPS)", preset.name().to_uppercase(), d.get_fps()),
+                10,
+                8,
+                20,
+                yellow,
+            );
+            d.draw_rectangle(10, 34, screen_width - 20, 8, Color::new(50, 50, 50, 255));
+            d.draw_rectangle(
+                10,
+                34,
+                ((screen_width - 20) as f32 * benchmark.progress()) as i32,
+                8,
+                yellow,
+            );
+            d.draw_text("BACKSPACE - Cancel", 10, screen_height - 25, 16, gray);
+            return;
+        }
+
+        // Results table
+        d.clear_background(Color::BLACK);
+        let title = "BENCHMARK RESULTS";
+        let title_width = d.measure_text(title, 36);
+        d.draw_text(title, screen_width / 2 - title_width / 2, screen_height / 6, 36, yellow);
+
+        let left = screen_width / 2 - 170;
+        let mut y = screen_height / 6 + 70;
+        d.draw_text("PRESET", left, y, 20, gray);
+        d.draw_text("AVG FPS", left + 140, y, 20, gray);
+        d.draw_text("1% LOW", left + 260, y, 20, gray);
+
+        let recommended = benchmark.recommended();
+        for result in &benchmark.results {
+            y += 30;
+            let color = if result.preset == recommended { yellow } else { gray };
+            d.draw_text(&result.preset.name().to_uppercase(), left, y, 20, color);
+            d.draw_text(&format!("{:.0}", result.avg_fps), left + 140, y, 20, color);
+            d.draw_text(&format!("{:.0}", result.low_fps), left + 260, y, 20, color);
+        }
+
+        let recommendation = format!("Recommended preset: {}", recommended.name().to_uppercase());
+        let recommendation_width = d.measure_text(&recommendation, 24);
+        d.draw_text(&recommendation, screen_width / 2 - recommendation_width / 2, y + 50, 24, yellow);
+
+        let options = "ENTER - Use recommended    BACKSPACE - Keep current";
+        let options_width = d.measure_text(options, 18);
+        d.draw_text(options, screen_width / 2 - options_width / 2, screen_height - 60, 18, gray);
+    }
+
+    /// Render the asset loading screen with a progress bar
+    pub fn render_loading(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, progress: f32, current_item: &str) {
+        d.clear_background(Color::BLACK);
+
+        let title = "LOADING...";
+        let title_size = 30;
+        let title_width = d.measure_text(title, title_size);
+        d.draw_text(
+            title,
+            screen_width / 2 - title_width / 2,
+            screen_height / 2 - 60,
+            title_size,
+            Color::new(255, 220, 0, 255),
+        );
+
+        // Progress bar
+        let bar_width = screen_width / 2;
+        let bar_height = 16;
+        let bar_x = screen_width / 2 - bar_width / 2;
+        let bar_y = screen_height / 2;
+        let progress = progress.clamp(0.0, 1.0);
+
+        d.draw_rectangle(bar_x, bar_y, bar_width, bar_height, Color::new(50, 50, 50, 255));
+        d.draw_rectangle(
+            bar_x,
+            bar_y,
+            (bar_width as f32 * progress) as i32,
+            bar_height,
+            Color::new(200, 180, 0, 255),
+        );
+        d.draw_rectangle_lines(bar_x, bar_y, bar_width, bar_height, Color::new(255, 220, 0, 255));
+
+        // Percentage and the asset currently being loaded
+        let percent = format!("{}%", (progress * 100.0) as i32);
+        let percent_width = d.measure_text(&percent, 20);
+        d.draw_text(
+            &percent,
+            screen_width / 2 - percent_width / 2,
+            bar_y + bar_height + 10,
+            20,
+            Color::new(200, 200, 200, 255),
+        );
+
+        let item_width = d.measure_text(current_item, 14);
+        d.draw_text(
+            current_item,
+            screen_width / 2 - item_width / 2,
+            bar_y + bar_height + 40,
+            14,
+            Color::new(120, 120, 120, 255),
+        );
+    }
+
+    /// Render the error screen (missing maze, bad texture, ...)
+    pub fn render_error(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, message: &str, options: &str) {
+        d.clear_background(Color::BLACK);
+
+        let title = "SOMETHING WENT WRONG";
+        let title_size = 36;
+        let title_width = d.measure_text(title, title_size);
+        d.draw_text(
+            title,
+            screen_width / 2 - title_width / 2,
+            screen_height / 4,
+            title_size,
+            Color::new(255, 80, 50, 255),
+        );
+
+        // Word-wrap the error message to fit the screen
+        let message_size = 18;
+        let max_width = screen_width - 80;
+        let mut lines: Vec<String> = Vec::new();
+        let mut current = String::new();
+        for word in message.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if d.measure_text(&candidate, message_size) > max_width && !current.is_empty() {
+                lines.push(current);
+                current = word.to_string();
+            } else {
+                current = candidate;
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+
+        let start_y = screen_height / 2 - 30;
+        for (i, line) in lines.iter().enumerate() {
+            let line_width = d.measure_text(line, message_size);
+            d.draw_text(
+                line,
+                screen_width / 2 - line_width / 2,
+                start_y + i as i32 * (message_size + 6),
+                message_size,
+                Color::new(220, 220, 220, 255),
+            );
+        }
+
+        // Available options
+        let options_width = d.measure_text(options, 20);
+        let pulse = tween::pulse(d.get_time(), 0.5, 0.4, 1.0);
+        d.draw_text(
+            options,
+            screen_width / 2 - options_width / 2,
+            screen_height - 100,
+            20,
+            Color::new((255.0 * pulse) as u8, (220.0 * pulse) as u8, 0, 255),
+        );
+    }
+
+    /// Render the overlay shown while the window is unfocused (countdown = None)
+    /// or counting down before the run resumes
+    pub fn render_focus_pause(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, countdown: Option<f32>) {
+        d.draw_rectangle(0, 0, screen_width, screen_height, Color::new(0, 0, 0, 160));
+
+        let text = match countdown {
+            Some(seconds) => format!("Resuming... {}", seconds.ceil() as i32),
+            None => "PAUSED".to_string(),
+        };
+        let text_size = 40;
+        let text_width = d.measure_text(&text, text_size);
+        d.draw_text(
+            &text,
+            screen_width / 2 - text_width / 2,
+            screen_height / 2 - text_size / 2,
+            text_size,
+            Color::new(255, 220, 0, 255),
+        );
+
+        if countdown.is_none() {
+            let hint = "Click the window to continue";
+            let hint_width = d.measure_text(hint, 18);
+            d.draw_text(
+                hint,
+                screen_width / 2 - hint_width / 2,
+                screen_height / 2 + 30,
+                18,
+                Color::new(200, 200, 200, 255),
+            );
+        }
+    }
+
+    /// Progress circle for searching a container, with the sweeping needle and lucky zone
+    pub fn render_search(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, search: &Search, label: &str, controls: &Controls) {
+        let center = Vector2::new(screen_width as f32 / 2.0, screen_height as f32 / 2.0 + 60.0);
+        let (inner, outer) = (26.0, 34.0);
+
+        // Background ring, progress, then the lucky zone on top
+        d.draw_ring(center, inner, outer, 0.0, 360.0, 48, Color::new(0, 0, 0, 170));
+        d.draw_ring(center, inner, outer, -90.0, -90.0 + 360.0 * search.progress, 48, Color::new(255, 220, 0, 230));
+        if !search.attempt_used {
+            let (start, end) = search.zone();
+            d.draw_ring(center, outer + 2.0, outer + 7.0, -90.0 + 360.0 * start, -90.0 + 360.0 * end, 12, Color::new(80, 255, 120, 230));
+        }
+
+        // Needle
+        let angle = (search.needle * 360.0 - 90.0).to_radians();
+        let tip = Vector2::new(center.x + angle.cos() * (outer + 8.0), center.y + angle.sin() * (outer + 8.0));
+        d.draw_line_ex(center, tip, 2.0, Color::WHITE);
+
+        let text = format!("Searching {}...", label);
+        let text_width = d.measure_text(&text, 18);
+        d.draw_text(&text, screen_width / 2 - text_width / 2, center.y as i32 + 44, 18, Color::WHITE);
+        let hint = if search.attempt_used {
+            "Move to stop".to_string()
+        } else {
+            format!("{} in the green zone - search faster", controls.label(Action::Interact))
+        };
+        let hint_width = d.measure_text(&hint, 14);
+        d.draw_text(&hint, screen_width / 2 - hint_width / 2, center.y as i32 + 66, 14, Color::new(200, 200, 200, 255));
+    }
+
+    /// Stealth eye at the bottom of the screen: opens wider the more visible the player is
+    /// and turns from white to red as enemies become aware of them
+    pub fn render_stealth_eye(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, visibility: f32, awareness: f32) {
+        let center_x = screen_width / 2;
+        let center_y = screen_height - 30;
+        let width = 22.0;
+        let openness = 0.15 + 0.85 * visibility.clamp(0.0, 1.0);
+
+        let awareness = awareness.clamp(0.0, 1.0);
+        let color = Color::new(
+            255,
+            (255.0 * (1.0 - awareness * 0.85)) as u8,
+            (255.0 * (1.0 - awareness)) as u8,
+            230,
+        );
+
+        d.draw_ellipse(center_x, center_y, width + 4.0, 14.0, Color::new(0, 0, 0, 140));
+        d.draw_ellipse_lines(center_x, center_y, width, 11.0 * openness, color);
+        if openness > 0.3 {
+            d.draw_circle(center_x, center_y, 7.0 * openness, color);
+            d.draw_circle(center_x, center_y, 3.0 * openness, Color::new(20, 20, 20, 255));
+        }
+    }
+
+    /// Status cluster in the bottom-left corner: active effects with their remaining duration,
+    /// cooldowns and gauges, each a ring filled to its fraction with a label underneath
+    pub fn render_status_cluster(&self, d: &mut RaylibDrawHandle, layout: &HudLayout, items: &[StatusItem]) {
+        let radius = layout.px(14.0);
+        let thickness = layout.px(4.0).max(2) as f32;
+        let spacing = layout.px(48.0);
+        let font_size = layout.px(10.0).max(8);
+        let size = (spacing * items.len() as i32, radius * 2 + font_size + layout.px(4.0));
+        let (x, y) = layout.place(Anchor::BottomLeft, size, (0.0, 0.0));
+
+        for (i, item) in items.iter().enumerate() {
+            let center = Vector2::new((x + spacing * i as i32 + spacing / 2) as f32, (y + radius) as f32);
+            let (inner, outer) = (radius as f32 - thickness, radius as f32);
+            d.draw_circle_v(center, outer, Color::new(0, 0, 0, 150));
+            d.draw_ring(center, inner, outer, 0.0, 360.0, 32, Color::new(60, 60, 60, 200));
+            d.draw_ring(center, inner, outer, -90.0, -90.0 + 360.0 * item.fraction, 32, item.color);
+            if let Some(icon) = item.icon {
+                let icon_width = d.measure_text(icon, font_size);
+                d.draw_text(icon, center.x as i32 - icon_width / 2, center.y as i32 - font_size / 2, font_size, item.color);
+            }
+
+            let label_width = d.measure_text(&item.label, font_size);
+            let label_x = center.x as i32 - label_width / 2;
+            d.draw_text(&item.label, label_x, y + radius * 2 + layout.px(4.0), font_size, Color::new(220, 220, 220, 255));
+        }
+    }
+
+    /// Inventory hotbar at the bottom center: one box per slot with its number, the item's
+    /// initial and count, the selected slot outlined and its item named above the bar
+    pub fn render_hotbar(&self, d: &mut RaylibDrawHandle, layout: &HudLayout, inventory: &Inventory, use_key: &str) {
+        let (slot, gap) = (layout.px(30.0), layout.px(4.0));
+        let font_size = layout.px(10.0).max(8);
+        let slots = inventory.slots();
+        let width = slot * slots.len() as i32 + gap * (slots.len() as i32 - 1);
+        let (x, y) = layout.place(Anchor::BottomCenter, (width, slot), (0.0, 0.0));
+
+        for (i, held) in slots.iter().enumerate() {
+            let left = x + i as i32 * (slot + gap);
+            let selected = i == inventory.selected;
+            d.draw_rectangle(left, y, slot, slot, Color::new(0, 0, 0, 160));
+            let border = if selected { Color::new(255, 220, 120, 255) } else { Color::new(90, 90, 90, 200) };
+            d.draw_rectangle_lines(left, y, slot, slot, border);
+            d.draw_text(&(i + 1).to_string(), left + 3, y + 2, font_size, Color::new(150, 150, 150, 255));
+
+            if let Some((item, count)) = held {
+                let initial = &item.label()[..1];
+                let initial_size = font_size * 2;
+                let initial_width = d.measure_text(initial, initial_size);
+                d.draw_text(initial, left + slot / 2 - initial_width / 2, y + slot / 2 - initial_size / 2, initial_size, item.color());
+                if *count > 1 {
+                    let count = format!("x{}", count);
+                    let count_width = d.measure_text(&count, font_size);
+                    d.draw_text(&count, left + slot - count_width - 2, y + slot - font_size - 1, font_size, Color::new(220, 220, 220, 255));
+                }
+            }
+        }
+
+        if let Some(item) = inventory.selected_item() {
+            let label = format!("{} [{}]", item.label(), use_key);
+            let label_width = d.measure_text(&label, font_size);
+            d.draw_text(&label, x + width / 2 - label_width / 2, y - font_size - gap, font_size, item.color());
+        }
+    }
+
+    /// Door struggle under the crosshair: how close the door is to giving way and stamina left to brace it
+    pub fn render_barricade(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, pressure: f32, stamina: f32, controls: &Controls) {
+        let bar_width = 180;
+        let x = screen_width / 2 - bar_width / 2;
+        let y = screen_height / 2 + 40;
+
+        let title = "Something is pushing the door!";
+        let title_width = d.measure_text(title, 18);
+        d.draw_text(title, screen_width / 2 - title_width / 2, y, 18, Color::new(255, 90, 60, 255));
+
+        // Pressure: the door bursts open when this fills up
+        let pressure = pressure.clamp(0.0, 1.0);
+        d.draw_rectangle(x, y + 24, bar_width, 12, Color::new(50, 50, 50, 200));
+        d.draw_rectangle(x, y + 24, (bar_width as f32 * pressure) as i32, 12, Color::new(200, 50, 40, 255));
+        d.draw_rectangle_lines(x, y + 24, bar_width, 12, Color::new(220, 220, 220, 180));
+
+        // Stamina spent while bracing
+        let stamina = stamina.clamp(0.0, 1.0);
+        d.draw_rectangle(x, y + 40, bar_width, 6, Color::new(50, 50, 50, 200));
+        d.draw_rectangle(x, y + 40, (bar_width as f32 * stamina) as i32, 6, Color::new(255, 220, 0, 255));
+
+        let hint = if stamina > 0.0 {
+            format!("Hold / mash {} - Keep it shut", controls.label(Action::Interact))
+        } else {
+            "Out of breath...".to_string()
+        };
+        let hint_width = d.measure_text(&hint, 14);
+        d.draw_text(&hint, screen_width / 2 - hint_width / 2, y + 52, 14, Color::new(200, 200, 200, 255));
+    }
+
+    /// Subtitle for a spoken callout, above the stealth eye
+    pub fn render_caption(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, speaker: &str, line: &str) {
+        let text = format!("{}: {}", speaker, line);
+        let text_width = d.measure_text(&text, 18);
+        let x = screen_width / 2 - text_width / 2;
+        let y = screen_height - 84;
+        d.draw_rectangle(x - 8, y - 4, text_width + 16, 26, Color::new(0, 0, 0, 170));
+        d.draw_text(&text, x, y, 18, Color::new(140, 230, 160, 255));
+    }
+
+    /// Caption for a sound cue, above the hints
+    pub fn render_sound_caption(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, caption: &str) {
+        let text_width = d.measure_text(caption, 18);
+        let x = screen_width / 2 - text_width / 2;
+        let y = screen_height - 150;
+        d.draw_rectangle(x - 8, y - 4, text_width + 16, 26, Color::new(0, 0, 0, 170));
+        d.draw_text(caption, x, y, 18, Color::new(220, 220, 220, 255));
+    }
+
+    /// Gentle hint above the captions, fading out over its last second
+    pub fn render_hint(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, hint: &str, time_left: f32) {
+        let alpha = time_left.clamp(0.0, 1.0);
+        let text_width = d.measure_text(hint, 18);
+        let x = screen_width / 2 - text_width / 2;
+        let y = screen_height - 120;
+        d.draw_rectangle(x - 8, y - 4, text_width + 16, 26, Color::new(0, 0, 0, (150.0 * alpha) as u8));
+        d.draw_text(hint, x, y, 18, Color::new(230, 220, 170, (255.0 * alpha) as u8));
+    }
+
+    /// Twitch chat poll: live tally while a vote is open, otherwise time until the next one
+    pub fn render_chat_vote(&self, d: &mut RaylibDrawHandle, screen_width: i32, top: i32, chat: &TwitchChat) {
+        let x = screen_width - 200;
+        let purple = Color::new(170, 120, 255, 255);
+        let time_left = chat.time_left();
+        d.draw_rectangle(x - 8, top - 6, 198, 112, Color::new(0, 0, 0, 150));
+        d.draw_text(&chat.status, x, top, 14, Color::new(180, 180, 180, 255));
+
+        if !chat.voting() {
+            d.draw_text(&format!("Next chat vote in {:.0}s", time_left.ceil()), x, top + 22, 16, purple);
+            return;
+        }
+
+        d.draw_text(&format!("CHAT VOTE  {:.0}s", time_left.ceil()), x, top + 22, 16, purple);
+        let tally = chat.tally();
+        let total = tally.iter().map(|(_, count)| count).sum::<usize>().max(1);
+        for (i, (scare, count)) in tally.iter().enumerate() {
+            let y = top + 46 + i as i32 * 20;
+            let bar = (*count as f32 / total as f32 * 80.0) as i32;
+            d.draw_rectangle(x + 100, y + 2, bar, 12, purple);
+            d.draw_text(&format!("!{} {}", scare.command(), count), x, y, 16, Color::new(220, 220, 220, 255));
+        }
+    }
+
+    /// Interaction hint shown under the crosshair
+    pub fn render_prompt(&self, d: &mut RaylibDrawHandle, screen_width: i32, screen_height: i32, text: &str) {
+        let text_width = d.measure_text(text, 18);
+        d.draw_text(text, screen_width / 2 - text_width / 2, screen_height / 2 + 40, 18, Color::new(255, 220, 0, 255));
+    }
+
     /// Render the countdown timer during gameplay
     pub fn render_timer(&self, d: &mut RaylibDrawHandle, time_remaining: f32) {
         // Convert time to minutes:seconds format
@@ -272,11 +1130,15 @@ impl UI {
         let seconds = (time_remaining % 60.0).floor() as i32;
         let timer_text = format!("{:01}:{:02}", minutes, seconds);
 
-        // Position at top center of screen
+        // Position at top center of screen, swelling for a moment when time is gained
         let screen_width = d.get_screen_width();
-        let timer_size = 32;
+        let flash = self.timer_flash.value();
+        let gained = self.timer_change > 0.0;
+        let timer_size = 32 + if gained { (flash * TIMER_SWELL) as i32 } else { 0 };
         let text_width = d.measure_text(&timer_text, timer_size);
-        let x = screen_width / 2 - text_width / 2;
+        // Losing time shakes the timer sideways, dying down
+        let shake = self.timer_shake.value();
+        let x = screen_width / 2 - text_width / 2 + (shake * TIMER_SHAKE_PIXELS * (self.timer_shake.elapsed() * 60.0).sin()) as i32;
         let y = 10;
 
         // Background box for better visibility
@@ -296,19 +1158,32 @@ impl UI {
             Color::new(255, 200, 50, 255)  // Yellow when less than 1 minute
         } else {
             // Red and pulsing when less than 30 seconds
-            let pulse = ((d.get_time() * 4.0).sin() * 0.3 + 0.7) as f32;
+            let pulse = tween::pulse(d.get_time(), 0.6, 0.4, 1.0);
             Color::new(255, (50.0 * pulse) as u8, (50.0 * pulse) as u8, 255)
         };
+        // Flash red for time lost, green for time gained
+        let flash_color = if gained { Color::new(80, 255, 120, 255) } else { Color::new(255, 40, 40, 255) };
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * flash) as u8;
+        let timer_color = Color::new(mix(timer_color.r, flash_color.r), mix(timer_color.g, flash_color.g), mix(timer_color.b, flash_color.b), 255);
 
         // Draw the timer
         d.draw_text(&timer_text, x, y, timer_size, timer_color);
 
+        // The change floats up beside the box, fading out
+        if !self.timer_delta.finished() {
+            let progress = self.timer_delta.value();
+            let change = format!("{:+.0}s", self.timer_change);
+            let alpha = ((1.0 - progress) * 255.0) as u8;
+            let change_y = y + 6 - (progress * TIMER_DELTA_RISE) as i32;
+            d.draw_text(&change, x + text_width + padding + 8, change_y, 22, Color::new(flash_color.r, flash_color.g, flash_color.b, alpha));
+        }
+
         // Add warning text when time is running out
         if time_remaining <= 10.0 && time_remaining > 0.0 {
             let warning = "HURRY!";
             let warning_size = 20;
             let warning_width = d.measure_text(warning, warning_size);
-            let pulse = ((d.get_time() * 5.0).sin() * 0.5 + 0.5) as f32;
+            let pulse = tween::pulse(d.get_time(), 0.8, 0.0, 1.0);
             d.draw_text(
                 warning,
                 screen_width / 2 - warning_width / 2,
@@ -0,0 +1,79 @@
+// Versus mode for Backrooms Doom
+// Asymmetric hotseat mode: the first player tries to escape as usual while a
+// second player on the same keyboard (I/J/K/L) controls the entity hunting them
+// from a top-down view
+
+use crate::maze::Maze;
+use crate::pathfinding;
+use crate::player::Player;
+use raylib::prelude::*;
+
+const HUNTER_SPEED: f32 = 0.85;     // Hunter moves slightly slower than the escaper
+pub const CATCH_DISTANCE: f32 = 0.6; // Touching the escaper ends the round
+pub const SENSE_RANGE: f32 = 4.0;    // Hunter sees the escaper on its map within this range
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Side {
+    Escaper,
+    Hunter,
+}
+
+impl Side {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Side::Escaper => "ESCAPER WINS",
+            Side::Hunter => "THE ENTITY WINS",
+        }
+    }
+}
+
+pub struct Versus {
+    pub hunter_index: usize, // Entry in GameState::players controlled by the second player
+    pub winner: Option<Side>,
+}
+
+impl Versus {
+    pub fn new(hunter_index: usize) -> Self {
+        Self { hunter_index, winner: None }
+    }
+
+    /// Hunter spawn: the reachable tile farthest from both the start and the exit,
+    /// so the escaper gets a head start and the hunter can't camp the exit
+    pub fn spawn_point(maze: &Maze) -> (f32, f32) {
+        let from_start = pathfinding::distances(maze, maze.start_pos);
+        let from_goal = pathfinding::distances(maze, maze.goal_pos);
+
+        let mut best = (maze.start_pos, 0);
+        for (index, (start_steps, goal_steps)) in from_start.iter().zip(&from_goal).enumerate() {
+            if let (Some(start_steps), Some(goal_steps)) = (start_steps, goal_steps) {
+                let score = (*start_steps).min(*goal_steps);
+                if score > best.1 {
+                    let (x, y) = (index % maze.width, index / maze.width);
+                    let center = ((x as f32 + 0.5) * maze.tile_size, (y as f32 + 0.5) * maze.tile_size);
+                    best = (center, score);
+                }
+            }
+        }
+        best.0
+    }
+
+    /// Second keyboard scheme, away from the escaper's keys (see Preset::hunter_keys):
+    /// forward, back, turn left, turn right
+    pub fn update_hunter(rl: &RaylibHandle, keys: [KeyboardKey; 4], hunter: &mut Player, maze: &Maze, delta_time: f32) {
+        let [forward, back, left, right] = keys;
+        // Slower movement is applied as a shorter step, turning stays at full speed
+        if rl.is_key_down(forward) {
+            hunter.move_forward();
+        }
+        if rl.is_key_down(back) {
+            hunter.move_backward();
+        }
+        hunter.update_movement(maze, delta_time * HUNTER_SPEED);
+        if rl.is_key_down(left) {
+            hunter.rotate(-hunter.rot_speed * delta_time);
+        }
+        if rl.is_key_down(right) {
+            hunter.rotate(hunter.rot_speed * delta_time);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// Entity whispers for Backrooms Doom
+// The first warning that something is near should be heard, not seen. Within
+// WHISPER_RANGE tiles of the player, an entity the player can't currently see
+// (out of the field of view or behind a wall) whispers: short phrases of the
+// whisper loop at random intervals and pitches, so it never settles into a
+// rhythm, panned towards the loudest entity. Loudness falls off with the square
+// of the distance and walls in the way muffle it much further. Once the entity
+// comes into view the whispering stops - by then the player knows. The loop
+// follows the loudest entity's Doppler shift (doppler.rs), rising as it closes in.
+//
+// The whispering wears on the player: its intensity keeps the anxiety effect
+// (vignette and shake, see effects.rs) at least that strong while it lasts.
+
+use crate::maze::Maze;
+use crate::stealth;
+use rand::Rng;
+use std::f32::consts::PI;
+
+const WHISPER_RANGE: f32 = 6.0;  // Tiles
+const OCCLUDED: f32 = 0.35;      // Loudness left through walls
+const MIN_GAP: f32 = 2.5;        // Seconds between phrases, picked at random...
+const MAX_GAP: f32 = 6.0;        // ...in this range
+const MIN_PITCH: f32 = 0.8;
+const MAX_PITCH: f32 = 1.15;
+const SMOOTHING: f32 = 3.0;      // How fast loudness follows the entities (per second)
+const AUDIBLE: f32 = 0.02;       // Quieter than this is silence
+const CAPTION_TIME: f32 = 2.5;
+pub const DREAD: f32 = 0.6;      // Anxiety at full whisper intensity
+
+pub struct Whispers {
+    pub intensity: f32, // 0.0 - 1.0, smoothed loudness of the loudest unseen entity
+    bearing: f32,       // Radians from the view direction to the loudest entity, positive = right
+    pitch: f32,         // Of the current phrase
+    shift: f32,         // Doppler pitch multiplier of the loudest entity
+    gap_timer: f32,     // Until the next phrase may start
+    phrase: bool,       // A new phrase starts this frame
+    pub caption: Option<(&'static str, f32)>, // Caption on screen and time left
+}
+
+impl Whispers {
+    pub fn new() -> Self {
+        Self { intensity: 0.0, bearing: 0.0, pitch: 1.0, shift: 1.0, gap_timer: 0.0, phrase: false, caption: None }
+    }
+
+    /// Listen from the player's position and view angle for the entities at `sources`
+    /// (x, y, Doppler pitch multiplier). Entities inside the view's `fov` (and unoccluded) are seen
+    pub fn update(&mut self, maze: &Maze, player: (f32, f32, f32), fov: f32, sources: &[(f32, f32, f32)], rng: &mut impl Rng, delta_time: f32) {
+        let (x, y, angle) = player;
+        let mut loudest = (0.0, self.bearing, self.shift);
+        for &(source_x, source_y, shift) in sources {
+            let (dx, dy) = (source_x - x, source_y - y);
+            let distance = (dx * dx + dy * dy).sqrt() / maze.tile_size;
+            if distance >= WHISPER_RANGE {
+                continue;
+            }
+
+            // Signed angle from the view direction to the entity
+            let bearing = (dy.atan2(dx) - angle + PI).rem_euclid(2.0 * PI) - PI;
+            let clear = stealth::line_of_sight(maze, (x, y), (source_x, source_y));
+            if clear && bearing.abs() < fov / 2.0 {
+                continue; // In plain sight
+            }
+
+            let falloff = 1.0 - distance / WHISPER_RANGE;
+            let loudness = falloff * falloff * if clear { 1.0 } else { OCCLUDED };
+            if loudness > loudest.0 {
+                loudest = (loudness, bearing, shift);
+            }
+        }
+
+        self.intensity += (loudest.0 - self.intensity) * (SMOOTHING * delta_time).min(1.0);
+        self.bearing = loudest.1;
+        self.shift = loudest.2;
+
+        if let Some((_, time_left)) = &mut self.caption {
+            *time_left -= delta_time;
+            if *time_left <= 0.0 {
+                self.caption = None;
+            }
+        }
+
+        self.gap_timer -= delta_time;
+        self.phrase = self.intensity > AUDIBLE && self.gap_timer <= 0.0;
+        if self.phrase {
+            self.gap_timer = rng.gen_range(MIN_GAP..MAX_GAP);
+            self.pitch = rng.gen_range(MIN_PITCH..MAX_PITCH);
+            let text = match self.bearing {
+                bearing if bearing.abs() > PI * 0.75 => "[Whispering behind you]",
+                bearing if bearing.abs() < PI * 0.25 => "[Whispering ahead]",
+                bearing if bearing > 0.0 => "[Whispering to the right]",
+                _ => "[Whispering to the left]",
+            };
+            self.caption = Some((text, CAPTION_TIME));
+        }
+    }
+
+    /// (volume, pan, pitch, start a new phrase) to play the whisper loop at, None when silent.
+    /// Pan is 0.0 - 1.0 with 0.5 centered (raylib puts 1.0 fully on the left)
+    pub fn playback(&self) -> Option<(f32, f32, f32, bool)> {
+        let pan = 0.5 - 0.5 * self.bearing.sin();
+        (self.intensity > AUDIBLE).then_some((self.intensity, pan, self.pitch * self.shift, self.phrase))
+    }
+}